@@ -0,0 +1,70 @@
+/*
+ * C interface of netgen_rs, built with the `ffi` feature.
+ *
+ * Link against libnetgen_rs.a (with -lpthread -ldl -lm on Linux) or
+ * libnetgen_rs.so. The generated networks are identical to those of the
+ * original netgen.c for the same seed and parms[].
+ *
+ * Ownership: the arrays of a NetgenCResult filled by netgen_generate belong
+ * to the library. Release them with netgen_free_result, never with free().
+ */
+
+#ifndef NETGEN_RS_H
+#define NETGEN_RS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Return codes; the first four match netgen.h. */
+#define NETGEN_OK                  0
+#define NETGEN_BAD_SEED           -1
+#define NETGEN_TOO_BIG            -2
+#define NETGEN_BAD_PARMS          -3
+#define NETGEN_ALLOCATION_FAILURE -4
+#define NETGEN_ARC_SHORTFALL      -5
+#define NETGEN_DISCONNECTED       -6
+#define NETGEN_CANCELLED          -7
+#define NETGEN_INVALID_ARGUMENT   -8 /* null pointer or non-UTF-8 path */
+#define NETGEN_IO_ERROR           -9
+
+/* Number of values in parms[], in the order of netgen.c. */
+#define NETGEN_PROBLEM_PARMS 13
+
+typedef struct {
+  int64_t from;
+  int64_t to;
+  int64_t cost;
+  int64_t cap;
+} NetgenCArc;
+
+/* arcs holds arc_count arcs; supply holds node_count supplies, node 1
+ * first. Both are null with a zero count when empty. */
+typedef struct {
+  NetgenCArc *arcs;
+  size_t arc_count;
+  int64_t *supply;
+  size_t node_count;
+} NetgenCResult;
+
+/* Generate the network for seed and parms[NETGEN_PROBLEM_PARMS] into *out.
+ * On error *out is left empty. */
+int32_t netgen_generate(int64_t seed, const int64_t *parms, NetgenCResult *out);
+
+/* Release the arrays of *result and reset it to empty. Does nothing for
+ * null or an empty result. */
+void netgen_free_result(NetgenCResult *result);
+
+/* Write *result in DIMACS format to the file at path, with the header for
+ * seed, problem number problem and parms[NETGEN_PROBLEM_PARMS]. */
+int32_t netgen_write_dimacs_file(const char *path, int64_t seed, int64_t problem,
+                                 const int64_t *parms, const NetgenCResult *result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NETGEN_RS_H */
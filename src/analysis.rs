@@ -0,0 +1,622 @@
+//! Graph analyses of generated networks.
+
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{NetgenParams, NetgenResult};
+
+/// Value of a maximum flow from all sources (nodes with positive supply) to
+/// all sinks (negative supply), as a DIMACS `p max` solver computes it.
+///
+/// Supplies only mark the terminals: they are joined to a virtual source
+/// and sink by unbounded arcs, so the value is limited by arc capacities
+/// alone. Because NETGEN's skeleton can route every supply, the value of a
+/// generated network is at least its total supply. Parallel arcs are
+/// handled, and the total is accumulated in `i128` and saturates at
+/// `i64::MAX`.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, analysis, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100,
+/// ]).unwrap();
+/// let result = generate(99999, &params).unwrap();
+/// assert!(analysis::max_flow_value(&result) >= 500);
+/// ```
+pub fn max_flow_value(result: &NetgenResult) -> i64 {
+    let nodes = result.supply.len();
+    let mut graph = Residual::new(nodes + 2);
+    for arc in &result.arcs {
+        graph.add(
+            arc.from as usize - 1,
+            arc.to as usize - 1,
+            arc.capacity.max(0),
+        );
+    }
+    let (source, sink) = (nodes, nodes + 1);
+    for (v, &s) in result.supply.iter().enumerate() {
+        if s > 0 {
+            graph.add(source, v, i64::MAX);
+        } else if s < 0 {
+            graph.add(v, sink, i64::MAX);
+        }
+    }
+    i64::try_from(graph.max_flow(source, sink)).unwrap_or(i64::MAX)
+}
+
+/// Reachability of the sinks from the sources, see [`reachability`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReachabilityReport {
+    /// One entry per sink node, in node order.
+    pub sinks: Vec<SinkReachability>,
+    /// Total demand of the sinks no source can reach.
+    pub unreachable_demand: i64,
+    /// Number of weakly connected components of the network.
+    pub weak_components: usize,
+}
+
+/// Whether one sink can be reached from a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinkReachability {
+    /// The sink's 1-based node id.
+    pub node: u64,
+    /// The sink's demand, the negated supply (0 for a sink no source
+    /// routed supply to).
+    pub demand: i64,
+    /// Whether a directed path leads to the sink from some source.
+    pub reachable: bool,
+}
+
+impl ReachabilityReport {
+    /// Whether every sink is reachable and the network is weakly connected.
+    pub fn is_clean(&self) -> bool {
+        self.weak_components == 1 && self.sinks.iter().all(|s| s.reachable)
+    }
+}
+
+/// Report which sinks can be reached from the sources along the arcs, and
+/// how many weakly connected components the network has.
+///
+/// Sources and sinks are the nodes `params` designates: the first
+/// `params.sources` nodes and the last `params.sinks`. A sink with demand
+/// that no source reaches makes the instance infeasible. Capacities are not
+/// considered, so a zero-capacity arc still connects its endpoints.
+///
+/// In a network from [`generate`](crate::generate) the skeleton reaches
+/// every sink with demand, so unreachable demand points to a network edited
+/// after generation. Sparse instances can still be disconnected: a
+/// transshipment node on no chain may get no arcs at all.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, analysis, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let report = analysis::reachability(&result, &params);
+/// assert_eq!(report.unreachable_demand, 0);
+/// ```
+pub fn reachability(result: &NetgenResult, params: &NetgenParams) -> ReachabilityReport {
+    let nodes = result.supply.len();
+    let mut out = vec![Vec::new(); nodes];
+    for arc in &result.arcs {
+        out[arc.from as usize - 1].push(arc.to as usize - 1);
+    }
+
+    // Multi-source BFS from the designated sources.
+    let sources = (params.sources.max(0) as usize).min(nodes);
+    let mut reached = vec![false; nodes];
+    let mut queue: VecDeque<usize> = (0..sources).collect();
+    for &v in &queue {
+        reached[v] = true;
+    }
+    while let Some(u) = queue.pop_front() {
+        for &v in &out[u] {
+            if !reached[v] {
+                reached[v] = true;
+                queue.push_back(v);
+            }
+        }
+    }
+
+    let first_sink = nodes.saturating_sub(params.sinks.max(0) as usize);
+    let sinks: Vec<SinkReachability> = (first_sink..nodes)
+        .map(|v| SinkReachability {
+            node: v as u64 + 1,
+            demand: -result.supply[v],
+            reachable: reached[v],
+        })
+        .collect();
+    let unreachable_demand = sinks
+        .iter()
+        .filter(|s| !s.reachable)
+        .map(|s| s.demand.max(0))
+        .sum();
+
+    ReachabilityReport {
+        sinks,
+        unreachable_demand,
+        weak_components: weak_components(result),
+    }
+}
+
+/// Number of weakly connected components, by union-find over the arcs.
+fn weak_components(result: &NetgenResult) -> usize {
+    fn find(parent: &mut [usize], mut v: usize) -> usize {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+    let nodes = result.supply.len();
+    let mut parent: Vec<usize> = (0..nodes).collect();
+    let mut components = nodes;
+    for arc in &result.arcs {
+        let a = find(&mut parent, arc.from as usize - 1);
+        let b = find(&mut parent, arc.to as usize - 1);
+        if a != b {
+            parent[a] = b;
+            components -= 1;
+        }
+    }
+    components
+}
+
+/// Structural metrics of a network, see [`metrics`].
+///
+/// Displays as a single line of `key=value` fields, e.g.
+/// `sccs=3 largest_scc=3 diameter>=3 core_nodes=4 core_arcs=4 core_density=0.3333`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GraphMetrics {
+    /// Number of strongly connected components.
+    pub scc_count: usize,
+    /// Number of nodes in the largest strongly connected component.
+    pub largest_scc: usize,
+    /// Double-sweep lower bound on the diameter of the underlying undirected
+    /// graph, within the component of node 1.
+    pub diameter_estimate: usize,
+    /// Transshipment nodes: neither designated sources nor sinks.
+    pub core_nodes: usize,
+    /// Arcs with both endpoints in the transshipment core.
+    pub core_arcs: usize,
+    /// `core_arcs / (core_nodes * (core_nodes - 1))`, 0 for fewer than two
+    /// core nodes.
+    pub core_density: f64,
+}
+
+impl fmt::Display for GraphMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sccs={} largest_scc={} diameter>={} core_nodes={} core_arcs={} core_density={:.4}",
+            self.scc_count,
+            self.largest_scc,
+            self.diameter_estimate,
+            self.core_nodes,
+            self.core_arcs,
+            self.core_density
+        )
+    }
+}
+
+/// Compute [`GraphMetrics`] in O(V + E) time.
+///
+/// Strongly connected components come from an iterative Tarjan, so
+/// multi-million-node instances do not overflow the stack. The diameter
+/// estimate is the eccentricity found by two breadth-first sweeps: from
+/// node 1 to a farthest node, then from there. The transshipment core is
+/// the nodes between the first `params.sources` and the last `params.sinks`.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, analysis, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let metrics = analysis::metrics(&result, &params);
+/// assert_eq!(metrics.core_nodes, 492);
+/// println!("{metrics}");
+/// ```
+pub fn metrics(result: &NetgenResult, params: &NetgenParams) -> GraphMetrics {
+    let nodes = result.supply.len();
+    let (scc_count, largest_scc) = strongly_connected(result);
+
+    let first_core = (params.sources.max(0) as usize).min(nodes);
+    let end_core = nodes
+        .saturating_sub(params.sinks.max(0) as usize)
+        .max(first_core);
+    let in_core = |node: u64| (first_core..end_core).contains(&(node as usize - 1));
+    let core_nodes = end_core - first_core;
+    let core_arcs = result
+        .arcs
+        .iter()
+        .filter(|a| in_core(a.from) && in_core(a.to))
+        .count();
+    let core_density = if core_nodes < 2 {
+        0.0
+    } else {
+        core_arcs as f64 / (core_nodes as f64 * (core_nodes - 1) as f64)
+    };
+
+    GraphMetrics {
+        scc_count,
+        largest_scc,
+        diameter_estimate: double_sweep(result),
+        core_nodes,
+        core_arcs,
+        core_density,
+    }
+}
+
+/// Count and largest size of the strongly connected components, by
+/// Tarjan's algorithm with an explicit call stack.
+fn strongly_connected(result: &NetgenResult) -> (usize, usize) {
+    let csr = result.to_csr();
+    let nodes = csr.nodes();
+    let mut index = vec![NONE; nodes];
+    let mut low = vec![0usize; nodes];
+    let mut on_stack = vec![false; nodes];
+    let mut stack = Vec::new();
+    // Frames of (node, position of the next arc to scan in `col_idx`).
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    let (mut next_index, mut count, mut largest) = (0, 0, 0);
+
+    for root in 0..nodes {
+        if index[root] != NONE {
+            continue;
+        }
+        calls.push((root, csr.row_ptr[root]));
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&mut (u, ref mut pos)) = calls.last_mut() {
+            if *pos < csr.row_ptr[u + 1] {
+                let v = csr.col_idx[*pos] as usize;
+                *pos += 1;
+                if index[v] == NONE {
+                    index[v] = next_index;
+                    low[v] = next_index;
+                    next_index += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    calls.push((v, csr.row_ptr[v]));
+                } else if on_stack[v] {
+                    low[u] = low[u].min(index[v]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[u]);
+            }
+            if low[u] == index[u] {
+                let mut size = 0;
+                while let Some(v) = stack.pop() {
+                    on_stack[v] = false;
+                    size += 1;
+                    if v == u {
+                        break;
+                    }
+                }
+                count += 1;
+                largest = largest.max(size);
+            }
+        }
+    }
+    (count, largest)
+}
+
+/// Double-sweep diameter lower bound on the underlying undirected graph.
+fn double_sweep(result: &NetgenResult) -> usize {
+    let nodes = result.supply.len();
+    if nodes == 0 {
+        return 0;
+    }
+    let mut row_ptr = vec![0usize; nodes + 1];
+    for arc in &result.arcs {
+        row_ptr[arc.from as usize] += 1;
+        row_ptr[arc.to as usize] += 1;
+    }
+    for i in 0..nodes {
+        row_ptr[i + 1] += row_ptr[i];
+    }
+    let mut next = row_ptr.clone();
+    let mut neighbors = vec![0usize; 2 * result.arcs.len()];
+    for arc in &result.arcs {
+        let (u, v) = (arc.from as usize - 1, arc.to as usize - 1);
+        neighbors[next[u]] = v;
+        next[u] += 1;
+        neighbors[next[v]] = u;
+        next[v] += 1;
+    }
+
+    // Returns the last node reached and its distance.
+    let sweep = |start: usize| {
+        let mut dist = vec![NONE; nodes];
+        let mut queue = VecDeque::from([start]);
+        dist[start] = 0;
+        let mut last = start;
+        while let Some(u) = queue.pop_front() {
+            last = u;
+            for &v in &neighbors[row_ptr[u]..row_ptr[u + 1]] {
+                if dist[v] == NONE {
+                    dist[v] = dist[u] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        (last, dist[last])
+    };
+    let (far, _) = sweep(0);
+    sweep(far).1
+}
+
+const NONE: usize = usize::MAX;
+
+/// Residual graph for Dinic's algorithm. Edge `e` and `e ^ 1` are
+/// reverses of each other, and edge `2k` is the `k`-th edge added.
+pub(crate) struct Residual {
+    first: Vec<usize>,
+    next: Vec<usize>,
+    to: Vec<usize>,
+    pub(crate) cap: Vec<i64>,
+}
+
+impl Residual {
+    pub(crate) fn new(nodes: usize) -> Self {
+        Residual {
+            first: vec![NONE; nodes],
+            next: Vec::new(),
+            to: Vec::new(),
+            cap: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, from: usize, to: usize, cap: i64) {
+        for (u, v, c) in [(from, to, cap), (to, from, 0)] {
+            self.next.push(self.first[u]);
+            self.first[u] = self.to.len();
+            self.to.push(v);
+            self.cap.push(c);
+        }
+    }
+
+    /// Breadth-first levels from `s`, `NONE` where unreachable.
+    fn levels(&self, s: usize) -> Vec<usize> {
+        let mut level = vec![NONE; self.first.len()];
+        let mut queue = VecDeque::from([s]);
+        level[s] = 0;
+        while let Some(u) = queue.pop_front() {
+            let mut e = self.first[u];
+            while e != NONE {
+                let v = self.to[e];
+                if self.cap[e] > 0 && level[v] == NONE {
+                    level[v] = level[u] + 1;
+                    queue.push_back(v);
+                }
+                e = self.next[e];
+            }
+        }
+        level
+    }
+
+    /// Push a maximum flow from `s` to `t`, returning its value.
+    pub(crate) fn max_flow(&mut self, s: usize, t: usize) -> i128 {
+        let mut total = 0i128;
+        loop {
+            let mut level = self.levels(s);
+            if level[t] == NONE {
+                return total;
+            }
+            let mut current = self.first.clone();
+            let mut path: Vec<usize> = Vec::new();
+            let mut u = s;
+            loop {
+                if u == t {
+                    let push = path.iter().map(|&e| self.cap[e]).min().unwrap();
+                    for &e in &path {
+                        self.cap[e] -= push;
+                        self.cap[e ^ 1] = self.cap[e ^ 1].saturating_add(push);
+                    }
+                    total += push as i128;
+                    path.clear();
+                    u = s;
+                    continue;
+                }
+                let mut e = current[u];
+                while e != NONE && (self.cap[e] == 0 || level[self.to[e]] != level[u] + 1) {
+                    e = self.next[e];
+                }
+                current[u] = e;
+                if e != NONE {
+                    path.push(e);
+                    u = self.to[e];
+                } else if let Some(back) = path.pop() {
+                    // Dead end: retreat and skip the edge that led here.
+                    level[u] = NONE;
+                    u = self.to[back ^ 1];
+                    current[u] = self.next[current[u]];
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::doc_params;
+    use crate::{Arc, ArcKind, NetgenParams, generate};
+
+    fn arc(from: u64, to: u64, capacity: i64) -> Arc {
+        Arc {
+            from,
+            to,
+            cost: 1,
+            capacity,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Unknown,
+            hicost: false,
+        }
+    }
+
+    #[test]
+    fn hand_built_max_flow() {
+        // Two sources (1, 2) and two sinks (5, 6). The cut {3 -> 5, 3 -> 6,
+        // 4 -> 6} has capacity 4 + 2 + 3 = 9; the parallel 1 -> 3 arcs add up.
+        let result = NetgenResult {
+            arcs: vec![
+                arc(1, 3, 5),
+                arc(1, 3, 5),
+                arc(2, 4, 7),
+                arc(2, 3, 1),
+                arc(3, 5, 4),
+                arc(3, 6, 2),
+                arc(4, 6, 3),
+                arc(4, 3, 10),
+            ],
+            supply: vec![1, 1, 0, 0, -1, -1],
+        };
+        assert_eq!(max_flow_value(&result), 9);
+    }
+
+    #[test]
+    fn large_capacities_do_not_overflow() {
+        let result = NetgenResult {
+            arcs: vec![
+                arc(1, 3, i64::MAX),
+                arc(2, 3, i64::MAX),
+                arc(3, 4, i64::MAX),
+            ],
+            supply: vec![1, 1, 0, -2],
+        };
+        assert_eq!(max_flow_value(&result), i64::MAX);
+    }
+
+    #[test]
+    fn sparse_instance_is_disconnected() {
+        let params =
+            NetgenParams::from_slice(&[100, 10, 10, 100, 1, 10, 100, 0, 0, 0, 100, 1, 10]).unwrap();
+        let result = generate(11, &params).unwrap();
+        let report = reachability(&result, &params);
+        assert_eq!(report.weak_components, 2);
+        assert_eq!(report.unreachable_demand, 0);
+        assert!(!report.is_clean());
+
+        // Cutting the arcs into a sink with demand strands that demand.
+        let sink = report.sinks.iter().find(|s| s.demand > 0).unwrap();
+        let mut edited = result.clone();
+        edited.arcs.retain(|a| a.to != sink.node);
+        let report = reachability(&edited, &params);
+        assert_eq!(report.unreachable_demand, sink.demand);
+        assert!(
+            report
+                .sinks
+                .iter()
+                .any(|s| s.node == sink.node && !s.reachable)
+        );
+
+        assert_eq!(
+            crate::generate_connected(11, &params, 0).unwrap_err(),
+            crate::NetgenError::Disconnected { attempts: 1 }
+        );
+        let (seed, connected) = crate::generate_connected(11, &params, 5).unwrap();
+        assert_eq!(seed, 13);
+        assert_eq!(connected, generate(13, &params).unwrap());
+    }
+
+    #[test]
+    fn dense_instance_is_clean() {
+        let params = doc_params();
+        let report = reachability(&generate(13502460, &params).unwrap(), &params);
+        assert!(report.is_clean());
+        assert_eq!(report.sinks.len(), 10);
+        assert_eq!(report.sinks[0].node, 503);
+        let (seed, _) = crate::generate_connected(13502460, &params, 0).unwrap();
+        assert_eq!(seed, 13502460);
+    }
+
+    #[test]
+    fn hand_built_metrics() {
+        // SCCs {1, 2, 3}, {4, 5} and {6}; the undirected diameter is 3
+        // (1 - 3 - 4 - 6 or 2 - 3 - 4 - 6).
+        let result = NetgenResult {
+            arcs: vec![
+                arc(1, 2, 1),
+                arc(2, 3, 1),
+                arc(3, 1, 1),
+                arc(3, 4, 1),
+                arc(4, 5, 1),
+                arc(5, 4, 1),
+                arc(5, 6, 1),
+                arc(4, 6, 1),
+            ],
+            supply: vec![1, 0, 0, 0, 0, -1],
+        };
+        let params = NetgenParams::from_slice(&[6, 1, 1, 8, 1, 1, 1, 0, 0, 0, 0, 1, 1]).unwrap();
+        let metrics = metrics(&result, &params);
+        assert_eq!(metrics.scc_count, 3);
+        assert_eq!(metrics.largest_scc, 3);
+        assert_eq!(metrics.diameter_estimate, 3);
+        assert_eq!((metrics.core_nodes, metrics.core_arcs), (4, 4));
+        assert_eq!(metrics.core_density, 4.0 / 12.0);
+        assert_eq!(
+            metrics.to_string(),
+            "sccs=3 largest_scc=3 diameter>=3 core_nodes=4 core_arcs=4 core_density=0.3333"
+        );
+    }
+
+    #[test]
+    fn metrics_on_large_instance() {
+        // A long skeleton chain would overflow a recursive Tarjan.
+        let params = NetgenParams::from_slice(&[
+            1 << 16,
+            4,
+            4,
+            1 << 17,
+            1,
+            100,
+            10000,
+            0,
+            0,
+            10,
+            50,
+            1,
+            1000,
+        ])
+        .unwrap();
+        let result = generate(4242, &params).unwrap();
+        let metrics = metrics(&result, &params);
+        assert!(metrics.scc_count >= 1);
+        assert!(metrics.largest_scc <= 1 << 16);
+        assert_eq!(metrics.core_nodes, (1 << 16) - 8);
+    }
+
+    #[test]
+    fn generated_max_flow_instances() {
+        // Two seeds, same parameters: the value must cover the skeleton's
+        // routing of the total supply.
+        let params =
+            NetgenParams::from_slice(&[200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]).unwrap();
+        for seed in [99999, 13502460] {
+            let result = generate(seed, &params).unwrap();
+            let value = max_flow_value(&result);
+            assert!(value >= params.supply, "seed {seed}: {value}");
+            let capacity: i64 = result.arcs.iter().map(|a| a.capacity).sum();
+            assert!(value <= capacity, "seed {seed}: {value}");
+        }
+    }
+}
@@ -0,0 +1,665 @@
+//! Compact binary instance format.
+//!
+//! A DIMACS file spends 20–25 bytes of text per arc; this format packs each
+//! arc into a fixed-width bit record, typically 6–7 bytes, and reads back
+//! without parsing. All integers are little-endian.
+//!
+//! The layout is documented on [`write_binary`].
+
+use std::io::{self, BufWriter, Read, Write};
+
+use crate::{Arc, ArcKind, NetgenParams, NetgenResult};
+
+const MAGIC: &[u8; 8] = b"NETGENBN";
+const VERSION: u32 = 3;
+
+/// Write an instance in the compact binary format, which [`read_binary`]
+/// reads back.
+///
+/// The layout, for readers in other languages:
+///
+/// | Offset | Size | Field |
+/// |--------|------|-------|
+/// | 0 | 8 | magic `b"NETGENBN"` |
+/// | 8 | 4 | format version, `u32` (currently 3) |
+/// | 12 | 8 | seed, `i64` |
+/// | 20 | 8 | problem number, `i64` |
+/// | 28 | 104 | the 13 parameters, `i64` each, in `parms[]` order |
+/// | 132 | 8 | node count `N`, `u64` |
+/// | 140 | 8 | arc count `M`, `u64` |
+/// | 148 | 8 | count `K` of nodes with nonzero supply, `u64` |
+/// | 156 | 1 | node width `wn` in bits |
+/// | 157 | 1 | cost width `wc` in bits |
+/// | 158 | 1 | capacity width `wk` in bits |
+/// | 159 | 1 | flag width `wf`, at most 5 |
+/// | 160 | 8 | cost base `c0`, `i64` |
+/// | 168 | 8 | capacity base `k0`, `i64` |
+/// | 176 | 1 | multiplier numerator width `wu` in bits |
+/// | 177 | 1 | multiplier denominator width `wd` in bits |
+/// | 178 | 8 | numerator base `u0`, `i64` |
+/// | 186 | 8 | denominator base `d0`, `i64` |
+/// | 194 | `12·K` | supplies: node id `u32` (1-based), supply `i64`, ascending by node |
+/// | | `⌈M·(2·wn + wc + wk + wf + wu + wd) / 8⌉` | arcs in generation order, bit-packed (see below) |
+/// | | 4 | CRC-32 (IEEE 802.3, as in zlib) of all preceding bytes, `u32` |
+///
+/// Each arc is the unsigned fields `tail − 1` and `head − 1` (`wn` bits
+/// each), `cost − c0` (`wc` bits), `capacity − k0` (`wk` bits), its
+/// flags (`wf` bits), `num − u0` (`wu` bits) and `den − d0` (`wd` bits),
+/// in that order. Flag bit 0 is
+/// [`uncapacitated`](crate::Arc::uncapacitated), bit 1 is
+/// [`hicost`](crate::Arc::hicost), bits 2–3 are the
+/// [`kind`](crate::Arc::kind): 0 unknown, 1 skeleton, 2 random, and bit 4
+/// is set when the arc has a [`multiplier`](crate::Arc::multiplier)
+/// `num / den`; without one, both multiplier fields are 0. The records
+/// form one bit stream, least significant bit first:
+/// bit `i` of the stream is bit `i % 8` of byte `i / 8`, and the last byte
+/// is zero-padded. Each width is the bit length of the largest value of its
+/// field, possibly 0. The bases are the minimum cost, capacity, numerator
+/// and denominator (0 when there are no arcs, or no multipliers). Nodes
+/// missing from the supply list have supply 0.
+///
+/// Older versions are still read. Version 2 files end the header at offset
+/// 176, with no multiplier fields, and have no multipliers. Version 1
+/// files also have no flag bits and a reserved 0 in place of `wf`; their
+/// uncapacitated arcs are inferred as for DIMACS and their provenance is
+/// unknown.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate, read_binary, write_binary};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut bytes = Vec::new();
+/// write_binary(&mut bytes, 13502460, 1, &params, &result).unwrap();
+/// let (seed, problem, read_params, read_result) = read_binary(bytes.as_slice()).unwrap();
+/// assert_eq!((seed, problem), (13502460, 1));
+/// assert_eq!((read_params, read_result), (params, result));
+/// ```
+pub fn write_binary(
+    w: &mut impl Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> io::Result<()> {
+    let mut w = CrcWriter {
+        inner: BufWriter::new(w),
+        crc: Crc32::new(),
+    };
+    let (cost_base, cost_span) = span(result.arcs.iter().map(|a| a.cost));
+    let (cap_base, cap_span) = span(result.arcs.iter().map(|a| a.capacity));
+    let node_width = bits(result.supply.len().saturating_sub(1) as u64);
+    let cost_width = bits(cost_span);
+    let cap_width = bits(cap_span);
+    let flag_width = bits(result.arcs.iter().map(flags).max().unwrap_or(0));
+    let multipliers = || result.arcs.iter().filter_map(|a| a.multiplier);
+    let (num_base, num_span) = span(multipliers().map(|(num, _)| num));
+    let (den_base, den_span) = span(multipliers().map(|(_, den)| den));
+    let num_width = bits(num_span);
+    let den_width = bits(den_span);
+    let supplies: Vec<(usize, i64)> = result
+        .supply
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, s)| s != 0)
+        .collect();
+
+    w.write_all(MAGIC)?;
+    w.write_all(&VERSION.to_le_bytes())?;
+    w.write_all(&seed.to_le_bytes())?;
+    w.write_all(&problem.to_le_bytes())?;
+    for p in params.to_array() {
+        w.write_all(&p.to_le_bytes())?;
+    }
+    for count in [result.supply.len(), result.arcs.len(), supplies.len()] {
+        w.write_all(&(count as u64).to_le_bytes())?;
+    }
+    w.write_all(&[node_width, cost_width, cap_width, flag_width])?;
+    w.write_all(&cost_base.to_le_bytes())?;
+    w.write_all(&cap_base.to_le_bytes())?;
+    w.write_all(&[num_width, den_width])?;
+    w.write_all(&num_base.to_le_bytes())?;
+    w.write_all(&den_base.to_le_bytes())?;
+    for (i, supply) in supplies {
+        w.write_all(&(i as u32 + 1).to_le_bytes())?;
+        w.write_all(&supply.to_le_bytes())?;
+    }
+    let mut bits = BitWriter::new(&mut w);
+    for arc in &result.arcs {
+        bits.put(arc.from - 1, node_width)?;
+        bits.put(arc.to - 1, node_width)?;
+        bits.put(arc.cost.wrapping_sub(cost_base) as u64, cost_width)?;
+        bits.put(arc.capacity.wrapping_sub(cap_base) as u64, cap_width)?;
+        bits.put(flags(arc), flag_width)?;
+        let (num, den) = arc.multiplier.unwrap_or((num_base, den_base));
+        bits.put(num.wrapping_sub(num_base) as u64, num_width)?;
+        bits.put(den.wrapping_sub(den_base) as u64, den_width)?;
+    }
+    bits.finish()?;
+    let crc = w.crc.finish();
+    w.inner.write_all(&crc.to_le_bytes())?;
+    w.inner.flush()
+}
+
+/// Read an instance written by [`write_binary`], returning the seed, the
+/// problem number, the parameters and the network.
+///
+/// The whole input is read into memory first, so the node and arc counts
+/// in the header can be checked against the bytes that follow before
+/// anything is allocated for them.
+///
+/// Fails with [`io::ErrorKind::UnexpectedEof`] on a truncated file and
+/// [`io::ErrorKind::InvalidData`] on a bad magic number, an unknown
+/// version, invalid parameters or contents, counts the rest of the file
+/// cannot hold, or a checksum mismatch.
+pub fn read_binary(mut r: impl Read) -> io::Result<(i64, i64, NetgenParams, NetgenResult)> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    let mut r = CrcReader {
+        inner: bytes.as_slice(),
+        crc: Crc32::new(),
+    };
+    let mut magic = [0u8; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a NETGEN binary file"));
+    }
+    let version = r.u32()?;
+    if !(1..=VERSION).contains(&version) {
+        return Err(invalid(format!("unsupported format version {version}")));
+    }
+    let seed = r.i64()?;
+    let problem = r.i64()?;
+    let mut parms = [0i64; 13];
+    for p in &mut parms {
+        *p = r.i64()?;
+    }
+    let params = NetgenParams::from_slice(&parms).map_err(invalid)?;
+    let nodes = r.u64()?;
+    let arcs = r.u64()?;
+    let nonzero = r.u64()?;
+    let mut widths = [0u8; 4];
+    r.read_exact(&mut widths)?;
+    let [node_width, cost_width, cap_width, flag_width] = widths;
+    for w in [node_width, cost_width, cap_width] {
+        if w > 64 {
+            return Err(invalid(format!("bad field width {w}")));
+        }
+    }
+    // Node ids are at most `u32::MAX` (see the node count check below), so
+    // a wider field could only hold ids that overflow `tail − 1 + 1`.
+    if node_width > 32 {
+        return Err(invalid(format!("bad node width {node_width}")));
+    }
+    let flag_width = if version == 1 { 0 } else { flag_width };
+    if flag_width > if version == 2 { 4 } else { 5 } {
+        return Err(invalid(format!("bad flag width {flag_width}")));
+    }
+    let cost_base = r.i64()?;
+    let cap_base = r.i64()?;
+    let (mut num_width, mut den_width, mut num_base, mut den_base) = (0, 0, 0, 0);
+    if version >= 3 {
+        let mut widths = [0u8; 2];
+        r.read_exact(&mut widths)?;
+        [num_width, den_width] = widths;
+        for w in widths {
+            if w > 64 {
+                return Err(invalid(format!("bad field width {w}")));
+            }
+        }
+        num_base = r.i64()?;
+        den_base = r.i64()?;
+    }
+
+    // The counts are untrusted until the checksum at the end is read, so
+    // bound them by what the remaining bytes can encode before allocating.
+    // Supply entries name their node in a u32, which bounds the node count;
+    // an all-zero-width record takes no space, so such arcs fall back to a
+    // fallible reservation.
+    let left = r.inner.len() as u64;
+    if nodes > u64::from(u32::MAX) {
+        return Err(invalid(format!("node count {nodes} out of range")));
+    }
+    if nonzero > nodes || nonzero.saturating_mul(12) > left {
+        return Err(invalid(format!("{nonzero} supplies do not fit the file")));
+    }
+    let record = [
+        node_width, node_width, cost_width, cap_width, flag_width, num_width, den_width,
+    ]
+    .into_iter()
+    .map(u64::from)
+    .sum::<u64>();
+    if arcs.saturating_mul(record) > (left - 12 * nonzero).saturating_mul(8) {
+        return Err(invalid(format!("{arcs} arcs do not fit the file")));
+    }
+    const PREALLOC: u64 = 1 << 20;
+    let mut supply = Vec::new();
+    supply
+        .try_reserve_exact(nodes as usize)
+        .map_err(|_| invalid(format!("cannot allocate {nodes} nodes")))?;
+    supply.resize(nodes as usize, 0);
+    for _ in 0..nonzero {
+        let node = r.u32()? as u64;
+        let value = r.i64()?;
+        if node == 0 || node > nodes {
+            return Err(invalid(format!("supply for node {node} out of range")));
+        }
+        supply[node as usize - 1] = value;
+    }
+
+    let mut arc_list = Vec::new();
+    let reserve = if record == 0 {
+        arcs
+    } else {
+        arcs.min(PREALLOC)
+    };
+    arc_list
+        .try_reserve_exact(reserve as usize)
+        .map_err(|_| invalid(format!("cannot allocate {arcs} arcs")))?;
+    let mut bits = BitReader::new(&mut r);
+    for _ in 0..arcs {
+        let from = bits.get(node_width)? + 1;
+        let to = bits.get(node_width)? + 1;
+        let cost = cost_base.wrapping_add(bits.get(cost_width)? as i64);
+        let capacity = cap_base.wrapping_add(bits.get(cap_width)? as i64);
+        let flags = bits.get(flag_width)?;
+        let num = num_base.wrapping_add(bits.get(num_width)? as i64);
+        let den = den_base.wrapping_add(bits.get(den_width)? as i64);
+        if from > nodes || to > nodes {
+            return Err(invalid(format!("arc {from} -> {to} out of range")));
+        }
+        let kind = match flags >> 2 & 3 {
+            0 => ArcKind::Unknown,
+            1 => ArcKind::Skeleton,
+            2 => ArcKind::Random,
+            k => return Err(invalid(format!("bad arc kind {k}"))),
+        };
+        arc_list.push(Arc {
+            from,
+            to,
+            cost,
+            capacity,
+            multiplier: (flags & 16 != 0).then_some((num, den)),
+            uncapacitated: flags & 1 != 0,
+            kind,
+            hicost: flags & 2 != 0,
+        });
+    }
+
+    let expected = r.crc.finish();
+    let mut trailer = [0u8; 4];
+    r.inner.read_exact(&mut trailer)?;
+    if u32::from_le_bytes(trailer) != expected {
+        return Err(invalid("checksum mismatch"));
+    }
+    let mut result = NetgenResult {
+        arcs: arc_list,
+        supply,
+    };
+    if version == 1 {
+        result.infer_uncapacitated();
+    }
+    Ok((seed, problem, params, result))
+}
+
+fn invalid(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// The flag bits of `arc`, as laid out on [`write_binary`].
+fn flags(arc: &Arc) -> u64 {
+    let kind = match arc.kind {
+        ArcKind::Unknown => 0,
+        ArcKind::Skeleton => 1,
+        ArcKind::Random => 2,
+    };
+    u64::from(arc.uncapacitated)
+        | u64::from(arc.hicost) << 1
+        | kind << 2
+        | u64::from(arc.multiplier.is_some()) << 4
+}
+
+/// Minimum of `values` and the largest offset from it (both 0 if empty).
+fn span(values: impl Iterator<Item = i64> + Clone) -> (i64, u64) {
+    let Some(min) = values.clone().min() else {
+        return (0, 0);
+    };
+    let max = values.max().unwrap();
+    (min, max.wrapping_sub(min) as u64)
+}
+
+/// Number of bits needed to represent `max`.
+fn bits(max: u64) -> u8 {
+    (u64::BITS - max.leading_zeros()) as u8
+}
+
+/// Packs values into a little-endian bit stream.
+struct BitWriter<'w, W: Write> {
+    w: &'w mut W,
+    buf: u128,
+    len: u32,
+}
+
+impl<'w, W: Write> BitWriter<'w, W> {
+    fn new(w: &'w mut W) -> Self {
+        BitWriter { w, buf: 0, len: 0 }
+    }
+
+    /// Append the low `width` bits of `value`.
+    fn put(&mut self, value: u64, width: u8) -> io::Result<()> {
+        if width == 0 {
+            return Ok(());
+        }
+        let mask = u64::MAX >> (64 - width);
+        self.buf |= ((value & mask) as u128) << self.len;
+        self.len += width as u32;
+        while self.len >= 8 {
+            self.w.write_all(&[self.buf as u8])?;
+            self.buf >>= 8;
+            self.len -= 8;
+        }
+        Ok(())
+    }
+
+    /// Write the partial last byte, zero-padded.
+    fn finish(self) -> io::Result<()> {
+        if self.len > 0 {
+            self.w.write_all(&[self.buf as u8])?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads values written by [`BitWriter`]; trailing padding is discarded.
+struct BitReader<'r, R: Read> {
+    r: &'r mut R,
+    buf: u128,
+    len: u32,
+}
+
+impl<'r, R: Read> BitReader<'r, R> {
+    fn new(r: &'r mut R) -> Self {
+        BitReader { r, buf: 0, len: 0 }
+    }
+
+    fn get(&mut self, width: u8) -> io::Result<u64> {
+        if width == 0 {
+            return Ok(0);
+        }
+        while self.len < width as u32 {
+            let mut byte = [0u8];
+            self.r.read_exact(&mut byte)?;
+            self.buf |= (byte[0] as u128) << self.len;
+            self.len += 8;
+        }
+        let value = self.buf as u64 & (u64::MAX >> (64 - width));
+        self.buf >>= width;
+        self.len -= width as u32;
+        Ok(value)
+    }
+}
+
+struct CrcWriter<W: Write> {
+    inner: W,
+    crc: Crc32,
+}
+
+impl<W: Write> Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct CrcReader<R: Read> {
+    inner: R,
+    crc: Crc32,
+}
+
+impl<R: Read> Read for CrcReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: Read> CrcReader<R> {
+    fn u32(&mut self) -> io::Result<u32> {
+        let mut bytes = [0u8; 4];
+        self.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0u8; 8];
+        self.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn i64(&mut self) -> io::Result<i64> {
+        Ok(self.u64()? as i64)
+    }
+}
+
+/// CRC-32 with the IEEE polynomial (reflected `0xEDB88320`), as in zlib.
+struct Crc32(u32);
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+impl Crc32 {
+    fn new() -> Self {
+        Crc32(!0)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{DOC_PARMS, doc_params};
+    use crate::{generate, write_dimacs};
+
+    fn instance(parms: &[i64]) -> (NetgenParams, NetgenResult, Vec<u8>) {
+        let params = NetgenParams::from_slice(parms).unwrap();
+        let result = generate(13502460, &params).unwrap();
+        let mut bytes = Vec::new();
+        write_binary(&mut bytes, 13502460, 7, &params, &result).unwrap();
+        (params, result, bytes)
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn bit_packing() {
+        let mut bytes = Vec::new();
+        let mut w = BitWriter::new(&mut bytes);
+        for (value, width) in [(5, 3), (0, 0), (0x1ff, 9), (u64::MAX, 64), (1, 1)] {
+            w.put(value, width).unwrap();
+        }
+        w.finish().unwrap();
+        assert_eq!(bytes.len(), 10);
+        let mut slice = bytes.as_slice();
+        let mut r = BitReader::new(&mut slice);
+        for (value, width) in [(5, 3), (0, 0), (0x1ff, 9), (u64::MAX, 64), (1, 1)] {
+            assert_eq!(r.get(width).unwrap(), value);
+        }
+        assert_eq!(bits(0), 0);
+        assert_eq!(bits(4095), 12);
+    }
+
+    #[test]
+    fn round_trip() {
+        for parms in [
+            &DOC_PARMS[..],
+            &[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+            &[200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+        ] {
+            let (params, result, bytes) = instance(parms);
+            let (seed, problem, p, r) = read_binary(bytes.as_slice()).unwrap();
+            assert_eq!((seed, problem), (13502460, 7));
+            assert_eq!(p, params);
+            assert_eq!(r, result);
+        }
+    }
+
+    /// Rewrite a version 3 file without multipliers as `version`: drop the
+    /// multiplier header fields (all zero) and fix up the checksum.
+    fn downgrade(mut bytes: Vec<u8>, version: u32) -> Vec<u8> {
+        assert!(bytes[176..194].iter().all(|&b| b == 0));
+        bytes.drain(176..194);
+        bytes[8..12].copy_from_slice(&version.to_le_bytes());
+        let body = bytes.len() - 4;
+        let mut crc = Crc32::new();
+        crc.update(&bytes[..body]);
+        bytes[body..].copy_from_slice(&crc.finish().to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn reads_version_1() {
+        // Fully capacitated below the total supply, so nothing is inferred
+        // uncapacitated. Without provenance the flag width is 0 as in
+        // version 1.
+        let params =
+            NetgenParams::from_slice(&[512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 100, 50, 900])
+                .unwrap();
+        let mut result = generate(13502460, &params).unwrap();
+        assert_eq!(result.uncapacitated_arc_count(), 0);
+        result.clear_provenance();
+        let mut bytes = Vec::new();
+        write_binary(&mut bytes, 13502460, 7, &params, &result).unwrap();
+        assert_eq!(bytes[159], 0);
+        let bytes = downgrade(bytes, 1);
+        let (_, _, p, r) = read_binary(bytes.as_slice()).unwrap();
+        assert_eq!((p, r), (params, result));
+    }
+
+    #[test]
+    fn reads_version_2() {
+        let (params, result, bytes) = instance(&DOC_PARMS);
+        let bytes = downgrade(bytes, 2);
+        let (_, _, p, r) = read_binary(bytes.as_slice()).unwrap();
+        assert_eq!((p, r), (params, result));
+    }
+
+    #[test]
+    fn round_trips_multipliers() {
+        let params = doc_params();
+        let options = crate::GenerateOptions {
+            multipliers: Some(crate::MultiplierRange {
+                num_min: 1,
+                num_max: 999,
+                den: 7,
+            }),
+            ..Default::default()
+        };
+        let (result, _) = crate::generate_with_options(13502460, &params, &options).unwrap();
+        assert!(result.arcs.iter().any(|a| a.multiplier.is_some()));
+        let mut bytes = Vec::new();
+        write_binary(&mut bytes, 13502460, 7, &params, &result).unwrap();
+        let (_, _, _, r) = read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(r, result);
+    }
+
+    #[test]
+    fn detects_corruption_and_truncation() {
+        let (_, _, bytes) = instance(&DOC_PARMS);
+        let mut corrupt = bytes.clone();
+        corrupt[bytes.len() / 2] ^= 0x10;
+        let err = read_binary(corrupt.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = read_binary(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        let err = read_binary(magic.as_slice()).unwrap_err();
+        assert_eq!(err.to_string(), "not a NETGEN binary file");
+    }
+
+    #[test]
+    fn rejects_counts_the_file_cannot_hold() {
+        let (_, _, bytes) = instance(&DOC_PARMS);
+        for (offset, count) in [(132, 1u64 << 52), (140, 1 << 40), (148, 1 << 30)] {
+            let mut crafted = bytes.clone();
+            crafted[offset..offset + 8].copy_from_slice(&count.to_le_bytes());
+            let err = read_binary(crafted.as_slice()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{err}");
+        }
+    }
+
+    #[test]
+    fn rejects_node_widths_beyond_u32() {
+        // One arc whose tail and head are all ones in 64 bits.
+        let (_, _, bytes) = instance(&DOC_PARMS);
+        let mut crafted = bytes[..194].to_vec();
+        for (offset, count) in [(132, 1u64), (140, 1), (148, 0)] {
+            crafted[offset..offset + 8].copy_from_slice(&count.to_le_bytes());
+        }
+        crafted[156..160].copy_from_slice(&[64, 0, 0, 0]);
+        crafted[176..178].copy_from_slice(&[0, 0]);
+        crafted.extend([0xff; 16]);
+        crafted.extend([0; 4]);
+        let err = read_binary(crafted.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "bad node width 64");
+    }
+
+    #[test]
+    fn at_least_three_times_smaller_than_dimacs() {
+        // netgen_8.sh, exponent 12.
+        let (params, result, bytes) = instance(&[
+            4096, 64, 64, 32768, 1, 10000, 64000, 0, 0, 100, 100, 1, 1000,
+        ]);
+        let mut dimacs = Vec::new();
+        write_dimacs(&mut dimacs, 13502460, 7, &params, &result).unwrap();
+        assert!(
+            3 * bytes.len() <= dimacs.len(),
+            "binary {} bytes, DIMACS {} bytes",
+            bytes.len(),
+            dimacs.len()
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Assignment instances as weighted bipartite graphs, for matching codes.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::{ArcId, NetgenParams, NetgenResult, NodeId, ProblemType};
+
+/// A weighted bipartite graph derived from an assignment instance by
+/// [`NetgenResult::to_bipartite`].
+///
+/// Each side numbers its nodes from 0 in the order of their original ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BipartiteInstance {
+    /// The original node of each left id: the sources, with supply 1.
+    pub left: Vec<NodeId>,
+    /// The original node of each right id: the sinks, with supply -1.
+    pub right: Vec<NodeId>,
+    /// `(left, right, weight)` per arc of the network, in order; the weight
+    /// is the arc's cost.
+    pub edges: Vec<(u32, u32, i64)>,
+}
+
+/// Why [`NetgenResult::to_bipartite`] could not convert a network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The parameters describe another type of problem.
+    NotAssignment(ProblemType),
+    /// A node's supply is neither 1 nor -1.
+    Supply { node: NodeId, supply: i64 },
+    /// An arc does not run from a source to a sink.
+    NotBipartite { arc: ArcId },
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::NotAssignment(problem_type) => {
+                write!(f, "{problem_type} instances are not bipartite matchings")
+            }
+            ConversionError::Supply { node, supply } => write!(
+                f,
+                "node {} has supply {supply}, not 1 or -1",
+                node.to_dimacs()
+            ),
+            ConversionError::NotBipartite { arc } => {
+                write!(
+                    f,
+                    "arc {} does not run from a source to a sink",
+                    arc.index()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl NetgenResult {
+    /// The bipartite graph of an assignment instance generated from
+    /// `params`: the sources on the left, the sinks on the right and an
+    /// edge per arc, weighted by its cost.
+    ///
+    /// Fails unless `params` describe an assignment problem and the network
+    /// is one: every supply 1 or -1 and every arc from a source to a sink,
+    /// as generated. An arc with an endpoint outside the network is
+    /// [`ConversionError::NotBipartite`] too.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100,
+    /// ]).unwrap();
+    /// let instance = generate(12345, &params).unwrap().to_bipartite(&params).unwrap();
+    /// assert_eq!((instance.left.len(), instance.right.len()), (50, 50));
+    /// let mut out = Vec::new();
+    /// netgen_rs::write_bipartite_edges(&mut out, &instance).unwrap();
+    /// assert!(String::from_utf8(out).unwrap().starts_with("50 50\n"));
+    /// ```
+    pub fn to_bipartite(
+        &self,
+        params: &NetgenParams,
+    ) -> Result<BipartiteInstance, ConversionError> {
+        let problem_type = params.problem_type();
+        if problem_type != ProblemType::Assignment {
+            return Err(ConversionError::NotAssignment(problem_type));
+        }
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        // Side and id within it of each node.
+        let mut side = Vec::with_capacity(self.supply.len());
+        for (i, &supply) in self.supply.iter().enumerate() {
+            let node = NodeId(i as u32);
+            let list = match supply {
+                1 => &mut left,
+                -1 => &mut right,
+                _ => return Err(ConversionError::Supply { node, supply }),
+            };
+            side.push((supply, list.len() as u32));
+            list.push(node);
+        }
+        // An endpoint outside 1..=nodes is on neither side.
+        let side_of = |id: u64| {
+            let index = usize::try_from(id.checked_sub(1)?).ok()?;
+            side.get(index).copied()
+        };
+        let edges = (self.arcs.iter().enumerate())
+            .map(|(i, arc)| match (side_of(arc.from), side_of(arc.to)) {
+                (Some((1, u)), Some((-1, v))) => Ok((u, v, arc.cost)),
+                _ => Err(ConversionError::NotBipartite {
+                    arc: ArcId(i as u32),
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(BipartiteInstance { left, right, edges })
+    }
+}
+
+/// Write `instance` as an edge list: a `<left> <right>` line with the side
+/// sizes, then a `<left> <right> <weight>` line per edge, with 0-based ids.
+pub fn write_bipartite_edges(w: &mut impl Write, instance: &BipartiteInstance) -> io::Result<()> {
+    writeln!(w, "{} {}", instance.left.len(), instance.right.len())?;
+    for (u, v, weight) in &instance.edges {
+        writeln!(w, "{u} {v} {weight}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::doc_params;
+    use crate::generate;
+
+    #[test]
+    fn only_assignment_networks_convert() {
+        let params = doc_params();
+        let result = generate(13502460, &params).unwrap();
+        assert_eq!(
+            result.to_bipartite(&params),
+            Err(ConversionError::NotAssignment(ProblemType::MinCostFlow))
+        );
+
+        let params =
+            NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap();
+        let mut result = generate(12345, &params).unwrap();
+        result.arcs[3].to = 1;
+        assert_eq!(
+            result.to_bipartite(&params),
+            Err(ConversionError::NotBipartite { arc: ArcId(3) })
+        );
+        for to in [0, 101, u64::MAX] {
+            result.arcs[3].to = to;
+            assert_eq!(
+                result.to_bipartite(&params),
+                Err(ConversionError::NotBipartite { arc: ArcId(3) })
+            );
+        }
+        result.supply[60] = 0;
+        assert_eq!(
+            result.to_bipartite(&params),
+            Err(ConversionError::Supply {
+                node: NodeId(60),
+                supply: 0
+            })
+        );
+    }
+}
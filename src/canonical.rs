@@ -0,0 +1,309 @@
+//! Canonical forms for comparing networks independently of arc order.
+
+use std::fmt;
+
+use crate::{Arc, NetgenResult};
+
+/// A network with its arcs sorted by `(from, to, cost, capacity,
+/// multiplier)`, a byte
+/// encoding of it, and a hash of the encoding (see
+/// [`NetgenResult::canonicalize`]).
+///
+/// Two networks have equal canonical forms exactly when they have the same
+/// supplies and the same multiset of arcs, each arc taken as its key
+/// `(from, to, cost, capacity, multiplier)`. Provenance ([`Arc::kind`],
+/// [`Arc::hicost`]) and [`Arc::uncapacitated`] are not part of the key, so
+/// a generated network and the same network read back from DIMACS compare
+/// equal.
+#[derive(Debug, Clone)]
+pub struct CanonicalForm {
+    supply: Vec<i64>,
+    arcs: Vec<Arc>,
+    bytes: Vec<u8>,
+    hash: u64,
+}
+
+impl CanonicalForm {
+    /// Node supplies, by node.
+    pub fn supply(&self) -> &[i64] {
+        &self.supply
+    }
+
+    /// The arcs in canonical order. Arcs with equal keys keep no
+    /// particular order among themselves.
+    pub fn arcs(&self) -> &[Arc] {
+        &self.arcs
+    }
+
+    /// The canonical encoding, little-endian: the node count (`u64`), one
+    /// `i64` per supply, the arc count (`u64`), then `from`, `to` (`u64`),
+    /// `cost`, `capacity` and the multiplier's numerator and denominator
+    /// (`i64`, 1 and 1 for an arc without one) per arc.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// 64-bit FNV-1a hash of [`bytes`](Self::bytes).
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The first difference between two canonical forms, or `None` if they
+    /// are equal. Supplies are compared before arcs.
+    pub fn first_difference(&self, other: &CanonicalForm) -> Option<CanonicalDifference> {
+        if self.supply.len() != other.supply.len() {
+            return Some(CanonicalDifference::Nodes {
+                left: self.supply.len(),
+                right: other.supply.len(),
+            });
+        }
+        if let Some(i) = (0..self.supply.len()).find(|&i| self.supply[i] != other.supply[i]) {
+            return Some(CanonicalDifference::Supply {
+                node: i as u64 + 1,
+                left: self.supply[i],
+                right: other.supply[i],
+            });
+        }
+        let len = self.arcs.len().max(other.arcs.len());
+        (0..len)
+            .find(|&k| self.arcs.get(k).map(key) != other.arcs.get(k).map(key))
+            .map(|k| CanonicalDifference::Arc {
+                index: k,
+                left: self.arcs.get(k).cloned(),
+                right: other.arcs.get(k).cloned(),
+            })
+    }
+}
+
+impl PartialEq for CanonicalForm {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.bytes == other.bytes
+    }
+}
+
+impl Eq for CanonicalForm {}
+
+/// The part of an arc that canonical forms compare. A missing multiplier
+/// is 1, as in the generalized DIMACS dialect.
+fn key(a: &Arc) -> (u64, u64, i64, i64, (i64, i64)) {
+    let multiplier = a.multiplier.unwrap_or((1, 1));
+    (a.from, a.to, a.cost, a.capacity, multiplier)
+}
+
+/// Where two canonical forms first differ (see
+/// [`CanonicalForm::first_difference`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanonicalDifference {
+    /// Different node counts.
+    Nodes { left: usize, right: usize },
+    /// Different supply at a 1-based node.
+    Supply { node: u64, left: i64, right: i64 },
+    /// Different arc at a 0-based position in canonical order; `None` past
+    /// the end of the shorter arc list.
+    Arc {
+        index: usize,
+        left: Option<Arc>,
+        right: Option<Arc>,
+    },
+}
+
+impl fmt::Display for CanonicalDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arc = |arc: &Option<Arc>| match arc {
+            Some(a) => {
+                let mut s = format!("{} -> {} cost {} cap {}", a.from, a.to, a.cost, a.capacity);
+                if let Some((num, den)) = a.multiplier {
+                    s += &format!(" mult {num}/{den}");
+                }
+                s
+            }
+            None => "none".to_string(),
+        };
+        match self {
+            CanonicalDifference::Nodes { left, right } => {
+                write!(f, "{left} nodes vs {right} nodes")
+            }
+            CanonicalDifference::Supply { node, left, right } => {
+                write!(f, "node {node} supply {left} vs {right}")
+            }
+            CanonicalDifference::Arc { index, left, right } => {
+                write!(f, "arc {index}: {} vs {}", arc(left), arc(right))
+            }
+        }
+    }
+}
+
+impl NetgenResult {
+    /// The canonical form of this network, for comparisons that ignore the
+    /// order of the arcs.
+    ///
+    /// Node numbering is part of the form: to compare relabeled networks,
+    /// undo the relabeling first.
+    pub fn canonicalize(&self) -> CanonicalForm {
+        let mut arcs = self.arcs.clone();
+        arcs.sort_unstable_by_key(key);
+
+        let mut bytes = Vec::with_capacity(16 + 8 * self.supply.len() + 48 * arcs.len());
+        bytes.extend_from_slice(&(self.supply.len() as u64).to_le_bytes());
+        for s in &self.supply {
+            bytes.extend_from_slice(&s.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(arcs.len() as u64).to_le_bytes());
+        for a in &arcs {
+            bytes.extend_from_slice(&a.from.to_le_bytes());
+            bytes.extend_from_slice(&a.to.to_le_bytes());
+            bytes.extend_from_slice(&a.cost.to_le_bytes());
+            bytes.extend_from_slice(&a.capacity.to_le_bytes());
+            let (num, den) = a.multiplier.unwrap_or((1, 1));
+            bytes.extend_from_slice(&num.to_le_bytes());
+            bytes.extend_from_slice(&den.to_le_bytes());
+        }
+        let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+            (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        CanonicalForm {
+            supply: self.supply.clone(),
+            arcs,
+            bytes,
+            hash,
+        }
+    }
+
+    /// Whether two networks have the same supplies and the same multiset of
+    /// arcs, in any order.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let mut shuffled = result.clone();
+    /// shuffled.arcs.reverse();
+    /// assert!(result.structurally_equal(&shuffled));
+    /// ```
+    pub fn structurally_equal(&self, other: &NetgenResult) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArcKind;
+    use crate::fixture::{doc_params, doc_result};
+
+    #[test]
+    fn relabeled_instance_matches_after_inverse_permutation() {
+        let result = doc_result();
+        let n = result.supply.len() as u64;
+        // Relabel node v as n + 1 - v, shuffle the arcs, then undo both.
+        let relabel = |v: u64| n + 1 - v;
+        let mut relabeled = result.clone();
+        relabeled.supply.reverse();
+        for a in &mut relabeled.arcs {
+            (a.from, a.to) = (relabel(a.from), relabel(a.to));
+        }
+        relabeled.arcs.rotate_left(17);
+        assert!(!result.structurally_equal(&relabeled));
+
+        let inverse = relabel;
+        let mut restored = relabeled;
+        restored.supply.reverse();
+        for a in &mut restored.arcs {
+            (a.from, a.to) = (inverse(a.from), inverse(a.to));
+        }
+        assert_ne!(restored, result);
+        assert!(result.structurally_equal(&restored));
+        assert_eq!(result.canonicalize(), restored.canonicalize());
+    }
+
+    #[test]
+    fn changed_cost_is_detected() {
+        let result = doc_result();
+        let mut changed = result.clone();
+        changed.arcs[100].cost += 1;
+        assert!(!result.structurally_equal(&changed));
+
+        let (a, b) = (result.canonicalize(), changed.canonicalize());
+        assert_ne!(a.hash(), b.hash());
+        match a.first_difference(&b) {
+            Some(CanonicalDifference::Arc {
+                left: Some(l),
+                right: Some(r),
+                ..
+            }) => {
+                assert_eq!((l.from, l.to), (r.from, r.to));
+                assert_eq!(l.cost + 1, r.cost);
+            }
+            other => panic!("unexpected difference {other:?}"),
+        }
+    }
+
+    #[test]
+    fn provenance_is_not_compared() {
+        let result = doc_result();
+        let parsed =
+            crate::parse_dimacs(&result.to_dimacs_string(13502460, 1, &doc_params())).unwrap();
+        assert_ne!(parsed, result);
+        assert_eq!(parsed.canonicalize(), result.canonicalize());
+        assert_eq!(
+            parsed
+                .canonicalize()
+                .first_difference(&result.canonicalize()),
+            None
+        );
+    }
+
+    #[test]
+    fn multipliers_are_compared() {
+        let result = doc_result();
+        let mut generalized = result.clone();
+        generalized.arcs[100].multiplier = Some((3, 2));
+        assert!(!result.structurally_equal(&generalized));
+        assert_ne!(
+            result.canonicalize().hash(),
+            generalized.canonicalize().hash()
+        );
+        let mut one = result.clone();
+        one.arcs[100].multiplier = Some((1, 1));
+        assert!(result.structurally_equal(&one));
+    }
+
+    #[test]
+    fn parallel_arcs_compare_as_multisets() {
+        let arc = |cost| Arc {
+            from: 1,
+            to: 2,
+            cost,
+            capacity: 5,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Unknown,
+            hicost: false,
+        };
+        let a = NetgenResult {
+            arcs: vec![arc(3), arc(3), arc(4)],
+            supply: vec![1, -1],
+        };
+        let b = NetgenResult {
+            arcs: vec![arc(4), arc(3), arc(3)],
+            supply: vec![1, -1],
+        };
+        let c = NetgenResult {
+            arcs: vec![arc(4), arc(4), arc(3)],
+            supply: vec![1, -1],
+        };
+        assert!(a.structurally_equal(&b));
+        assert!(!a.structurally_equal(&c));
+        assert_eq!(
+            a.canonicalize().first_difference(&c.canonicalize()),
+            Some(CanonicalDifference::Arc {
+                index: 1,
+                left: Some(arc(3)),
+                right: Some(arc(4)),
+            })
+        );
+    }
+}
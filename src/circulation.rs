@@ -0,0 +1,241 @@
+//! Min-cost circulation instances: zero supplies, feasibility forced by arc
+//! lower bounds.
+
+use std::io::{self, Write};
+
+use crate::analysis::Residual;
+use crate::{Arc, ArcKind, FlowError, NetgenParams, NetgenResult, write_dimacs_header};
+
+/// A circulation derived from a network by [`NetgenResult::to_circulation`].
+///
+/// Every node has zero supply. The arcs are the original arcs (lower bound
+/// 0) followed by the return arcs, which carry the supplies back from the
+/// sinks to the sources with `lower == capacity`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circulation {
+    /// The network, with all supplies zero.
+    pub result: NetgenResult,
+    /// Lower bound of each arc in `result.arcs`.
+    pub lower: Vec<i64>,
+}
+
+impl NetgenResult {
+    /// Convert a min-cost flow network into a circulation.
+    ///
+    /// The supplies are matched in node order, the first source's supply
+    /// going to the first sinks' demands and so on, and each matched
+    /// amount becomes a cost-0 return arc from the sink to the source with
+    /// lower bound and capacity equal to the amount. Any such matching
+    /// gives a feasible circulation whenever the network can route its
+    /// supplies, since flow conservation only sees each node's total.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let circulation = generate(13502460, &params).unwrap().to_circulation();
+    /// assert!(circulation.result.supply.iter().all(|&s| s == 0));
+    /// assert!(circulation.feasible_flow().is_some());
+    /// ```
+    pub fn to_circulation(&self) -> Circulation {
+        let mut arcs = self.arcs.clone();
+        let mut lower = vec![0; arcs.len()];
+        let mut sources = self.supply.iter().enumerate().filter(|(_, s)| **s > 0);
+        let mut sinks = self.supply.iter().enumerate().filter(|(_, s)| **s < 0);
+        let (mut source, mut sink) = (sources.next(), sinks.next());
+        let (mut left, mut need) = (source.map_or(0, |(_, &s)| s), sink.map_or(0, |(_, &s)| -s));
+        while let (Some((i, _)), Some((j, _))) = (source, sink) {
+            let amount = left.min(need);
+            arcs.push(Arc {
+                from: j as u64 + 1,
+                to: i as u64 + 1,
+                cost: 0,
+                capacity: amount,
+                multiplier: None,
+                uncapacitated: false,
+                kind: ArcKind::Unknown,
+                hicost: false,
+            });
+            lower.push(amount);
+            (left, need) = (left - amount, need - amount);
+            if left == 0 {
+                source = sources.next();
+                left = source.map_or(0, |(_, &s)| s);
+            }
+            if need == 0 {
+                sink = sinks.next();
+                need = sink.map_or(0, |(_, &s)| -s);
+            }
+        }
+        Circulation {
+            result: NetgenResult {
+                arcs,
+                supply: vec![0; self.supply.len()],
+            },
+            lower,
+        }
+    }
+}
+
+impl Circulation {
+    /// A feasible circulation, one flow per arc, or `None` if the lower
+    /// bounds cannot be met.
+    ///
+    /// Uses the standard reduction: each lower bound is shipped up front,
+    /// leaving node imbalances that a maximum flow over the remaining
+    /// capacities must clear.
+    pub fn feasible_flow(&self) -> Option<Vec<i64>> {
+        let nodes = self.result.supply.len();
+        let mut excess = vec![0i64; nodes];
+        let mut graph = Residual::new(nodes + 2);
+        for (arc, &lower) in self.result.arcs.iter().zip(&self.lower) {
+            let (u, v) = (arc.from as usize - 1, arc.to as usize - 1);
+            graph.add(u, v, (arc.capacity - lower).max(0));
+            excess[u] -= lower;
+            excess[v] += lower;
+        }
+        let (source, sink) = (nodes, nodes + 1);
+        let mut required = 0i128;
+        for (v, &e) in excess.iter().enumerate() {
+            if e > 0 {
+                graph.add(source, v, e);
+                required += e as i128;
+            } else if e < 0 {
+                graph.add(v, sink, -e);
+            }
+        }
+        if graph.max_flow(source, sink) < required {
+            return None;
+        }
+        // Arc `k` is edge `2k`; its reverse edge holds the flow pushed.
+        let flow = self
+            .lower
+            .iter()
+            .enumerate()
+            .map(|(k, &lower)| lower + graph.cap[2 * k + 1])
+            .collect();
+        Some(flow)
+    }
+
+    /// Check that `flow` is a feasible circulation: every arc carries
+    /// between its lower bound and its capacity, and flow is conserved at
+    /// every node.
+    pub fn verify_flow(&self, flow: &[i64]) -> Result<(), FlowError> {
+        if flow.len() == self.lower.len() {
+            for (arc, (&f, &lower)) in flow.iter().zip(&self.lower).enumerate() {
+                if f < lower {
+                    return Err(FlowError::BelowLower {
+                        arc,
+                        flow: f,
+                        lower,
+                    });
+                }
+            }
+        }
+        crate::verify_flow(&self.result, flow)
+    }
+
+    /// Write the circulation in DIMACS `p min` format: the header for
+    /// `params`, no `n` lines (every supply is zero) and
+    /// `a <from> <to> <lower> <capacity> <cost>` arc lines.
+    pub fn write_dimacs(
+        &self,
+        w: &mut impl Write,
+        seed: i64,
+        problem: i64,
+        params: &NetgenParams,
+    ) -> io::Result<()> {
+        write_dimacs_header(w, seed, problem, params)?;
+        writeln!(w)?;
+        writeln!(w, "c")?;
+        writeln!(w, "c  *** Minimum cost circulation ***")?;
+        writeln!(w, "c")?;
+        writeln!(
+            w,
+            "p min {} {}",
+            self.result.supply.len(),
+            self.result.arcs.len()
+        )?;
+        for (arc, lower) in self.result.arcs.iter().zip(&self.lower) {
+            writeln!(
+                w,
+                "a {} {} {} {} {}",
+                arc.from, arc.to, lower, arc.capacity, arc.cost
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::DOC_PARMS;
+    use crate::{generate, parse_dimacs};
+
+    #[test]
+    fn circulation_is_feasible_with_zero_supplies() {
+        let cases: [&[i64]; 2] = [
+            &DOC_PARMS,
+            &[1000, 50, 30, 5000, 1, 100, 20000, 10, 5, 30, 100, 1, 50],
+        ];
+        for parms in cases {
+            let params = NetgenParams::from_slice(parms).unwrap();
+            let result = generate(13502460, &params).unwrap();
+            let circulation = result.to_circulation();
+            assert!(circulation.result.supply.iter().all(|&s| s == 0));
+            assert_eq!(circulation.lower.iter().sum::<i64>(), params.supply);
+            assert_eq!(
+                circulation.result.arcs[..result.arcs.len()],
+                result.arcs[..]
+            );
+
+            let flow = circulation.feasible_flow().expect("feasible");
+            assert_eq!(circulation.verify_flow(&flow), Ok(()));
+        }
+    }
+
+    #[test]
+    fn lower_bounds_are_enforced_and_written() {
+        let result = NetgenResult {
+            arcs: vec![Arc {
+                from: 1,
+                to: 2,
+                cost: 3,
+                capacity: 5,
+                multiplier: None,
+                uncapacitated: false,
+                kind: ArcKind::Unknown,
+                hicost: false,
+            }],
+            supply: vec![4, -4],
+        };
+        let circulation = result.to_circulation();
+        assert_eq!(circulation.lower, [0, 4]);
+        assert_eq!(circulation.feasible_flow(), Some(vec![4, 4]));
+        assert_eq!(
+            circulation.verify_flow(&[3, 3]),
+            Err(FlowError::BelowLower {
+                arc: 1,
+                flow: 3,
+                lower: 4
+            })
+        );
+
+        // A bottleneck below the lower bound makes it infeasible.
+        let mut tight = circulation.clone();
+        tight.result.arcs[0].capacity = 3;
+        assert_eq!(tight.feasible_flow(), None);
+
+        let params = NetgenParams::from_slice(&[2, 1, 1, 2, 3, 3, 4, 0, 0, 0, 0, 5, 5]).unwrap();
+        let mut out = Vec::new();
+        circulation.write_dimacs(&mut out, 1, 1, &params).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("\np min 2 2\na 1 2 0 5 3\na 2 1 4 4 0\n"));
+        assert!(!text.lines().any(|l| l.starts_with("n ")));
+        // The parser rejects lower bounds it cannot represent.
+        assert!(parse_dimacs(&text).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+//! Compressed DIMACS output, behind the `flate2` and `zstd` features.
+
+use std::io::{self, BufWriter, Write};
+
+use crate::{NetgenParams, NetgenResult};
+
+/// Default gzip level (0–9), matching the `gzip` command.
+#[cfg(feature = "flate2")]
+pub const GZIP_DEFAULT_LEVEL: u32 = 6;
+
+/// Default zstd level (1–22), matching the `zstd` command.
+#[cfg(feature = "zstd")]
+pub const ZSTD_DEFAULT_LEVEL: i32 = 3;
+
+/// Write gzip-compressed DIMACS output and return the underlying writer.
+///
+/// Decompresses to exactly the bytes of [`write_dimacs`](crate::write_dimacs).
+#[cfg(feature = "flate2")]
+pub fn write_dimacs_gz<W: Write>(
+    w: W,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    level: u32,
+) -> io::Result<W> {
+    let encoder = flate2::write::GzEncoder::new(w, flate2::Compression::new(level));
+    let mut buf = BufWriter::new(encoder);
+    result.write_dimacs(&mut buf, seed, problem, params)?;
+    buf.into_inner().map_err(|e| e.into_error())?.finish()
+}
+
+/// Write zstd-compressed DIMACS output and return the underlying writer.
+///
+/// Decompresses to exactly the bytes of [`write_dimacs`](crate::write_dimacs).
+#[cfg(feature = "zstd")]
+pub fn write_dimacs_zstd<W: Write>(
+    w: W,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    level: i32,
+) -> io::Result<W> {
+    let mut buf = BufWriter::new(zstd::Encoder::new(w, level)?);
+    result.write_dimacs(&mut buf, seed, problem, params)?;
+    buf.into_inner().map_err(|e| e.into_error())?.finish()
+}
@@ -0,0 +1,356 @@
+//! Cost-profile variants of a network: rounding costs to a granularity and
+//! rescaling them to a new range, for cost-scaling experiments.
+
+use std::fmt;
+
+use crate::NetgenResult;
+
+/// How [`NetgenResult::round_costs`] picks a multiple of the granularity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundMode {
+    /// The closest multiple; halfway costs round away from zero.
+    Nearest,
+    /// The largest multiple not above the cost.
+    Down,
+    /// The smallest multiple not below the cost.
+    Up,
+}
+
+impl fmt::Display for RoundMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RoundMode::Nearest => "nearest",
+            RoundMode::Down => "down",
+            RoundMode::Up => "up",
+        })
+    }
+}
+
+/// Which cost transform produced a network, for recording next to it.
+///
+/// Displays as one line, for example `Costs rounded to multiples of 10
+/// (nearest)`, suitable for [`HeaderOptions::notes`](crate::HeaderOptions::notes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostNote {
+    Rounded {
+        granularity: i64,
+        mode: RoundMode,
+    },
+    /// The observed cost range `from` was mapped onto `to`.
+    Rescaled {
+        from: (i64, i64),
+        to: (i64, i64),
+    },
+}
+
+impl fmt::Display for CostNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CostNote::Rounded { granularity, mode } => {
+                write!(f, "Costs rounded to multiples of {granularity} ({mode})")
+            }
+            CostNote::Rescaled { from, to } => write!(
+                f,
+                "Costs rescaled from [{}, {}] to [{}, {}]",
+                from.0, from.1, to.0, to.1
+            ),
+        }
+    }
+}
+
+/// Why a cost transform could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CostTransformError {
+    /// The rounding granularity is zero or negative.
+    Granularity(i64),
+    /// Rounding arc `arc` (0-based) would take its cost past the `i64`
+    /// range.
+    Overflow { arc: usize },
+    /// The target range is empty: `new_min > new_max`.
+    EmptyRange { new_min: i64, new_max: i64 },
+    /// The arcs do not span two distinct costs, so no affine map takes
+    /// their minimum and maximum onto a target range wider than one value.
+    ConstantCosts,
+}
+
+impl fmt::Display for CostTransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CostTransformError::Granularity(g) => {
+                write!(f, "cost granularity {g} must be positive")
+            }
+            CostTransformError::Overflow { arc } => {
+                write!(f, "rounding the cost of arc {arc} overflows i64")
+            }
+            CostTransformError::EmptyRange { new_min, new_max } => {
+                write!(f, "cost range [{new_min}, {new_max}] is empty")
+            }
+            CostTransformError::ConstantCosts => {
+                write!(f, "the arcs do not have two distinct costs to rescale")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CostTransformError {}
+
+impl NetgenResult {
+    /// A copy with every cost rounded to a multiple of `granularity`.
+    ///
+    /// See [`round_costs_with_note`](Self::round_costs_with_note).
+    pub fn round_costs(
+        &self,
+        granularity: i64,
+        mode: RoundMode,
+    ) -> Result<NetgenResult, CostTransformError> {
+        self.round_costs_with_note(granularity, mode)
+            .map(|(r, _)| r)
+    }
+
+    /// A copy with every cost rounded to a multiple of `granularity`
+    /// according to `mode`, and a note describing the transform. Topology,
+    /// capacities and supplies are untouched.
+    ///
+    /// Fails if `granularity` is not positive, or if a cost near the ends
+    /// of the `i64` range has no multiple in range to round to.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, RoundMode};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = params.generate(13502460).unwrap();
+    /// let (rounded, note) = result.round_costs_with_note(25, RoundMode::Nearest).unwrap();
+    /// assert!(rounded.arcs.iter().all(|a| a.cost % 25 == 0));
+    /// assert_eq!(note.to_string(), "Costs rounded to multiples of 25 (nearest)");
+    /// ```
+    pub fn round_costs_with_note(
+        &self,
+        granularity: i64,
+        mode: RoundMode,
+    ) -> Result<(NetgenResult, CostNote), CostTransformError> {
+        if granularity <= 0 {
+            return Err(CostTransformError::Granularity(granularity));
+        }
+        let g = i128::from(granularity);
+        let mut result = self.clone();
+        for (k, arc) in result.arcs.iter_mut().enumerate() {
+            let cost = i128::from(arc.cost);
+            let down = cost.div_euclid(g) * g;
+            let rem = cost - down;
+            let up = rem > 0;
+            let rounded = match mode {
+                RoundMode::Down => down,
+                RoundMode::Up if up => down + g,
+                RoundMode::Up => down,
+                // At a tie `down` is the multiple toward zero only for
+                // positive costs.
+                RoundMode::Nearest if 2 * rem > g || (2 * rem == g && cost > 0) => down + g,
+                RoundMode::Nearest => down,
+            };
+            arc.cost =
+                i64::try_from(rounded).map_err(|_| CostTransformError::Overflow { arc: k })?;
+        }
+        Ok((result, CostNote::Rounded { granularity, mode }))
+    }
+
+    /// A copy with the costs mapped affinely onto `new_min..=new_max`.
+    ///
+    /// See [`rescale_costs_to_range_with_note`](Self::rescale_costs_to_range_with_note).
+    pub fn rescale_costs_to_range(
+        &self,
+        new_min: i64,
+        new_max: i64,
+    ) -> Result<NetgenResult, CostTransformError> {
+        self.rescale_costs_to_range_with_note(new_min, new_max)
+            .map(|(r, _)| r)
+    }
+
+    /// A copy with the costs mapped affinely onto `new_min..=new_max`, and a
+    /// note describing the transform.
+    ///
+    /// The smallest cost present maps to `new_min` and the largest to
+    /// `new_max` exactly; the others are rounded to the nearest integer,
+    /// halves upward, so equal costs stay equal and the order of costs is
+    /// kept (ties may appear when the range shrinks). The arithmetic is
+    /// exact over the whole `i64` range and every result lies between the
+    /// targets, so this cannot overflow.
+    ///
+    /// If `new_min == new_max` every cost becomes that value. Otherwise the
+    /// arcs must have at least two distinct costs.
+    ///
+    /// ```rust
+    /// use netgen_rs::NetgenParams;
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = params.generate(13502460).unwrap();
+    /// let rescaled = result.rescale_costs_to_range(0, 10).unwrap();
+    /// assert!(rescaled.arcs.iter().all(|a| (0..=10).contains(&a.cost)));
+    /// ```
+    pub fn rescale_costs_to_range_with_note(
+        &self,
+        new_min: i64,
+        new_max: i64,
+    ) -> Result<(NetgenResult, CostNote), CostTransformError> {
+        if new_min > new_max {
+            return Err(CostTransformError::EmptyRange { new_min, new_max });
+        }
+        let costs = self.arcs.iter().map(|a| a.cost);
+        let (min, max) = match (costs.clone().min(), costs.max()) {
+            (Some(min), Some(max)) => (min, max),
+            _ if new_min == new_max => (new_min, new_max),
+            _ => return Err(CostTransformError::ConstantCosts),
+        };
+        if min == max && new_min != new_max {
+            return Err(CostTransformError::ConstantCosts);
+        }
+        // Both factors are below 2^64, so the product fits in a u128.
+        let span = (i128::from(max) - i128::from(min)) as u128;
+        let new_span = (i128::from(new_max) - i128::from(new_min)) as u128;
+        let mut result = self.clone();
+        for arc in &mut result.arcs {
+            let scaled = (i128::from(arc.cost) - i128::from(min)) as u128 * new_span;
+            // With `span == 0` every cost is `min` and maps to `new_min`.
+            let offset = scaled.checked_div(span).map_or(0, |q| {
+                let r = scaled - q * span;
+                q + u128::from(r >= span - r)
+            });
+            // `offset <= new_span`, so the cost lands in the target range.
+            arc.cost = (i128::from(new_min) + offset as i128) as i64;
+        }
+        let note = CostNote::Rescaled {
+            from: (min, max),
+            to: (new_min, new_max),
+        };
+        Ok((result, note))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, generate, parse_dimacs};
+
+    fn result() -> NetgenResult {
+        let params =
+            NetgenParams::from_slice(&[512, 10, 10, 2000, -50, 500, 1000, 3, 3, 20, 80, 50, 2000])
+                .unwrap();
+        generate(13502460, &params).unwrap()
+    }
+
+    #[test]
+    fn rounded_costs_are_multiples_of_the_granularity() {
+        let original = result();
+        for mode in [RoundMode::Nearest, RoundMode::Down, RoundMode::Up] {
+            for granularity in [1, 7, 100, 1000] {
+                let rounded = original.round_costs(granularity, mode).unwrap();
+                assert_eq!(rounded.supply, original.supply);
+                for (old, new) in original.arcs.iter().zip(&rounded.arcs) {
+                    assert_eq!(new.cost % granularity, 0, "{mode} {granularity}");
+                    assert!((new.cost - old.cost).abs() < granularity);
+                    match mode {
+                        RoundMode::Down => assert!(new.cost <= old.cost),
+                        RoundMode::Up => assert!(new.cost >= old.cost),
+                        RoundMode::Nearest => {
+                            assert!(2 * (new.cost - old.cost).abs() <= granularity)
+                        }
+                    }
+                    assert_eq!(
+                        (new.from, new.to, new.capacity),
+                        (old.from, old.to, old.capacity)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_rounds_halves_away_from_zero() {
+        let result =
+            parse_dimacs("p min 2 4\na 1 2 0 1 5\na 1 2 0 1 -5\na 1 2 0 1 4\na 1 2 0 1 -6\n")
+                .unwrap();
+        let rounded = result.round_costs(10, RoundMode::Nearest).unwrap();
+        let costs: Vec<i64> = rounded.arcs.iter().map(|a| a.cost).collect();
+        assert_eq!(costs, [10, -10, 0, -10]);
+    }
+
+    #[test]
+    fn rounding_rejects_bad_granularity_and_overflow() {
+        let result = result();
+        assert_eq!(
+            result.round_costs(0, RoundMode::Down),
+            Err(CostTransformError::Granularity(0))
+        );
+        assert_eq!(
+            result.round_costs(-3, RoundMode::Down),
+            Err(CostTransformError::Granularity(-3))
+        );
+        let mut extreme = result.clone();
+        extreme.arcs[5].cost = i64::MAX;
+        assert_eq!(
+            extreme.round_costs(10, RoundMode::Up),
+            Err(CostTransformError::Overflow { arc: 5 })
+        );
+        assert!(extreme.round_costs(10, RoundMode::Down).is_ok());
+        extreme.arcs[5].cost = i64::MIN;
+        assert_eq!(
+            extreme.round_costs(10, RoundMode::Down),
+            Err(CostTransformError::Overflow { arc: 5 })
+        );
+    }
+
+    #[test]
+    fn rescale_maps_the_observed_range_onto_the_targets() {
+        let original = result();
+        let min = original.arcs.iter().map(|a| a.cost).min().unwrap();
+        let max = original.arcs.iter().map(|a| a.cost).max().unwrap();
+        for (new_min, new_max) in [(0, 10), (-1000, 1000), (1, 1_000_000), (i64::MIN, i64::MAX)] {
+            let (rescaled, note) = original
+                .rescale_costs_to_range_with_note(new_min, new_max)
+                .unwrap();
+            assert_eq!(
+                note,
+                CostNote::Rescaled {
+                    from: (min, max),
+                    to: (new_min, new_max)
+                }
+            );
+            for (old, new) in original.arcs.iter().zip(&rescaled.arcs) {
+                assert!((new_min..=new_max).contains(&new.cost));
+                if old.cost == min {
+                    assert_eq!(new.cost, new_min);
+                }
+                if old.cost == max {
+                    assert_eq!(new.cost, new_max);
+                }
+            }
+            // Order is kept.
+            let mut pairs: Vec<(i64, i64)> = (original.arcs.iter().zip(&rescaled.arcs))
+                .map(|(o, n)| (o.cost, n.cost))
+                .collect();
+            pairs.sort_unstable();
+            assert!(pairs.windows(2).all(|w| w[0].1 <= w[1].1));
+        }
+    }
+
+    #[test]
+    fn rescale_rejects_empty_and_degenerate_ranges() {
+        let result = result();
+        assert_eq!(
+            result.rescale_costs_to_range(5, 4),
+            Err(CostTransformError::EmptyRange {
+                new_min: 5,
+                new_max: 4
+            })
+        );
+        let flat = result.rescale_costs_to_range(3, 3).unwrap();
+        assert!(flat.arcs.iter().all(|a| a.cost == 3));
+        assert_eq!(
+            flat.rescale_costs_to_range(0, 10),
+            Err(CostTransformError::ConstantCosts)
+        );
+    }
+}
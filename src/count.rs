@@ -0,0 +1,171 @@
+//! Counting what a writer writes, for logging and sanity checks.
+
+use std::io::{self, Write};
+
+use crate::{DimacsOptions, NetgenParams, NetgenResult};
+
+/// What was written through a [`CountingWriter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteSummary {
+    /// Bytes the inner writer accepted.
+    pub bytes: u64,
+    /// Lines starting with `n`: the DIMACS node lines.
+    pub node_lines: usize,
+    /// Lines starting with `a`: the DIMACS arc lines.
+    pub arc_lines: usize,
+}
+
+/// An [`io::Write`] that passes everything on to its inner writer and
+/// counts the bytes it accepts and the DIMACS node and arc lines among
+/// them, without buffering.
+///
+/// Any writer can be wrapped, such as that of [`write_csv`](crate::write_csv)
+/// or [`write_mps`](crate::write_mps), for the byte count; the line counts
+/// only mean something for DIMACS.
+///
+/// ```rust
+/// use std::io::Write;
+/// use netgen_rs::CountingWriter;
+///
+/// let mut w = CountingWriter::new(Vec::new());
+/// w.write_all(b"p min 2 1\nn 1 5\nn 2 -5\na 1 2 0 5 3\n").unwrap();
+/// let summary = w.summary();
+/// assert_eq!((summary.bytes, summary.node_lines, summary.arc_lines), (35, 2, 1));
+/// ```
+#[derive(Debug)]
+pub struct CountingWriter<W> {
+    inner: W,
+    summary: WriteSummary,
+    /// The next byte starts a line.
+    line_start: bool,
+}
+
+impl<W: Write> CountingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        CountingWriter {
+            inner,
+            summary: WriteSummary::default(),
+            line_start: true,
+        }
+    }
+
+    /// What has been written so far.
+    pub fn summary(&self) -> WriteSummary {
+        self.summary
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        for &byte in &buf[..n] {
+            if self.line_start {
+                match byte {
+                    b'n' => self.summary.node_lines += 1,
+                    b'a' => self.summary.arc_lines += 1,
+                    _ => {}
+                }
+            }
+            self.line_start = byte == b'\n';
+        }
+        self.summary.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// [`write_dimacs_with_options`](crate::write_dimacs_with_options),
+/// returning what it wrote.
+///
+/// ```rust
+/// use netgen_rs::{DimacsOptions, NetgenParams, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut out = Vec::new();
+/// let summary = netgen_rs::write_dimacs_with_summary(
+///     &mut out, 13502460, 1, &params, &result, &DimacsOptions::default(),
+/// ).unwrap();
+/// assert_eq!(summary.bytes, out.len() as u64);
+/// assert_eq!(summary.arc_lines, 2000);
+/// ```
+pub fn write_dimacs_with_summary(
+    w: &mut impl Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &DimacsOptions,
+) -> io::Result<WriteSummary> {
+    let mut w = CountingWriter::new(w);
+    crate::write_dimacs_with_options(&mut w, seed, problem, params, result, options)?;
+    Ok(w.summary())
+}
+
+/// [`write_dimacs_network_with_options`](crate::write_dimacs_network_with_options),
+/// returning what it wrote.
+pub fn write_dimacs_network_with_summary(
+    w: &mut impl Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &DimacsOptions,
+) -> io::Result<WriteSummary> {
+    let mut w = CountingWriter::new(w);
+    crate::write_dimacs_network_with_options(&mut w, params, result, options)?;
+    Ok(w.summary())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes at most `chunk` bytes per write.
+    struct Trickle {
+        out: Vec<u8>,
+        chunk: usize,
+    }
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.chunk);
+            self.out.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn short_writes_count_what_was_taken() {
+        let text = b"c a comment\r\np max 3 2\r\nn 1 s\r\nn 3 t\r\na 1 2 5\r\na 2 3 5";
+        for chunk in [1, 2, 7, 100] {
+            let mut w = CountingWriter::new(Trickle {
+                out: Vec::new(),
+                chunk,
+            });
+            assert_eq!(w.write(text).unwrap(), chunk.min(text.len()));
+            w.write_all(&text[chunk.min(text.len())..]).unwrap();
+            let summary = w.summary();
+            assert_eq!(w.into_inner().out, text);
+            assert_eq!(
+                summary,
+                WriteSummary {
+                    bytes: text.len() as u64,
+                    node_lines: 2,
+                    arc_lines: 2,
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Compressed sparse row (CSR) export for solvers that take arrays rather
+//! than DIMACS text.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::NetgenResult;
+
+/// A network in CSR form, arcs grouped by tail node.
+///
+/// Nodes are 0-based here: NETGEN node `id` is row `id - 1`. The arcs of row
+/// `i` occupy positions `row_ptr[i]..row_ptr[i + 1]` of `col_idx`, `cost`
+/// and `capacity`, in generation order.
+///
+/// This reorders arcs relative to [`NetgenResult::arcs`] and the DIMACS
+/// output. `permutation[k]` is the index in `arcs` of CSR arc `k`, so a flow
+/// vector `x` computed on the CSR arrays maps back as
+/// `dimacs_flow[permutation[k]] = x[k]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrNetwork {
+    /// `nodes + 1` offsets, nondecreasing, from 0 to the arc count.
+    pub row_ptr: Vec<usize>,
+    /// Head node (0-based) of each arc.
+    pub col_idx: Vec<u32>,
+    pub cost: Vec<i64>,
+    pub capacity: Vec<i64>,
+    /// Supply (positive) or demand (negative) of each node.
+    pub supply: Vec<i64>,
+    /// Original arc index of each CSR arc.
+    pub permutation: Vec<usize>,
+}
+
+impl CsrNetwork {
+    /// Number of nodes (rows).
+    pub fn nodes(&self) -> usize {
+        self.row_ptr.len() - 1
+    }
+
+    /// Number of arcs (stored entries).
+    pub fn arcs(&self) -> usize {
+        self.col_idx.len()
+    }
+}
+
+impl NetgenResult {
+    /// Convert to CSR arrays grouped by tail node. See [`CsrNetwork`] for
+    /// the layout and how to map results back to the original arc order.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let csr = result.to_csr();
+    /// assert_eq!(csr.nodes(), 512);
+    /// assert_eq!(csr.arcs(), result.arcs.len());
+    /// ```
+    pub fn to_csr(&self) -> CsrNetwork {
+        let (row_ptr, permutation) = self.tail_index();
+        let arc = |k: &usize| &self.arcs[*k];
+        CsrNetwork {
+            col_idx: permutation.iter().map(|k| arc(k).to as u32 - 1).collect(),
+            cost: permutation.iter().map(|k| arc(k).cost).collect(),
+            capacity: permutation.iter().map(|k| arc(k).capacity).collect(),
+            supply: self.supply.clone(),
+            row_ptr,
+            permutation,
+        }
+    }
+
+    /// Row offsets and the arc indices grouped by tail, as in
+    /// [`CsrNetwork::row_ptr`] and [`CsrNetwork::permutation`].
+    pub(crate) fn tail_index(&self) -> (Vec<usize>, Vec<usize>) {
+        let nodes = self.supply.len();
+        let mut row_ptr = vec![0usize; nodes + 1];
+        for arc in &self.arcs {
+            row_ptr[arc.from as usize] += 1;
+        }
+        for i in 0..nodes {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+        // Counting sort: `next[i]` is the next free slot in row `i`.
+        let mut next = row_ptr.clone();
+        let mut permutation = vec![0usize; self.arcs.len()];
+        for (k, arc) in self.arcs.iter().enumerate() {
+            let row = arc.from as usize - 1;
+            permutation[next[row]] = k;
+            next[row] += 1;
+        }
+        (row_ptr, permutation)
+    }
+
+    /// Node–arc incidence matrix as `(node, arc, ±1)` triplets, two per arc:
+    /// +1 at the tail and −1 at the head.
+    ///
+    /// Nodes are 0-based and arcs keep their index in
+    /// [`arcs`](Self::arcs), so the flow conservation rows read
+    /// `A x = supply`.
+    pub fn to_incidence_triplets(&self) -> Vec<(u32, usize, i8)> {
+        self.arcs
+            .iter()
+            .enumerate()
+            .flat_map(|(k, arc)| [(arc.from as u32 - 1, k, 1), (arc.to as u32 - 1, k, -1)])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture::doc_result;
+
+    #[test]
+    fn csr_layout() {
+        let result = doc_result();
+        let csr = result.to_csr();
+        assert_eq!(csr.row_ptr.len(), 513);
+        assert_eq!(csr.row_ptr[0], 0);
+        assert!(csr.row_ptr.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(*csr.row_ptr.last().unwrap(), result.arcs.len());
+        assert_eq!(csr.arcs(), result.arcs.len());
+        assert_eq!(csr.cost.len(), result.arcs.len());
+        assert_eq!(csr.capacity.len(), result.arcs.len());
+
+        let mut seen = csr.permutation.clone();
+        seen.sort_unstable();
+        assert!(seen.iter().copied().eq(0..result.arcs.len()));
+    }
+
+    #[test]
+    fn csr_row_matches_arcs() {
+        let result = doc_result();
+        let csr = result.to_csr();
+        // Node 1 is a source, so its row holds skeleton and random arcs.
+        let expected: Vec<_> = result
+            .arcs
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.from == 1)
+            .collect();
+        let row = csr.row_ptr[0]..csr.row_ptr[1];
+        assert!(!row.is_empty());
+        assert_eq!(row.len(), expected.len());
+        for (slot, (k, arc)) in row.zip(expected) {
+            assert_eq!(csr.permutation[slot], k);
+            assert_eq!(csr.col_idx[slot] as u64 + 1, arc.to);
+            assert_eq!(csr.cost[slot], arc.cost);
+            assert_eq!(csr.capacity[slot], arc.capacity);
+        }
+    }
+
+    #[test]
+    fn incidence_rows_sum_to_zero() {
+        let result = doc_result();
+        let triplets = result.to_incidence_triplets();
+        assert_eq!(triplets.len(), 2 * result.arcs.len());
+        let mut column = vec![0i64; result.arcs.len()];
+        for &(node, arc, sign) in &triplets {
+            assert!((node as usize) < result.supply.len());
+            column[arc] += sign as i64;
+        }
+        assert!(column.iter().all(|&c| c == 0));
+        let arc = &result.arcs[42];
+        assert_eq!(triplets[84], (arc.from as u32 - 1, 42, 1));
+        assert_eq!(triplets[85], (arc.to as u32 - 1, 42, -1));
+    }
+}
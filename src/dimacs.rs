@@ -0,0 +1,491 @@
+//! Reading networks back from DIMACS files.
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::{
+    Arc, ArcKind, GenerateOptions, GenerationMode, NetgenParams, NetgenResult, PARAM_NAMES,
+    generate_with_options,
+};
+
+/// Labels of the classic header lines, in [`NetgenParams::to_array`] order.
+const HEADER_LABELS: [&str; 13] = [
+    "Number of nodes:",
+    "Source nodes:",
+    "Sink nodes:",
+    "Number of arcs:",
+    "Minimum arc cost:",
+    "Maximum arc cost:",
+    "Total supply:",
+    "Sources:",
+    "Sinks:",
+    "With max cost:",
+    "Capacitated:",
+    "Minimum arc capacity:",
+    "Maximum arc capacity:",
+];
+
+/// Why a DIMACS file could not be read (see [`parse_dimacs`]).
+///
+/// Lines are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DimacsError {
+    /// No `p` line before the first node or arc line, or none at all.
+    MissingProblemLine,
+    /// A line that is not a comment, problem, node or arc line, or has the
+    /// wrong fields for the problem type.
+    Malformed { line: usize, content: String },
+    /// A node id outside `1..=nodes`.
+    NodeOutOfRange { line: usize, node: u64 },
+    /// A `p min` arc with a nonzero lower bound, which [`NetgenResult`]
+    /// cannot represent.
+    LowerBound { line: usize, lower: i64 },
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DimacsError::MissingProblemLine => write!(f, "missing problem line"),
+            DimacsError::Malformed { line, content } => {
+                write!(f, "line {line}: malformed line {content:?}")
+            }
+            DimacsError::NodeOutOfRange { line, node } => {
+                write!(f, "line {line}: node {node} out of range")
+            }
+            DimacsError::LowerBound { line, lower } => {
+                write!(f, "line {line}: nonzero lower bound {lower}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+/// Read a network from DIMACS text, as written by
+/// [`write_dimacs`](crate::write_dimacs).
+///
+/// `p min` files are read exactly, except that [`Arc::uncapacitated`] is
+/// inferred (see [`NetgenResult::infer_uncapacitated`]) and arcs have no
+/// provenance ([`ArcKind::Unknown`]). The other formats do not carry every
+/// field, so the missing ones are filled in: `p max` sources (`n i s`) get
+/// supply 1, sinks (`n i t`) supply −1 and arcs cost 0; `p asn` listed
+/// nodes get supply 1, the others −1, and arcs capacity 1. No arc of these
+/// is flagged uncapacitated, as the total supply is not in the file.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate, parse_dimacs, to_dimacs_string};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let dimacs = to_dimacs_string(13502460, 1, &params).unwrap();
+/// let result = parse_dimacs(&dimacs).unwrap();
+/// let expected = generate(13502460, &params).unwrap();
+/// assert_eq!(result.canonicalize(), expected.canonicalize());
+/// ```
+pub fn parse_dimacs(input: &str) -> Result<NetgenResult, DimacsError> {
+    const PREALLOC: usize = 1 << 20;
+    let mut problem: Option<(&str, NetgenResult)> = None;
+    for (i, text) in input.lines().enumerate() {
+        let line = i + 1;
+        let malformed = || DimacsError::Malformed {
+            line,
+            content: text.to_string(),
+        };
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        let int = |k: usize| -> Result<i64, DimacsError> {
+            fields
+                .get(k)
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(malformed)
+        };
+        match fields.first().copied() {
+            None | Some("c") => {}
+            Some("p") => {
+                let kind = match fields.get(1).copied() {
+                    Some(kind @ ("min" | "max" | "asn")) if fields.len() == 4 => kind,
+                    _ => return Err(malformed()),
+                };
+                // The counts are only as trustworthy as the file: node ids
+                // past u32::MAX have no NodeId, and every arc line takes at
+                // least 8 bytes, so larger counts are rejected outright.
+                let nodes = u32::try_from(int(2)?).map_err(|_| malformed())? as usize;
+                let arcs = usize::try_from(int(3)?).unwrap_or(0);
+                if arcs > input.len() / 8 {
+                    return Err(malformed());
+                }
+                let mut supply = Vec::new();
+                supply.try_reserve_exact(nodes).map_err(|_| malformed())?;
+                supply.resize(nodes, if kind == "asn" { -1 } else { 0 });
+                let result = NetgenResult {
+                    arcs: Vec::with_capacity(arcs.min(PREALLOC)),
+                    supply,
+                };
+                problem = Some((kind, result));
+            }
+            Some(tag @ ("n" | "a")) => {
+                let (kind, result) = problem.as_mut().ok_or(DimacsError::MissingProblemLine)?;
+                let node = |k: usize| -> Result<u64, DimacsError> {
+                    let node = int(k)?;
+                    if node < 1 || node as usize > result.supply.len() {
+                        return Err(DimacsError::NodeOutOfRange {
+                            line,
+                            node: node as u64,
+                        });
+                    }
+                    Ok(node as u64)
+                };
+                let expected = match (tag, *kind) {
+                    ("n", "asn") => 2,
+                    ("n", _) => 3,
+                    ("a", "min") => 6,
+                    ("a", _) => 4,
+                    _ => unreachable!(),
+                };
+                if fields.len() != expected {
+                    return Err(malformed());
+                }
+                let from = node(1)?;
+                if tag == "n" {
+                    result.supply[from as usize - 1] = match (*kind, fields.get(2).copied()) {
+                        ("asn", _) | ("max", Some("s")) => 1,
+                        ("max", Some("t")) => -1,
+                        ("max", _) => return Err(malformed()),
+                        _ => int(2)?,
+                    };
+                    continue;
+                }
+                let to = node(2)?;
+                let (cost, capacity) = match *kind {
+                    "min" => {
+                        let lower = int(3)?;
+                        if lower != 0 {
+                            return Err(DimacsError::LowerBound { line, lower });
+                        }
+                        (int(5)?, int(4)?)
+                    }
+                    "max" => (0, int(3)?),
+                    _ => (int(3)?, 1),
+                };
+                result.arcs.push(Arc {
+                    from,
+                    to,
+                    cost,
+                    capacity,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                });
+            }
+            Some(_) => return Err(malformed()),
+        }
+    }
+    let (kind, mut result) = problem.ok_or(DimacsError::MissingProblemLine)?;
+    // `p max` and `p asn` supplies are stand-ins, so their sum is not the
+    // capacity NETGEN gave uncapacitated arcs.
+    if kind == "min" {
+        result.infer_uncapacitated();
+    }
+    Ok(result)
+}
+
+/// Recover the seed, problem number and parameters from the header
+/// comments of a DIMACS file, so that the instance can be regenerated.
+///
+/// Reads comment lines up to the first other line. If the header has a
+/// complete set of `c PARAM` lines (see
+/// [`HeaderOptions::machine_readable`](crate::HeaderOptions::machine_readable)),
+/// they are used; otherwise the values come from the classic header that
+/// [`write_dimacs_header`](crate::write_dimacs_header) writes. Returns
+/// `None` if neither is complete, the parameters are invalid, or reading
+/// fails.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, parse_header, to_dimacs_string};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let dimacs = to_dimacs_string(13502460, 1, &params).unwrap();
+/// assert_eq!(parse_header(dimacs.as_bytes()), Some((13502460, 1, params)));
+/// ```
+pub fn parse_header(r: impl BufRead) -> Option<(i64, i64, NetgenParams)> {
+    // Seed, problem number and the 13 parameters.
+    let mut classic = [None; 15];
+    let mut tagged = [None; 15];
+    for line in r.lines() {
+        let line = line.ok()?;
+        let Some(comment) = line.strip_prefix('c') else {
+            if line.trim().is_empty() {
+                continue;
+            }
+            break;
+        };
+        let comment = comment.trim();
+        if let Some(rest) = comment.strip_prefix("PARAM ") {
+            let mut fields = rest.split_whitespace();
+            let (Some(key), Some(value), None) = (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let slot = match key {
+                "seed" => 0,
+                "problem" => 1,
+                _ => match PARAM_NAMES.iter().position(|&name| name == key) {
+                    Some(i) => i + 2,
+                    None => continue,
+                },
+            };
+            tagged[slot] = value.parse().ok();
+        } else if let Some(rest) = comment.strip_prefix("Problem ") {
+            classic[1] = rest.split_whitespace().next().and_then(|v| v.parse().ok());
+        } else if let Some(value) = comment.strip_prefix("Random seed:") {
+            classic[0] = value.trim().parse().ok();
+        } else if let Some((i, value)) = HEADER_LABELS
+            .iter()
+            .enumerate()
+            .find_map(|(i, label)| comment.strip_prefix(label).map(|value| (i, value)))
+        {
+            classic[i + 2] = value.trim().trim_end_matches('%').parse().ok();
+        }
+    }
+    let values = if tagged.iter().all(Option::is_some) {
+        tagged
+    } else {
+        classic
+    };
+    let values: Vec<i64> = values.into_iter().collect::<Option<_>>()?;
+    let params = NetgenParams::from_slice(&values[2..]).ok()?;
+    Some((values[0], values[1], params))
+}
+
+/// What [`verify_instance`] found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    /// The network section is exactly what the header's seed and
+    /// parameters generate.
+    Match,
+    /// The header names a seed and parameters, but the network section
+    /// differs from what they generate, first at 1-based `line` of the file.
+    HeaderOnly { line: usize },
+    /// No seed and parameters could be read from the header.
+    NotNetgen,
+}
+
+/// Why [`verify_instance`] could not check a file.
+#[derive(Debug)]
+pub enum VerifyError {
+    Io(io::Error),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Io(e) => write!(f, "reading the instance: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VerifyError::Io(e) => Some(e),
+        }
+    }
+}
+
+/// Check that a DIMACS file is an unmodified instance of this generator.
+///
+/// The seed and parameters are read from the header as by
+/// [`parse_header`], the network is regenerated, and the file's network
+/// section (from the comments before the `p` line on) is compared line by line with what
+/// [`write_dimacs`](crate::write_dimacs) writes for it. The generation
+/// mode and maximum out-degree are read from the `c Generation mode` and
+/// `c Maximum out-degree` stamps of [`HeaderOptions`](crate::HeaderOptions)
+/// too; other [`GenerateOptions`], such as skeleton cost ranges or supply
+/// distributions, leave no trace in the header, so their files are
+/// [`VerifyOutcome::HeaderOnly`]. Header comments
+/// are not compared, so files written with extra
+/// [`HeaderOptions`](crate::HeaderOptions) still match, but other
+/// [`DimacsOptions`](crate::DimacsOptions) change the network section. A
+/// file that is not UTF-8 text is [`VerifyOutcome::NotNetgen`].
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, VerifyOutcome, to_dimacs_string, verify_instance};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let dimacs = to_dimacs_string(13502460, 1, &params).unwrap();
+/// assert_eq!(verify_instance(dimacs.as_bytes()).unwrap(), VerifyOutcome::Match);
+///
+/// let edited = dimacs.replacen("\na ", "\na  ", 1);
+/// assert!(matches!(
+///     verify_instance(edited.as_bytes()).unwrap(),
+///     VerifyOutcome::HeaderOnly { .. }
+/// ));
+/// ```
+pub fn verify_instance(mut file: impl BufRead) -> Result<VerifyOutcome, VerifyError> {
+    let mut text = String::new();
+    match file.read_to_string(&mut text) {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => return Ok(VerifyOutcome::NotNetgen),
+        Err(e) => return Err(VerifyError::Io(e)),
+    }
+    let Some((seed, _, params)) = parse_header(text.as_bytes()) else {
+        return Ok(VerifyOutcome::NotNetgen);
+    };
+    let Ok((result, _)) = generate_with_options(seed, &params, &stamped_options(&text)) else {
+        return Ok(VerifyOutcome::NotNetgen);
+    };
+    let mut expected = Vec::new();
+    crate::write_dimacs_network(&mut expected, &params, &result).map_err(VerifyError::Io)?;
+    let expected = String::from_utf8(expected).expect("DIMACS output is ASCII");
+
+    // The network section starts with the comments before the `p` line.
+    let is_problem = |line: &&str| line.starts_with("p ");
+    let expected: Vec<&str> = expected.lines().collect();
+    let lead = expected.iter().position(is_problem).unwrap_or(0);
+    let lines: Vec<&str> = text.lines().collect();
+    let start = match lines.iter().position(is_problem) {
+        Some(p) => p.saturating_sub(lead),
+        None => lines.len(),
+    };
+    let mut found = lines[start..].iter();
+    let mut expected = expected.into_iter();
+    let mut line = start;
+    loop {
+        line += 1;
+        match (found.next(), expected.next()) {
+            (None, None) => return Ok(VerifyOutcome::Match),
+            (a, b) if a != b.as_ref() => return Ok(VerifyOutcome::HeaderOnly { line }),
+            _ => {}
+        }
+    }
+}
+
+/// The generation options stamped in the header comments of `text`: the
+/// `c Generation mode` and `c Maximum out-degree` lines that
+/// [`HeaderOptions`](crate::HeaderOptions) writes.
+fn stamped_options(text: &str) -> GenerateOptions {
+    let mut options = GenerateOptions::default();
+    for line in text.lines() {
+        let Some(comment) = line.strip_prefix('c') else {
+            if line.trim().is_empty() {
+                continue;
+            }
+            break;
+        };
+        let comment = comment.trim();
+        if comment.starts_with("Generation mode: fast") {
+            options.mode = GenerationMode::Fast;
+        } else if let Some(value) = comment.strip_prefix("Maximum out-degree:") {
+            options.max_out_degree = value.trim().parse().ok();
+        }
+    }
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_problem_type() {
+        let min = "c comment\np min 3 2\nn 1 4\nn 3 -4\na 1 2 0 5 3\na 2 3 0 5 4\n";
+        let result = parse_dimacs(min).unwrap();
+        assert_eq!(result.supply, [4, 0, -4]);
+        assert_eq!(
+            result.arcs[1],
+            Arc {
+                from: 2,
+                to: 3,
+                cost: 4,
+                capacity: 5,
+                multiplier: None,
+                uncapacitated: false,
+                kind: ArcKind::Unknown,
+                hicost: false,
+            }
+        );
+
+        let max = parse_dimacs("p max 3 2\nn 1 s\nn 3 t\na 1 3 7\na 1 2 1\n").unwrap();
+        assert_eq!(max.supply, [1, 0, -1]);
+        assert_eq!((max.arcs[0].cost, max.arcs[0].capacity), (0, 7));
+        // The stand-in supply 1 is not the total supply.
+        assert_eq!(max.uncapacitated_arc_count(), 0);
+
+        let asn = parse_dimacs("p asn 2 1\nn 1\na 1 2 9\n").unwrap();
+        assert_eq!(asn.supply, [1, -1]);
+        assert_eq!((asn.arcs[0].cost, asn.arcs[0].capacity), (9, 1));
+        assert_eq!(asn.uncapacitated_arc_count(), 0);
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            parse_dimacs("a 1 2 0 5 3\n"),
+            Err(DimacsError::MissingProblemLine)
+        );
+        assert_eq!(
+            parse_dimacs("p min 2 1\na 1 3 0 5 3\n"),
+            Err(DimacsError::NodeOutOfRange { line: 2, node: 3 })
+        );
+        assert_eq!(
+            parse_dimacs("p min 2 1\na 1 2 1 5 3\n"),
+            Err(DimacsError::LowerBound { line: 2, lower: 1 })
+        );
+        assert_eq!(
+            parse_dimacs("p min 2 1\nx\n"),
+            Err(DimacsError::Malformed {
+                line: 2,
+                content: "x".to_string()
+            })
+        );
+        for p in ["p min 2 9999999999999999", "p min 9999999999999999 1"] {
+            assert_eq!(
+                parse_dimacs(&format!("{p}\na 1 2 0 5 3\n")),
+                Err(DimacsError::Malformed {
+                    line: 1,
+                    content: p.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn header_prefers_complete_param_lines() {
+        let params =
+            NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap();
+        let mut classic = String::new();
+        crate::write_header(&mut classic, 12345, 2, &params, &Default::default()).unwrap();
+        let header = |extra: &str| format!("{classic}\n{extra}p asn 100 500\nc PARAM seed 1\n");
+        assert_eq!(
+            parse_header(header("").as_bytes()),
+            Some((12345, 2, params.clone()))
+        );
+
+        let mut tagged = String::from("c PARAM seed 3\nc PARAM problem 4\n");
+        for (name, value) in PARAM_NAMES.iter().zip(params.to_array()) {
+            tagged += &format!("c PARAM {name} {value}\n");
+        }
+        let nodes = tagged.replace("nodes 100", "nodes 200");
+        let expected = NetgenParams {
+            nodes: 200,
+            ..params.clone()
+        };
+        assert_eq!(
+            parse_header(header(&nodes).as_bytes()),
+            Some((3, 4, expected))
+        );
+        // An incomplete PARAM set falls back to the classic header.
+        let partial = tagged.replace("c PARAM maxcap 100\n", "");
+        assert_eq!(
+            parse_header(header(&partial).as_bytes()),
+            Some((12345, 2, params))
+        );
+        assert_eq!(parse_header(partial.as_bytes()), None);
+        assert_eq!(parse_header(&b"p min 2 1\n"[..]), None);
+    }
+}
@@ -0,0 +1,326 @@
+//! Size estimates for an instance, computed from its parameters alone.
+
+use core::fmt;
+use core::mem::size_of;
+
+use crate::{Arc, HeaderOptions, NetgenParams, NetgenResult, ProblemType};
+
+/// What generating and writing an instance will take, as estimated by
+/// [`NetgenParams::estimate`] without generating it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeEstimate {
+    /// Arcs expected: `density`, capped at [`NetgenParams::max_arcs`]. The
+    /// reference generator can fall slightly short of it (see
+    /// [`Shortfall`](crate::Shortfall)).
+    pub arcs: i64,
+    /// Peak heap use of the generator in bytes: the arc vector while it
+    /// grows, plus the per-node arrays. Writing the output needs no more.
+    pub peak_memory_bytes: u64,
+    /// Length of the DIMACS output of [`write_dimacs`](crate::write_dimacs)
+    /// in bytes, from the expected number of digits of every field.
+    pub dimacs_bytes: u64,
+}
+
+impl NetgenParams {
+    /// Estimate the arc count, memory use and DIMACS output size of the
+    /// instance, without generating it.
+    ///
+    /// ```rust
+    /// use netgen_rs::NetgenParams;
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let estimate = params.estimate();
+    /// assert_eq!(estimate.arcs, 2000);
+    /// let actual = params.generate(13502460).unwrap().to_dimacs_string(13502460, 1, &params);
+    /// assert!(estimate.dimacs_bytes.abs_diff(actual.len() as u64) < actual.len() as u64 / 20);
+    /// ```
+    pub fn estimate(&self) -> SizeEstimate {
+        let arcs = self.density.min(self.max_arcs()).max(0);
+        SizeEstimate {
+            arcs,
+            peak_memory_bytes: self.peak_memory(arcs as u64),
+            dimacs_bytes: self.dimacs_bytes(arcs as u64),
+        }
+    }
+
+    fn peak_memory(&self, arcs: u64) -> u64 {
+        let nodes = self.nodes as u64;
+        // The arc vector doubles as it grows; at the last reallocation the
+        // old buffer (half the capacity) and the new one are both alive.
+        let capacity = arcs.next_power_of_two();
+        let arc_bytes = (capacity + capacity / 2) * size_of::<Arc>() as u64;
+        // Supplies (twice), chain predecessors and the sink flags.
+        let node_bytes = nodes * (3 * size_of::<i64>() as u64 + 1);
+        // The recorded skeleton: at most one arc and position per node.
+        let skeleton = self.nodes.min(arcs as i64) as u64;
+        let skeleton_bytes = skeleton * (size_of::<Arc>() + size_of::<usize>()) as u64;
+        arc_bytes + node_bytes + skeleton_bytes
+    }
+
+    fn dimacs_bytes(&self, arcs: u64) -> u64 {
+        let mut header = Counter(0);
+        crate::write_header(&mut header, 1, 1, self, &HeaderOptions::default())
+            .expect("counting cannot fail");
+        let problem_type = self.problem_type();
+        let title = match problem_type {
+            ProblemType::Assignment => "Assignment",
+            ProblemType::MaxFlow => "Maximum flow",
+            ProblemType::MinCostFlow => "Minimum cost flow",
+        };
+        // "\nc\nc  *** title ***\nc\np xxx nodes arcs\n"
+        let problem_line = 25 + title.len() + (digits(self.nodes) + digits(arcs as i64)) as usize;
+
+        let node_id = mean_digits(1, self.nodes);
+        let supply_per_source = self.supply / self.sources.max(1);
+        let supply_per_sink = self.supply / self.sinks.max(1);
+        let node_lines = match problem_type {
+            // "n id\n" per source.
+            ProblemType::Assignment => self.sources as f64 * (3.0 + node_id),
+            // "n id s\n" and "n id t\n".
+            ProblemType::MaxFlow => (self.sources + self.sinks) as f64 * (5.0 + node_id),
+            // "n id supply\n", demands negative.
+            ProblemType::MinCostFlow => {
+                self.sources as f64 * (4.0 + node_id + digits(supply_per_source) as f64)
+                    + self.sinks as f64 * (5.0 + node_id + digits(supply_per_sink) as f64)
+            }
+        };
+
+        let capacitated = self.capacitated_pct.clamp(0, 100) as f64 / 100.0;
+        let capacity = capacitated * mean_digits(self.mincap, self.maxcap)
+            + (1.0 - capacitated) * digits(self.supply) as f64;
+        let cost = mean_digits(self.mincost, self.maxcost);
+        let arc_line = 5.0
+            + 2.0 * node_id
+            + match problem_type {
+                // "a from to cost\n"
+                ProblemType::Assignment => cost,
+                // "a from to cap\n"
+                ProblemType::MaxFlow => capacity,
+                // "a from to 0 cap cost\n"
+                ProblemType::MinCostFlow => 3.0 + capacity + cost,
+            };
+
+        header.0 + (problem_line as f64 + node_lines + arcs as f64 * arc_line + 0.5) as u64
+    }
+}
+
+/// An upper bound on the length in bytes of the DIMACS output of `result`
+/// with default [`DimacsOptions`](crate::DimacsOptions), for any seed and
+/// problem number, e.g. for a `Content-Length` or to size a buffer.
+///
+/// The node and arc lines are measured by the digit counts of their fields,
+/// without formatting them, so the bound exceeds the actual length only by
+/// the width the seed and problem number could take in the header, a few
+/// dozen bytes. Unlike [`NetgenParams::estimate`] it needs the generated
+/// network.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, estimate_dimacs_bytes};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = params.generate(13502460).unwrap();
+/// let bound = estimate_dimacs_bytes(&params, &result);
+/// let actual = result.to_dimacs_string(13502460, 1, &params).len();
+/// assert!(actual <= bound && bound - actual < 32);
+/// ```
+pub fn estimate_dimacs_bytes(params: &NetgenParams, result: &NetgenResult) -> usize {
+    // The seed and problem number are padded to at least 10 and 2 columns,
+    // and none is wider than `i64::MIN`.
+    let mut fixed = Counter(0);
+    crate::write_header(
+        &mut fixed,
+        i64::MIN,
+        i64::MIN,
+        params,
+        &HeaderOptions::default(),
+    )
+    .and_then(|()| fmt::Write::write_char(&mut fixed, '\n'))
+    .and_then(|()| {
+        crate::write_problem(
+            &mut fixed,
+            params.nodes as usize,
+            params.problem_type(),
+            result.arcs.len(),
+        )
+    })
+    .expect("counting cannot fail");
+
+    let problem_type = params.problem_type();
+    let node_lines: usize = (1..)
+        .zip(&result.supply)
+        .map(|(node, &supply)| {
+            let id = digits(node) as usize;
+            match problem_type {
+                // "n id\n", sources only.
+                ProblemType::Assignment if supply > 0 => 3 + id,
+                // "n id s\n" or "n id t\n".
+                ProblemType::MaxFlow if supply != 0 => 5 + id,
+                // "n id supply\n".
+                ProblemType::MinCostFlow if supply != 0 => 4 + id + digits(supply) as usize,
+                _ => 0,
+            }
+        })
+        .sum();
+
+    let arc_lines: usize = result
+        .arcs
+        .iter()
+        .map(|arc| {
+            let ends = (digits(arc.from as i64) + digits(arc.to as i64)) as usize;
+            ends + match problem_type {
+                // "a from to cost\n"
+                ProblemType::Assignment => 5 + digits(arc.cost) as usize,
+                // "a from to cap\n"
+                ProblemType::MaxFlow => 5 + digits(arc.capacity) as usize,
+                // "a from to 0 cap cost\n"
+                ProblemType::MinCostFlow => 8 + (digits(arc.capacity) + digits(arc.cost)) as usize,
+            }
+        })
+        .sum();
+
+    fixed.0 as usize + node_lines + arc_lines
+}
+
+/// Decimal length of `n`, with its sign.
+pub(crate) fn digits(n: i64) -> u32 {
+    (n < 0) as u32 + n.unsigned_abs().checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Mean decimal length of the integers in `lo..=hi`, drawn uniformly.
+fn mean_digits(lo: i64, hi: i64) -> f64 {
+    if lo > hi {
+        return digits(lo) as f64;
+    }
+    // Sum the lengths decade by decade over the magnitudes on either side
+    // of zero.
+    let total_len = |from: u64, to: u64| -> u128 {
+        let mut sum = 0u128;
+        let mut start = from;
+        while start <= to {
+            let len = start.checked_ilog10().unwrap_or(0) + 1;
+            let end = 10u64.checked_pow(len).map_or(to, |p| (p - 1).min(to));
+            sum += (end - start + 1) as u128 * len as u128;
+            match end.checked_add(1) {
+                Some(next) => start = next,
+                None => break,
+            }
+        }
+        sum
+    };
+    let mut sum = 0u128;
+    if hi >= 0 {
+        sum += total_len(lo.max(0) as u64, hi as u64);
+    }
+    if lo < 0 {
+        let (near, far) = (hi.min(-1).unsigned_abs(), lo.unsigned_abs());
+        sum += total_len(near, far) + (far - near + 1) as u128;
+    }
+    sum as f64 / (hi as i128 - lo as i128 + 1) as f64
+}
+
+/// A `fmt::Write` that only counts bytes.
+struct Counter(u64);
+
+impl fmt::Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len() as u64;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::DOC_PARMS;
+    use crate::limits::MAX_SEED;
+
+    #[test]
+    fn mean_digits_counts_every_value() {
+        let brute = |lo: i64, hi: i64| {
+            (lo..=hi).map(|n| digits(n) as f64).sum::<f64>() / (hi - lo + 1) as f64
+        };
+        for (lo, hi) in [
+            (1, 1),
+            (1, 9),
+            (1, 10),
+            (5, 500),
+            (0, 99),
+            (-12, 7),
+            (-100, -3),
+        ] {
+            assert!(
+                (mean_digits(lo, hi) - brute(lo, hi)).abs() < 1e-9,
+                "{lo}..={hi}"
+            );
+        }
+        assert_eq!(digits(i64::MIN), 20);
+        assert!(mean_digits(i64::MIN, i64::MAX) > 18.0);
+    }
+
+    #[test]
+    fn estimates_are_close_to_the_generated_output() {
+        for parms in [
+            DOC_PARMS,
+            [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+            [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+            [
+                8192, 50, 50, 50000, 1, 1000, 10000, 10, 10, 25, 75, 100, 5000,
+            ],
+        ] {
+            let params = NetgenParams::from_slice(&parms).unwrap();
+            let result = params.generate(13502460).unwrap();
+            let estimate = params.estimate();
+            assert_eq!(estimate.arcs, params.density, "{parms:?}");
+            let actual = result.to_dimacs_string(13502460, 1, &params).len() as u64;
+            let error = estimate.dimacs_bytes.abs_diff(actual);
+            assert!(
+                error < actual / 20,
+                "{parms:?}: {} vs {actual}",
+                estimate.dimacs_bytes
+            );
+            let arcs = result.arcs.len() * size_of::<Arc>();
+            assert!(estimate.peak_memory_bytes as usize >= arcs, "{parms:?}");
+        }
+    }
+
+    #[test]
+    fn dimacs_bound_is_tight() {
+        for parms in [
+            DOC_PARMS,
+            [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+            [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+            [1000, 20, 20, 8000, -50, 50, 100000, 0, 0, 30, 60, 1, 9],
+            [
+                8192, 50, 50, 50000, 1, 1000, 10000, 10, 10, 25, 75, 100, 5000,
+            ],
+        ] {
+            let params = NetgenParams::from_slice(&parms).unwrap();
+            let result = params.generate(13502460).unwrap();
+            let bound = estimate_dimacs_bytes(&params, &result);
+            // Preallocating does not change the text.
+            let mut unsized_out = alloc::string::String::new();
+            let options = crate::DimacsOptions::default();
+            crate::write_dimacs_fmt(&mut unsized_out, 7, 3, &params, &result, &options).unwrap();
+            assert_eq!(result.to_dimacs_string(7, 3, &params), unsized_out);
+            for (seed, problem) in [
+                (1, 1),
+                (13502460, 42),
+                (MAX_SEED, i64::MAX),
+                (-1, i64::MIN),
+                (i64::MIN, i64::MIN),
+            ] {
+                let actual = result.to_dimacs_string(seed, problem, &params).len();
+                assert!(actual <= bound, "{parms:?}: {bound} < {actual}");
+                assert!(
+                    bound - actual < actual / 4,
+                    "{parms:?}: {bound} vs {actual}"
+                );
+                assert!(bound - actual < 32, "{parms:?}: {bound} vs {actual}");
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! C interface mirroring the original `netgen(seed, parms)` entry point,
+//! for tools that link against netgen.c. The declarations are in
+//! `include/netgen_rs.h`.
+//!
+//! Arrays handed out in a [`NetgenCResult`] are allocated by Rust and must
+//! be released with [`netgen_free_result`], never with `free`.
+
+use std::ffi::{CStr, c_char};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ptr;
+
+use crate::{Arc, ArcKind, NetgenError, NetgenParams, NetgenResult, generate, write_dimacs};
+
+/// Success.
+pub const NETGEN_OK: i32 = 0;
+/// [`NetgenError::BadSeed`], `BAD_SEED` in netgen.h.
+pub const NETGEN_BAD_SEED: i32 = -1;
+/// [`NetgenError::TooBig`], `TOO_BIG` in netgen.h.
+pub const NETGEN_TOO_BIG: i32 = -2;
+/// [`NetgenError::BadParms`], `BAD_PARMS` in netgen.h, and
+/// [`NetgenError::Options`].
+pub const NETGEN_BAD_PARMS: i32 = -3;
+/// [`NetgenError::AllocationFailure`], `ALLOCATION_FAILURE` in netgen.h.
+pub const NETGEN_ALLOCATION_FAILURE: i32 = -4;
+/// [`NetgenError::ArcShortfall`].
+pub const NETGEN_ARC_SHORTFALL: i32 = -5;
+/// [`NetgenError::Disconnected`].
+pub const NETGEN_DISCONNECTED: i32 = -6;
+/// [`NetgenError::Cancelled`].
+pub const NETGEN_CANCELLED: i32 = -7;
+/// A required pointer argument was null, or the path was not UTF-8.
+pub const NETGEN_INVALID_ARGUMENT: i32 = -8;
+/// Writing the output file failed.
+pub const NETGEN_IO_ERROR: i32 = -9;
+
+/// The code returned for `error`.
+pub fn error_code(error: &NetgenError) -> i32 {
+    match error {
+        NetgenError::BadSeed => NETGEN_BAD_SEED,
+        NetgenError::TooBig => NETGEN_TOO_BIG,
+        NetgenError::BadParms | NetgenError::Options(_) => NETGEN_BAD_PARMS,
+        NetgenError::AllocationFailure => NETGEN_ALLOCATION_FAILURE,
+        NetgenError::ArcShortfall { .. } => NETGEN_ARC_SHORTFALL,
+        NetgenError::Disconnected { .. } => NETGEN_DISCONNECTED,
+        NetgenError::Cancelled => NETGEN_CANCELLED,
+    }
+}
+
+/// An arc, with the fields of the C generator's `FROM`, `TO`, `C` and `U`
+/// arrays.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetgenCArc {
+    pub from: i64,
+    pub to: i64,
+    pub cost: i64,
+    pub cap: i64,
+}
+
+/// A generated network. `arcs` holds `arc_count` arcs and `supply` holds
+/// `node_count` supplies, node 1 first. Both are owned by the library
+/// until passed to [`netgen_free_result`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct NetgenCResult {
+    pub arcs: *mut NetgenCArc,
+    pub arc_count: usize,
+    pub supply: *mut i64,
+    pub node_count: usize,
+}
+
+impl NetgenCResult {
+    fn empty() -> Self {
+        NetgenCResult {
+            arcs: ptr::null_mut(),
+            arc_count: 0,
+            supply: ptr::null_mut(),
+            node_count: 0,
+        }
+    }
+
+    fn from_result(result: NetgenResult) -> Self {
+        let arcs: Box<[NetgenCArc]> = result
+            .arcs
+            .iter()
+            .map(|a| NetgenCArc {
+                from: a.from as i64,
+                to: a.to as i64,
+                cost: a.cost,
+                cap: a.capacity,
+            })
+            .collect();
+        let supply = result.supply.into_boxed_slice();
+        NetgenCResult {
+            arc_count: arcs.len(),
+            arcs: Box::into_raw(arcs).cast(),
+            node_count: supply.len(),
+            supply: Box::into_raw(supply).cast(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The arrays must be valid for their counts (or null with count 0).
+    unsafe fn to_result(&self) -> NetgenResult {
+        let (arcs, supply) = unsafe {
+            (
+                slice_or_empty(self.arcs, self.arc_count),
+                slice_or_empty(self.supply, self.node_count),
+            )
+        };
+        let mut result = NetgenResult {
+            arcs: arcs
+                .iter()
+                .map(|a| Arc {
+                    from: a.from as u64,
+                    to: a.to as u64,
+                    cost: a.cost,
+                    capacity: a.cap,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                })
+                .collect(),
+            supply: supply.to_vec(),
+        };
+        result.infer_uncapacitated();
+        result
+    }
+}
+
+/// # Safety
+///
+/// `data` must be null or valid for `len` reads.
+unsafe fn slice_or_empty<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if data.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(data, len) }
+    }
+}
+
+/// # Safety
+///
+/// `parms` must be valid for 13 reads.
+unsafe fn read_parms(parms: *const i64) -> NetgenParams {
+    let parms = unsafe { std::slice::from_raw_parts(parms, 13) };
+    NetgenParams {
+        nodes: parms[0],
+        sources: parms[1],
+        sinks: parms[2],
+        density: parms[3],
+        mincost: parms[4],
+        maxcost: parms[5],
+        supply: parms[6],
+        tsources: parms[7],
+        tsinks: parms[8],
+        hicost_pct: parms[9],
+        capacitated_pct: parms[10],
+        mincap: parms[11],
+        maxcap: parms[12],
+    }
+}
+
+/// Generate the network for `seed` and the 13 values of `parms`, in the
+/// order of netgen.c's `parms[]`, into `*out`.
+///
+/// Returns [`NETGEN_OK`] or a negative error code; on error `*out` is
+/// left empty (null arrays, zero counts). Free a successful result with
+/// [`netgen_free_result`].
+///
+/// # Safety
+///
+/// `parms` must point to 13 readable values and `out` to a writable
+/// [`NetgenCResult`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn netgen_generate(
+    seed: i64,
+    parms: *const i64,
+    out: *mut NetgenCResult,
+) -> i32 {
+    if parms.is_null() || out.is_null() {
+        return NETGEN_INVALID_ARGUMENT;
+    }
+    let params = unsafe { read_parms(parms) };
+    let (code, result) = match generate(seed, &params) {
+        Ok(result) => (NETGEN_OK, NetgenCResult::from_result(result)),
+        Err(e) => (error_code(&e), NetgenCResult::empty()),
+    };
+    unsafe { out.write(result) };
+    code
+}
+
+/// Release the arrays of a result filled by [`netgen_generate`] and reset
+/// it to empty. Freeing an empty result, or passing null, does nothing.
+///
+/// # Safety
+///
+/// `result` must be null or point to a result filled by
+/// [`netgen_generate`], or reset by this function, whose fields were not
+/// modified since.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn netgen_free_result(result: *mut NetgenCResult) {
+    let Some(result) = (unsafe { result.as_mut() }) else {
+        return;
+    };
+    if !result.arcs.is_null() {
+        let arcs = ptr::slice_from_raw_parts_mut(result.arcs, result.arc_count);
+        drop(unsafe { Box::from_raw(arcs) });
+    }
+    if !result.supply.is_null() {
+        let supply = ptr::slice_from_raw_parts_mut(result.supply, result.node_count);
+        drop(unsafe { Box::from_raw(supply) });
+    }
+    *result = NetgenCResult::empty();
+}
+
+/// Write `result` as DIMACS to the file at `path`, with the header for
+/// `seed`, problem number `problem` and `parms`, exactly as
+/// [`write_dimacs`] does.
+///
+/// Returns [`NETGEN_OK`], [`NETGEN_INVALID_ARGUMENT`] for null pointers or
+/// a non-UTF-8 path, or [`NETGEN_IO_ERROR`].
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string, `parms` must point to 13
+/// readable values and `result` to a valid result, such as one filled by
+/// [`netgen_generate`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn netgen_write_dimacs_file(
+    path: *const c_char,
+    seed: i64,
+    problem: i64,
+    parms: *const i64,
+    result: *const NetgenCResult,
+) -> i32 {
+    if path.is_null() || parms.is_null() || result.is_null() {
+        return NETGEN_INVALID_ARGUMENT;
+    }
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return NETGEN_INVALID_ARGUMENT;
+    };
+    let params = unsafe { read_parms(parms) };
+    let result = unsafe { (*result).to_result() };
+    let written = File::create(path).and_then(|file| {
+        let mut w = BufWriter::new(file);
+        write_dimacs(&mut w, seed, problem, &params, &result)?;
+        w.flush()
+    });
+    match written {
+        Ok(()) => NETGEN_OK,
+        Err(_) => NETGEN_IO_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{DOC_PARMS, doc_params, doc_result};
+    use crate::to_dimacs_string;
+
+    #[test]
+    fn generate_round_trips_and_frees() {
+        let mut out = NetgenCResult::empty();
+        let code = unsafe { netgen_generate(13502460, DOC_PARMS.as_ptr(), &mut out) };
+        assert_eq!(code, NETGEN_OK);
+        let expected = doc_result();
+        assert_eq!((out.arc_count, out.node_count), (2000, 512));
+        assert_eq!(
+            unsafe { out.to_result() }.canonicalize(),
+            expected.canonicalize()
+        );
+        unsafe { netgen_free_result(&mut out) };
+        assert!(out.arcs.is_null() && out.supply.is_null());
+        // A second free is harmless.
+        unsafe { netgen_free_result(&mut out) };
+        unsafe { netgen_free_result(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn errors_map_to_codes() {
+        let mut out = NetgenCResult::empty();
+        assert_eq!(
+            unsafe { netgen_generate(0, DOC_PARMS.as_ptr(), &mut out) },
+            NETGEN_BAD_SEED
+        );
+        assert!(out.arcs.is_null());
+        let mut bad = DOC_PARMS;
+        bad[1] = 600;
+        assert_eq!(
+            unsafe { netgen_generate(1, bad.as_ptr(), &mut out) },
+            NETGEN_BAD_PARMS
+        );
+        assert_eq!(
+            unsafe { netgen_generate(1, ptr::null(), &mut out) },
+            NETGEN_INVALID_ARGUMENT
+        );
+        assert_eq!(error_code(&NetgenError::Cancelled), NETGEN_CANCELLED);
+    }
+
+    #[test]
+    fn writes_the_same_dimacs() {
+        let mut out = NetgenCResult::empty();
+        unsafe { netgen_generate(42, DOC_PARMS.as_ptr(), &mut out) };
+        let path = std::env::temp_dir().join(format!("netgen_ffi_{}.min", std::process::id()));
+        let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+        let code =
+            unsafe { netgen_write_dimacs_file(c_path.as_ptr(), 42, 3, DOC_PARMS.as_ptr(), &out) };
+        assert_eq!(code, NETGEN_OK);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let params = doc_params();
+        assert_eq!(written, to_dimacs_string(42, 3, &params).unwrap());
+        unsafe { netgen_free_result(&mut out) };
+    }
+}
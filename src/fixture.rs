@@ -0,0 +1,18 @@
+//! The instance of the crate's examples, shared by the unit tests.
+
+use crate::{NetgenParams, NetgenResult, generate};
+
+/// The parameters of the examples: a min-cost flow problem on 512 nodes
+/// and 2000 arcs.
+pub(crate) const DOC_PARMS: [i64; 13] = [512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000];
+
+/// [`DOC_PARMS`] as parameters.
+pub(crate) fn doc_params() -> NetgenParams {
+    NetgenParams::from_slice(&DOC_PARMS).unwrap()
+}
+
+/// The network the examples generate from [`doc_params`], with seed
+/// 13502460.
+pub(crate) fn doc_result() -> NetgenResult {
+    generate(13502460, &doc_params()).unwrap()
+}
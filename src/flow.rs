@@ -0,0 +1,236 @@
+//! Feasible flows for generated networks, their verification, and the DIMACS
+//! flow solution format.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::NetgenResult;
+use crate::analysis::Residual;
+
+/// Why a flow is not feasible for a network (see [`verify_flow`]).
+///
+/// Arc indices are 0-based positions in [`NetgenResult::arcs`]; nodes are
+/// 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlowError {
+    /// The flow does not have one entry per arc.
+    Length { expected: usize, found: usize },
+    /// An arc's flow is negative or above its capacity.
+    OutOfBounds {
+        arc: usize,
+        flow: i64,
+        capacity: i64,
+    },
+    /// Outflow minus inflow at a node differs from its supply.
+    Conservation { node: u64, net: i64, supply: i64 },
+    /// An arc's flow is below its lower bound (see
+    /// [`Circulation::verify_flow`](crate::Circulation::verify_flow)).
+    BelowLower { arc: usize, flow: i64, lower: i64 },
+}
+
+impl fmt::Display for FlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowError::Length { expected, found } => {
+                write!(f, "flow has {found} entries, expected {expected}")
+            }
+            FlowError::OutOfBounds {
+                arc,
+                flow,
+                capacity,
+            } => write!(f, "arc {arc} carries {flow}, outside 0..={capacity}"),
+            FlowError::Conservation { node, net, supply } => {
+                write!(f, "node {node} has net outflow {net}, expected {supply}")
+            }
+            FlowError::BelowLower { arc, flow, lower } => {
+                write!(f, "arc {arc} carries {flow}, below its lower bound {lower}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FlowError {}
+
+/// Check that `flow` (one entry per arc) is feasible for `result`: every
+/// arc carries between 0 and its capacity, and outflow minus inflow at each
+/// node equals its supply.
+pub fn verify_flow(result: &NetgenResult, flow: &[i64]) -> Result<(), FlowError> {
+    if flow.len() != result.arcs.len() {
+        return Err(FlowError::Length {
+            expected: result.arcs.len(),
+            found: flow.len(),
+        });
+    }
+    let mut net = vec![0i64; result.supply.len()];
+    for (arc, (a, &f)) in result.arcs.iter().zip(flow).enumerate() {
+        if f < 0 || f > a.capacity {
+            return Err(FlowError::OutOfBounds {
+                arc,
+                flow: f,
+                capacity: a.capacity,
+            });
+        }
+        net[a.from as usize - 1] += f;
+        net[a.to as usize - 1] -= f;
+    }
+    for (i, (&net, &supply)) in net.iter().zip(&result.supply).enumerate() {
+        if net != supply {
+            return Err(FlowError::Conservation {
+                node: i as u64 + 1,
+                net,
+                supply,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Write `flow` in the DIMACS flow solution format: an `s <objective>`
+/// line with the total cost, then one `f <from> <to> <flow>` line per arc
+/// in [`NetgenResult::arcs`] order, zero flows included.
+pub fn write_dimacs_flow(
+    w: &mut impl Write,
+    result: &NetgenResult,
+    flow: &[i64],
+) -> io::Result<()> {
+    let objective: i64 = result.arcs.iter().zip(flow).map(|(a, f)| a.cost * f).sum();
+    writeln!(w, "s {objective}")?;
+    for (arc, f) in result.arcs.iter().zip(flow) {
+        writeln!(w, "f {} {} {f}", arc.from, arc.to)?;
+    }
+    Ok(())
+}
+
+impl NetgenResult {
+    /// A feasible flow, one entry per arc, for warm-starting solvers.
+    ///
+    /// NETGEN's skeleton guarantees that the supplies can be routed within
+    /// the capacities. The flow is found by a maximum flow computation
+    /// from the sources to the sinks, so it also works for networks edited
+    /// after generation. It is feasible but not, in general, of minimum
+    /// cost. If the network cannot carry its supplies, the flow falls short
+    /// and [`verify_flow`] rejects it.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate, verify_flow};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let flow = result.skeleton_flow();
+    /// assert_eq!(verify_flow(&result, &flow), Ok(()));
+    /// ```
+    pub fn skeleton_flow(&self) -> Vec<i64> {
+        let nodes = self.supply.len();
+        let mut graph = Residual::new(nodes + 2);
+        for arc in &self.arcs {
+            graph.add(
+                arc.from as usize - 1,
+                arc.to as usize - 1,
+                arc.capacity.max(0),
+            );
+        }
+        let (source, sink) = (nodes, nodes + 1);
+        for (v, &s) in self.supply.iter().enumerate() {
+            if s > 0 {
+                graph.add(source, v, s);
+            } else if s < 0 {
+                graph.add(v, sink, -s);
+            }
+        }
+        graph.max_flow(source, sink);
+        // Arc `k` is edge `2k`; its reverse edge holds the flow pushed.
+        (0..self.arcs.len()).map(|k| graph.cap[2 * k + 1]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::DOC_PARMS;
+    use crate::{Arc, ArcKind, NetgenParams, generate};
+
+    #[test]
+    fn skeleton_flow_is_feasible() {
+        let cases: [&[i64]; 4] = [
+            &DOC_PARMS,
+            &[256, 16, 16, 2048, 1, 10000, 16000, 0, 0, 100, 100, 1, 1000],
+            &[1000, 50, 30, 5000, 1, 100, 20000, 10, 5, 30, 100, 1, 50],
+            &[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+        ];
+        for parms in cases {
+            let params = NetgenParams::from_slice(parms).unwrap();
+            for seed in [1, 42, 13502460, 2147483646] {
+                let result = generate(seed, &params).unwrap();
+                let flow = result.skeleton_flow();
+                assert_eq!(verify_flow(&result, &flow), Ok(()), "{params} seed {seed}");
+
+                let mut out = Vec::new();
+                write_dimacs_flow(&mut out, &result, &flow).unwrap();
+                let out = String::from_utf8(out).unwrap();
+                let objective: i64 = result.arcs.iter().zip(&flow).map(|(a, f)| a.cost * f).sum();
+                assert_eq!(out.lines().next(), Some(format!("s {objective}").as_str()));
+                assert_eq!(out.lines().count(), 1 + result.arcs.len());
+            }
+        }
+    }
+
+    #[test]
+    fn verify_flow_reports_violations() {
+        let result = NetgenResult {
+            arcs: vec![
+                Arc {
+                    from: 1,
+                    to: 2,
+                    cost: 3,
+                    capacity: 5,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                },
+                Arc {
+                    from: 2,
+                    to: 3,
+                    cost: 4,
+                    capacity: 5,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                },
+            ],
+            supply: vec![4, 0, -4],
+        };
+        assert_eq!(result.skeleton_flow(), [4, 4]);
+        assert_eq!(verify_flow(&result, &[4, 4]), Ok(()));
+        assert_eq!(
+            verify_flow(&result, &[4]),
+            Err(FlowError::Length {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            verify_flow(&result, &[6, 6]),
+            Err(FlowError::OutOfBounds {
+                arc: 0,
+                flow: 6,
+                capacity: 5
+            })
+        );
+        assert_eq!(
+            verify_flow(&result, &[4, 3]),
+            Err(FlowError::Conservation {
+                node: 2,
+                net: -1,
+                supply: 0
+            })
+        );
+
+        let mut out = Vec::new();
+        write_dimacs_flow(&mut out, &result, &[4, 4]).unwrap();
+        assert_eq!(out, b"s 28\nf 1 2 4\nf 2 3 4\n");
+    }
+}
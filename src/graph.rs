@@ -0,0 +1,120 @@
+//! Conversion to and from [`petgraph`] directed graphs.
+//!
+//! NETGEN node `id` (1-based) is petgraph node `NodeIndex::new(id - 1)`, and
+//! arcs become edges in generation order, so `EdgeIndex::new(k)` is
+//! `result.arcs[k]`.
+
+use std::fmt;
+
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::{Arc, ArcKind, NetgenResult};
+
+/// Node weight of [`NetgenResult::to_petgraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeData {
+    /// Supply (positive) or demand (negative).
+    pub supply: i64,
+}
+
+/// Edge weight of [`NetgenResult::to_petgraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArcData {
+    pub cost: i64,
+    pub capacity: i64,
+    /// Lower bound on the flow. NETGEN arcs always have 0.
+    pub lower: i64,
+}
+
+/// Error from [`NetgenResult::from_petgraph`]: an edge has a nonzero lower
+/// bound, which a NETGEN network cannot represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowerBoundError {
+    /// Index of the offending edge.
+    pub edge: usize,
+    pub lower: i64,
+}
+
+impl fmt::Display for LowerBoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "edge {} has lower bound {}; NETGEN arcs must have 0",
+            self.edge, self.lower
+        )
+    }
+}
+
+impl std::error::Error for LowerBoundError {}
+
+impl NetgenResult {
+    /// Build a petgraph [`DiGraph`] with one node per NETGEN node and one
+    /// edge per arc, parallel arcs included.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, NetgenResult, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let graph = result.to_petgraph();
+    /// assert_eq!(graph.node_count(), 512);
+    /// let back = NetgenResult::from_petgraph(&graph).unwrap();
+    /// assert_eq!(back.canonicalize(), result.canonicalize());
+    /// ```
+    pub fn to_petgraph(&self) -> DiGraph<NodeData, ArcData> {
+        let mut graph = DiGraph::with_capacity(self.supply.len(), self.arcs.len());
+        for &supply in &self.supply {
+            graph.add_node(NodeData { supply });
+        }
+        for arc in &self.arcs {
+            graph.add_edge(
+                NodeIndex::new(arc.from as usize - 1),
+                NodeIndex::new(arc.to as usize - 1),
+                ArcData {
+                    cost: arc.cost,
+                    capacity: arc.capacity,
+                    lower: 0,
+                },
+            );
+        }
+        graph
+    }
+
+    /// Rebuild a network from a graph in the layout of
+    /// [`to_petgraph`](Self::to_petgraph), e.g. to write an edited graph
+    /// with [`write_dimacs`](crate::write_dimacs).
+    ///
+    /// Node `NodeIndex::new(i)` becomes NETGEN node `i + 1`; arcs keep the
+    /// edge order. Fails if any edge has a nonzero lower bound.
+    pub fn from_petgraph(graph: &DiGraph<NodeData, ArcData>) -> Result<Self, LowerBoundError> {
+        let arcs = graph
+            .raw_edges()
+            .iter()
+            .enumerate()
+            .map(|(edge, e)| {
+                if e.weight.lower != 0 {
+                    return Err(LowerBoundError {
+                        edge,
+                        lower: e.weight.lower,
+                    });
+                }
+                Ok(Arc {
+                    from: e.source().index() as u64 + 1,
+                    to: e.target().index() as u64 + 1,
+                    cost: e.weight.cost,
+                    capacity: e.weight.capacity,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let supply = graph.raw_nodes().iter().map(|n| n.weight.supply).collect();
+        let mut result = NetgenResult { arcs, supply };
+        result.infer_uncapacitated();
+        Ok(result)
+    }
+}
@@ -0,0 +1,83 @@
+//! GraphML export for Gephi, networkx and yEd.
+
+use std::io::{self, Write};
+
+use crate::NetgenResult;
+
+/// Options for [`write_graphml_with_options`].
+///
+/// The default writes every attribute of every node and arc.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphmlOptions {
+    /// Omit the `supply` attribute of transshipment nodes. The key declares
+    /// a default of 0, so readers still see their supply as 0.
+    pub skip_zero_supply: bool,
+}
+
+/// Write a network as a directed GraphML document.
+///
+/// Nodes are `n1..nN` (the NETGEN ids) with an integer `supply` attribute;
+/// arcs are edges `e1..eM` in generation order with integer `cost`,
+/// `capacity` and `lower` attributes (`lower` is always 0).
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate, write_graphml};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut xml = Vec::new();
+/// write_graphml(&mut xml, &result).unwrap();
+/// ```
+pub fn write_graphml(w: &mut impl Write, result: &NetgenResult) -> io::Result<()> {
+    write_graphml_with_options(w, result, &GraphmlOptions::default())
+}
+
+/// Write a network as GraphML, with [`GraphmlOptions`].
+pub fn write_graphml_with_options(
+    w: &mut impl Write,
+    result: &NetgenResult,
+    options: &GraphmlOptions,
+) -> io::Result<()> {
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="supply" for="node" attr.name="supply" attr.type="long"><default>0</default></key>"#
+    )?;
+    for name in ["cost", "capacity", "lower"] {
+        writeln!(
+            w,
+            r#"  <key id="{name}" for="edge" attr.name="{name}" attr.type="long"/>"#
+        )?;
+    }
+    writeln!(w, r#"  <graph id="G" edgedefault="directed">"#)?;
+    for (i, &supply) in result.supply.iter().enumerate() {
+        if supply == 0 && options.skip_zero_supply {
+            writeln!(w, r#"    <node id="n{}"/>"#, i + 1)?;
+        } else {
+            writeln!(
+                w,
+                r#"    <node id="n{}"><data key="supply">{supply}</data></node>"#,
+                i + 1
+            )?;
+        }
+    }
+    for (k, arc) in result.arcs.iter().enumerate() {
+        writeln!(
+            w,
+            r#"    <edge id="e{}" source="n{}" target="n{}"><data key="cost">{}</data><data key="capacity">{}</data><data key="lower">0</data></edge>"#,
+            k + 1,
+            arc.from,
+            arc.to,
+            arc.cost,
+            arc.capacity
+        )?;
+    }
+    writeln!(w, "  </graph>")?;
+    writeln!(w, "</graphml>")
+}
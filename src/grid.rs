@@ -0,0 +1,241 @@
+//! Grid networks in the style of the DIMACS challenge GOTO and grid
+//! generators, built on the NETGEN [`Rng`] and [`NetgenResult`].
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::random::Rng;
+use crate::{Arc, ArcKind, NetgenError, NetgenParams, NetgenResult};
+
+/// Parameters of a grid network (see [`generate_grid`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridParams {
+    /// Number of columns.
+    pub width: usize,
+    /// Number of rows.
+    pub height: usize,
+    /// Range of arc costs.
+    pub cost_range: RangeInclusive<i64>,
+    /// Range of arc capacities.
+    pub cap_range: RangeInclusive<i64>,
+    /// Supply of the source corner, and demand of the sink corner.
+    pub supply: i64,
+    /// Join the first and last row and column, making a torus.
+    pub wrap: bool,
+}
+
+impl GridParams {
+    /// Number of nodes, `width * height`.
+    pub fn nodes(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Number of arcs: two per pair of neighbouring nodes,
+    /// `2 * ((width - 1) * height + width * (height - 1))`, or
+    /// `4 * width * height` with wrapping.
+    pub fn arcs(&self) -> usize {
+        if self.wrap {
+            4 * self.width * self.height
+        } else {
+            2 * ((self.width - 1) * self.height + self.width * (self.height - 1))
+        }
+    }
+
+    /// Whether the parameters describe a grid [`generate_grid`] can build:
+    /// at least 2 rows and columns (3 with wrapping, so wrap arcs do not
+    /// duplicate neighbours), at most `u32::MAX` nodes, non-empty ranges,
+    /// non-negative capacities and positive supply.
+    pub fn is_valid(&self) -> bool {
+        let min_side = if self.wrap { 3 } else { 2 };
+        self.width >= min_side
+            && self.height >= min_side
+            && self
+                .width
+                .checked_mul(self.height)
+                .is_some_and(|n| n <= u32::MAX as usize)
+            && !self.cost_range.is_empty()
+            && !self.cap_range.is_empty()
+            && *self.cap_range.start() >= 0
+            && self.supply > 0
+    }
+
+    /// NETGEN parameters describing the grid, for the DIMACS writers and
+    /// [`NetgenResult::check_invariants`].
+    ///
+    /// Node 1 is the single source and the last node the single sink; both
+    /// count as transshipment nodes since grid arcs enter and leave them.
+    /// Every arc is capacitated and none is forced to the maximum cost.
+    pub fn netgen_params(&self) -> NetgenParams {
+        NetgenParams {
+            nodes: self.nodes() as i64,
+            sources: 1,
+            sinks: 1,
+            density: self.arcs() as i64,
+            mincost: *self.cost_range.start(),
+            maxcost: *self.cost_range.end(),
+            supply: self.supply,
+            tsources: 1,
+            tsinks: 1,
+            hicost_pct: 0,
+            capacitated_pct: 100,
+            mincap: *self.cap_range.start(),
+            maxcap: *self.cap_range.end(),
+        }
+    }
+}
+
+/// Generate a grid network.
+///
+/// Nodes are numbered row by row from 1, so node `y * width + x + 1` sits
+/// in column `x` of row `y`. The top-left corner (node 1) supplies
+/// `supply` and the bottom-right corner (the last node) demands it. Each
+/// node has arcs to its right, left, lower and upper neighbours, in that
+/// order, wrapping around the edges in `wrap` mode. Every arc draws a cost
+/// and then a capacity from `Rng::new(seed)`.
+///
+/// To keep the instance feasible, the arcs along the top row and then down
+/// the last column carry at least `supply`.
+///
+/// Fails with [`NetgenError::BadSeed`] for a non-positive seed and
+/// [`NetgenError::BadParms`] if [`GridParams::is_valid`] does not hold.
+///
+/// ```rust
+/// use netgen_rs::{GridParams, generate_grid};
+///
+/// let params = GridParams {
+///     width: 8,
+///     height: 4,
+///     cost_range: 1..=100,
+///     cap_range: 10..=50,
+///     supply: 200,
+///     wrap: false,
+/// };
+/// let result = generate_grid(42, &params).unwrap();
+/// assert_eq!(result.arcs.len(), params.arcs());
+/// let dimacs = result.to_dimacs_string(42, 1, &params.netgen_params());
+/// assert!(dimacs.contains("p min 32 104"));
+/// ```
+pub fn generate_grid(seed: i64, params: &GridParams) -> Result<NetgenResult, NetgenError> {
+    if seed <= 0 {
+        return Err(NetgenError::BadSeed);
+    }
+    if !params.is_valid() {
+        return Err(NetgenError::BadParms);
+    }
+    let (w, h) = (params.width, params.height);
+    let node = |x: usize, y: usize| (y * w + x + 1) as u64;
+    let mut rng = Rng::new(seed);
+    let mut arcs = Vec::with_capacity(params.arcs());
+    for y in 0..h {
+        for x in 0..w {
+            let neighbours = [
+                (x + 1 < w || params.wrap).then(|| ((x + 1) % w, y)),
+                (x > 0 || params.wrap).then(|| ((x + w - 1) % w, y)),
+                (y + 1 < h || params.wrap).then(|| (x, (y + 1) % h)),
+                (y > 0 || params.wrap).then(|| (x, (y + h - 1) % h)),
+            ];
+            for (nx, ny) in neighbours.into_iter().flatten() {
+                let cost = rng.next_in(*params.cost_range.start(), *params.cost_range.end());
+                let mut capacity = rng.next_in(*params.cap_range.start(), *params.cap_range.end());
+                // The top row, left to right, then the last column, down.
+                let on_path = (y == 0 && ny == 0 && nx == x + 1)
+                    || (x == w - 1 && nx == w - 1 && ny == y + 1);
+                if on_path {
+                    capacity = capacity.max(params.supply);
+                }
+                arcs.push(Arc {
+                    from: node(x, y),
+                    to: node(nx, ny),
+                    cost,
+                    capacity,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                });
+            }
+        }
+    }
+    let mut supply = vec![0; w * h];
+    supply[0] = params.supply;
+    supply[w * h - 1] = -params.supply;
+    Ok(NetgenResult { arcs, supply })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_flow;
+
+    fn params(width: usize, height: usize, wrap: bool) -> GridParams {
+        GridParams {
+            width,
+            height,
+            cost_range: 1..=100,
+            cap_range: 1..=20,
+            supply: 500,
+            wrap,
+        }
+    }
+
+    #[test]
+    fn counts_match_grid_formulas() {
+        for (w, h) in [(2, 2), (3, 5), (10, 7)] {
+            let p = params(w, h, false);
+            let result = generate_grid(7, &p).unwrap();
+            assert_eq!(result.supply.len(), w * h);
+            assert_eq!(result.arcs.len(), 2 * ((w - 1) * h + w * (h - 1)));
+        }
+        for (w, h) in [(3, 3), (10, 7)] {
+            let p = params(w, h, true);
+            let result = generate_grid(7, &p).unwrap();
+            assert_eq!(result.arcs.len(), 4 * w * h);
+        }
+    }
+
+    #[test]
+    fn wrap_adds_the_wrap_arcs() {
+        let (flat, torus) = (params(4, 3, false), params(4, 3, true));
+        let pairs = |p: &GridParams| -> Vec<(u64, u64)> {
+            let mut pairs: Vec<_> = generate_grid(7, p)
+                .unwrap()
+                .arcs
+                .iter()
+                .map(|a| (a.from, a.to))
+                .collect();
+            pairs.sort_unstable();
+            pairs
+        };
+        let (flat, torus) = (pairs(&flat), pairs(&torus));
+        assert!(flat.iter().all(|p| torus.contains(p)));
+        let extra: Vec<_> = torus.iter().filter(|p| !flat.contains(p)).collect();
+        // Rows wrap 4 -> 1 and back, columns 9 -> 1 and back.
+        assert_eq!(extra.len(), 2 * 3 + 2 * 4);
+        assert!(extra.contains(&&(4, 1)) && extra.contains(&&(1, 9)));
+    }
+
+    #[test]
+    fn grid_is_valid_and_feasible() {
+        for wrap in [false, true] {
+            let p = params(12, 9, wrap);
+            for seed in [1, 42, 13502460] {
+                let result = generate_grid(seed, &p).unwrap();
+                assert_eq!(result.check_invariants(&p.netgen_params()), Ok(()));
+                assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+            }
+        }
+        assert_eq!(
+            generate_grid(1, &params(1, 5, false)),
+            Err(NetgenError::BadParms)
+        );
+        assert_eq!(
+            generate_grid(1, &params(2, 5, true)),
+            Err(NetgenError::BadParms)
+        );
+        assert_eq!(
+            generate_grid(0, &params(3, 3, false)),
+            Err(NetgenError::BadSeed)
+        );
+    }
+}
@@ -3,50 +3,116 @@
 //! An "index list" is an ascending sequence of positive integers that supports:
 //! - `choose`: remove and return the k-th element
 //! - `remove`: remove a specific integer
+//! - `remove_range`, `remove_all`: remove many integers at once, splitting
+//!   each interval once per contiguous run
 //! - `size`: actual count of remaining elements
 //! - `pseudo_size`: size adjusted for failed remove attempts (preserves original NETGEN bug)
+//! - `iter`, `contains` and `peek`: inspect the remaining elements without
+//!   changing either size
 //!
-//! Uses a flag array for small lists (≤ 100 elements) and a binary interval tree for larger ones.
+//! Uses a sorted vector of the remaining integers for small lists (≤
+//! [`FLAG_LIMIT`] elements by default, see [`IndexStrategy`]) and a binary
+//! interval tree for larger ones. index.c uses a flag array for the small
+//! ones, which `choose` has to scan from the start.
 
-const FLAG_LIMIT: usize = 100;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::limits::FLAG_LIMIT;
 
 /// A node in the interval tree (large list implementation).
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 struct IntervalNode {
     base: usize,
     count: usize,
     left_child: Option<usize>, // index into the nodes vec (left child; right child is +1)
 }
 
-/// Small list: flag array. Large list: interval tree.
+/// How an [`IndexList`] stores its integers. Every strategy gives the same
+/// results; they differ only in speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexStrategy {
+    /// A sorted vector for lists of at most this many integers, an interval
+    /// tree for larger ones. The default is [`FLAG_LIMIT`].
+    Threshold(usize),
+    /// Always a sorted vector: `choose` and `remove` shift the integers
+    /// after the removed one, `O(size)` each.
+    Flat,
+    /// Always an interval tree (except for an empty list): `choose` and
+    /// `remove` are `O(depth)`, but each split allocates two nodes.
+    Tree,
+}
+
+impl Default for IndexStrategy {
+    fn default() -> Self {
+        IndexStrategy::Threshold(FLAG_LIMIT)
+    }
+}
+
+/// Small list: sorted remaining integers. Large list: interval tree.
+#[derive(Debug, Clone)]
 enum ListImpl {
-    Small {
-        base: usize,
-        flags: Vec<bool>, // true = removed
-    },
-    Large {
-        nodes: Vec<IntervalNode>,
-    },
+    Small { values: Vec<usize> },
+    Large { nodes: Vec<IntervalNode> },
 }
 
+/// An order-statistics set of the integers in a range, from which elements
+/// are drawn by position, as NETGEN draws nodes.
+///
+/// ```rust
+/// use netgen_rs::IndexList;
+///
+/// let mut list = IndexList::new(1, 5);
+/// assert_eq!(list.choose(2), 2);
+/// list.remove(4);
+/// assert_eq!(list.iter().collect::<Vec<_>>(), [1, 3, 5]);
+/// assert_eq!(list.peek(2), Some(3));
+/// assert!(!list.contains(4));
+/// assert_eq!(list.size(), 3);
+/// ```
+#[derive(Debug, Clone)]
 pub struct IndexList {
     original_size: usize,
     index_size: usize,
-    pseudo_size: usize,
+    /// Signed, as in C: failed removes can drive it below zero.
+    pseudo_size: i64,
     imp: ListImpl,
 }
 
 impl IndexList {
     /// Create a new index list containing integers from `from` through `to` inclusive.
-    /// `from` must be >= 1 and `from <= to`.
+    ///
+    /// An empty or invalid range (`from == 0` or `from > to`) gives an empty
+    /// list on which `remove` is a no-op, like the invalid handle that
+    /// `make_index_list` returns in C.
     pub fn new(from: usize, to: usize) -> Self {
-        assert!(from >= 1 && from <= to);
-        let size = to - from + 1;
+        Self::with_strategy(from, to, IndexStrategy::default())
+    }
+
+    /// Like [`new`](Self::new), storing the integers as `strategy` says.
+    ///
+    /// ```rust
+    /// use netgen_rs::{IndexList, IndexStrategy};
+    ///
+    /// let mut flat = IndexList::with_strategy(1, 1000, IndexStrategy::Flat);
+    /// let mut tree = IndexList::with_strategy(1, 1000, IndexStrategy::Tree);
+    /// assert_eq!(flat.choose(500), tree.choose(500));
+    /// ```
+    pub fn with_strategy(from: usize, to: usize, strategy: IndexStrategy) -> Self {
+        let size = if from >= 1 && from <= to {
+            to - from + 1
+        } else {
+            0
+        };
 
-        let imp = if size <= FLAG_LIMIT {
+        let flat = match strategy {
+            IndexStrategy::Threshold(limit) => size <= limit,
+            IndexStrategy::Flat => true,
+            IndexStrategy::Tree => size == 0,
+        };
+        let imp = if flat {
             ListImpl::Small {
-                base: from,
-                flags: vec![false; size],
+                values: (from..from + size).collect(),
             }
         } else {
             let mut nodes = Vec::with_capacity(size);
@@ -61,7 +127,7 @@ impl IndexList {
         IndexList {
             original_size: size,
             index_size: size,
-            pseudo_size: size,
+            pseudo_size: size as i64,
             imp,
         }
     }
@@ -77,19 +143,7 @@ impl IndexList {
         self.pseudo_size -= 1;
 
         match &mut self.imp {
-            ListImpl::Small { base, flags } => {
-                let mut remaining = position;
-                for (i, flag) in flags.iter_mut().enumerate() {
-                    if !*flag {
-                        remaining -= 1;
-                        if remaining == 0 {
-                            *flag = true;
-                            return *base + i;
-                        }
-                    }
-                }
-                unreachable!()
-            }
+            ListImpl::Small { values } => values.remove(position - 1),
             ListImpl::Large { nodes } => {
                 let mut pos = position;
                 let mut idx = 0; // root node
@@ -139,28 +193,25 @@ impl IndexList {
     /// Remove a specific integer from the list. If it doesn't exist,
     /// the pseudo_size is still decremented (preserving original NETGEN behavior).
     pub fn remove(&mut self, index: usize) {
+        if self.original_size == 0 {
+            return;
+        }
         self.pseudo_size -= 1;
 
         match &mut self.imp {
-            ListImpl::Small { base, flags } => {
-                if index < *base || index >= *base + self.original_size {
-                    return;
-                }
-                let offset = index - *base;
-                if !flags[offset] {
-                    flags[offset] = true;
+            ListImpl::Small { values } => {
+                if let Ok(i) = values.binary_search(&index) {
+                    values.remove(i);
                     self.index_size -= 1;
                 }
             }
             ListImpl::Large { nodes } => {
-                // Walk down, decrementing counts along the way
+                // Walk down to the interval that would hold index
                 let mut idx = 0;
                 let mut path = Vec::new();
 
-                while nodes[idx].left_child.is_some() {
+                while let Some(left) = nodes[idx].left_child {
                     path.push(idx);
-                    nodes[idx].count -= 1;
-                    let left = nodes[idx].left_child.unwrap();
                     let right = left + 1;
                     if index < nodes[right].base {
                         idx = left;
@@ -169,15 +220,16 @@ impl IndexList {
                     }
                 }
 
-                // Check if index is actually in this interval
+                // Check if index is actually in this interval. index.c
+                // decrements the counts on the way down and backs them out on
+                // a mistake, which would underflow an empty subtree here.
                 if index < nodes[idx].base || index >= nodes[idx].base + nodes[idx].count {
-                    // mistake - back out the decrements
-                    for &p in &path {
-                        nodes[p].count += 1;
-                    }
                     return;
                 }
 
+                for &p in &path {
+                    nodes[p].count -= 1;
+                }
                 nodes[idx].count -= 1;
                 if index == nodes[idx].base {
                     // beginning of interval
@@ -204,13 +256,214 @@ impl IndexList {
         }
     }
 
+    /// Remove every integer in `from..=to`, as if by one [`remove`](Self::remove)
+    /// per integer in the range: `pseudo_size` drops by the length of the
+    /// range, whether or not its integers are still in the list, saturating
+    /// at `i64::MIN` for ranges far beyond the list. Each interval is split
+    /// at most once.
+    pub fn remove_range(&mut self, from: usize, to: usize) {
+        if self.original_size == 0 || from > to {
+            return;
+        }
+        let len = i64::try_from(to - from).map_or(i64::MAX, |n| n.saturating_add(1));
+        self.pseudo_size = self.pseudo_size.saturating_sub(len);
+        self.remove_run(from, to);
+    }
+
+    /// Remove every integer in `indices`, as if by one [`remove`](Self::remove)
+    /// per item, duplicates included: `pseudo_size` drops by the number of
+    /// items. Contiguous runs are removed together, as by
+    /// [`remove_range`](Self::remove_range).
+    pub fn remove_all(&mut self, indices: impl IntoIterator<Item = usize>) {
+        if self.original_size == 0 {
+            return;
+        }
+        let mut indices: Vec<usize> = indices.into_iter().collect();
+        self.pseudo_size = self.pseudo_size.saturating_sub(indices.len() as i64);
+        indices.sort_unstable();
+        indices.dedup();
+        let mut rest = &indices[..];
+        while let Some(&first) = rest.first() {
+            let run = rest
+                .iter()
+                .enumerate()
+                .take_while(|&(i, &index)| index == first + i)
+                .count();
+            self.remove_run(first, first + run - 1);
+            rest = &rest[run..];
+        }
+    }
+
+    /// Remove the integers of `lo..=hi` that are still in the list, leaving
+    /// `pseudo_size` to the caller.
+    fn remove_run(&mut self, lo: usize, hi: usize) {
+        match &mut self.imp {
+            ListImpl::Small { values } => {
+                let start = values.partition_point(|&v| v < lo);
+                let end = values.partition_point(|&v| v <= hi);
+                values.drain(start..end);
+                self.index_size -= end - start;
+            }
+            ListImpl::Large { nodes } => {
+                // Post-order walk over the subtrees that can hold part of the
+                // run. Each internal node is visited twice, the second time
+                // with the number of its children, whose removed counts are
+                // then on top of `removed`.
+                let mut stack = vec![(0, None)];
+                let mut removed: Vec<usize> = Vec::new();
+                while let Some((idx, children)) = stack.pop() {
+                    if let Some(children) = children {
+                        let total: usize = removed.drain(removed.len() - children..).sum();
+                        nodes[idx].count -= total;
+                        removed.push(total);
+                    } else if let Some(left) = nodes[idx].left_child {
+                        // Everything left of the right child is below its base.
+                        let right = left + 1;
+                        let split = nodes[right].base;
+                        let visit: Vec<usize> =
+                            [(lo < split).then_some(left), (hi >= split).then_some(right)]
+                                .into_iter()
+                                .flatten()
+                                .collect();
+                        stack.push((idx, Some(visit.len())));
+                        stack.extend(visit.into_iter().rev().map(|child| (child, None)));
+                    } else {
+                        removed.push(Self::cut_leaf(nodes, idx, lo, hi));
+                    }
+                }
+                self.index_size -= removed.iter().sum::<usize>();
+            }
+        }
+    }
+
+    /// Remove `lo..=hi` from the interval of leaf `idx`, splitting it if the
+    /// run falls strictly inside, and return how many integers went.
+    fn cut_leaf(nodes: &mut Vec<IntervalNode>, idx: usize, lo: usize, hi: usize) -> usize {
+        let (base, end) = (nodes[idx].base, nodes[idx].base + nodes[idx].count);
+        let (cut_lo, cut_hi) = (lo.max(base), hi.saturating_add(1).min(end));
+        if cut_lo >= cut_hi {
+            return 0;
+        }
+        let cut = cut_hi - cut_lo;
+        nodes[idx].count -= cut;
+        if cut_lo == base {
+            nodes[idx].base = cut_hi;
+        } else if cut_hi < end {
+            let new_left = nodes.len();
+            nodes.push(IntervalNode {
+                base,
+                count: cut_lo - base,
+                left_child: None,
+            });
+            nodes.push(IntervalNode {
+                base: cut_hi,
+                count: end - cut_hi,
+                left_child: None,
+            });
+            nodes[idx].left_child = Some(new_left);
+        }
+        cut
+    }
+
+    /// Number of integers remaining.
     pub fn size(&self) -> usize {
         self.index_size
     }
 
-    pub fn pseudo_size(&self) -> usize {
+    /// The size NETGEN draws positions from: like [`size`](Self::size),
+    /// but also decremented by every [`remove`](Self::remove) of an integer
+    /// not in the list.
+    pub fn pseudo_size(&self) -> i64 {
         self.pseudo_size
     }
+
+    /// The remaining integers in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let (values, leaves) = match &self.imp {
+            ListImpl::Small { values } => (&values[..], None),
+            ListImpl::Large { nodes } => (&[][..], Some(Leaves::new(nodes))),
+        };
+        let small = values.iter().copied();
+        let large = leaves
+            .into_iter()
+            .flatten()
+            .flat_map(|node| node.base..node.base + node.count);
+        small.chain(large)
+    }
+
+    /// Whether `index` is still in the list.
+    pub fn contains(&self, index: usize) -> bool {
+        match &self.imp {
+            ListImpl::Small { values } => values.binary_search(&index).is_ok(),
+            ListImpl::Large { nodes } => {
+                let mut idx = 0;
+                while let Some(left) = nodes[idx].left_child {
+                    idx = if index < nodes[left + 1].base {
+                        left
+                    } else {
+                        left + 1
+                    };
+                }
+                let leaf = &nodes[idx];
+                (leaf.base..leaf.base + leaf.count).contains(&index)
+            }
+        }
+    }
+
+    /// The integer [`choose`](Self::choose) would return for the 1-based
+    /// `position`, without removing it, or `None` if `position` is not in
+    /// `1..=size`.
+    pub fn peek(&self, position: usize) -> Option<usize> {
+        if position < 1 || position > self.index_size {
+            return None;
+        }
+        match &self.imp {
+            ListImpl::Small { values } => Some(values[position - 1]),
+            ListImpl::Large { nodes } => {
+                let mut pos = position;
+                let mut idx = 0;
+                while let Some(left) = nodes[idx].left_child {
+                    if pos > nodes[left].count {
+                        pos -= nodes[left].count;
+                        idx = left + 1;
+                    } else {
+                        idx = left;
+                    }
+                }
+                Some(nodes[idx].base + pos - 1)
+            }
+        }
+    }
+}
+
+/// The leaves of an interval tree from left to right, i.e. its intervals in
+/// ascending order.
+struct Leaves<'a> {
+    nodes: &'a [IntervalNode],
+    stack: Vec<usize>,
+}
+
+impl<'a> Leaves<'a> {
+    fn new(nodes: &'a [IntervalNode]) -> Self {
+        Leaves {
+            nodes,
+            stack: vec![0],
+        }
+    }
+}
+
+impl<'a> Iterator for Leaves<'a> {
+    type Item = &'a IntervalNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(idx) = self.stack.pop() {
+            match self.nodes[idx].left_child {
+                Some(left) => self.stack.extend([left + 1, left]),
+                None => return Some(&self.nodes[idx]),
+            }
+        }
+        None
+    }
 }
 
 #[cfg(test)]
@@ -245,9 +498,18 @@ mod tests {
         assert_eq!(list.pseudo_size(), 4); // decremented anyway
     }
 
+    #[test]
+    fn invalid_range_is_empty() {
+        let mut list = IndexList::new(6, 5);
+        assert_eq!(list.size(), 0);
+        assert_eq!(list.choose(1), 0);
+        list.remove(5);
+        assert_eq!(list.pseudo_size(), 0);
+    }
+
     #[test]
     fn large_list_basic() {
-        let mut list = IndexList::new(1, 200);
+        let mut list = IndexList::with_strategy(1, 200, IndexStrategy::Tree);
         assert_eq!(list.size(), 200);
         let v = list.choose(1);
         assert_eq!(v, 1);
@@ -259,7 +521,7 @@ mod tests {
 
     #[test]
     fn large_list_remove() {
-        let mut list = IndexList::new(1, 200);
+        let mut list = IndexList::with_strategy(1, 200, IndexStrategy::Tree);
         list.remove(100);
         assert_eq!(list.size(), 199);
         // Choose position that would have been 100
@@ -275,4 +537,219 @@ mod tests {
         assert_eq!(list.choose(0), 0);
         assert_eq!(list.choose(6), 0);
     }
+
+    /// Apply random `choose`s and `remove`s (some of integers not in the
+    /// list) to `from..=to`, checking the inspection methods against a
+    /// sorted `Vec` after every step.
+    fn check_against_model(
+        from: usize,
+        to: usize,
+        steps: usize,
+        seed: i64,
+        strategy: IndexStrategy,
+    ) {
+        let mut list = IndexList::with_strategy(from, to, strategy);
+        let mut model: Vec<usize> = (from..=to).collect();
+        let mut pseudo = model.len() as i64;
+        let mut rng = crate::Rng::new(seed);
+        for _ in 0..steps {
+            if model.is_empty() {
+                break;
+            }
+            if rng.next(0, 2) == 0 {
+                let index = rng.next(from as i64 - 5, to as i64 + 5).max(0) as usize;
+                list.remove(index);
+                model.retain(|&v| v != index);
+                pseudo -= 1;
+            } else {
+                let position = rng.next(1, model.len() as i64) as usize;
+                assert_eq!(list.peek(position), Some(model[position - 1]));
+                assert_eq!(list.choose(position), model.remove(position - 1));
+                pseudo -= 1;
+            }
+            assert_eq!(list.iter().collect::<Vec<_>>(), model);
+            for v in from.saturating_sub(2)..=to + 2 {
+                assert_eq!(list.contains(v), model.contains(&v), "{v}");
+            }
+            for position in 0..=model.len() + 1 {
+                let expected = position.checked_sub(1).and_then(|i| model.get(i));
+                assert_eq!(list.peek(position), expected.copied());
+            }
+            assert_eq!(list.size(), model.len());
+            assert_eq!(list.pseudo_size(), pseudo);
+        }
+    }
+
+    #[test]
+    fn inspection_matches_a_vec_model() {
+        for seed in [1, 42, 13502460] {
+            for strategy in [IndexStrategy::Flat, IndexStrategy::Tree] {
+                check_against_model(1, 20, 30, seed, strategy);
+                check_against_model(3, 400, 500, seed, strategy);
+            }
+            check_against_model(7, FLAG_LIMIT + 6, 120, seed, IndexStrategy::default());
+        }
+    }
+
+    #[test]
+    fn inspection_of_an_empty_list() {
+        let list = IndexList::new(6, 5);
+        assert_eq!(list.iter().count(), 0);
+        assert!(!list.contains(5));
+        assert_eq!(list.peek(1), None);
+    }
+
+    /// Build the same list twice with each representation, apply `setup` to
+    /// all, then `batch` to one of each pair and `one_at_a_time` to the
+    /// other, and check each pair agrees.
+    fn check_batch(
+        from: usize,
+        to: usize,
+        setup: impl Fn(&mut IndexList),
+        batch: impl Fn(&mut IndexList),
+        one_at_a_time: impl IntoIterator<Item = usize>,
+    ) {
+        let mut a = IndexList::with_strategy(from, to, IndexStrategy::Flat);
+        let mut b = IndexList::with_strategy(from, to, IndexStrategy::Flat);
+        let mut c = IndexList::with_strategy(from, to, IndexStrategy::Tree);
+        let mut d = IndexList::with_strategy(from, to, IndexStrategy::Tree);
+        setup(&mut a);
+        setup(&mut b);
+        setup(&mut c);
+        setup(&mut d);
+        batch(&mut a);
+        batch(&mut c);
+        for index in one_at_a_time {
+            b.remove(index);
+            d.remove(index);
+        }
+        for (a, b) in [(a, b), (c, d)] {
+            check_same(a, b);
+        }
+    }
+
+    /// Check that `a` and `b` agree on everything observable, down to the
+    /// order `choose` empties them in.
+    fn check_same(mut a: IndexList, mut b: IndexList) {
+        assert_eq!(a.iter().collect::<Vec<_>>(), b.iter().collect::<Vec<_>>());
+        assert_eq!(a.size(), b.size());
+        assert_eq!(a.pseudo_size(), b.pseudo_size());
+        let mut position = 7;
+        while a.size() > 0 {
+            position = position * 31 % 1009 + 1;
+            let p = position % a.size() + 1;
+            assert_eq!(a.choose(p), b.choose(p));
+        }
+        assert_eq!(b.size(), 0);
+    }
+
+    /// Split the tree of `1..=300` into several intervals.
+    fn fragment(list: &mut IndexList) {
+        for index in [50, 51, 120, 200, 201, 202] {
+            list.remove(index);
+        }
+        list.choose(90);
+    }
+
+    #[test]
+    fn remove_range_matches_single_removes() {
+        for (lo, hi) in [
+            // Spans interval boundaries and already-removed integers.
+            (40, 130),
+            (45, 210),
+            (1, 300),
+            // Inside one interval, at either end of one, or a single integer.
+            (60, 70),
+            (52, 60),
+            (110, 119),
+            (150, 150),
+            // Only already-removed integers, or entirely outside the list.
+            (200, 202),
+            (301, 400),
+            (0, 0),
+        ] {
+            check_batch(1, 300, fragment, |l| l.remove_range(lo, hi), lo..=hi);
+            check_batch(1, 300, |_| {}, |l| l.remove_range(lo, hi), lo..=hi);
+            check_batch(1, 80, |_| {}, |l| l.remove_range(lo, hi), lo..=hi);
+        }
+        // An empty range changes nothing.
+        check_batch(1, 300, fragment, |l| l.remove_range(9, 8), []);
+    }
+
+    #[test]
+    fn remove_range_to_usize_max_saturates() {
+        for strategy in [IndexStrategy::Flat, IndexStrategy::Tree] {
+            for from in [0, 1] {
+                let mut list = IndexList::with_strategy(1, 300, strategy);
+                list.remove_range(from, usize::MAX);
+                assert_eq!(list.size(), 0);
+                assert_eq!(list.pseudo_size(), 300 - i64::MAX);
+                list.remove_range(from, usize::MAX);
+                assert_eq!(list.pseudo_size(), i64::MIN);
+            }
+        }
+    }
+
+    #[test]
+    fn remove_all_matches_single_removes() {
+        let indices = [7, 130, 5, 6, 6, 50, 400, 49, 52, 199, 200, 8, 0, 300, 299];
+        check_batch(1, 300, fragment, |l| l.remove_all(indices), indices);
+        check_batch(1, 80, |_| {}, |l| l.remove_all(indices), indices);
+        check_batch(1, 300, |_| {}, |l| l.remove_all(1..=300), 1..=300);
+    }
+
+    #[test]
+    fn batch_removal_of_an_invalid_list_is_a_no_op() {
+        let mut list = IndexList::new(6, 5);
+        list.remove_range(1, 10);
+        list.remove_all([1, 2]);
+        assert_eq!((list.size(), list.pseudo_size()), (0, 0));
+    }
+
+    #[test]
+    fn strategies_agree_on_random_operations() {
+        let strategies = [
+            IndexStrategy::Flat,
+            IndexStrategy::Tree,
+            IndexStrategy::default(),
+            IndexStrategy::Threshold(0),
+        ];
+        for (from, to) in [(1, 1), (1, 40), (3, 100), (1, 101), (10, 700)] {
+            for seed in [1, 42, 13502460] {
+                let mut rng = crate::Rng::new(seed);
+                let mut lists =
+                    strategies.map(|strategy| IndexList::with_strategy(from, to, strategy));
+                while lists[0].size() > 0 {
+                    let (lo, hi) = ((from as i64 - 3).max(0), to as i64 + 3);
+                    let results = match rng.next(0, 4) {
+                        0 | 1 => {
+                            let position = rng.next(1, lists[0].size() as i64) as usize;
+                            lists.each_mut().map(|list| list.choose(position))
+                        }
+                        2 => {
+                            let index = rng.next(lo, hi) as usize;
+                            lists.each_mut().map(|list| {
+                                list.remove(index);
+                                0
+                            })
+                        }
+                        _ => {
+                            let a = rng.next(lo, hi) as usize;
+                            let b = rng.next(a as i64, (a + 5) as i64) as usize;
+                            lists.each_mut().map(|list| {
+                                list.remove_range(a, b);
+                                0
+                            })
+                        }
+                    };
+                    assert!(results.iter().all(|&r| r == results[0]));
+                    for list in &lists[1..] {
+                        assert_eq!(list.size(), lists[0].size());
+                        assert_eq!(list.pseudo_size(), lists[0].pseudo_size());
+                        assert!(list.iter().eq(lists[0].iter()));
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,302 @@
+//! Structural guarantees of generated instances, checkable at runtime.
+
+use std::fmt;
+
+use crate::{ArcId, NetgenParams, NetgenResult, ProblemType};
+
+/// A property that a [`NetgenResult`] violates for its parameters.
+///
+/// Arc indices are 0-based positions in [`NetgenResult::arcs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The supply vector does not have one entry per node.
+    SupplyLength { expected: usize, found: usize },
+    /// Supplies and demands do not cancel out.
+    UnbalancedSupply { sum: i64 },
+    /// More arcs than `density` allows (see [`NetgenResult::check_invariants`]).
+    TooManyArcs { arcs: usize, limit: i64 },
+    /// An arc endpoint is outside `1..=nodes`.
+    NodeOutOfRange { arc: usize, node: u64 },
+    /// An arc leaves a pure (non-transshipment) sink.
+    ArcFromPureSink { arc: usize, node: u64 },
+    /// An arc enters a pure (non-transshipment) source.
+    ArcIntoPureSource { arc: usize, node: u64 },
+    /// An arc cost is outside `mincost..=maxcost`.
+    CostOutOfRange { arc: usize, cost: i64 },
+    /// An arc capacity is not one NETGEN can assign.
+    CapacityOutOfRange { arc: usize, capacity: i64 },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::SupplyLength { expected, found } => {
+                write!(f, "supply has {found} entries, expected {expected}")
+            }
+            InvariantViolation::UnbalancedSupply { sum } => {
+                write!(f, "supplies sum to {sum}, expected 0")
+            }
+            InvariantViolation::TooManyArcs { arcs, limit } => {
+                write!(f, "{arcs} arcs exceed the limit of {limit}")
+            }
+            InvariantViolation::NodeOutOfRange { arc, node } => {
+                write!(f, "arc {arc} has endpoint {node} outside the node range")
+            }
+            InvariantViolation::ArcFromPureSink { arc, node } => {
+                write!(f, "arc {arc} leaves pure sink {node}")
+            }
+            InvariantViolation::ArcIntoPureSource { arc, node } => {
+                write!(f, "arc {arc} enters pure source {node}")
+            }
+            InvariantViolation::CostOutOfRange { arc, cost } => {
+                write!(f, "arc {arc} has cost {cost} outside mincost..=maxcost")
+            }
+            InvariantViolation::CapacityOutOfRange { arc, capacity } => {
+                write!(
+                    f,
+                    "arc {arc} has capacity {capacity} not allowed by the parameters"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+impl NetgenResult {
+    /// Check the guarantees NETGEN makes about its output for `params`.
+    ///
+    /// - there is one supply entry per node, and (except for assignment
+    ///   problems) supplies sum to zero;
+    /// - arc endpoints are in `1..=nodes`, and there are at most `density`
+    ///   arcs plus the most arcs a skeleton can have. The skeleton is never
+    ///   trimmed, and random arcs for earlier sources are drawn before the
+    ///   skeleton arcs of later ones, so the reference implementation itself
+    ///   can exceed `density` when it is close to `nodes`;
+    /// - no arc leaves a pure sink or enters a pure source (see
+    ///   [`terminal_violations`](Self::terminal_violations));
+    /// - costs lie in `mincost..=maxcost` (high-cost skeleton arcs get
+    ///   exactly `maxcost`);
+    /// - capacities are either `supply` (uncapacitated) or lie in
+    ///   `mincap..=max(maxcap, supply)`, since capacitated skeleton arcs
+    ///   carry their source's supply. Assignment skeleton arcs have
+    ///   capacity 1.
+    ///
+    /// Useful as a sanity check for instances read back from disk.
+    pub fn check_invariants(&self, params: &NetgenParams) -> Result<(), InvariantViolation> {
+        let nodes = params.nodes as u64;
+        if self.supply.len() as u64 != nodes {
+            return Err(InvariantViolation::SupplyLength {
+                expected: nodes as usize,
+                found: self.supply.len(),
+            });
+        }
+        let assignment = params.problem_type() == ProblemType::Assignment;
+        if !assignment {
+            let sum: i64 = self.supply.iter().sum();
+            if sum != 0 {
+                return Err(InvariantViolation::UnbalancedSupply { sum });
+            }
+        }
+        let limit = params.density + max_skeleton_arcs(params);
+        if self.arcs.len() as i64 > limit {
+            return Err(InvariantViolation::TooManyArcs {
+                arcs: self.arcs.len(),
+                limit,
+            });
+        }
+
+        let (last_pure_source, first_pure_sink) = pure_terminals(params);
+        let max_capacity = params.maxcap.max(params.supply);
+        for (i, arc) in self.arcs.iter().enumerate() {
+            for node in [arc.from, arc.to] {
+                if !(1..=nodes).contains(&node) {
+                    return Err(InvariantViolation::NodeOutOfRange { arc: i, node });
+                }
+            }
+            if arc.from >= first_pure_sink {
+                return Err(InvariantViolation::ArcFromPureSink {
+                    arc: i,
+                    node: arc.from,
+                });
+            }
+            if arc.to <= last_pure_source {
+                return Err(InvariantViolation::ArcIntoPureSource {
+                    arc: i,
+                    node: arc.to,
+                });
+            }
+            if !(params.mincost..=params.maxcost).contains(&arc.cost) {
+                return Err(InvariantViolation::CostOutOfRange {
+                    arc: i,
+                    cost: arc.cost,
+                });
+            }
+            let capacity_ok = (assignment && arc.capacity == 1)
+                || arc.capacity == params.supply
+                || (params.mincap..=max_capacity).contains(&arc.capacity);
+            if !capacity_ok {
+                return Err(InvariantViolation::CapacityOutOfRange {
+                    arc: i,
+                    capacity: arc.capacity,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The arcs that leave a pure sink or enter a pure source of `params`,
+    /// in order.
+    ///
+    /// Pure sources are nodes `1..=sources - tsources` and pure sinks the
+    /// last `sinks - tsinks` nodes. NETGEN never draws them as heads and
+    /// tails respectively, and some codes rely on that, but instances read
+    /// back from disk or transformed need not respect it.
+    /// [`check_invariants`](Self::check_invariants) reports the first such
+    /// arc.
+    ///
+    /// ```rust
+    /// use netgen_rs::NetgenParams;
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let mut result = params.generate(13502460).unwrap();
+    /// assert!(result.terminal_violations(&params).is_empty());
+    /// result.arcs[4].to = 1; // node 1 is a pure source
+    /// let stripped = result.strip_terminal_violations(&params);
+    /// assert_eq!(stripped.arcs.len(), result.arcs.len() - 1);
+    /// ```
+    pub fn terminal_violations(&self, params: &NetgenParams) -> Vec<ArcId> {
+        let (last_pure_source, first_pure_sink) = pure_terminals(params);
+        (self.arcs.iter().enumerate())
+            .filter(|(_, arc)| arc.from >= first_pure_sink || arc.to <= last_pure_source)
+            .map(|(k, _)| ArcId(k as u32))
+            .collect()
+    }
+
+    /// A copy without the arcs [`terminal_violations`](Self::terminal_violations)
+    /// reports. The remaining arcs keep their order; supplies are untouched,
+    /// so the result may no longer be feasible.
+    pub fn strip_terminal_violations(&self, params: &NetgenParams) -> NetgenResult {
+        let (last_pure_source, first_pure_sink) = pure_terminals(params);
+        NetgenResult {
+            arcs: (self.arcs.iter())
+                .filter(|arc| arc.from < first_pure_sink && arc.to > last_pure_source)
+                .cloned()
+                .collect(),
+            supply: self.supply.clone(),
+        }
+    }
+}
+
+/// The last pure source and the first pure sink, as DIMACS ids.
+fn pure_terminals(params: &NetgenParams) -> (u64, u64) {
+    let last_pure_source = (params.sources - params.tsources) as u64;
+    let first_pure_sink = (params.nodes - params.sinks + params.tsinks + 1) as u64;
+    (last_pure_source, first_pure_sink)
+}
+
+/// Upper bound on the skeleton size: one chain arc per transshipment node,
+/// and per source at most `max(2, 2 * chain * sinks / transshipment)` sink
+/// arcs (capped at `sinks`), plus the leftover sinks given to the last
+/// source.
+fn max_skeleton_arcs(params: &NetgenParams) -> i64 {
+    if params.problem_type() == ProblemType::Assignment {
+        return params.nodes / 2;
+    }
+    let transshipment = params.nodes - params.sources - params.sinks;
+    let sink_arcs = (params.sources * params.sinks).min(2 * (params.sources + params.sinks));
+    transshipment + sink_arcs + params.sinks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::doc_params;
+    use crate::{Arc, ArcKind, generate};
+
+    #[test]
+    fn violations_are_detected() {
+        let params = doc_params();
+        let result = generate(13502460, &params).unwrap();
+        assert_eq!(result.check_invariants(&params), Ok(()));
+
+        let with_arc = |arc: Arc| {
+            let mut r = result.clone();
+            r.arcs[0] = arc;
+            r.check_invariants(&params).unwrap_err()
+        };
+        let arc = |from, to, cost, capacity| Arc {
+            from,
+            to,
+            cost,
+            capacity,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Unknown,
+            hicost: false,
+        };
+        assert_eq!(
+            with_arc(arc(1, 513, 5, 50)),
+            InvariantViolation::NodeOutOfRange { arc: 0, node: 513 }
+        );
+        assert_eq!(
+            with_arc(arc(512, 20, 5, 50)),
+            InvariantViolation::ArcFromPureSink { arc: 0, node: 512 }
+        );
+        assert_eq!(
+            with_arc(arc(20, 7, 5, 50)),
+            InvariantViolation::ArcIntoPureSource { arc: 0, node: 7 }
+        );
+        assert_eq!(
+            with_arc(arc(20, 30, 501, 50)),
+            InvariantViolation::CostOutOfRange { arc: 0, cost: 501 }
+        );
+        assert_eq!(
+            with_arc(arc(20, 30, 5, 49)),
+            InvariantViolation::CapacityOutOfRange {
+                arc: 0,
+                capacity: 49
+            }
+        );
+
+        let mut r = result.clone();
+        r.supply[0] += 1;
+        assert_eq!(
+            r.check_invariants(&params),
+            Err(InvariantViolation::UnbalancedSupply { sum: 1 })
+        );
+    }
+
+    #[test]
+    fn terminal_violations_are_stripped() {
+        let params = doc_params();
+        let result = generate(13502460, &params).unwrap();
+        assert_eq!(result.terminal_violations(&params), []);
+        assert_eq!(result.strip_terminal_violations(&params), result);
+
+        // Nodes 1..=7 are pure sources and 506..=512 pure sinks; 8..=10
+        // and 503..=505 are transshipment sources and sinks.
+        let mut modified = result.clone();
+        modified.arcs[3].to = 7;
+        modified.arcs[10].from = 506;
+        modified.arcs[11].to = 8;
+        modified.arcs[12].from = 505;
+        modified.arcs[0].from = 512;
+        modified.arcs[0].to = 1;
+        assert_eq!(
+            modified.terminal_violations(&params),
+            [ArcId(0), ArcId(3), ArcId(10)]
+        );
+        assert_eq!(
+            modified.check_invariants(&params),
+            Err(InvariantViolation::ArcFromPureSink { arc: 0, node: 512 })
+        );
+        let stripped = modified.strip_terminal_violations(&params);
+        assert_eq!(stripped.arcs.len(), modified.arcs.len() - 3);
+        assert_eq!(stripped.arcs[0], modified.arcs[1]);
+        assert_eq!(stripped.terminal_violations(&params), []);
+        assert_eq!(stripped.supply, modified.supply);
+    }
+}
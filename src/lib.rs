@@ -55,22 +55,239 @@
 //! # Ok(()) }
 //! ```
 //!
+//! With the `flate2` or `zstd` feature enabled, `write_dimacs_gz` and
+//! `write_dimacs_zstd` write the same output compressed. The `sha2` feature
+//! adds `NetgenResult::fingerprint`, a SHA-256 of the DIMACS output, and
+//! `verify_reference`, which checks the generator against outputs recorded
+//! from the C implementation. It is enabled by default. The `arbitrary`
+//! feature implements `arbitrary::Arbitrary` for [`NetgenParams`] on top of
+//! [`NetgenParams::random`], for use with cargo-fuzz. The `petgraph` feature
+//! adds `NetgenResult::to_petgraph` and `NetgenResult::from_petgraph`. The
+//! `solver` feature adds `solve_min_cost_flow`, an exact reference solver for
+//! small instances. The `ffi` feature adds the `ffi` module, a C interface
+//! declared in `include/netgen_rs.h`. The `serde` feature implements
+//! `serde::Serialize` for [`analysis::GraphMetrics`] and [`NetgenResult`],
+//! and `serde::Serialize` and `serde::Deserialize` for [`NetgenParams`],
+//! [`ProblemType`] and `InstanceManifest`.
+//! The `wasm` feature adds the `wasm` module of wasm-bindgen wrappers for
+//! use in the browser. The `tokio` feature adds `write_dimacs_async`, which
+//! streams the same output to a `tokio::io::AsyncWrite`.
+//!
+//! Without the default `std` feature the crate is `no_std` and needs only
+//! `alloc`: generation, [`write_dimacs_fmt`] and [`to_dimacs_string`]
+//! remain, while the `io::Write` writers, parsers and exports go.
+//!
 //! The 13 integers mirror the original `parms[]` array (see
 //! [`NetgenParams`]).
+//!
+//! # Determinism
+//!
+//! A seed and parameters give byte-identical output on every platform:
+//! x86_64 and aarch64, Linux, macOS and Windows, little- and big-endian,
+//! with or without `rayon`. Every drawn value comes from `i64` arithmetic;
+//! `usize` only indexes, so a 32-bit target produces the same output for
+//! any instance it can hold. The only floating point in generation is the
+//! BCJL overflow fix, which converts integers to `f64`, multiplies,
+//! divides, compares and truncates. IEEE 754 rounds each of these
+//! correctly, and Rust neither fuses them nor keeps extended precision, so
+//! they give the same result on any target with IEEE binary64. The binary
+//! format is little-endian on every target.
+//! `tests/determinism.rs` pins SHA-256 fingerprints of instances covering
+//! each of these.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod analysis;
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+mod binary;
+#[cfg(feature = "std")]
+mod bipartite;
+#[cfg(feature = "std")]
+mod canonical;
+#[cfg(feature = "std")]
+mod circulation;
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+mod compress;
+#[cfg(feature = "std")]
+mod costs;
+#[cfg(feature = "std")]
+mod count;
+mod csr;
+#[cfg(feature = "std")]
+mod csv;
+#[cfg(feature = "std")]
+mod dimacs;
+mod estimate;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "sha2")]
+mod fingerprint;
+#[cfg(test)]
+mod fixture;
+#[cfg(feature = "std")]
+mod flow;
+#[cfg(feature = "petgraph")]
+mod graph;
+#[cfg(feature = "std")]
+mod graphml;
+mod grid;
 mod index_list;
+#[cfg(feature = "std")]
+mod invariants;
+pub mod limits;
+mod lint;
+#[cfg(feature = "std")]
+mod lp;
+#[cfg(feature = "std")]
+mod manifest;
+#[cfg(feature = "std")]
+mod matrix_market;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "std")]
+mod mps;
+#[cfg(feature = "std")]
+mod mutate;
+pub mod naming;
 mod netgen;
+mod options;
 mod random;
+mod regular;
+mod relabel;
+mod report;
+#[cfg(feature = "std")]
+mod sample;
+mod seed;
+#[cfg(feature = "std")]
+mod shortest_path;
+#[cfg(feature = "solver")]
+mod solver;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod subsample;
+mod suite;
+#[cfg(feature = "std")]
+mod tee;
+#[cfg(feature = "std")]
+mod tighten;
+mod time_expand;
+mod trace;
+#[cfg(feature = "sha2")]
+mod verification;
+mod view;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "tokio")]
+pub use async_io::{write_dimacs_async, write_dimacs_with_options_async};
+#[cfg(feature = "std")]
+pub use batch::{BatchEntry, BatchPolicy, BatchSummary, write_dimacs_batch};
+#[cfg(feature = "std")]
+pub use binary::{read_binary, write_binary};
+#[cfg(feature = "std")]
+pub use bipartite::{BipartiteInstance, ConversionError, write_bipartite_edges};
+#[cfg(feature = "std")]
+pub use canonical::{CanonicalDifference, CanonicalForm};
+#[cfg(feature = "std")]
+pub use circulation::Circulation;
+#[cfg(feature = "flate2")]
+pub use compress::{GZIP_DEFAULT_LEVEL, write_dimacs_gz};
+#[cfg(feature = "zstd")]
+pub use compress::{ZSTD_DEFAULT_LEVEL, write_dimacs_zstd};
+#[cfg(feature = "std")]
+pub use costs::{CostNote, CostTransformError, RoundMode};
+#[cfg(feature = "std")]
+pub use count::{
+    CountingWriter, WriteSummary, write_dimacs_network_with_summary, write_dimacs_with_summary,
+};
+pub use csr::CsrNetwork;
+#[cfg(feature = "std")]
+pub use csv::{write_csv, write_csv_to_dir};
+#[cfg(feature = "std")]
+pub use dimacs::{
+    DimacsError, VerifyError, VerifyOutcome, parse_dimacs, parse_header, verify_instance,
+};
+pub use estimate::{SizeEstimate, estimate_dimacs_bytes};
+#[cfg(feature = "sha2")]
+pub use fingerprint::{FingerprintWriter, to_hex};
+#[cfg(feature = "std")]
+pub use flow::{FlowError, verify_flow, write_dimacs_flow};
+#[cfg(feature = "petgraph")]
+pub use graph::{ArcData, LowerBoundError, NodeData};
+#[cfg(feature = "std")]
+pub use graphml::{GraphmlOptions, write_graphml, write_graphml_with_options};
+pub use grid::{GridParams, generate_grid};
+pub use index_list::{IndexList, IndexStrategy};
+#[cfg(feature = "std")]
+pub use invariants::InvariantViolation;
+pub use limits::MAX_SEED;
+pub use lint::ParamWarning;
+#[cfg(feature = "std")]
+pub use lp::{write_lp, write_lp_with_options};
+#[cfg(feature = "std")]
+pub use manifest::{InstanceManifest, write_manifest};
+#[cfg(feature = "std")]
+pub use matrix_market::{MatrixMarketKind, write_matrix_market};
+#[cfg(feature = "std")]
+pub use merge::{MergePolicy, MergeReport};
+#[cfg(feature = "std")]
+pub use mps::{MpsNames, write_mps, write_mps_with_options};
+#[cfg(feature = "std")]
+pub use mutate::{AppliedMutation, Change, Mutation, MutationLog};
+pub use netgen::Skeleton;
+pub use options::{
+    ArcOrder, DimacsOptions, GenerateOptions, GenerationMode, HeaderOptions, LineEnding, LpOptions,
+    MaxFlowExtra, MultiplierRange, Shortfall, SupplyDistribution, UncapacitatedAs,
+};
+pub use random::Rng;
+pub use regular::{RegularReport, generate_regular};
+pub use relabel::RelabelMap;
+pub use report::{GenerationReport, Progress};
+#[cfg(feature = "std")]
+pub use sample::{ParamBounds, RngLike};
+pub use seed::{SeedSequence, fold_seed};
+#[cfg(feature = "std")]
+pub use shortest_path::{ShortestPathError, ShortestPathInstance, write_dimacs_sp};
+#[cfg(feature = "solver")]
+pub use solver::{Solution, SolveError, solve_min_cost_flow};
+#[cfg(feature = "std")]
+pub use stats::InstanceStats;
+#[cfg(feature = "std")]
+pub use subsample::{KeepPolicy, SubsampleError};
+pub use suite::{LEMON_SEEDS, LemonSuite, ParseSuiteError, SuiteInstance};
+#[cfg(feature = "std")]
+pub use tee::TeeWriter;
+#[cfg(feature = "std")]
+pub use tighten::{TIGHTEN_ROUNDS, TightenError};
+pub use time_expand::TransitTimes;
+pub use trace::{PhaseDraws, RngPhase, RngTrace};
+#[cfg(feature = "sha2")]
+pub use verification::{REFERENCE_CASES, ReferenceCase, VerificationMismatch, verify_reference};
+pub use view::NetworkView;
+extern crate alloc;
 
-use std::fmt;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::ControlFlow;
+use core::str::FromStr;
+#[cfg(feature = "std")]
 use std::io::{self, Write};
 
+use limits::{MAX_ARCS_SUPPORTED, MAX_NODES_SUPPORTED};
+
 /// Parameters for network generation.
 ///
 /// All fields are validated at construction time. Use [`NetgenParams::new`] or
 /// [`NetgenParams::from_slice`] to create an instance, or construct manually
 /// and call [`NetgenParams::validate`].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetgenParams {
     pub nodes: i64,
     pub sources: i64,
@@ -78,6 +295,8 @@ pub struct NetgenParams {
     pub density: i64,
     pub mincost: i64,
     pub maxcost: i64,
+    /// Total supply of the sources. Anything up to `i64::MAX` is
+    /// distributed without overflow.
     pub supply: i64,
     pub tsources: i64,
     pub tsinks: i64,
@@ -133,46 +352,195 @@ impl NetgenParams {
         )
     }
 
+    /// The 13 values in C `parms[]` order (the inverse of [`from_slice`](Self::from_slice)).
+    pub fn to_array(&self) -> [i64; 13] {
+        [
+            self.nodes,
+            self.sources,
+            self.sinks,
+            self.density,
+            self.mincost,
+            self.maxcost,
+            self.supply,
+            self.tsources,
+            self.tsinks,
+            self.hicost_pct,
+            self.capacitated_pct,
+            self.mincap,
+            self.maxcap,
+        ]
+    }
+
+    /// Check the parameters against the C generator's sanity checks, plus
+    /// two size limits in place of the C code's static arrays (see
+    /// [`limits`]):
+    ///
+    /// - `nodes` and `density` must not exceed
+    ///   [`limits::MAX_NODES_SUPPORTED`] and [`limits::MAX_ARCS_SUPPORTED`],
+    ///   `u32::MAX` ([`ParamError::TooBig`]);
+    /// - `density` must not exceed `nodes * nodes`, which no network on
+    ///   `nodes` nodes can reach ([`ParamError::DensityTooHigh`]). Densities
+    ///   between [`max_arcs`](Self::max_arcs) and that bound are accepted, as
+    ///   the C generator accepts them, but yield a network short of `density`.
+    ///
+    /// Cost and capacity ranges may be negative and as wide as `i64` allows:
+    /// ranges wider than [`limits::MAX_EXACT_RANGE`] values are drawn with
+    /// [`Rng::next_wide`], where the C generator would overflow or skip
+    /// values. Negative costs are allowed for every problem type: max-flow
+    /// detection needs `mincost == maxcost == 1`, and assignment costs may
+    /// have any sign. [`is_reference_compatible`](Self::is_reference_compatible)
+    /// tells whether the output also matches the C generator.
     pub fn validate(&self) -> Result<(), ParamError> {
+        if self.nodes > MAX_NODES_SUPPORTED || self.density > MAX_ARCS_SUPPORTED {
+            return Err(ParamError::TooBig {
+                nodes: self.nodes,
+                density: self.density,
+            });
+        }
         if self.nodes <= 0 {
-            return Err(ParamError::NonPositiveNodes);
+            return Err(ParamError::NonPositiveNodes { nodes: self.nodes });
         }
         if self.sources <= 0 {
-            return Err(ParamError::NonPositiveSources);
+            return Err(ParamError::NonPositiveSources {
+                sources: self.sources,
+            });
         }
         if self.sinks <= 0 {
-            return Err(ParamError::NonPositiveSinks);
+            return Err(ParamError::NonPositiveSinks { sinks: self.sinks });
         }
-        if self.sources + self.sinks > self.nodes {
-            return Err(ParamError::SourcesSinksExceedNodes);
+        if self.sources.saturating_add(self.sinks) > self.nodes {
+            return Err(ParamError::SourcesSinksExceedNodes {
+                sources: self.sources,
+                sinks: self.sinks,
+                nodes: self.nodes,
+            });
         }
         if self.nodes > self.density {
-            return Err(ParamError::DensityTooLow);
+            return Err(ParamError::DensityTooLow {
+                density: self.density,
+                nodes: self.nodes,
+            });
+        }
+        let max = self.nodes.saturating_mul(self.nodes);
+        if self.density > max {
+            return Err(ParamError::DensityTooHigh {
+                density: self.density,
+                max,
+            });
         }
         if self.mincost > self.maxcost {
-            return Err(ParamError::MinCostExceedsMaxCost);
+            return Err(ParamError::MinCostExceedsMaxCost {
+                mincost: self.mincost,
+                maxcost: self.maxcost,
+            });
         }
         if self.supply < self.sources {
-            return Err(ParamError::SupplyTooLow);
+            return Err(ParamError::SupplyTooLow {
+                supply: self.supply,
+                sources: self.sources,
+            });
         }
         if self.tsources > self.sources {
-            return Err(ParamError::TSourcesExceedSources);
+            return Err(ParamError::TSourcesExceedSources {
+                tsources: self.tsources,
+                sources: self.sources,
+            });
         }
         if self.tsinks > self.sinks {
-            return Err(ParamError::TSinksExceedSinks);
+            return Err(ParamError::TSinksExceedSinks {
+                tsinks: self.tsinks,
+                sinks: self.sinks,
+            });
         }
         if self.hicost_pct < 0 || self.hicost_pct > 100 {
-            return Err(ParamError::HiCostOutOfRange);
+            return Err(ParamError::HiCostOutOfRange {
+                hicost_pct: self.hicost_pct,
+            });
         }
         if self.capacitated_pct < 0 || self.capacitated_pct > 100 {
-            return Err(ParamError::CapacitatedOutOfRange);
+            return Err(ParamError::CapacitatedOutOfRange {
+                capacitated_pct: self.capacitated_pct,
+            });
         }
         if self.mincap > self.maxcap {
-            return Err(ParamError::MinCapExceedsMaxCap);
+            return Err(ParamError::MinCapExceedsMaxCap {
+                mincap: self.mincap,
+                maxcap: self.maxcap,
+            });
         }
         Ok(())
     }
 
+    /// Parameters in the style of the LEMON benchmark families, sized by
+    /// average out-degree instead of arc count.
+    ///
+    /// Uses `density = nodes * deg`, `sources = sinks = round(sqrt(nodes))`,
+    /// `supply = 1000 * sources`, costs in `1..=10000`, capacities in
+    /// `1..=1000`, no transshipment sources or sinks, and 100% hicost and
+    /// capacitated skeleton arcs, matching `lemon_scripts/netgen_8.sh` and
+    /// `netgen_deg.sh`. Adjust fields afterwards and call
+    /// [`validate`](Self::validate) for other families.
+    pub fn with_average_degree(nodes: i64, deg: i64) -> Result<Self, ParamError> {
+        let density = match density_for_degree(nodes, deg) {
+            Some(density) => density,
+            None if nodes > 0 && deg > 0 => {
+                return Err(ParamError::TooBig {
+                    nodes,
+                    density: i64::MAX,
+                });
+            }
+            // Fails validation below, as non-positive nodes or density.
+            None => nodes.saturating_mul(deg),
+        };
+        let terminals = round_sqrt(nodes.max(0));
+        let params = NetgenParams::new(
+            nodes,
+            terminals,
+            terminals,
+            density,
+            1,
+            10000,
+            1000 * terminals,
+            0,
+            0,
+            100,
+            100,
+            1,
+            1000,
+        )?;
+        let max = params.max_arcs();
+        if params.density > max {
+            return Err(ParamError::DensityTooHigh {
+                density: params.density,
+                max,
+            });
+        }
+        Ok(params)
+    }
+
+    /// Average out-degree, `density / nodes`.
+    pub fn average_degree(&self) -> f64 {
+        self.density as f64 / self.nodes as f64
+    }
+
+    /// Upper bound on the number of distinct arcs NETGEN can place.
+    ///
+    /// Arcs never leave pure sinks, never enter pure sources, and are never
+    /// self-loops, so at most `tails * heads - overlap` distinct arcs exist.
+    /// A larger `density` is accepted but leaves the network short.
+    pub fn max_arcs(&self) -> i64 {
+        let last_tail = self.nodes - self.sinks + self.tsinks;
+        let first_head = self.sources - self.tsources + 1;
+        let heads = self.nodes - first_head + 1;
+        let overlap = (last_tail - first_head + 1).max(0);
+        last_tail.saturating_mul(heads) - overlap
+    }
+
+    /// Generate a network from these parameters. Equivalent to [`generate`].
+    pub fn generate(&self, seed: i64) -> Result<NetgenResult, NetgenError> {
+        generate(seed, self)
+    }
+
     /// Detect the problem type from the parameters.
     pub fn problem_type(&self) -> ProblemType {
         if (self.sources - self.tsources) + (self.sinks - self.tsinks) == self.nodes
@@ -188,14 +556,278 @@ impl NetgenParams {
     }
 }
 
-/// Specific parameter validation errors.
+/// Formats the 13 values in `parms[]` order, separated by single spaces.
+impl fmt::Display for NetgenParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, v) in self.to_array().iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{v}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses exactly 13 whitespace-separated integers in `parms[]` order and
+/// validates them. Use [`parse_problem_line`] for full 15-integer lines.
+impl FromStr for NetgenParams {
+    type Err = ParseParamsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = parse_integers(s, 13)?;
+        Ok(NetgenParams::from_slice(&values)?)
+    }
+}
+
+/// Arc count giving an average out-degree of `deg`, or `None` on overflow
+/// or non-positive input.
+pub fn density_for_degree(nodes: i64, deg: i64) -> Option<i64> {
+    if nodes <= 0 || deg <= 0 {
+        return None;
+    }
+    nodes.checked_mul(deg)
+}
+
+/// `round(sqrt(x))` for `x >= 0`, in integers: `r + 1` when `x > r^2 + r`.
+pub(crate) fn round_sqrt(x: i64) -> i64 {
+    let root = x.isqrt();
+    root + i64::from(x - root * root > root)
+}
+
+/// Parse a full problem line as consumed by the C tool: seed, problem
+/// number, then the 13 parameters, separated by arbitrary whitespace.
+pub fn parse_problem_line(line: &str) -> Result<(i64, i64, NetgenParams), ParseParamsError> {
+    let values = parse_integers(line, 15)?;
+    Ok((
+        values[0],
+        values[1],
+        NetgenParams::from_slice(&values[2..])?,
+    ))
+}
+
+impl NetgenParams {
+    /// The problem line the C tool reads for these parameters: seed,
+    /// problem number, then the 13 values, separated by single spaces (the
+    /// inverse of [`parse_problem_line`]).
+    pub fn to_problem_line(&self, seed: i64, problem: i64) -> String {
+        alloc::format!("{seed} {problem} {self}")
+    }
+}
+
+/// Write a problem file for the C tool (or `netgen`): one
+/// [problem line](NetgenParams::to_problem_line) per `(seed, problem,
+/// params)` spec, then a line holding `0`, the non-positive seed the C
+/// reader stops at.
+///
+/// A spec with a non-positive seed or problem number would end the batch
+/// early, as both the C reader and the CLI stop there, so it fails with
+/// [`io::ErrorKind::InvalidInput`] before anything is written.
+#[cfg(feature = "std")]
+pub fn write_problem_file(
+    w: &mut impl Write,
+    specs: &[(i64, i64, NetgenParams)],
+) -> io::Result<()> {
+    for (seed, problem, _) in specs {
+        let reason = if *seed <= 0 {
+            alloc::format!("problem {problem} has non-positive seed {seed}")
+        } else if *problem <= 0 {
+            alloc::format!("non-positive problem number {problem}")
+        } else {
+            continue;
+        };
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, reason));
+    }
+    for (seed, problem, params) in specs {
+        writeln!(w, "{}", params.to_problem_line(*seed, *problem))?;
+    }
+    writeln!(w, "0")
+}
+
+fn parse_integers(s: &str, expected: usize) -> Result<Vec<i64>, ParseParamsError> {
+    let values = s
+        .split_whitespace()
+        .map(|tok| {
+            tok.parse::<i64>()
+                .map_err(|_| ParseParamsError::InvalidInteger(tok.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.len() != expected {
+        return Err(ParseParamsError::WrongCount {
+            expected,
+            found: values.len(),
+        });
+    }
+    Ok(values)
+}
+
+/// Errors from parsing parameter lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseParamsError {
+    /// The line did not contain the expected number of integers.
+    WrongCount { expected: usize, found: usize },
+    /// A token was not a valid integer.
+    InvalidInteger(String),
+    /// The values parsed but failed validation.
+    Invalid(ParamError),
+}
+
+impl fmt::Display for ParseParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseParamsError::WrongCount { expected, found } => {
+                write!(f, "expected {expected} integers, found {found}")
+            }
+            ParseParamsError::InvalidInteger(tok) => write!(f, "invalid integer: {tok:?}"),
+            ParseParamsError::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for ParseParamsError {}
+
+impl From<ParamError> for ParseParamsError {
+    fn from(e: ParamError) -> Self {
+        ParseParamsError::Invalid(e)
+    }
+}
+
+/// Specific parameter validation errors, carrying the offending values.
+///
+/// The variants used to be fieldless; patterns that name a variant now
+/// need `{ .. }` (e.g. `ParamError::SupplyTooLow { .. }`). To match on the
+/// condition alone, match on [`kind`](Self::kind) instead.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParamError {
+    NonPositiveNodes {
+        nodes: i64,
+    },
+    NonPositiveSources {
+        sources: i64,
+    },
+    NonPositiveSinks {
+        sinks: i64,
+    },
+    SourcesSinksExceedNodes {
+        sources: i64,
+        sinks: i64,
+        nodes: i64,
+    },
+    DensityTooLow {
+        density: i64,
+        nodes: i64,
+    },
+    /// `density` exceeds `max`: `nodes * nodes` in
+    /// [`NetgenParams::validate`], or the most arcs the network can hold
+    /// ([`NetgenParams::max_arcs`]) in
+    /// [`NetgenParams::with_average_degree`].
+    DensityTooHigh {
+        density: i64,
+        max: i64,
+    },
+    /// `nodes` or `density` exceeds the size limits. An arc count that
+    /// overflows `i64` is reported as `i64::MAX`.
+    TooBig {
+        nodes: i64,
+        density: i64,
+    },
+    MinCostExceedsMaxCost {
+        mincost: i64,
+        maxcost: i64,
+    },
+    SupplyTooLow {
+        supply: i64,
+        sources: i64,
+    },
+    TSourcesExceedSources {
+        tsources: i64,
+        sources: i64,
+    },
+    TSinksExceedSinks {
+        tsinks: i64,
+        sinks: i64,
+    },
+    HiCostOutOfRange {
+        hicost_pct: i64,
+    },
+    CapacitatedOutOfRange {
+        capacitated_pct: i64,
+    },
+    MinCapExceedsMaxCap {
+        mincap: i64,
+        maxcap: i64,
+    },
+    /// [`GenerateOptions`] skeleton cost range is empty.
+    SkeletonMinCostExceedsMaxCost {
+        mincost: i64,
+        maxcost: i64,
+    },
+    /// [`GenerateOptions`] skeleton capacity range is empty.
+    SkeletonMinCapExceedsMaxCap {
+        mincap: i64,
+        maxcap: i64,
+    },
+    /// A [`SupplyDistribution::Custom`] does not have one entry per source.
+    CustomSupplyLength {
+        len: usize,
+        sources: i64,
+    },
+    /// A [`SupplyDistribution::Custom`] has a negative entry.
+    NegativeCustomSupply {
+        index: usize,
+        value: i64,
+    },
+    /// A [`SupplyDistribution::Custom`] does not sum to `supply`.
+    CustomSupplySum {
+        sum: i128,
+        supply: i64,
+    },
+    /// [`GenerateOptions::demand_distribution`] does not have one entry per
+    /// sink.
+    DemandLength {
+        len: usize,
+        sinks: i64,
+    },
+    /// [`GenerateOptions::demand_distribution`] has a negative entry.
+    NegativeDemand {
+        index: usize,
+        value: i64,
+    },
+    /// [`GenerateOptions::demand_distribution`] does not sum to `supply`.
+    DemandSum {
+        sum: i128,
+        supply: i64,
+    },
+    /// [`GenerateOptions::multipliers`] is empty or not positive.
+    MultiplierRange {
+        num_min: i64,
+        num_max: i64,
+        den: i64,
+    },
+    /// [`GenerateOptions::max_out_degree`] is set outside
+    /// [`GenerationMode::Fast`].
+    OutDegreeNeedsFastMode {
+        max_out_degree: i64,
+    },
+    /// [`GenerateOptions::max_out_degree`] is negative, or too small for
+    /// `tails` nodes to hold `density` arcs.
+    OutDegreeTooLow {
+        max_out_degree: i64,
+        density: i64,
+        tails: i64,
+    },
+}
+
+/// The condition a [`ParamError`] reports, without its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamErrorKind {
     NonPositiveNodes,
     NonPositiveSources,
     NonPositiveSinks,
     SourcesSinksExceedNodes,
     DensityTooLow,
+    DensityTooHigh,
+    TooBig,
     MinCostExceedsMaxCost,
     SupplyTooLow,
     TSourcesExceedSources,
@@ -203,64 +835,475 @@ pub enum ParamError {
     HiCostOutOfRange,
     CapacitatedOutOfRange,
     MinCapExceedsMaxCap,
+    SkeletonMinCostExceedsMaxCost,
+    SkeletonMinCapExceedsMaxCap,
+    CustomSupplyLength,
+    NegativeCustomSupply,
+    CustomSupplySum,
+    DemandLength,
+    NegativeDemand,
+    DemandSum,
+    MultiplierRange,
+    OutDegreeNeedsFastMode,
+    OutDegreeTooLow,
+}
+
+impl ParamError {
+    /// The condition this error reports.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, ParamErrorKind};
+    ///
+    /// let err = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 5, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap_err();
+    /// assert_eq!(err.kind(), ParamErrorKind::SupplyTooLow);
+    /// assert_eq!(err.to_string(), "supply (5) must be at least sources (10)");
+    /// ```
+    pub fn kind(&self) -> ParamErrorKind {
+        match self {
+            ParamError::NonPositiveNodes { .. } => ParamErrorKind::NonPositiveNodes,
+            ParamError::NonPositiveSources { .. } => ParamErrorKind::NonPositiveSources,
+            ParamError::NonPositiveSinks { .. } => ParamErrorKind::NonPositiveSinks,
+            ParamError::SourcesSinksExceedNodes { .. } => ParamErrorKind::SourcesSinksExceedNodes,
+            ParamError::DensityTooLow { .. } => ParamErrorKind::DensityTooLow,
+            ParamError::DensityTooHigh { .. } => ParamErrorKind::DensityTooHigh,
+            ParamError::TooBig { .. } => ParamErrorKind::TooBig,
+            ParamError::MinCostExceedsMaxCost { .. } => ParamErrorKind::MinCostExceedsMaxCost,
+            ParamError::SupplyTooLow { .. } => ParamErrorKind::SupplyTooLow,
+            ParamError::TSourcesExceedSources { .. } => ParamErrorKind::TSourcesExceedSources,
+            ParamError::TSinksExceedSinks { .. } => ParamErrorKind::TSinksExceedSinks,
+            ParamError::HiCostOutOfRange { .. } => ParamErrorKind::HiCostOutOfRange,
+            ParamError::CapacitatedOutOfRange { .. } => ParamErrorKind::CapacitatedOutOfRange,
+            ParamError::MinCapExceedsMaxCap { .. } => ParamErrorKind::MinCapExceedsMaxCap,
+            ParamError::SkeletonMinCostExceedsMaxCost { .. } => {
+                ParamErrorKind::SkeletonMinCostExceedsMaxCost
+            }
+            ParamError::SkeletonMinCapExceedsMaxCap { .. } => {
+                ParamErrorKind::SkeletonMinCapExceedsMaxCap
+            }
+            ParamError::CustomSupplyLength { .. } => ParamErrorKind::CustomSupplyLength,
+            ParamError::NegativeCustomSupply { .. } => ParamErrorKind::NegativeCustomSupply,
+            ParamError::CustomSupplySum { .. } => ParamErrorKind::CustomSupplySum,
+            ParamError::DemandLength { .. } => ParamErrorKind::DemandLength,
+            ParamError::NegativeDemand { .. } => ParamErrorKind::NegativeDemand,
+            ParamError::DemandSum { .. } => ParamErrorKind::DemandSum,
+            ParamError::MultiplierRange { .. } => ParamErrorKind::MultiplierRange,
+            ParamError::OutDegreeNeedsFastMode { .. } => ParamErrorKind::OutDegreeNeedsFastMode,
+            ParamError::OutDegreeTooLow { .. } => ParamErrorKind::OutDegreeTooLow,
+        }
+    }
 }
 
 impl fmt::Display for ParamError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ParamError::NonPositiveNodes => write!(f, "nodes must be positive"),
-            ParamError::NonPositiveSources => write!(f, "sources must be positive"),
-            ParamError::NonPositiveSinks => write!(f, "sinks must be positive"),
-            ParamError::SourcesSinksExceedNodes => {
-                write!(f, "sources + sinks must not exceed nodes")
+        match *self {
+            ParamError::NonPositiveNodes { nodes } => {
+                write!(f, "nodes ({nodes}) must be positive")
+            }
+            ParamError::NonPositiveSources { sources } => {
+                write!(f, "sources ({sources}) must be positive")
             }
-            ParamError::DensityTooLow => {
-                write!(f, "density (arc count) must be at least nodes")
+            ParamError::NonPositiveSinks { sinks } => write!(f, "sinks ({sinks}) must be positive"),
+            ParamError::SourcesSinksExceedNodes {
+                sources,
+                sinks,
+                nodes,
+            } => write!(
+                f,
+                "sources + sinks ({sources} + {sinks}) must not exceed nodes ({nodes})"
+            ),
+            ParamError::DensityTooLow { density, nodes } => write!(
+                f,
+                "density (arc count, {density}) must be at least nodes ({nodes})"
+            ),
+            ParamError::DensityTooHigh { density, max } => write!(
+                f,
+                "density (arc count, {density}) exceeds the feasible maximum ({max})"
+            ),
+            ParamError::TooBig { nodes, density } => write!(
+                f,
+                "nodes ({nodes}) and density ({density}) must not exceed 2^32 - 1"
+            ),
+            ParamError::MinCostExceedsMaxCost { mincost, maxcost } => {
+                write!(f, "mincost ({mincost}) must not exceed maxcost ({maxcost})")
             }
-            ParamError::MinCostExceedsMaxCost => write!(f, "mincost must not exceed maxcost"),
-            ParamError::SupplyTooLow => write!(f, "supply must be at least sources"),
-            ParamError::TSourcesExceedSources => {
-                write!(f, "transshipment sources must not exceed sources")
+            ParamError::SupplyTooLow { supply, sources } => {
+                write!(f, "supply ({supply}) must be at least sources ({sources})")
             }
-            ParamError::TSinksExceedSinks => {
-                write!(f, "transshipment sinks must not exceed sinks")
+            ParamError::TSourcesExceedSources { tsources, sources } => write!(
+                f,
+                "transshipment sources ({tsources}) must not exceed sources ({sources})"
+            ),
+            ParamError::TSinksExceedSinks { tsinks, sinks } => write!(
+                f,
+                "transshipment sinks ({tsinks}) must not exceed sinks ({sinks})"
+            ),
+            ParamError::HiCostOutOfRange { hicost_pct } => {
+                write!(f, "hicost percentage ({hicost_pct}) must be 0..=100")
             }
-            ParamError::HiCostOutOfRange => write!(f, "hicost percentage must be 0..=100"),
-            ParamError::CapacitatedOutOfRange => {
-                write!(f, "capacitated percentage must be 0..=100")
+            ParamError::CapacitatedOutOfRange { capacitated_pct } => write!(
+                f,
+                "capacitated percentage ({capacitated_pct}) must be 0..=100"
+            ),
+            ParamError::MinCapExceedsMaxCap { mincap, maxcap } => {
+                write!(f, "mincap ({mincap}) must not exceed maxcap ({maxcap})")
             }
-            ParamError::MinCapExceedsMaxCap => write!(f, "mincap must not exceed maxcap"),
+            ParamError::SkeletonMinCostExceedsMaxCost { mincost, maxcost } => write!(
+                f,
+                "skeleton mincost ({mincost}) must not exceed skeleton maxcost ({maxcost})"
+            ),
+            ParamError::SkeletonMinCapExceedsMaxCap { mincap, maxcap } => write!(
+                f,
+                "skeleton mincap ({mincap}) must not exceed skeleton maxcap ({maxcap})"
+            ),
+            ParamError::CustomSupplyLength { len, sources } => write!(
+                f,
+                "custom supplies must have one entry per source ({len} entries, {sources} sources)"
+            ),
+            ParamError::NegativeCustomSupply { index, value } => write!(
+                f,
+                "custom supplies must be non-negative (entry {index} is {value})"
+            ),
+            ParamError::CustomSupplySum { sum, supply } => write!(
+                f,
+                "custom supplies must sum to supply (sum {sum}, supply {supply})"
+            ),
+            ParamError::DemandLength { len, sinks } => write!(
+                f,
+                "demands must have one entry per sink ({len} entries, {sinks} sinks)"
+            ),
+            ParamError::NegativeDemand { index, value } => {
+                write!(f, "demands must be non-negative (entry {index} is {value})")
+            }
+            ParamError::DemandSum { sum, supply } => {
+                write!(f, "demands must sum to supply (sum {sum}, supply {supply})")
+            }
+            ParamError::MultiplierRange {
+                num_min,
+                num_max,
+                den,
+            } => write!(
+                f,
+                "multipliers must be positive with num_min <= num_max \
+                 (num_min {num_min}, num_max {num_max}, den {den})"
+            ),
+            ParamError::OutDegreeNeedsFastMode { max_out_degree } => write!(
+                f,
+                "max out-degree ({max_out_degree}) requires fast generation mode"
+            ),
+            ParamError::OutDegreeTooLow {
+                max_out_degree,
+                density,
+                tails,
+            } => write!(
+                f,
+                "density ({density}) exceeds max out-degree ({max_out_degree}) \
+                 times the {tails} tail nodes"
+            ),
         }
     }
 }
 
-impl std::error::Error for ParamError {}
+impl core::error::Error for ParamError {}
 
 /// A single arc in the generated network.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Arc {
+    /// Tail node, a 1-based DIMACS id: node `from` has supply
+    /// `supply[from - 1]` in [`NetgenResult`]. See [`NodeId`].
     pub from: u64,
+    /// Head node, a 1-based DIMACS id like `from`.
     pub to: u64,
     pub cost: i64,
     pub capacity: i64,
+    /// Gain multiplier `numerator / denominator` for generalized networks
+    /// (see [`GenerateOptions::multipliers`]); `None` means 1.
+    pub multiplier: Option<(i64, i64)>,
+    /// Whether the generator left the arc without a capacity of its own.
+    /// `capacity` then holds the total supply, the reference generator's
+    /// stand-in for infinity; [`DimacsOptions::uncapacitated_as`] can write
+    /// something else instead.
+    ///
+    /// Formats that do not record the distinction (DIMACS, version 1
+    /// binary files, petgraph, the C interface) are read back with the flag
+    /// set on exactly the arcs whose capacity equals the total supply; see
+    /// [`NetgenResult::infer_uncapacitated`].
+    pub uncapacitated: bool,
+    /// The generator phase that produced the arc.
+    pub kind: ArcKind,
+    /// Whether the `hicost_pct` roll forced the arc's cost to `maxcost`.
+    /// Only skeleton arcs take the roll; any arc may still draw `maxcost`
+    /// at random.
+    pub hicost: bool,
+}
+
+/// Where an [`Arc`] came from.
+///
+/// Only the generator and the binary format know this; the other readers
+/// return [`Unknown`](Self::Unknown) for every arc (see
+/// [`NetgenResult::clear_provenance`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ArcKind {
+    /// Not recorded: read from a format that does not store it, or built
+    /// outside the NETGEN generator.
+    #[default]
+    Unknown,
+    /// A skeleton arc, which guarantees feasibility: chain and sink arcs,
+    /// assignment matching arcs and demand-routing arcs.
+    Skeleton,
+    /// A random ("rubbish") arc, including top-up arcs.
+    Random,
+}
+
+/// A node, stored 0-based, for code that indexes arrays by node.
+///
+/// [`Arc::from`] and [`Arc::to`] are 1-based DIMACS ids while
+/// [`NetgenResult::supply`] is indexed from 0; converting through `NodeId`
+/// keeps the two apart.
+///
+/// ```rust
+/// use netgen_rs::NodeId;
+///
+/// let node = NodeId::from_dimacs(1);
+/// assert_eq!(node.index(), 0);
+/// assert_eq!(node.to_dimacs(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct NodeId(pub u32);
+
+impl NodeId {
+    /// The node with DIMACS id `id`.
+    ///
+    /// # Panics
+    ///
+    /// If `id` is 0 or above `u32::MAX + 1`, so that its 0-based index
+    /// does not fit in a `u32`.
+    pub fn from_dimacs(id: u64) -> Self {
+        match id.checked_sub(1).map(u32::try_from) {
+            Some(Ok(index)) => NodeId(index),
+            _ => panic!("node {id} is not a valid DIMACS id"),
+        }
+    }
+
+    /// The node's 1-based DIMACS id.
+    pub fn to_dimacs(self) -> u64 {
+        u64::from(self.0) + 1
+    }
+
+    /// The node's 0-based index, e.g. into [`NetgenResult::supply`].
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// An arc, by its 0-based position in [`NetgenResult::arcs`].
+///
+/// Used with [`NetworkView`], which checks it against the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct ArcId(pub u32);
+
+impl ArcId {
+    /// The arc's index into [`NetgenResult::arcs`].
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
 }
 
 /// Result of network generation.
-#[derive(Debug, Clone)]
+///
+/// Arc endpoints are 1-based DIMACS node ids, as written to files, while
+/// `supply` is a 0-based vector: node `i` has supply `supply[i - 1]`.
+/// [`supply_of`](Self::supply_of) and
+/// [`arcs_zero_based`](Self::arcs_zero_based) go through [`NodeId`]
+/// instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NetgenResult {
     pub arcs: Vec<Arc>,
     /// Supply (positive) or demand (negative) at each node, 0-indexed.
     pub supply: Vec<i64>,
 }
 
+impl NetgenResult {
+    /// Supply (positive) or demand (negative) of `node`.
+    ///
+    /// # Panics
+    ///
+    /// If `node` is not in the network.
+    pub fn supply_of(&self, node: NodeId) -> i64 {
+        self.supply[node.index()]
+    }
+
+    /// The arcs as `(from, to, cost, capacity)`, with 0-based endpoints
+    /// that index [`supply`](Self::supply) directly.
+    ///
+    /// ```rust
+    /// use netgen_rs::NetgenParams;
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = params.generate(13502460).unwrap();
+    /// let (from, _, _, _) = result.arcs_zero_based().next().unwrap();
+    /// assert!(result.supply[from as usize] > 0); // the first arc leaves a source
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// On an arc with an endpoint that [`NodeId::from_dimacs`] rejects: 0 or
+    /// above `u32::MAX + 1`. Generated and parsed networks have none.
+    pub fn arcs_zero_based(&self) -> impl Iterator<Item = (u32, u32, i64, i64)> + '_ {
+        self.arcs.iter().map(|arc| {
+            let from = NodeId::from_dimacs(arc.from);
+            let to = NodeId::from_dimacs(arc.to);
+            (from.0, to.0, arc.cost, arc.capacity)
+        })
+    }
+
+    /// Write complete DIMACS output (header + network) for this result.
+    #[cfg(feature = "std")]
+    pub fn write_dimacs(
+        &self,
+        w: &mut impl Write,
+        seed: i64,
+        problem: i64,
+        params: &NetgenParams,
+    ) -> io::Result<()> {
+        write_dimacs(w, seed, problem, params, self)
+    }
+
+    /// Format this result as a DIMACS string.
+    pub fn to_dimacs_string(&self, seed: i64, problem: i64, params: &NetgenParams) -> String {
+        let mut out = String::with_capacity(estimate_dimacs_bytes(params, self));
+        write_dimacs_fmt(
+            &mut out,
+            seed,
+            problem,
+            params,
+            self,
+            &DimacsOptions::default(),
+        )
+        .expect("writing to a String should not fail");
+        out
+    }
+
+    /// Number of arcs without a capacity of their own (see
+    /// [`Arc::uncapacitated`]). Each skeleton and random arc is capacitated
+    /// with probability `capacitated_pct`%, so this is about
+    /// `(100 - capacitated_pct)`% of the arcs.
+    pub fn uncapacitated_arc_count(&self) -> usize {
+        self.arcs.iter().filter(|a| a.uncapacitated).count()
+    }
+
+    /// Set [`Arc::uncapacitated`] on exactly the arcs whose capacity equals
+    /// the total supply, as the readers of formats that do not record the
+    /// flag do.
+    ///
+    /// The total supply is the sum of the positive [`supply`](Self::supply)
+    /// entries, so this needs the real supplies: it is wrong for networks
+    /// read from `p max` or `p asn` DIMACS files, whose supplies are ±1
+    /// stand-ins and which `parse_dimacs` leaves unflagged.
+    ///
+    /// A capacitated arc that happens to draw the total supply as its
+    /// capacity is flagged too, so a generated network compares equal to
+    /// one read back from DIMACS only after this and
+    /// [`clear_provenance`](Self::clear_provenance) are applied to it:
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate, parse_dimacs, to_dimacs_string};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let mut result = generate(13502460, &params).unwrap();
+    /// result.clear_provenance();
+    /// let parsed = parse_dimacs(&to_dimacs_string(13502460, 1, &params).unwrap()).unwrap();
+    /// assert_ne!(parsed, result);
+    /// result.infer_uncapacitated();
+    /// assert_eq!(parsed, result);
+    /// ```
+    pub fn infer_uncapacitated(&mut self) {
+        let total: i64 = self.supply.iter().filter(|&&s| s > 0).sum();
+        for arc in &mut self.arcs {
+            arc.uncapacitated = arc.capacity == total;
+        }
+    }
+
+    /// Number of arcs whose cost the `hicost_pct` roll forced to `maxcost`
+    /// (see [`Arc::hicost`]), about `hicost_pct`% of the skeleton arcs.
+    pub fn hicost_arc_count(&self) -> usize {
+        self.arcs.iter().filter(|a| a.hicost).count()
+    }
+
+    /// Reset [`Arc::kind`] to [`ArcKind::Unknown`] and clear [`Arc::hicost`]
+    /// on every arc, as the readers of formats other than the binary one
+    /// leave them. See [`infer_uncapacitated`](Self::infer_uncapacitated)
+    /// for comparing a generated network with one read back using `==`;
+    /// [`canonicalize`](Self::canonicalize) ignores both.
+    pub fn clear_provenance(&mut self) {
+        for arc in &mut self.arcs {
+            arc.kind = ArcKind::Unknown;
+            arc.hicost = false;
+        }
+    }
+}
+
 /// Problem type detected from parameters.
+///
+/// Displays as `assignment`, `max-flow`, or `min-cost-flow`. Parsing also
+/// accepts the DIMACS problem-line tokens `asn`, `max`, and `min`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum ProblemType {
     Assignment,
     MaxFlow,
     MinCostFlow,
 }
 
+impl fmt::Display for ProblemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemType::Assignment => write!(f, "assignment"),
+            ProblemType::MaxFlow => write!(f, "max-flow"),
+            ProblemType::MinCostFlow => write!(f, "min-cost-flow"),
+        }
+    }
+}
+
+impl FromStr for ProblemType {
+    type Err = ParseProblemTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "assignment" | "asn" => Ok(ProblemType::Assignment),
+            "max-flow" | "max" => Ok(ProblemType::MaxFlow),
+            "min-cost-flow" | "min" => Ok(ProblemType::MinCostFlow),
+            _ => Err(ParseProblemTypeError(s.to_string())),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown [`ProblemType`] name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseProblemTypeError(String);
+
+impl fmt::Display for ParseProblemTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown problem type: {:?}", self.0)
+    }
+}
+
+impl core::error::Error for ParseProblemTypeError {}
+
 /// Errors that may occur while running the generator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NetgenError {
@@ -268,6 +1311,20 @@ pub enum NetgenError {
     TooBig,
     BadParms,
     AllocationFailure,
+    /// Fewer arcs than `density` were generated under [`Shortfall::Error`].
+    ArcShortfall {
+        requested: usize,
+        produced: usize,
+    },
+    /// [`generate_connected`] found no clean network within its retries.
+    Disconnected {
+        attempts: u32,
+    },
+    /// The progress callback of [`generate_with_progress`] asked to stop.
+    Cancelled,
+    /// The [`GenerateOptions`] do not pass
+    /// [`GenerateOptions::validate`] for the parameters.
+    Options(ParamError),
 }
 
 impl fmt::Display for NetgenError {
@@ -277,27 +1334,354 @@ impl fmt::Display for NetgenError {
             NetgenError::TooBig => write!(f, "problem size exceeds limits"),
             NetgenError::BadParms => write!(f, "invalid parameters"),
             NetgenError::AllocationFailure => write!(f, "allocation failure"),
+            NetgenError::ArcShortfall {
+                requested,
+                produced,
+            } => write!(f, "generated {produced} arcs, {requested} requested"),
+            NetgenError::Disconnected { attempts } => {
+                write!(f, "no connected network in {attempts} attempts")
+            }
+            NetgenError::Cancelled => write!(f, "generation cancelled"),
+            NetgenError::Options(e) => write!(f, "invalid options: {e}"),
         }
     }
 }
 
-impl std::error::Error for NetgenError {}
+impl core::error::Error for NetgenError {}
 
 /// Generate a network flow problem.
+///
+/// Fails with [`NetgenError::BadSeed`] for a non-positive seed, and with
+/// [`NetgenError::TooBig`] or [`NetgenError::BadParms`] if `params` does not
+/// pass [`NetgenParams::validate`], mirroring the C generator's checks.
 pub fn generate(seed: i64, params: &NetgenParams) -> Result<NetgenResult, NetgenError> {
+    check_inputs(seed, params)?;
+    Ok(netgen::netgen(seed, params))
+}
+
+fn check_inputs(seed: i64, params: &NetgenParams) -> Result<(), NetgenError> {
     if seed <= 0 {
         return Err(NetgenError::BadSeed);
     }
-    Ok(netgen::netgen(seed, params))
+    match params.validate() {
+        Ok(()) => Ok(()),
+        Err(ParamError::TooBig { .. }) => Err(NetgenError::TooBig),
+        Err(_) => Err(NetgenError::BadParms),
+    }
+}
+
+/// Generate a network and report how it was built.
+///
+/// The returned network is identical to the one from [`generate`].
+pub fn generate_with_report(
+    seed: i64,
+    params: &NetgenParams,
+) -> Result<(NetgenResult, GenerationReport), NetgenError> {
+    generate_with_options(seed, params, &GenerateOptions::default())
+}
+
+/// Generate a network and count the RNG draws of each generation phase.
+///
+/// The returned network is identical to the one from [`generate`]. When a
+/// patched generator's output diverges from the reference, comparing traces
+/// with [`RngTrace::first_divergence`] shows which phase consumed the RNG
+/// differently.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, RngPhase, generate, generate_traced};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let (result, trace) = generate_traced(13502460, &params).unwrap();
+/// assert_eq!(result, generate(13502460, &params).unwrap());
+/// assert_eq!(trace.phase(RngPhase::SupplyCreation).draws, 21);
+/// ```
+pub fn generate_traced(
+    seed: i64,
+    params: &NetgenParams,
+) -> Result<(NetgenResult, RngTrace), NetgenError> {
+    check_inputs(seed, params)?;
+    Ok(netgen::netgen_traced(seed, params))
+}
+
+/// Generate a network, reporting progress to `progress` as it goes.
+///
+/// The callback runs after the supplies and chains are drawn, after each
+/// source, and every few thousand random arcs, so it is called at a bounded
+/// rate even for a single huge source. Returning [`ControlFlow::Break`]
+/// stops generation at the next such point with [`NetgenError::Cancelled`].
+/// The returned network is identical to the one from [`generate`].
+///
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use netgen_rs::{NetgenError, NetgenParams, generate_with_progress};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate_with_progress(13502460, &params, |p| {
+///     eprintln!("{}/{} sources, {} arcs", p.sources_done, p.sources, p.arcs);
+///     ControlFlow::Continue(())
+/// });
+/// assert_eq!(result.unwrap().arcs.len(), 2000);
+///
+/// let cancelled = generate_with_progress(13502460, &params, |p| {
+///     if p.sources_done >= 1 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+/// });
+/// assert_eq!(cancelled, Err(NetgenError::Cancelled));
+/// ```
+pub fn generate_with_progress(
+    seed: i64,
+    params: &NetgenParams,
+    mut progress: impl FnMut(Progress) -> ControlFlow<()>,
+) -> Result<NetgenResult, NetgenError> {
+    check_inputs(seed, params)?;
+    netgen::netgen_with_progress(seed, params, &mut progress).ok_or(NetgenError::Cancelled)
+}
+
+/// Generate a network in the given [`GenerationMode`]. With
+/// [`GenerationMode::ReferenceExact`] this is [`generate`].
+///
+/// ```rust
+/// use netgen_rs::{GenerationMode, NetgenParams, generate, generate_with};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let exact = generate_with(13502460, &params, GenerationMode::ReferenceExact).unwrap();
+/// assert_eq!(exact, generate(13502460, &params).unwrap());
+/// let fast = generate_with(13502460, &params, GenerationMode::Fast).unwrap();
+/// assert_eq!(fast.check_invariants(&params), Ok(()));
+/// assert_eq!(fast.supply[..10], exact.supply[..10]);
+/// ```
+pub fn generate_with(
+    seed: i64,
+    params: &NetgenParams,
+    mode: GenerationMode,
+) -> Result<NetgenResult, NetgenError> {
+    let options = GenerateOptions {
+        mode,
+        ..GenerateOptions::default()
+    };
+    generate_with_options(seed, params, &options).map(|(result, _)| result)
+}
+
+/// Generate a network with non-default [`GenerateOptions`], reporting how it
+/// was built.
+///
+/// Fails as [`generate`] does, and with [`NetgenError::Options`], carrying
+/// the [`ParamError`], if the options do not pass
+/// [`GenerateOptions::validate`].
+pub fn generate_with_options(
+    seed: i64,
+    params: &NetgenParams,
+    options: &GenerateOptions,
+) -> Result<(NetgenResult, GenerationReport), NetgenError> {
+    check_inputs(seed, params)?;
+    options.validate(params).map_err(NetgenError::Options)?;
+    let (result, report) = netgen::netgen_with(seed, params, options);
+    if report.shortfall > 0 {
+        match options.on_shortfall {
+            Shortfall::Ignore | Shortfall::TopUp => {}
+            Shortfall::Warn => log::warn!(
+                "generated {} arcs, {} requested",
+                result.arcs.len(),
+                report.requested_arcs
+            ),
+            Shortfall::Error => {
+                return Err(NetgenError::ArcShortfall {
+                    requested: report.requested_arcs,
+                    produced: result.arcs.len(),
+                });
+            }
+        }
+    }
+    Ok((result, report))
 }
 
+/// Generate a network from an arbitrary `u64` seed.
+///
+/// The seed is mapped onto the valid range with [`fold_seed`], so any value
+/// (including 0 and `u64::MAX`) is accepted. Use `fold_seed(seed)` to learn
+/// the NETGEN seed that was actually used.
+pub fn generate_seeded(seed: u64, params: &NetgenParams) -> Result<NetgenResult, NetgenError> {
+    generate(fold_seed(seed), params)
+}
+
+/// Generate one instance per parameter set, seeding instance `i` with
+/// `SeedSequence::new(master).nth(i)`.
+///
+/// Returns each instance together with the NETGEN seed it was generated from.
+pub fn generate_family(
+    master: u64,
+    params_list: &[NetgenParams],
+) -> Result<Vec<(i64, NetgenResult)>, NetgenError> {
+    let seq = SeedSequence::new(master);
+    params_list
+        .iter()
+        .zip(seq.iter())
+        .map(|(params, seed)| Ok((seed, generate(seed, params)?)))
+        .collect()
+}
+
+/// Generate a network from a freshly drawn random seed.
+///
+/// Returns the seed alongside the result; passing it to [`generate`]
+/// reproduces the same instance.
+#[cfg(feature = "std")]
+pub fn generate_random(params: &NetgenParams) -> Result<(i64, NetgenResult), NetgenError> {
+    let seed = seed::entropy_seed();
+    Ok((seed, generate(seed, params)?))
+}
+
+/// Generate a network whose [`analysis::reachability`] report is clean:
+/// every sink reachable from a source and the network weakly connected.
+///
+/// Tries `seed`, then the successive seeds `seed + 1`, `seed + 2`, ...
+/// (wrapping from [`MAX_SEED`] to 1), for at most `max_retries` retries.
+/// The seed actually used may therefore differ from `seed`; it is returned
+/// alongside the result. Fails with [`NetgenError::Disconnected`] if every
+/// attempt is unclean.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate_connected};
+///
+/// let params = NetgenParams::from_slice(&[
+///     100, 10, 10, 100, 1, 10, 100, 0, 0, 0, 100, 1, 10,
+/// ]).unwrap();
+/// let (seed, _result) = generate_connected(11, &params, 10).unwrap();
+/// assert_eq!(seed, 13);
+/// ```
+pub fn generate_connected(
+    seed: i64,
+    params: &NetgenParams,
+    max_retries: u32,
+) -> Result<(i64, NetgenResult), NetgenError> {
+    check_inputs(seed, params)?;
+    let mut current = seed;
+    for _ in 0..=max_retries {
+        let result = netgen::netgen(current, params);
+        if analysis::reachability(&result, params).is_clean() {
+            return Ok((current, result));
+        }
+        current = if current >= MAX_SEED { 1 } else { current + 1 };
+    }
+    Err(NetgenError::Disconnected {
+        attempts: max_retries.saturating_add(1),
+    })
+}
+
+/// Names of the [`NetgenParams`] fields, in [`NetgenParams::to_array`] order.
+pub(crate) const PARAM_NAMES: [&str; 13] = [
+    "nodes",
+    "sources",
+    "sinks",
+    "density",
+    "mincost",
+    "maxcost",
+    "supply",
+    "tsources",
+    "tsinks",
+    "hicost_pct",
+    "capacitated_pct",
+    "mincap",
+    "maxcap",
+];
+
 /// Write the DIMACS-format header comments.
+#[cfg(feature = "std")]
 pub fn write_dimacs_header(
     w: &mut impl Write,
     seed: i64,
     problem: i64,
     params: &NetgenParams,
 ) -> io::Result<()> {
+    write_dimacs_header_with_options(w, seed, problem, params, &HeaderOptions::default())
+}
+
+/// Write the DIMACS-format header comments, with [`HeaderOptions`]. The
+/// lines end in `\n` and the last one is left open, as the C generator
+/// leaves it; [`write_header_lines`] honors the line ending of
+/// [`DimacsOptions`] instead.
+///
+/// ```rust
+/// use netgen_rs::{HeaderOptions, NetgenParams, analysis, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100,
+/// ]).unwrap();
+/// let result = generate(99999, &params).unwrap();
+/// let options = HeaderOptions {
+///     optimal_max_flow: Some(analysis::max_flow_value(&result)),
+///     ..HeaderOptions::default()
+/// };
+/// let mut out = Vec::new();
+/// netgen_rs::write_dimacs_header_with_options(&mut out, 99999, 1, &params, &options).unwrap();
+/// assert!(String::from_utf8(out).unwrap().contains("c optimal max flow: "));
+/// ```
+#[cfg(feature = "std")]
+pub fn write_dimacs_header_with_options(
+    w: &mut impl Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    options: &HeaderOptions,
+) -> io::Result<()> {
+    via_fmt(w, |w| write_header(w, seed, problem, params, options))
+}
+
+/// Write the header comments as [`write_dimacs_with_options`] does:
+/// `options.header`, in [`DimacsOptions::line_ending`], with the line break
+/// that ends the header. Unlike [`write_dimacs_header_with_options`], the
+/// last line is terminated, as the network data follows;
+/// [`trailing_newline`](DimacsOptions::trailing_newline) only applies to
+/// the last line of a file, which the header never is.
+///
+/// With [`write_dimacs_network_with_options`], or the section writers
+/// starting at [`write_problem_line`], this writes the whole file:
+///
+/// ```rust
+/// use netgen_rs::{DimacsOptions, LineEnding, NetgenParams, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let options = DimacsOptions {
+///     line_ending: LineEnding::CrLf,
+///     ..DimacsOptions::default()
+/// };
+/// let mut out = Vec::new();
+/// netgen_rs::write_header_lines(&mut out, 13502460, 1, &params, &options).unwrap();
+/// netgen_rs::write_dimacs_network_with_options(&mut out, &params, &result, &options).unwrap();
+/// let mut whole = Vec::new();
+/// netgen_rs::write_dimacs_with_options(&mut whole, 13502460, 1, &params, &result, &options)
+///     .unwrap();
+/// assert_eq!(out, whole);
+/// ```
+#[cfg(feature = "std")]
+pub fn write_header_lines(
+    w: &mut impl Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    options: &DimacsOptions,
+) -> io::Result<()> {
+    via_fmt(w, |w| {
+        let mut w = LineEndings::section(w, options);
+        write_header(&mut w, seed, problem, params, &options.header)?;
+        fmt::Write::write_str(&mut w, "\n")
+    })
+}
+
+fn write_header(
+    w: &mut impl fmt::Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    options: &HeaderOptions,
+) -> fmt::Result {
     writeln!(w, "c NETGEN flow network generator (C version)")?;
     writeln!(w, "c  Problem {:2} input parameters", problem)?;
     writeln!(w, "c  ---------------------------")?;
@@ -321,73 +1705,408 @@ pub fn write_dimacs_header(
     )?;
     writeln!(w, "c   Minimum arc capacity: {:10}", params.mincap)?;
     write!(w, "c   Maximum arc capacity: {:10}", params.maxcap)?;
+    if let Some(value) = options.optimal_max_flow {
+        write!(w, "\nc optimal max flow: {value}")?;
+    }
+    if options.mode == GenerationMode::Fast {
+        write!(w, "\nc Generation mode: fast (not reference-exact)")?;
+    }
+    if options.dropped_arcs > 0 {
+        write!(w, "\nc Dropped arcs: {}", options.dropped_arcs)?;
+    }
+    if let Some(max) = options.max_out_degree {
+        write!(w, "\nc Maximum out-degree: {max}")?;
+    }
+    if options.include_provenance {
+        write!(w, "\nc generator: netgen_rs v{}", env!("CARGO_PKG_VERSION"))?;
+        if let Some(timestamp) = &options.generated {
+            write!(w, "\nc generated: {}", OneLine(timestamp))?;
+        }
+        if let Some(command) = &options.command {
+            write!(w, "\nc command: {}", OneLine(command))?;
+        }
+    }
+    if options.machine_readable {
+        write!(w, "\nc PARAM seed {seed}\nc PARAM problem {problem}")?;
+        for (name, value) in PARAM_NAMES.iter().zip(params.to_array()) {
+            write!(w, "\nc PARAM {name} {value}")?;
+        }
+    }
+    for note in &options.notes {
+        // Each line of a multi-line note gets its own comment line, so a
+        // note cannot end the header early.
+        if note.is_empty() {
+            write!(w, "\nc ")?;
+        }
+        for line in note.lines().flat_map(|l| l.split('\r')) {
+            write!(w, "\nc {line}")?;
+        }
+    }
     Ok(())
 }
 
+/// Displays a string with each `\r` and `\n` escaped as the two characters
+/// `\r` and `\n`, so that it cannot end the comment line it is written on.
+struct OneLine<'a>(&'a str);
+
+impl fmt::Display for OneLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in self.0.split_inclusive(['\r', '\n']) {
+            match part.strip_suffix('\n') {
+                Some(rest) => write!(f, "{rest}\\n")?,
+                None => match part.strip_suffix('\r') {
+                    Some(rest) => write!(f, "{rest}\\r")?,
+                    None => f.write_str(part)?,
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Write the DIMACS-format network data (problem line, node lines, arc lines).
+#[cfg(feature = "std")]
 pub fn write_dimacs_network(
     w: &mut impl Write,
     params: &NetgenParams,
     result: &NetgenResult,
 ) -> io::Result<()> {
-    let num_arcs = result.arcs.len();
-    let problem_type = params.problem_type();
+    write_dimacs_network_with_options(w, params, result, &DimacsOptions::default())
+}
 
-    match problem_type {
-        ProblemType::Assignment => {
-            writeln!(w, "c")?;
-            writeln!(w, "c  *** Assignment ***")?;
-            writeln!(w, "c")?;
-            writeln!(w, "p asn {} {}", params.nodes, num_arcs)?;
-            for (i, &s) in result.supply.iter().enumerate() {
-                if s > 0 {
-                    writeln!(w, "n {}", i + 1)?;
-                }
-            }
-            for arc in &result.arcs {
-                writeln!(w, "a {} {} {}", arc.from, arc.to, arc.cost)?;
-            }
+/// Write the DIMACS-format network data with the arc options of
+/// [`DimacsOptions`]; `options.header` is not used.
+///
+/// ```rust
+/// use netgen_rs::{ArcOrder, DimacsOptions, NetgenParams, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let options = DimacsOptions {
+///     arc_order: ArcOrder::SortedTailHead,
+///     ..DimacsOptions::default()
+/// };
+/// let mut out = Vec::new();
+/// netgen_rs::write_dimacs_network_with_options(&mut out, &params, &result, &options).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn write_dimacs_network_with_options(
+    w: &mut impl Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &DimacsOptions,
+) -> io::Result<()> {
+    via_fmt(w, |w| {
+        write_network(&mut LineEndings::new(w, options), params, result, options)
+    })
+}
+
+/// Write the banner and problem line that open the network data, as
+/// [`write_dimacs_network_with_options`] does: `c *** <type> ***` between
+/// two `c` lines, then `p <type> <nodes> <arcs>`.
+///
+/// With [`write_node_lines`] and [`write_arc_lines`], the network can be
+/// written a section at a time, with other lines in between:
+///
+/// ```rust
+/// use netgen_rs::{DimacsOptions, NetgenParams, generate};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let (kind, options) = (params.problem_type(), DimacsOptions::default());
+/// let mut out = Vec::new();
+/// netgen_rs::write_problem_line(&mut out, kind, result.supply.len(), result.arcs.len(), &options)
+///     .unwrap();
+/// netgen_rs::write_node_lines(&mut out, kind, &result.supply, &options).unwrap();
+/// out.extend_from_slice(b"c arcs follow\n");
+/// netgen_rs::write_arc_lines(&mut out, kind, &result.arcs, &options).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn write_problem_line(
+    w: &mut impl Write,
+    problem_type: ProblemType,
+    nodes: usize,
+    arcs: usize,
+    options: &DimacsOptions,
+) -> io::Result<()> {
+    let problem_type = written_type(problem_type, options);
+    via_fmt(w, |w| {
+        write_problem(
+            &mut LineEndings::section(w, options),
+            nodes,
+            problem_type,
+            arcs,
+        )
+    })
+}
+
+/// Write the node lines for `supply`, indexed by node id less one, as
+/// [`write_dimacs_network_with_options`] does for a network of
+/// `problem_type`, honoring the options that apply to them
+/// ([`DimacsOptions::aligned`], [`asn_list_both_sides`](DimacsOptions::asn_list_both_sides),
+/// the line ending and the written type). The last line is always
+/// terminated, since arc lines follow.
+///
+/// A network without arcs is the exception: its node lines end the
+/// output, and under [`trailing_newline`](DimacsOptions::trailing_newline)
+/// `false` [`write_dimacs_network_with_options`] leaves the last one open,
+/// while this writes its line ending (and [`write_arc_lines`] nothing). Drop
+/// that final line ending to match the whole writer.
+///
+/// [`DimacsOptions::relabel_nodes`] is not applied here or by
+/// [`write_arc_lines`], which do not see the whole network: pass the
+/// supplies and arcs of [`NetgenResult::relabeled`] instead.
+#[cfg(feature = "std")]
+pub fn write_node_lines(
+    w: &mut impl Write,
+    problem_type: ProblemType,
+    supply: &[i64],
+    options: &DimacsOptions,
+) -> io::Result<()> {
+    let problem_type = written_type(problem_type, options);
+    let widths = Widths::new(supply, &[], options);
+    via_fmt(w, |w| {
+        write_node_section(
+            &mut LineEndings::section(w, options),
+            problem_type,
+            supply,
+            options,
+            &widths,
+        )
+    })
+}
+
+/// Write the arc lines for `arcs`, as
+/// [`write_dimacs_network_with_options`] does for a network of
+/// `problem_type`, honoring the arc options of [`DimacsOptions`] and
+/// [`trailing_newline`](DimacsOptions::trailing_newline), which applies to
+/// the last arc line. See [`write_node_lines`] for relabeling.
+#[cfg(feature = "std")]
+pub fn write_arc_lines(
+    w: &mut impl Write,
+    problem_type: ProblemType,
+    arcs: &[Arc],
+    options: &DimacsOptions,
+) -> io::Result<()> {
+    let problem_type = written_type(problem_type, options);
+    let widths = Widths::new(&[], arcs, options);
+    via_fmt(w, |w| {
+        write_arc_section(
+            &mut LineEndings::new(w, options),
+            problem_type,
+            arcs,
+            options,
+            &widths,
+        )
+    })
+}
+
+/// The network as [`write_problem_line`], [`write_node_lines`] and
+/// [`write_arc_lines`] write it, under one [`LineEndings`] so that only
+/// the very last line loses its terminator without
+/// [`DimacsOptions::trailing_newline`].
+fn write_network(
+    w: &mut impl fmt::Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &DimacsOptions,
+) -> fmt::Result {
+    let relabeled;
+    let result = match options.relabel_nodes {
+        Some(seed) => {
+            relabeled = result.relabeled(seed).0;
+            &relabeled
         }
+        None => result,
+    };
+    let problem_type = written_type(params.problem_type(), options);
+    let widths = Widths::new(&result.supply, &result.arcs, options);
+    write_problem(w, params.nodes as usize, problem_type, result.arcs.len())?;
+    write_node_section(w, problem_type, &result.supply, options, &widths)?;
+    write_arc_section(w, problem_type, &result.arcs, options, &widths)
+}
+
+fn write_node_section(
+    w: &mut impl fmt::Write,
+    problem_type: ProblemType,
+    supply: &[i64],
+    options: &DimacsOptions,
+    widths: &Widths,
+) -> fmt::Result {
+    for (node, supply) in listed_nodes(supply, problem_type, options) {
+        write_node(w, problem_type, node, supply, widths)?;
+    }
+    Ok(())
+}
+
+fn write_arc_section(
+    w: &mut impl fmt::Write,
+    problem_type: ProblemType,
+    arcs: &[Arc],
+    options: &DimacsOptions,
+    widths: &Widths,
+) -> fmt::Result {
+    for arc in ordered_arcs(arcs, options.arc_order) {
+        write_arc(w, problem_type, arc, options, widths)?;
+    }
+    Ok(())
+}
+
+/// Column widths for [`DimacsOptions::aligned`]. All zero, the compact
+/// format.
+#[derive(Default)]
+pub(crate) struct Widths {
+    node: usize,
+    supply: usize,
+    from: usize,
+    to: usize,
+    capacity: usize,
+    cost: usize,
+}
+
+impl Widths {
+    /// The widths of the largest values in each column of the node lines
+    /// for `supply` and the arc lines for `arcs` if `options.aligned` is
+    /// set, otherwise zero. Node columns depend on `supply` only and arc
+    /// columns on `arcs` only.
+    pub(crate) fn new(supply: &[i64], arcs: &[Arc], options: &DimacsOptions) -> Self {
+        if !options.aligned {
+            return Widths::default();
+        }
+        let width = |n: i64| estimate::digits(n) as usize;
+        let mut widths = Widths {
+            node: width(supply.len() as i64),
+            supply: supply.iter().map(|&s| width(s)).max().unwrap_or(0),
+            ..Widths::default()
+        };
+        for arc in arcs {
+            let capacity = options.uncapacitated_as.bound(arc).unwrap_or(arc.capacity);
+            widths.from = widths.from.max(width(arc.from as i64));
+            widths.to = widths.to.max(width(arc.to as i64));
+            widths.capacity = widths.capacity.max(width(capacity));
+            widths.cost = widths.cost.max(width(arc.cost));
+        }
+        widths
+    }
+}
+
+/// The nodes with their supplies, in the order of their node lines: by
+/// id, except that with [`DimacsOptions::asn_list_both_sides`] the sink
+/// side of an assignment problem follows the source side.
+fn listed_nodes<'a>(
+    supply: &'a [i64],
+    problem_type: ProblemType,
+    options: &DimacsOptions,
+) -> impl Iterator<Item = (usize, i64)> + 'a {
+    let nodes = move || (1..).zip(supply.iter().copied());
+    let assignment = problem_type == ProblemType::Assignment;
+    let sinks = assignment && options.asn_list_both_sides;
+    nodes()
+        .filter(move |&(_, supply)| !(assignment && supply < 0))
+        .chain(
+            sinks
+                .then(|| nodes().filter(|&(_, supply)| supply < 0))
+                .into_iter()
+                .flatten(),
+        )
+}
+
+/// `arcs` in `order`.
+fn ordered_arcs(arcs: &[Arc], order: ArcOrder) -> impl Iterator<Item = &Arc> {
+    let order = order.permutation(arcs);
+    (0..arcs.len()).map(move |i| &arcs[order.as_ref().map_or(i, |o| o[i])])
+}
+
+/// The problem type a network of `problem_type` is written as: the same,
+/// unless [`MaxFlowExtra::MinCostFormat`] turns max flow into min-cost flow.
+fn written_type(problem_type: ProblemType, options: &DimacsOptions) -> ProblemType {
+    match (problem_type, options.max_flow_extended) {
+        (ProblemType::MaxFlow, MaxFlowExtra::MinCostFormat) => ProblemType::MinCostFlow,
+        (problem_type, _) => problem_type,
+    }
+}
+
+/// The banner and problem line that open the network data.
+fn write_problem(
+    w: &mut impl fmt::Write,
+    nodes: usize,
+    problem_type: ProblemType,
+    num_arcs: usize,
+) -> fmt::Result {
+    let (title, kind) = match problem_type {
+        ProblemType::Assignment => ("Assignment", "asn"),
+        ProblemType::MaxFlow => ("Maximum flow", "max"),
+        ProblemType::MinCostFlow => ("Minimum cost flow", "min"),
+    };
+    writeln!(w, "c")?;
+    writeln!(w, "c  *** {title} ***")?;
+    writeln!(w, "c")?;
+    writeln!(w, "p {kind} {nodes} {num_arcs}")
+}
+
+/// The node line of `node`, if its supply calls for one.
+fn write_node(
+    w: &mut impl fmt::Write,
+    problem_type: ProblemType,
+    node: usize,
+    supply: i64,
+    widths: &Widths,
+) -> fmt::Result {
+    let Widths {
+        node: n, supply: s, ..
+    } = *widths;
+    match problem_type {
+        // Sink-side nodes are only passed in by `asn_list_both_sides`.
+        ProblemType::Assignment if supply != 0 => writeln!(w, "n {node:>n$}"),
+        ProblemType::MaxFlow if supply > 0 => writeln!(w, "n {node:>n$} s"),
+        ProblemType::MaxFlow if supply < 0 => writeln!(w, "n {node:>n$} t"),
+        ProblemType::MinCostFlow if supply != 0 => writeln!(w, "n {node:>n$} {supply:>s$}"),
+        _ => Ok(()),
+    }
+}
+
+fn write_arc(
+    w: &mut impl fmt::Write,
+    problem_type: ProblemType,
+    arc: &Arc,
+    options: &DimacsOptions,
+    widths: &Widths,
+) -> fmt::Result {
+    let capacity = options.uncapacitated_as.bound(arc).unwrap_or(arc.capacity);
+    let Widths {
+        from: f,
+        to: t,
+        capacity: c,
+        cost: k,
+        ..
+    } = *widths;
+    let (from, to, cost) = (arc.from, arc.to, arc.cost);
+    match problem_type {
+        ProblemType::Assignment => writeln!(w, "a {from:>f$} {to:>t$} {cost:>k$}"),
         ProblemType::MaxFlow => {
-            writeln!(w, "c")?;
-            writeln!(w, "c  *** Maximum flow ***")?;
-            writeln!(w, "c")?;
-            writeln!(w, "p max {} {}", params.nodes, num_arcs)?;
-            for (i, &s) in result.supply.iter().enumerate() {
-                if s > 0 {
-                    writeln!(w, "n {} s", i + 1)?;
-                } else if s < 0 {
-                    writeln!(w, "n {} t", i + 1)?;
-                }
-            }
-            for arc in &result.arcs {
-                writeln!(w, "a {} {} {}", arc.from, arc.to, arc.capacity)?;
+            writeln!(w, "a {from:>f$} {to:>t$} {capacity:>c$}")?;
+            match options.max_flow_extended {
+                MaxFlowExtra::CostComments => writeln!(w, "c cost {cost}"),
+                _ => Ok(()),
             }
         }
         ProblemType::MinCostFlow => {
-            writeln!(w, "c")?;
-            writeln!(w, "c  *** Minimum cost flow ***")?;
-            writeln!(w, "c")?;
-            writeln!(w, "p min {} {}", params.nodes, num_arcs)?;
-            for (i, &s) in result.supply.iter().enumerate() {
-                if s != 0 {
-                    writeln!(w, "n {} {}", i + 1, s)?;
-                }
-            }
-            for arc in &result.arcs {
-                writeln!(
-                    w,
-                    "a {} {} {} {} {}",
-                    arc.from, arc.to, 0, arc.capacity, arc.cost
-                )?;
+            write!(w, "a {from:>f$} {to:>t$} 0 {capacity:>c$} {cost:>k$}")?;
+            if options.generalized {
+                let (num, den) = arc.multiplier.unwrap_or((1, 1));
+                write!(w, " {num}/{den}")?;
             }
+            writeln!(w)
         }
     }
-
-    Ok(())
 }
 
 /// Write complete DIMACS output (header + network).
+#[cfg(feature = "std")]
 pub fn write_dimacs(
     w: &mut impl Write,
     seed: i64,
@@ -395,10 +2114,227 @@ pub fn write_dimacs(
     params: &NetgenParams,
     result: &NetgenResult,
 ) -> io::Result<()> {
-    write_dimacs_header(w, seed, problem, params)?;
-    writeln!(w)?;
-    write_dimacs_network(w, params, result)?;
-    Ok(())
+    write_dimacs_with_options(w, seed, problem, params, result, &DimacsOptions::default())
+}
+
+/// Write complete DIMACS output with [`DimacsOptions`].
+///
+/// ```rust
+/// use netgen_rs::{DimacsOptions, GenerateOptions, MultiplierRange, NetgenParams};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let options = GenerateOptions {
+///     multipliers: Some(MultiplierRange { num_min: 80, num_max: 120, den: 100 }),
+///     ..GenerateOptions::default()
+/// };
+/// let (result, _) = netgen_rs::generate_with_options(13502460, &params, &options).unwrap();
+/// let dimacs = DimacsOptions { generalized: true, ..DimacsOptions::default() };
+/// let mut out = Vec::new();
+/// netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &result, &dimacs).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn write_dimacs_with_options(
+    w: &mut impl Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &DimacsOptions,
+) -> io::Result<()> {
+    via_fmt(w, |w| {
+        write_dimacs_fmt(w, seed, problem, params, result, options)
+    })
+}
+
+/// Write complete DIMACS output to each of `writers` in one pass, e.g. to a
+/// file and a [`FingerprintWriter`], without holding the text in memory.
+/// Stops at the first error; see [`TeeWriter`].
+///
+/// ```rust
+/// use netgen_rs::NetgenParams;
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = params.generate(13502460).unwrap();
+/// let (mut a, mut b) = (Vec::new(), Vec::new());
+/// netgen_rs::write_dimacs_multi(&mut [&mut a, &mut b], 13502460, 1, &params, &result).unwrap();
+/// assert_eq!(a, b);
+/// ```
+#[cfg(feature = "std")]
+pub fn write_dimacs_multi(
+    writers: &mut [&mut dyn Write],
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> io::Result<()> {
+    // Buffer ahead of the fan-out, so each writer sees large writes.
+    let mut w = io::BufWriter::new(TeeWriter::new(writers.iter_mut()));
+    write_dimacs(&mut w, seed, problem, params, result)?;
+    w.into_inner().map(drop).map_err(|e| e.into_error())
+}
+
+/// Like [`write_dimacs`], but also return the wall-clock time the writing
+/// took, for comparison with [`GenerationReport::duration`].
+///
+/// ```rust
+/// use netgen_rs::NetgenParams;
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let (result, report) = netgen_rs::generate_with_report(13502460, &params).unwrap();
+/// let mut out = Vec::new();
+/// let written = netgen_rs::write_dimacs_timed(&mut out, 13502460, 1, &params, &result).unwrap();
+/// eprintln!("generated in {:?}, written in {written:?}", report.duration.unwrap());
+/// ```
+#[cfg(feature = "std")]
+pub fn write_dimacs_timed(
+    w: &mut impl Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> io::Result<std::time::Duration> {
+    let start = std::time::Instant::now();
+    write_dimacs(w, seed, problem, params, result)?;
+    Ok(start.elapsed())
+}
+
+/// Write complete DIMACS output with [`DimacsOptions`] to a
+/// [`fmt::Write`], such as a `String`. This is the writer behind the
+/// `io::Write` ones and is available without the `std` feature.
+///
+/// ```rust
+/// use netgen_rs::{DimacsOptions, NetgenParams, generate, write_dimacs_fmt};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut out = String::new();
+/// write_dimacs_fmt(&mut out, 13502460, 1, &params, &result, &DimacsOptions::default()).unwrap();
+/// assert_eq!(out, result.to_dimacs_string(13502460, 1, &params));
+/// ```
+pub fn write_dimacs_fmt(
+    w: &mut impl fmt::Write,
+    seed: i64,
+    problem: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &DimacsOptions,
+) -> fmt::Result {
+    let mut w = LineEndings::new(w, options);
+    write_header(&mut w, seed, problem, params, &options.header)?;
+    fmt::Write::write_str(&mut w, "\n")?;
+    write_network(&mut w, params, result, options)
+}
+
+/// A [`fmt::Write`] that applies [`DimacsOptions::line_ending`] and
+/// [`DimacsOptions::trailing_newline`] to the `\n`-terminated text written
+/// to it, without buffering it.
+pub(crate) struct LineEndings<W> {
+    pub(crate) inner: W,
+    crlf: bool,
+    trailing_newline: bool,
+    /// A line ended with the last write; its terminator is held back until
+    /// more text shows it was not the last line.
+    pending: bool,
+}
+
+impl<W: fmt::Write> LineEndings<W> {
+    pub(crate) fn new(inner: W, options: &DimacsOptions) -> Self {
+        LineEndings {
+            inner,
+            crlf: options.line_ending == LineEnding::CrLf,
+            trailing_newline: options.trailing_newline,
+            pending: false,
+        }
+    }
+
+    /// For a section that more lines follow: every line is terminated,
+    /// whatever [`DimacsOptions::trailing_newline`] says.
+    #[cfg(feature = "std")]
+    fn section(inner: W, options: &DimacsOptions) -> Self {
+        LineEndings {
+            trailing_newline: true,
+            ..LineEndings::new(inner, options)
+        }
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        self.inner.write_str(if self.crlf { "\r\n" } else { "\n" })
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for LineEndings<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.crlf && self.trailing_newline {
+            return self.inner.write_str(s);
+        }
+        if s.is_empty() {
+            return Ok(());
+        }
+        if self.pending {
+            self.pending = false;
+            self.newline()?;
+        }
+        let s = match s.strip_suffix('\n') {
+            Some(rest) if !self.trailing_newline => {
+                self.pending = true;
+                rest
+            }
+            _ => s,
+        };
+        if !self.crlf {
+            return self.inner.write_str(s);
+        }
+        for (i, part) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.newline()?;
+            }
+            self.inner.write_str(part)?;
+        }
+        Ok(())
+    }
+}
+
+/// An [`io::Write`] seen as a [`fmt::Write`], keeping the first I/O error.
+#[cfg(feature = "std")]
+struct IoAdapter<'a, W> {
+    inner: &'a mut W,
+    error: io::Result<()>,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Err(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Run a `fmt::Write` writer against `w`, returning its I/O error if any.
+#[cfg(feature = "std")]
+fn via_fmt<W: Write>(
+    w: &mut W,
+    write: impl FnOnce(&mut IoAdapter<'_, W>) -> fmt::Result,
+) -> io::Result<()> {
+    let mut adapter = IoAdapter {
+        inner: w,
+        error: Ok(()),
+    };
+    match write(&mut adapter) {
+        Ok(()) => Ok(()),
+        Err(fmt::Error) => adapter
+            .error
+            .and(Err(io::Error::other("formatting failed"))),
+    }
 }
 
 /// Generate and format as DIMACS string.
@@ -407,8 +2343,5 @@ pub fn to_dimacs_string(
     problem: i64,
     params: &NetgenParams,
 ) -> Result<String, NetgenError> {
-    let result = generate(seed, params)?;
-    let mut buf = Vec::new();
-    write_dimacs(&mut buf, seed, problem, params, &result).expect("writing to Vec should not fail");
-    Ok(String::from_utf8(buf).expect("DIMACS output is ASCII"))
+    Ok(generate(seed, params)?.to_dimacs_string(seed, problem, params))
 }
@@ -0,0 +1,72 @@
+//! The generator's limits: what [`NetgenParams::validate`] accepts, and the
+//! narrower domain in which the output matches the original C
+//! implementation exactly (see [`NetgenParams::is_reference_compatible`]).
+
+use crate::NetgenParams;
+
+/// Largest seed for which the LCG behaves exactly like the C implementation
+/// (2^31 − 2). Valid seeds are `1..=MAX_SEED`.
+pub const MAX_SEED: i64 = 2_147_483_646;
+
+/// Widest cost or capacity range, counted in values (`max - min + 1`),
+/// that is drawn exactly as the C generator draws it: one LCG step yields
+/// one of 2^31 − 2 values. Wider ranges are drawn with
+/// [`Rng::next_wide`](crate::Rng::next_wide).
+pub const MAX_EXACT_RANGE: i64 = 2_147_483_646;
+
+/// Largest total supply for which the output matches the C generator,
+/// which keeps the partial supplies routed to each sink in an `int`.
+pub const MAX_EXACT_SUPPLY: i64 = i32::MAX as i64;
+
+/// Largest node count accepted by [`NetgenParams::validate`].
+pub const MAX_NODES_SUPPORTED: i64 = u32::MAX as i64;
+
+/// Largest arc count (`density`) accepted by [`NetgenParams::validate`].
+pub const MAX_ARCS_SUPPORTED: i64 = u32::MAX as i64;
+
+/// `MAXNODES` of the C generator, which rejects larger problems as too big.
+pub const REFERENCE_MAX_NODES: i64 = 17_000;
+
+/// `MAXARCS` of the C generator, which rejects larger problems as too big.
+pub const REFERENCE_MAX_ARCS: i64 = 150_000;
+
+/// By default, index lists with at most this many elements are kept in a
+/// sorted vector, larger ones in an interval tree (see
+/// [`IndexStrategy`](crate::IndexStrategy)). Both give the same results; the
+/// limit only matters for performance. The C `index.c` switches at 100, but
+/// the vector's `choose` stays faster than the tree's up to about a thousand
+/// elements.
+pub const FLAG_LIMIT: usize = 1024;
+
+impl NetgenParams {
+    /// Whether these parameters are in the domain where the output is
+    /// guaranteed to match the original C implementation for every seed in
+    /// `1..=`[`MAX_SEED`]:
+    ///
+    /// - `nodes` and `density` within the C generator's
+    ///   [`REFERENCE_MAX_NODES`] and [`REFERENCE_MAX_ARCS`];
+    /// - cost and capacity ranges of at most [`MAX_EXACT_RANGE`] values;
+    /// - `supply` at most [`MAX_EXACT_SUPPLY`].
+    ///
+    /// Parameters outside this domain still generate valid networks, which
+    /// may differ from what a C generator with larger arrays would produce.
+    ///
+    /// ```rust
+    /// use netgen_rs::NetgenParams;
+    ///
+    /// let mut params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// assert!(params.is_reference_compatible());
+    /// params.maxcost = 1 << 40;
+    /// assert!(!params.is_reference_compatible());
+    /// ```
+    pub fn is_reference_compatible(&self) -> bool {
+        let exact = |min: i64, max: i64| (max as i128 - min as i128) < MAX_EXACT_RANGE as i128;
+        self.nodes <= REFERENCE_MAX_NODES
+            && self.density <= REFERENCE_MAX_ARCS
+            && exact(self.mincost, self.maxcost)
+            && exact(self.mincap, self.maxcap)
+            && self.supply <= MAX_EXACT_SUPPLY
+    }
+}
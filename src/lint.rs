@@ -0,0 +1,195 @@
+//! Warnings for parameters that are valid but almost certainly mistakes.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{NetgenParams, ProblemType};
+
+/// A legal parameter combination that is probably not what was meant,
+/// reported by [`NetgenParams::lint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamWarning {
+    /// `maxcap` is below the average supply of a source, so no random arc
+    /// can carry a whole skeleton chain's flow. Not reported for
+    /// assignment problems, whose arcs all have capacity 1.
+    MaxCapBelowChainSupply { maxcap: i64, supply_per_source: i64 },
+    /// `hicost_pct > 0` with `mincost == maxcost`: high-cost skeleton arcs
+    /// cost the same as the others. Not reported for maximum flow
+    /// problems, which have no costs.
+    HiCostWithoutCostRange { hicost_pct: i64 },
+    /// The sources, sinks and supply describe an assignment problem, but
+    /// transshipment sources or sinks make it a min-cost flow problem
+    /// instead (see [`NetgenParams::problem_type`]).
+    TransshipmentBreaksAssignment { tsources: i64, tsinks: i64 },
+    /// `density` exceeds [`NetgenParams::max_arcs`], so the network will
+    /// have fewer arcs than requested.
+    DensityAboveMaxArcs { density: i64, max_arcs: i64 },
+    /// `mincap < 0`: random arcs can get negative capacities, which no
+    /// flow satisfies. Not reported for assignment problems.
+    NegativeCapacity { mincap: i64 },
+}
+
+impl fmt::Display for ParamWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParamWarning::MaxCapBelowChainSupply {
+                maxcap,
+                supply_per_source,
+            } => write!(
+                f,
+                "maxcap {maxcap} is below the average supply per source \
+                 {supply_per_source}, so no random arc can carry a skeleton chain's flow"
+            ),
+            ParamWarning::HiCostWithoutCostRange { hicost_pct } => write!(
+                f,
+                "hicost percentage {hicost_pct} has no effect because mincost equals maxcost"
+            ),
+            ParamWarning::TransshipmentBreaksAssignment { tsources, tsinks } => write!(
+                f,
+                "{tsources} transshipment sources and {tsinks} transshipment sinks turn \
+                 an assignment-shaped problem into a min-cost flow problem"
+            ),
+            ParamWarning::DensityAboveMaxArcs { density, max_arcs } => write!(
+                f,
+                "density {density} exceeds the {max_arcs} distinct arcs the network can \
+                 hold, so it will have fewer arcs"
+            ),
+            ParamWarning::NegativeCapacity { mincap } => write!(
+                f,
+                "mincap {mincap} is negative, so random arcs can get negative capacities"
+            ),
+        }
+    }
+}
+
+impl NetgenParams {
+    /// Warnings about parameters that are valid but probably mistakes,
+    /// taking the detected [`problem_type`](Self::problem_type) into
+    /// account. Empty for sensible parameters.
+    ///
+    /// Unlike [`validate`](Self::validate), this never rejects anything:
+    /// generation proceeds as usual whatever it reports.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, ParamWarning};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 5, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// assert_eq!(
+    ///     params.lint(),
+    ///     [ParamWarning::HiCostWithoutCostRange { hicost_pct: 20 }]
+    /// );
+    /// ```
+    pub fn lint(&self) -> Vec<ParamWarning> {
+        let problem_type = self.problem_type();
+        let mut warnings = Vec::new();
+        let supply_per_source = self.supply / self.sources.max(1);
+        if problem_type != ProblemType::Assignment && self.maxcap < supply_per_source {
+            warnings.push(ParamWarning::MaxCapBelowChainSupply {
+                maxcap: self.maxcap,
+                supply_per_source,
+            });
+        }
+        if problem_type != ProblemType::MaxFlow
+            && self.hicost_pct > 0
+            && self.mincost == self.maxcost
+        {
+            warnings.push(ParamWarning::HiCostWithoutCostRange {
+                hicost_pct: self.hicost_pct,
+            });
+        }
+        if (self.tsources > 0 || self.tsinks > 0)
+            && self.sources + self.sinks == self.nodes
+            && self.sources == self.sinks
+            && self.sources == self.supply
+        {
+            warnings.push(ParamWarning::TransshipmentBreaksAssignment {
+                tsources: self.tsources,
+                tsinks: self.tsinks,
+            });
+        }
+        let max_arcs = self.max_arcs();
+        if self.density > max_arcs {
+            warnings.push(ParamWarning::DensityAboveMaxArcs {
+                density: self.density,
+                max_arcs,
+            });
+        }
+        if problem_type != ProblemType::Assignment && self.mincap < 0 {
+            warnings.push(ParamWarning::NegativeCapacity {
+                mincap: self.mincap,
+            });
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::DOC_PARMS;
+
+    fn params(parms: [i64; 13]) -> NetgenParams {
+        NetgenParams::from_slice(&parms).unwrap()
+    }
+
+    #[test]
+    fn sensible_parameters_have_no_warnings() {
+        for parms in [
+            DOC_PARMS,
+            [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+            [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+        ] {
+            assert_eq!(params(parms).lint(), [], "{parms:?}");
+        }
+    }
+
+    #[test]
+    fn each_condition_is_reported() {
+        let cases = [
+            (
+                [512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 1, 50],
+                ParamWarning::MaxCapBelowChainSupply {
+                    maxcap: 50,
+                    supply_per_source: 100,
+                },
+            ),
+            (
+                [512, 10, 10, 2000, 7, 7, 1000, 3, 3, 20, 80, 50, 2000],
+                ParamWarning::HiCostWithoutCostRange { hicost_pct: 20 },
+            ),
+            (
+                [100, 50, 50, 500, 1, 100, 50, 0, 2, 0, 0, 1, 100],
+                ParamWarning::TransshipmentBreaksAssignment {
+                    tsources: 0,
+                    tsinks: 2,
+                },
+            ),
+            (
+                [10, 3, 3, 80, 5, 500, 30, 0, 0, 20, 80, 50, 2000],
+                ParamWarning::DensityAboveMaxArcs {
+                    density: 80,
+                    max_arcs: 45,
+                },
+            ),
+            (
+                [512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, -5, 2000],
+                ParamWarning::NegativeCapacity { mincap: -5 },
+            ),
+        ];
+        for (parms, warning) in cases {
+            assert_eq!(params(parms).lint(), [warning], "{parms:?}");
+        }
+    }
+
+    #[test]
+    fn warnings_depend_on_the_problem_type() {
+        // Max flow: costs are not written, so hicost cannot matter.
+        let max_flow = params([200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]);
+        assert_eq!(max_flow.lint(), []);
+        // Assignment: capacities are 1, whatever mincap and maxcap say.
+        let assignment = params([100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, -3, 0]);
+        assert_eq!(assignment.lint(), []);
+    }
+}
@@ -0,0 +1,344 @@
+//! CPLEX LP export.
+//!
+//! Arc `k` (0-based, in [`NetgenResult::arcs`] order) is the variable
+//! `x_{from}_{to}_{k + 1}`, which stays unique for parallel arcs. Node `v`
+//! is the constraint `n{v}`.
+
+use std::io::{self, Write};
+
+use crate::{LpOptions, NetgenParams, NetgenResult, ProblemType};
+
+/// Terms per line; CPLEX caps LP lines at 560 characters.
+const TERMS_PER_LINE: usize = 8;
+
+/// Write the LP of a generated network in CPLEX LP format.
+///
+/// The formulation follows the problem type:
+///
+/// - **Min-cost flow**: minimize `sum cost_a x_a` subject to
+///   `out(v) - in(v) = supply_v` at every node and `0 <= x_a <= capacity_a`.
+/// - **Assignment**: the same with `0 <= x_a <= 1`, since DIMACS assignment
+///   arcs carry no capacity.
+/// - **Maximum flow**: arc costs are ignored. An artificial super source
+///   `s` feeds every source through `x_s_{v}`, every sink drains into a
+///   super sink `t` through `x_{v}_t`, and the return arc `r` from `t` to
+///   `s` closes the circulation. The LP maximizes `r` with `out - in = 0`
+///   at every node, including `s` and `t`.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate, write_lp};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut lp = Vec::new();
+/// write_lp(&mut lp, &params, &result).unwrap();
+/// assert!(String::from_utf8(lp).unwrap().starts_with("\\ NETGEN min-cost-flow"));
+/// ```
+pub fn write_lp(
+    w: &mut impl Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> io::Result<()> {
+    write_lp_with_options(w, params, result, &LpOptions::default())
+}
+
+/// Write the LP of a generated network with [`LpOptions`].
+///
+/// With [`UncapacitatedAs::Free`](crate::UncapacitatedAs::Free), arcs
+/// flagged [`uncapacitated`](crate::Arc::uncapacitated) get the bound
+/// `x >= 0` instead of an upper bound. See [`write_lp`].
+pub fn write_lp_with_options(
+    w: &mut impl Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &LpOptions,
+) -> io::Result<()> {
+    let problem_type = params.problem_type();
+    let nodes = result.supply.len();
+    let names: Vec<String> = result
+        .arcs
+        .iter()
+        .enumerate()
+        .map(|(k, a)| format!("x_{}_{}_{}", a.from, a.to, k + 1))
+        .collect();
+    // Incident arcs per node: `(arc, +1)` leaving, `(arc, -1)` entering.
+    let mut incident: Vec<Vec<(usize, i64)>> = vec![Vec::new(); nodes];
+    for (k, arc) in result.arcs.iter().enumerate() {
+        incident[arc.from as usize - 1].push((k, 1));
+        incident[arc.to as usize - 1].push((k, -1));
+    }
+
+    writeln!(
+        w,
+        "\\ NETGEN {problem_type}: {nodes} nodes, {} arcs",
+        result.arcs.len()
+    )?;
+    if problem_type == ProblemType::MaxFlow {
+        return write_max_flow(w, result, &names, &incident, options);
+    }
+
+    let zero = names.first().map_or("x", String::as_str);
+    writeln!(w, "Minimize")?;
+    let mut line = Line::new(w, " obj:");
+    for (arc, name) in result.arcs.iter().zip(&names) {
+        line.term(arc.cost, name)?;
+    }
+    line.finish(zero, "")?;
+
+    writeln!(w, "Subject To")?;
+    for (v, arcs) in incident.iter().enumerate() {
+        let mut line = Line::new(w, &format!(" n{}:", v + 1));
+        for &(k, sign) in arcs {
+            line.term(sign, &names[k])?;
+        }
+        line.finish(zero, &format!(" = {}", result.supply[v]))?;
+    }
+
+    writeln!(w, "Bounds")?;
+    for (arc, name) in result.arcs.iter().zip(&names) {
+        let upper = match problem_type {
+            ProblemType::Assignment => Some(1),
+            _ => options.uncapacitated_as.bound(arc),
+        };
+        write_bound(w, name, upper)?;
+    }
+    writeln!(w, "End")
+}
+
+fn write_max_flow(
+    w: &mut impl Write,
+    result: &NetgenResult,
+    names: &[String],
+    incident: &[Vec<(usize, i64)>],
+    options: &LpOptions,
+) -> io::Result<()> {
+    writeln!(w, "Maximize")?;
+    writeln!(w, " obj: r")?;
+
+    writeln!(w, "Subject To")?;
+    for (v, arcs) in incident.iter().enumerate() {
+        let id = v + 1;
+        let mut line = Line::new(w, &format!(" n{id}:"));
+        for &(k, sign) in arcs {
+            line.term(sign, &names[k])?;
+        }
+        match result.supply[v].signum() {
+            1 => line.term(-1, &format!("x_s_{id}"))?,
+            -1 => line.term(1, &format!("x_{id}_t"))?,
+            _ => {}
+        }
+        line.finish("r", " = 0")?;
+    }
+    let terminals = |sign: i64| {
+        result
+            .supply
+            .iter()
+            .enumerate()
+            .filter(move |(_, s)| s.signum() == sign)
+            .map(|(v, _)| v + 1)
+    };
+    let mut line = Line::new(w, " s:");
+    for id in terminals(1) {
+        line.term(1, &format!("x_s_{id}"))?;
+    }
+    line.term(-1, "r")?;
+    line.finish("r", " = 0")?;
+    let mut line = Line::new(w, " t:");
+    for id in terminals(-1) {
+        line.term(-1, &format!("x_{id}_t"))?;
+    }
+    line.term(1, "r")?;
+    line.finish("r", " = 0")?;
+
+    writeln!(w, "Bounds")?;
+    for (arc, name) in result.arcs.iter().zip(names) {
+        write_bound(w, name, options.uncapacitated_as.bound(arc))?;
+    }
+    for id in terminals(1) {
+        writeln!(w, " x_s_{id} >= 0")?;
+    }
+    for id in terminals(-1) {
+        writeln!(w, " x_{id}_t >= 0")?;
+    }
+    writeln!(w, " r >= 0")?;
+    writeln!(w, "End")
+}
+
+/// The bounds of arc variable `name`: `0 <= name <= upper`, or `name >= 0`
+/// without an upper bound.
+fn write_bound(w: &mut impl Write, name: &str, upper: Option<i64>) -> io::Result<()> {
+    match upper {
+        Some(upper) => writeln!(w, " 0 <= {name} <= {upper}"),
+        None => writeln!(w, " {name} >= 0"),
+    }
+}
+
+/// A linear expression, wrapped every [`TERMS_PER_LINE`] terms.
+struct Line<'w, W: Write> {
+    w: &'w mut W,
+    label: String,
+    terms: usize,
+}
+
+impl<'w, W: Write> Line<'w, W> {
+    fn new(w: &'w mut W, label: &str) -> Self {
+        Line {
+            w,
+            label: label.to_owned(),
+            terms: 0,
+        }
+    }
+
+    fn term(&mut self, coefficient: i64, name: &str) -> io::Result<()> {
+        if self.terms == 0 {
+            write!(self.w, "{}", self.label)?;
+        } else if self.terms.is_multiple_of(TERMS_PER_LINE) {
+            write!(self.w, "\n   ")?;
+        }
+        let sign = if coefficient < 0 { '-' } else { '+' };
+        match coefficient.unsigned_abs() {
+            1 => write!(self.w, " {sign} {name}")?,
+            c => write!(self.w, " {sign} {c} {name}")?,
+        }
+        self.terms += 1;
+        Ok(())
+    }
+
+    /// End the expression with `tail` (e.g. ` = 5`). CPLEX needs at least
+    /// one variable per row, so an empty expression becomes `0 {zero}`.
+    fn finish(self, zero: &str, tail: &str) -> io::Result<()> {
+        if self.terms == 0 {
+            write!(self.w, "{} 0 {zero}", self.label)?;
+        }
+        writeln!(self.w, "{tail}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UncapacitatedAs, generate};
+
+    fn lp(parms: &[i64]) -> (NetgenResult, String) {
+        let params = NetgenParams::from_slice(parms).unwrap();
+        let result = generate(13502460, &params).unwrap();
+        let mut out = Vec::new();
+        write_lp(&mut out, &params, &result).unwrap();
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    /// Split into sections keyed by their heading line.
+    fn sections(lp: &str) -> Vec<(&str, Vec<&str>)> {
+        let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+        for line in lp.lines().skip(1) {
+            if line.starts_with(' ') {
+                sections.last_mut().unwrap().1.push(line);
+            } else {
+                sections.push((line, Vec::new()));
+            }
+        }
+        sections
+    }
+
+    /// Join wrapped lines back into one row per label.
+    fn rows(lines: &[&str]) -> Vec<String> {
+        let mut rows: Vec<String> = Vec::new();
+        for line in lines {
+            match line.strip_prefix("   ") {
+                Some(rest) => rows.last_mut().unwrap().push_str(rest),
+                None => rows.push(line.to_string()),
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn min_cost_flow_structure() {
+        let (result, lp) = lp(&[64, 4, 4, 300, 5, 500, 100, 1, 1, 20, 80, 10, 50]);
+        let sections = sections(&lp);
+        let headings: Vec<_> = sections.iter().map(|s| s.0).collect();
+        assert_eq!(headings, ["Minimize", "Subject To", "Bounds", "End"]);
+
+        let objective = sections[0].1.join(" ");
+        assert_eq!(objective.matches(" x_").count(), result.arcs.len());
+        assert_eq!(rows(&sections[1].1).len(), 64);
+        let constraints = sections[1].1.join(" ");
+        assert_eq!(constraints.matches(" x_").count(), 2 * result.arcs.len());
+        assert_eq!(sections[2].1.len(), result.arcs.len());
+
+        let arc = &result.arcs[0];
+        assert!(
+            objective.starts_with(&format!(" obj: + {} x_{}_{}_1", arc.cost, arc.from, arc.to))
+        );
+        assert!(
+            sections[2].1[0].ends_with(&format!("x_{}_{}_1 <= {}", arc.from, arc.to, arc.capacity))
+        );
+        assert!(lp.contains(&format!(" n1: + x_{}_{}_1", arc.from, arc.to)));
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let (result, lp) = lp(&[64, 4, 4, 300, 5, 500, 100, 1, 1, 20, 80, 10, 50]);
+        let bounds = &sections(&lp)[2].1;
+        let mut names: Vec<_> = bounds
+            .iter()
+            .map(|l| l.split_whitespace().nth(2).unwrap())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), result.arcs.len());
+    }
+
+    #[test]
+    fn assignment_bounds_are_unit() {
+        let (result, lp) = lp(&[20, 10, 10, 60, 1, 100, 10, 0, 0, 0, 0, 1, 100]);
+        let sections = sections(&lp);
+        assert_eq!(sections[0].0, "Minimize");
+        assert_eq!(rows(&sections[1].1).len(), 20);
+        assert_eq!(sections[2].1.len(), result.arcs.len());
+        assert!(sections[2].1.iter().all(|l| l.ends_with(" <= 1")));
+        assert!(lp.contains(" = 1\n") && lp.contains(" = -1\n"));
+    }
+
+    #[test]
+    fn max_flow_uses_return_arc() {
+        let (result, lp) = lp(&[64, 3, 2, 300, 1, 1, 100, 0, 0, 0, 50, 10, 50]);
+        let sections = sections(&lp);
+        assert_eq!(sections[0], ("Maximize", vec![" obj: r"]));
+        // One row per node plus the super source and super sink.
+        assert_eq!(rows(&sections[1].1).len(), 66);
+        assert!(rows(&sections[1].1).iter().all(|r| r.ends_with(" = 0")));
+        assert!(lp.contains(" s: + x_s_1 + x_s_2 + x_s_3 - r = 0\n"));
+        assert!(lp.contains(" t: - x_63_t - x_64_t + r = 0\n"));
+        assert_eq!(sections[2].1.len(), result.arcs.len() + 3 + 2 + 1);
+    }
+
+    #[test]
+    fn free_mode_drops_uncapacitated_upper_bounds() {
+        for parms in [
+            [64, 4, 4, 300, 5, 500, 100, 1, 1, 20, 80, 10, 50],
+            [64, 3, 2, 300, 1, 1, 100, 0, 0, 0, 50, 10, 50],
+        ] {
+            let params = NetgenParams::from_slice(&parms).unwrap();
+            let result = generate(13502460, &params).unwrap();
+            assert!(result.uncapacitated_arc_count() > 0);
+            let options = LpOptions {
+                uncapacitated_as: UncapacitatedAs::Free,
+            };
+            let mut out = Vec::new();
+            write_lp_with_options(&mut out, &params, &result, &options).unwrap();
+            let lp = String::from_utf8(out).unwrap();
+            let bounds = &sections(&lp)[2].1;
+            for (k, (arc, bound)) in result.arcs.iter().zip(bounds).enumerate() {
+                let name = format!("x_{}_{}_{}", arc.from, arc.to, k + 1);
+                let expected = match arc.uncapacitated {
+                    true => format!(" {name} >= 0"),
+                    false => format!(" 0 <= {name} <= {}", arc.capacity),
+                };
+                assert_eq!(*bound, expected);
+            }
+        }
+    }
+}
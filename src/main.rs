@@ -1,72 +1,1618 @@
-use std::io::{self, BufWriter, Read};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use netgen_rs::{
+    DimacsOptions, GenerationReport, HeaderOptions, InstanceManifest, NetgenParams, NetgenResult,
+    SeedSequence, VerifyError, VerifyOutcome, naming,
+};
+
+const USAGE: &str = "\
+Usage: netgen_rs [options] [seed problem nodes sources sinks density mincost maxcost \
+supply tsources tsinks hicost% capacitated% mincap maxcap]
+       netgen_rs diff A B
+       netgen_rs suite NAME [--min-exp E] [--max-exp E] [--out-dir DIR] [--dry-run]
+
+Pass 15 arguments directly, or provide them via stdin (one or more problems,
+whitespace-separated). Text after `#`, lines starting with `c`, and blank
+lines are ignored. Processing stops at EOF or when seed/problem <= 0.
+
+Options:
+  --stats-only    Print a one-line summary per problem instead of DIMACS
+  --validate      Check each problem's parameters and print OK or the error,
+                  without generating; exits nonzero if any problem is invalid
+  --dry-run       Like --validate, but print each valid problem's type,
+                  node count and estimated arcs, peak memory and DIMACS
+                  output size (NetgenParams::estimate) instead of OK
+  --interactive   Prompt for the seed, problem number and each parameter
+                  (enter accepts the default shown), then for an output
+                  path, and write the instance there
+  --params-file F Read one problem from the LEMON-style .param file F
+                  instead of the command line or stdin, and write it to the
+                  file named after F without .param, with the problem
+                  type's extension (foo.min.param -> foo.min). Repeatable
+  --params-dir D  Like --params-file for every *.param file in D, sorted by
+                  name. An unreadable or malformed file is reported with its
+                  path and skipped (see --fail-fast); --output overrides the
+                  file names, and `{name}` in it is the name above
+  --fail-fast     Stop at the first invalid problem instead of skipping it
+  --timing        Print the wall-clock time spent generating and writing
+                  each instance, and its arcs per second, to stderr, then
+                  the totals for the run
+  --no-warnings   Do not print warnings about valid parameters that are
+                  probably mistakes (NetgenParams::lint) to stderr
+  --seed S        Use seed S instead of the seed given with each problem
+  --count N       Generate N instances per problem, varying only the seed:
+                  instance i (0-based) uses SeedSequence::new(S).nth(i)
+  --output PATH   Write to PATH instead of stdout; a `{index}` placeholder
+                  writes each instance to its own file (numbered from 1), as
+                  does `{name}`, replaced by the benchmark-style name of the
+                  instance, e.g. netgen-14a.min; instances that would
+                  share a file are an error
+  --family F      Family for `{name}` (default netgen); the name continues
+                  with floor(log2(nodes)), a letter per --count instance
+                  and the problem type's extension (.min, .max or .asn)
+  --jobs N        Generate up to N instances concurrently (default 1); output
+                  is identical to a serial run
+  --compress C    Compress output with C (gzip or zstd); implied by an
+                  --output path ending in .gz or .zst. Needs the flate2 or
+                  zstd feature
+  --level L       Compression level (gzip 0-9, default 6; zstd 1-22, default 3)
+  --checksum      With --output, write a sha256sum-compatible PATH.sha256
+                  next to each output file; without it, print one
+                  `sha256  -` line per instance instead of the instance.
+                  Needs the sha2 feature
+  --manifest      With --output, write a JSON provenance manifest PATH.json
+                  next to each output file (one line per instance)
+  --provenance    Record in each header the netgen_rs version, the time of
+                  generation (SOURCE_DATE_EPOCH if set), this command line
+                  and any random arcs dropped
+  -h, --help      Show this message
+
+`diff A B` reads two DIMACS files and reports whether they describe the same
+network up to arc order, or the first differing supply or arc. Exits 0 if they
+are equal, 1 if they differ and 2 on error.
+
+`verify FILE` regenerates a DIMACS file from the seed and parameters in its
+header and compares the network line by line. It prints `match`, `header only`
+with the first differing line, or `not netgen` if the header has no seed and
+parameters. Exits 0 on a match, 1 otherwise and 2 on error.
+
+`suite NAME` writes the instance files of a LEMON benchmark family into DIR
+(default `.`), named as by its script, e.g. netgen_8_13a.min. NAME is one of
+lemon-netgen-8, lemon-netgen-sr, lemon-netgen-lo-8, lemon-netgen-lo-sr and
+lemon-netgen-deg; --min-exp and --max-exp restrict the exponents (problem
+numbers). --dry-run prints each file name and its problem line instead.";
+
+#[derive(Debug, Default)]
+struct Options {
+    stats_only: bool,
+    validate: bool,
+    dry_run: bool,
+    interactive: bool,
+    fail_fast: bool,
+    no_warnings: bool,
+    timing: bool,
+    seed: Option<i64>,
+    count: Option<u64>,
+    output: Option<String>,
+    family: Option<String>,
+    jobs: usize,
+    compression: Option<Compression>,
+    level: Option<i32>,
+    checksum: bool,
+    manifest: bool,
+    /// With `--provenance`, the command line written in each header.
+    provenance: Option<String>,
+    /// `--params-file` and `--params-dir` arguments, in order.
+    params: Vec<ParamSource>,
+}
+
+impl Options {
+    /// Whether instances go to stdout: there is no `--output`, and no
+    /// `--params-file` whose instance files take its place.
+    fn to_stdout(&self) -> bool {
+        self.output.is_none() && self.params.is_empty()
+    }
+}
+
+/// Where `--params-file` or `--params-dir` reads problems from.
+#[derive(Debug)]
+enum ParamSource {
+    File(PathBuf),
+    Dir(PathBuf),
+}
+
+/// Split the command line into options and positional problem tokens.
+fn parse_args(args: Vec<String>) -> Result<(Options, Vec<String>), String> {
+    let mut options = Options {
+        jobs: 1,
+        ..Options::default()
+    };
+    let mut positional = Vec::new();
+    let command = format!("netgen_rs {}", args.join(" "));
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--stats-only" => options.stats_only = true,
+            "--validate" => options.validate = true,
+            "--dry-run" => options.dry_run = true,
+            "--interactive" => options.interactive = true,
+            "--fail-fast" => options.fail_fast = true,
+            "--no-warnings" => options.no_warnings = true,
+            "--timing" => options.timing = true,
+            "--checksum" => options.checksum = true,
+            "--manifest" => options.manifest = true,
+            "--provenance" => options.provenance = Some(command.clone()),
+            "--seed" => {
+                let v = value()?;
+                let seed = v.parse().ok().filter(|&s| s > 0);
+                options.seed = Some(seed.ok_or_else(|| format!("invalid seed: {v}"))?);
+            }
+            "--count" => {
+                let v = value()?;
+                let count = v.parse().ok().filter(|&n| n > 0);
+                options.count = Some(count.ok_or_else(|| format!("invalid count: {v}"))?);
+            }
+            "--output" => options.output = Some(value()?),
+            "--family" => options.family = Some(value()?),
+            "--params-file" => options.params.push(ParamSource::File(value()?.into())),
+            "--params-dir" => options.params.push(ParamSource::Dir(value()?.into())),
+            "--jobs" => {
+                let v = value()?;
+                let jobs = v.parse().ok().filter(|&n| n > 0);
+                options.jobs = jobs.ok_or_else(|| format!("invalid job count: {v}"))?;
+            }
+            "--compress" => options.compression = Some(Compression::parse(&value()?)?),
+            "--level" => {
+                let v = value()?;
+                let level = v.parse().ok();
+                options.level = Some(level.ok_or_else(|| format!("invalid level: {v}"))?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {arg}")),
+            _ => positional.push(arg),
+        }
+    }
+    Ok((options, positional))
+}
+
+/// One problem read from the input: seed, problem number and the 13 raw
+/// parameters, or a description of why they could not be read.
+struct RawProblem {
+    /// 1-based position of the problem in the input.
+    index: usize,
+    /// 1-based input line on which the problem starts.
+    line: usize,
+    /// Seed and problem number, both 0 when either is malformed.
+    seed: i64,
+    problem: i64,
+    parms: Result<[i64; 13], String>,
+}
+
+/// Reads problems from whitespace-separated tokens, tracking line numbers.
+struct ProblemReader<'a> {
+    tokens: Box<dyn Iterator<Item = (usize, &'a str)> + 'a>,
+    count: usize,
+}
+
+impl<'a> ProblemReader<'a> {
+    fn new(input: &'a str) -> Self {
+        let tokens = input.lines().enumerate().flat_map(|(i, line)| {
+            strip_comment(line)
+                .split_whitespace()
+                .map(move |tok| (i + 1, tok))
+        });
+        ProblemReader {
+            tokens: Box::new(tokens),
+            count: 0,
+        }
+    }
+
+    /// Read the 13 parameters that follow a seed and problem number.
+    fn parameters(&mut self) -> Result<[i64; 13], String> {
+        let mut parms = [0i64; 13];
+        for p in &mut parms {
+            let (l, tok) = self
+                .tokens
+                .next()
+                .ok_or("insufficient parameters".to_string())?;
+            *p = parse_token(l, tok)?;
+        }
+        Ok(parms)
+    }
+}
+
+/// Remove a `#` comment from `line`, or the whole line if it is a
+/// DIMACS-style `c` comment line.
+fn strip_comment(line: &str) -> &str {
+    let line = line.split_once('#').map_or(line, |(before, _)| before);
+    let trimmed = line.trim_start();
+    if trimmed == "c" || trimmed.starts_with("c ") || trimmed.starts_with("c\t") {
+        ""
+    } else {
+        line
+    }
+}
+
+impl Iterator for ProblemReader<'_> {
+    type Item = RawProblem;
+
+    /// Yields the next problem, or `None` at EOF or when the seed or problem
+    /// number is missing or not positive. A malformed seed or problem number
+    /// is reported like a malformed parameter.
+    fn next(&mut self) -> Option<RawProblem> {
+        let (line, seed) = self.tokens.next()?;
+        let numbers = match parse_token(line, seed) {
+            Ok(seed) => match self.tokens.next() {
+                Some((l, tok)) => parse_token(l, tok).map(|problem| (seed, problem)),
+                None => return None,
+            },
+            Err(e) => Err(e),
+        };
+        let (seed, problem, parms) = match numbers {
+            Ok((seed, problem)) if seed <= 0 || problem <= 0 => return None,
+            Ok((seed, problem)) => (seed, problem, self.parameters()),
+            Err(e) => (0, 0, Err(e)),
+        };
+        self.count += 1;
+        Some(RawProblem {
+            index: self.count,
+            line,
+            seed,
+            problem,
+            parms,
+        })
+    }
+}
+
+/// Parse `tok`, read from `line`, as an integer.
+fn parse_token(line: usize, tok: &str) -> Result<i64, String> {
+    tok.parse()
+        .map_err(|_| format!("invalid integer {tok:?} on line {line}"))
+}
 
 fn main() {
+    #[cfg(feature = "logging")]
+    env_logger::init();
     let args: Vec<String> = std::env::args().skip(1).collect();
-    if matches!(args.first().map(|s| s.as_str()), Some("-h" | "--help")) {
-        eprintln!(
-            "Usage: netgen_rs [seed problem nodes sources sinks density mincost maxcost \
-             supply tsources tsinks hicost% capacitated% mincap maxcap]\n\
-             \n\
-             Pass 15 arguments directly, or provide them via stdin (one or more problems,\n\
-             whitespace-separated). Processing stops at EOF or when seed/problem <= 0."
-        );
+    match args.first().map(String::as_str) {
+        Some("diff") => std::process::exit(run_diff(&args[1..])),
+        Some("suite") => std::process::exit(run_suite(&args[1..])),
+        Some("verify") => std::process::exit(run_verify(&args[1..])),
+        _ => {}
+    }
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        eprintln!("{USAGE}");
         return;
     }
 
-    let input: String;
-    let tokens: Box<dyn Iterator<Item = &str>>;
+    let (options, positional) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    if options.interactive {
+        std::process::exit(run_interactive(&options));
+    }
+
+    if !options.params.is_empty() {
+        std::process::exit(run_param_files(&options, &positional));
+    }
 
-    if args.is_empty() {
-        let mut buf = String::new();
-        io::stdin().read_to_string(&mut buf).unwrap();
-        input = buf;
-        tokens = Box::new(input.split_whitespace());
+    let input = if positional.is_empty() {
+        read_stdin()
     } else {
-        input = args.join(" ");
-        tokens = Box::new(input.split_whitespace());
+        positional.join(" ")
+    };
+
+    if options.validate || options.dry_run {
+        let stdout = io::stdout();
+        let mut out = BufWriter::new(stdout.lock());
+        match validate_all(&mut out, &input, &options).and_then(|ok| out.flush().map(|()| ok)) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => std::process::exit(output_failed(e)),
+        }
+    }
+
+    let mut sink = match Sink::new(&options) {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
     };
+    match generate_all(&mut sink, collect_jobs(&input, &options), &options) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => std::process::exit(output_failed(e)),
+    }
+}
 
-    let stdout = io::stdout();
-    let mut out = BufWriter::new(stdout.lock());
-    let mut tokens = tokens.peekable();
+/// The exit code after writing the output failed with `e`, reported on
+/// stderr. A reader that went away early, as `netgen_rs ... | head` does,
+/// is not an error: that exits 0 quietly.
+fn output_failed(e: io::Error) -> i32 {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        return 0;
+    }
+    eprintln!("Error: writing output: {e}");
+    1
+}
+
+/// Generate the problems of the `--params-file` and `--params-dir` files.
+/// Returns the exit code.
+fn run_param_files(options: &Options, positional: &[String]) -> i32 {
+    let conflict = if !positional.is_empty() {
+        Some("problems on the command line")
+    } else if options.validate || options.dry_run {
+        Some("--validate or --dry-run")
+    } else if options.count.is_some() && options.output.is_none() {
+        Some("--count without --output")
+    } else {
+        None
+    };
+    if let Some(conflict) = conflict {
+        eprintln!("Error: --params-file and --params-dir cannot be combined with {conflict}");
+        return 2;
+    }
+    let paths = match param_files(&options.params) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+    let mut sink = match Sink::new(options) {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+    match generate_all(&mut sink, collect_param_jobs(&paths, options), options) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(e) => output_failed(e),
+    }
+}
 
-    while let Some(seed) = tokens.next().and_then(|s| s.parse::<i64>().ok()) {
-        if seed <= 0 {
+/// The files named by `sources`, with each directory replaced by its
+/// `*.param` files sorted by name.
+fn param_files(sources: &[ParamSource]) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    for source in sources {
+        match source {
+            ParamSource::File(path) => paths.push(path.clone()),
+            ParamSource::Dir(dir) => {
+                let error = |e: io::Error| format!("cannot read {}: {e}", dir.display());
+                let mut files = Vec::new();
+                for entry in std::fs::read_dir(dir).map_err(error)? {
+                    let path = entry.map_err(error)?.path();
+                    if path.extension().is_some_and(|ext| ext == "param") && path.is_file() {
+                        files.push(path);
+                    }
+                }
+                files.sort();
+                paths.extend(files);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// The instance file for the problem in `param_file`: its name without
+/// `.param`, and with the extension for `params` in place of any `.min`,
+/// `.max` or `.asn` it ends with.
+fn param_output_name(param_file: &Path, params: &NetgenParams) -> PathBuf {
+    let stem = param_file.with_extension("");
+    let stem = match stem.extension().and_then(|ext| ext.to_str()) {
+        Some("min" | "max" | "asn") => stem.with_extension(""),
+        _ => stem,
+    };
+    let mut name = stem.into_os_string();
+    name.push(".");
+    name.push(naming::extension(params.problem_type()));
+    name.into()
+}
+
+/// Set once anything arrives on stdin, so the `--interactive` hint is not
+/// printed over someone's typing.
+static STDIN_TYPED: AtomicBool = AtomicBool::new(false);
+
+/// Read all of stdin. On a terminal that stays silent for a few seconds,
+/// point the user at `--interactive`.
+fn read_stdin() -> String {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        thread::spawn(|| {
+            thread::sleep(Duration::from_secs(3));
+            if !STDIN_TYPED.load(Ordering::Relaxed) {
+                eprintln!(
+                    "Reading problems from stdin (end with Ctrl-D). \
+                     Run `netgen_rs --interactive` to be prompted for the parameters instead."
+                );
+            }
+        });
+    }
+    let mut buf = Vec::new();
+    let mut stdin = stdin.lock();
+    let mut chunk = [0; 8192];
+    loop {
+        let n = stdin.read(&mut chunk).expect("reading stdin");
+        STDIN_TYPED.store(true, Ordering::Relaxed);
+        if n == 0 {
             break;
         }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+/// What `--interactive` asks for, in input order: name, meaning and
+/// default. The defaults are the README example.
+const PROMPTS: [(&str, &str, i64); 15] = [
+    ("seed", "positive random seed", 13502460),
+    ("problem", "problem number, shown in the header", 1),
+    ("nodes", "total number of nodes", 512),
+    (
+        "sources",
+        "source nodes, including transshipment sources",
+        10,
+    ),
+    ("sinks", "sink nodes, including transshipment sinks", 10),
+    ("density", "number of arcs", 2000),
+    ("mincost", "minimum arc cost", 5),
+    ("maxcost", "maximum arc cost", 500),
+    ("supply", "total supply", 1000),
+    ("tsources", "transshipment sources", 3),
+    ("tsinks", "transshipment sinks", 3),
+    ("hicost%", "skeleton arcs with the maximum cost, 0-100", 20),
+    ("capacitated%", "capacitated arcs, 0-100", 80),
+    ("mincap", "minimum arc capacity", 50),
+    ("maxcap", "maximum arc capacity", 2000),
+];
+
+/// The `--interactive` mode: prompt on stderr for every value in
+/// [`PROMPTS`], re-prompting until the parameters are valid, then for an
+/// output path, and write the instance there. Returns the exit code.
+fn run_interactive(options: &Options) -> i32 {
+    let mut lines = io::stdin().lock().lines();
+    let eof = std::cell::Cell::new(false);
+    // The answer to `question`, or `default` for an empty line or at EOF.
+    let mut ask = |question: &str, default: &str| -> String {
+        eprint!("{question} [{default}]: ");
+        match lines.next() {
+            Some(Ok(line)) if !line.trim().is_empty() => line.trim().to_string(),
+            Some(Ok(_)) => default.to_string(),
+            Some(Err(_)) | None => {
+                eof.set(true);
+                eprintln!();
+                default.to_string()
+            }
+        }
+    };
+
+    let mut values = PROMPTS.map(|(_, _, default)| default);
+    let params = loop {
+        for ((name, meaning, _), value) in PROMPTS.iter().zip(&mut values) {
+            loop {
+                let answer = ask(&format!("{name} ({meaning})"), &value.to_string());
+                match answer.parse::<i64>() {
+                    Ok(v) if v > 0 || !matches!(*name, "seed" | "problem") => {
+                        *value = v;
+                        break;
+                    }
+                    Ok(_) => eprintln!("{name} must be positive"),
+                    Err(_) => eprintln!("not an integer: {answer:?}"),
+                }
+                if eof.get() {
+                    return 1;
+                }
+            }
+        }
+        match NetgenParams::from_slice(&values[2..]) {
+            Ok(params) => break params,
+            Err(e) if !eof.get() => {
+                eprintln!("Invalid parameters: {e}. Press enter to keep a value.");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return 1;
+            }
+        }
+    };
+    let (seed, problem) = (values[0], values[1]);
+    eprintln!("Problem type: {}", params.problem_type());
+    if !options.no_warnings {
+        for warning in params.lint() {
+            eprintln!("Warning: {warning}");
+        }
+    }
+
+    let family = options.family.as_deref().unwrap_or("netgen");
+    let default_path = naming::instance_name(family, &params, 0, params.problem_type());
+    let path = ask("output path", &default_path);
+    let result = match netgen_rs::generate(seed, &params) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+    let written = File::create(&path).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        netgen_rs::write_dimacs(&mut out, seed, problem, &params, &result)?;
+        out.flush()
+    });
+    match written {
+        Ok(()) => {
+            eprintln!("Wrote {} arcs to {path}", result.arcs.len());
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {path}: {e}");
+            1
+        }
+    }
+}
+
+/// The `diff` subcommand: compare two DIMACS files canonically and return
+/// the exit code.
+fn run_diff(paths: &[String]) -> i32 {
+    let [a, b] = paths else {
+        eprintln!("Error: diff expects two files");
+        return 2;
+    };
+    let read = |path: &String| -> Result<NetgenResult, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        netgen_rs::parse_dimacs(&text).map_err(|e| format!("{path}: {e}"))
+    };
+    let (left, right) = match (read(a), read(b)) {
+        (Ok(left), Ok(right)) => (left.canonicalize(), right.canonicalize()),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {e}");
+            return 2;
+        }
+    };
+    match left.first_difference(&right) {
+        None => print_verdict("equal", 0),
+        Some(difference) => print_verdict(&format!("different: {difference}"), 1),
+    }
+}
+
+/// Print the one-line verdict of `diff` or `verify` and return its exit
+/// `code`. A reader that went away early is handled as by
+/// [`output_failed`], except that the verdict's code is kept; other write
+/// errors exit 2 like the subcommands' other errors.
+fn print_verdict(verdict: &str, code: i32) -> i32 {
+    match writeln!(io::stdout(), "{verdict}") {
+        Ok(()) => code,
+        Err(e) => match output_failed(e) {
+            0 => code,
+            _ => 2,
+        },
+    }
+}
+
+/// The `verify` subcommand: check that a DIMACS file is what its header's
+/// seed and parameters generate, and return the exit code.
+fn run_verify(paths: &[String]) -> i32 {
+    let [path] = paths else {
+        eprintln!("Error: verify expects one file");
+        return 2;
+    };
+    let outcome = File::open(path)
+        .map_err(VerifyError::Io)
+        .and_then(|file| netgen_rs::verify_instance(BufReader::new(file)));
+    match outcome {
+        Ok(VerifyOutcome::Match) => print_verdict("match", 0),
+        Ok(VerifyOutcome::HeaderOnly { line }) => print_verdict(
+            &format!("header only: the network differs from line {line}"),
+            1,
+        ),
+        Ok(VerifyOutcome::NotNetgen) => {
+            print_verdict("not netgen: no seed and parameters in the header", 1)
+        }
+        Err(e) => {
+            eprintln!("Error: {path}: {e}");
+            2
+        }
+    }
+}
 
-        let problem: i64 = match tokens.next().and_then(|s| s.parse().ok()) {
-            Some(v) if v > 0 => v,
-            _ => break,
+/// The `suite` subcommand: write (or with `--dry-run`, list) the instances
+/// of a LEMON family and return the exit code.
+fn run_suite(args: &[String]) -> i32 {
+    let mut name = None;
+    let mut exps = (u32::MIN, u32::MAX);
+    let mut out_dir = std::path::PathBuf::from(".");
+    let mut dry_run = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {arg}"))
+        };
+        let parsed = match arg.as_str() {
+            "--dry-run" => {
+                dry_run = true;
+                Ok(())
+            }
+            "--out-dir" => value().map(|v| out_dir = v.into()),
+            "--min-exp" | "--max-exp" => value().and_then(|v| {
+                let exp = v.parse().map_err(|_| format!("invalid exponent: {v}"))?;
+                match arg.as_str() {
+                    "--min-exp" => exps.0 = exp,
+                    _ => exps.1 = exp,
+                }
+                Ok(())
+            }),
+            _ if arg.starts_with("--") => Err(format!("unknown option: {arg}")),
+            _ if name.is_some() => Err("suite expects one name".to_string()),
+            _ => arg
+                .parse::<netgen_rs::LemonSuite>()
+                .map(|suite| name = Some(suite))
+                .map_err(|e| e.to_string()),
         };
+        if let Err(e) = parsed {
+            eprintln!("Error: {e}");
+            return 2;
+        }
+    }
+    let Some(suite) = name else {
+        eprintln!("Error: suite expects a name");
+        return 2;
+    };
 
-        let mut parms = [0i64; 13];
-        for p in &mut parms {
-            *p = match tokens.next().and_then(|s| s.parse().ok()) {
-                Some(v) => v,
-                None => {
-                    eprintln!("Error: insufficient parameters");
-                    std::process::exit(1);
+    let instances = suite.instances(exps.0..=exps.1);
+    if dry_run {
+        let mut out = io::stdout().lock();
+        let listed = instances.iter().try_for_each(|instance| {
+            writeln!(
+                out,
+                "{}  {} {} {}",
+                instance.file_name, instance.seed, instance.problem, instance.params
+            )
+        });
+        return listed.map_or_else(output_failed, |()| 0);
+    }
+    if let Err(e) = std::fs::create_dir_all(&out_dir) {
+        eprintln!("Error: cannot create {}: {e}", out_dir.display());
+        return 1;
+    }
+    for instance in &instances {
+        let path = out_dir.join(&instance.file_name);
+        let result = match netgen_rs::generate(instance.seed, &instance.params) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("Error: {}: {e}", instance.file_name);
+                return 1;
+            }
+        };
+        let written = File::create(&path).and_then(|file| {
+            let mut w = BufWriter::new(file);
+            result.write_dimacs(&mut w, instance.seed, instance.problem, &instance.params)?;
+            w.flush()
+        });
+        if let Err(e) = written {
+            eprintln!("Error: writing {}: {e}", path.display());
+            return 1;
+        }
+    }
+    0
+}
+
+/// Compression applied to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(format!(
+                "unknown compression: {name} (expected gzip or zstd)"
+            )),
+        }
+    }
+
+    /// The compression implied by an output path's extension, if any.
+    fn from_path(path: &str) -> Option<Self> {
+        if path.ends_with(".gz") {
+            Some(Compression::Gzip)
+        } else if path.ends_with(".zst") {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Check that support is compiled in and `level` is in range.
+    fn check(self, level: Option<i32>) -> Result<(), String> {
+        let (feature, levels) = match self {
+            Compression::Gzip => ("flate2", 0..=9),
+            Compression::Zstd => ("zstd", 1..=22),
+        };
+        let enabled = match self {
+            Compression::Gzip => cfg!(feature = "flate2"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        };
+        if !enabled {
+            return Err(format!(
+                "{self:?} output requires building with the `{feature}` feature"
+            ));
+        }
+        match level {
+            Some(level) if !levels.contains(&level) => Err(format!(
+                "compression level {level} out of range {}..={}",
+                levels.start(),
+                levels.end()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A buffered output stream, optionally compressed.
+enum Encoder {
+    Plain(BufWriter<Box<dyn Write>>),
+    #[cfg(feature = "flate2")]
+    Gzip(BufWriter<flate2::write::GzEncoder<Box<dyn Write>>>),
+    #[cfg(feature = "zstd")]
+    Zstd(BufWriter<zstd::Encoder<'static, Box<dyn Write>>>),
+}
+
+impl Encoder {
+    /// Wrap `inner`; `compression` must have passed [`Compression::check`].
+    #[cfg_attr(
+        not(any(feature = "flate2", feature = "zstd")),
+        allow(unused_variables)
+    )]
+    fn new(
+        inner: Box<dyn Write>,
+        compression: Option<Compression>,
+        level: Option<i32>,
+    ) -> io::Result<Self> {
+        Ok(match compression {
+            None => Encoder::Plain(BufWriter::new(inner)),
+            #[cfg(feature = "flate2")]
+            Some(Compression::Gzip) => {
+                let level = level.map_or(netgen_rs::GZIP_DEFAULT_LEVEL, |l| l as u32);
+                let gz = flate2::write::GzEncoder::new(inner, flate2::Compression::new(level));
+                Encoder::Gzip(BufWriter::new(gz))
+            }
+            #[cfg(feature = "zstd")]
+            Some(Compression::Zstd) => {
+                let level = level.unwrap_or(netgen_rs::ZSTD_DEFAULT_LEVEL);
+                Encoder::Zstd(BufWriter::new(zstd::Encoder::new(inner, level)?))
+            }
+            #[allow(unreachable_patterns)]
+            Some(c) => unreachable!("{c:?} support not compiled in"),
+        })
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Encoder::Plain(w) => w,
+            #[cfg(feature = "flate2")]
+            Encoder::Gzip(w) => w,
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(w) => w,
+        }
+    }
+
+    /// Flush all buffered data and write the compressed stream's trailer.
+    fn finish(self) -> io::Result<()> {
+        fn unbuffer<W: Write>(w: BufWriter<W>) -> io::Result<W> {
+            w.into_inner().map_err(|e| e.into_error())
+        }
+        let mut inner = match self {
+            Encoder::Plain(w) => unbuffer(w)?,
+            #[cfg(feature = "flate2")]
+            Encoder::Gzip(w) => unbuffer(w)?.finish()?,
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(w) => unbuffer(w)?.finish()?,
+        };
+        inner.flush()
+    }
+}
+
+/// Where generated instances are written: one stream shared by all
+/// instances, or one file per instance named from a template containing
+/// `{index}` or `{name}`.
+struct Sink {
+    template: Option<String>,
+    compression: Option<Compression>,
+    level: Option<i32>,
+    out: Option<Encoder>,
+    /// Output file to write a `.sha256` sidecar for once finished.
+    checksum_path: Option<String>,
+    checksum: bool,
+    /// Output file to write a `.json` manifest for once finished, and the
+    /// manifests of the instances written to it so far.
+    manifest_path: Option<String>,
+    manifests: Vec<InstanceManifest>,
+}
+
+impl Sink {
+    fn new(options: &Options) -> Result<Self, String> {
+        let output = options.output.as_deref();
+        let compression = options
+            .compression
+            .or_else(|| output.and_then(Compression::from_path));
+        if let Some(c) = compression {
+            c.check(options.level)?;
+        } else if options.level.is_some() {
+            return Err("--level requires --compress or a .gz/.zst output".to_string());
+        }
+        if options.checksum {
+            if !cfg!(feature = "sha2") {
+                return Err("--checksum requires building with the `sha2` feature".to_string());
+            }
+            if options.stats_only {
+                return Err("--checksum cannot be combined with --stats-only".to_string());
+            }
+        }
+        if options.manifest {
+            if options.to_stdout() {
+                return Err("--manifest requires --output".to_string());
+            }
+            if options.stats_only {
+                return Err("--manifest cannot be combined with --stats-only".to_string());
+            }
+        }
+        let mut sink = Sink {
+            template: None,
+            compression,
+            level: options.level,
+            out: None,
+            checksum_path: None,
+            checksum: options.checksum,
+            manifest_path: None,
+            manifests: Vec::new(),
+        };
+        let out: Box<dyn Write> = match output {
+            Some(path) if path.contains("{index}") || path.contains("{name}") => {
+                sink.template = Some(path.to_string());
+                return Ok(sink);
+            }
+            Some(path) => {
+                if options.checksum {
+                    sink.checksum_path = Some(path.to_string());
+                }
+                if options.manifest {
+                    sink.manifest_path = Some(path.to_string());
+                }
+                Box::new(File::create(path).map_err(|e| format!("cannot create {path}: {e}"))?)
+            }
+            // Each `--params-file` instance goes to the path from `Job::name`.
+            None if !options.to_stdout() => {
+                sink.template = Some("{name}".to_string());
+                return Ok(sink);
+            }
+            None => Box::new(io::stdout()),
+        };
+        let out = Encoder::new(out, compression, options.level).map_err(|e| e.to_string())?;
+        sink.out = Some(out);
+        Ok(sink)
+    }
+
+    /// Run `f` with the writer for the instance numbered `index` (1-based)
+    /// and called `name`, recording `manifest` for it if given, with the
+    /// fingerprint of the bytes `f` wrote.
+    fn emit(
+        &mut self,
+        index: usize,
+        name: &str,
+        mut manifest: Option<InstanceManifest>,
+        f: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+    ) -> io::Result<()> {
+        match self.path(index, name) {
+            Some(path) => {
+                let file = Box::new(File::create(&path)?);
+                let mut w = Encoder::new(file, self.compression, self.level)?;
+                write_fingerprinted(w.writer(), manifest.as_mut(), f)?;
+                w.finish()?;
+                if self.checksum {
+                    write_sidecar(&path)?;
+                }
+                if let Some(manifest) = manifest {
+                    write_manifests(&path, &[manifest])?;
+                }
+                Ok(())
+            }
+            None => {
+                // Flush each instance, so that a reader of a batch can start
+                // on it before the next one is generated.
+                let out = self.out.as_mut().expect("sink already finished").writer();
+                write_fingerprinted(out, manifest.as_mut(), f)?;
+                self.manifests.extend(manifest);
+                out.flush()
+            }
+        }
+    }
+
+    /// The file the instance numbered `index` and called `name` goes to,
+    /// if each instance gets its own.
+    fn path(&self, index: usize, name: &str) -> Option<String> {
+        let template = self.template.as_ref()?;
+        Some(
+            template
+                .replace("{index}", &index.to_string())
+                .replace("{name}", name),
+        )
+    }
+
+    /// Report each job whose file is also an earlier job's, which it would
+    /// overwrite. Returns whether there were none.
+    fn check_paths(&self, jobs: &[Job], options: &Options) -> bool {
+        let mut owners = HashMap::new();
+        let mut all_ok = true;
+        for job in jobs {
+            // Invalid parameters are reported when generated, writing nothing.
+            let Ok(params) = NetgenParams::from_slice(&job.parms) else {
+                continue;
+            };
+            let Some(path) = self.path(job.instance, &job.name(&params, options)) else {
+                return true;
+            };
+            match owners.entry(path) {
+                Entry::Vacant(entry) => {
+                    entry.insert(job);
+                }
+                Entry::Occupied(entry) => {
+                    let owner = entry.get();
+                    job.report(&format!(
+                        "{} is also the output of {} (line {}, seed {}); \
+                         add {{index}} to --output to tell them apart",
+                        entry.key(),
+                        owner.problem(),
+                        owner.line,
+                        owner.seed
+                    ));
+                    all_ok = false;
                 }
+            }
+        }
+        all_ok
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.take().map_or(Ok(()), Encoder::finish)?;
+        if let Some(path) = self.checksum_path.take() {
+            write_sidecar(&path)?;
+        }
+        match self.manifest_path.take() {
+            Some(path) => write_manifests(&path, &self.manifests),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Run `f` on `out`. With a `manifest`, also hash what `f` writes and
+/// record it as the manifest's fingerprint: the header `--provenance`
+/// adds is not in the plain DIMACS text that
+/// [`InstanceManifest::from_run`] hashes.
+#[cfg(feature = "sha2")]
+fn write_fingerprinted(
+    out: &mut dyn Write,
+    manifest: Option<&mut InstanceManifest>,
+    f: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    let Some(manifest) = manifest else {
+        return f(out);
+    };
+    let mut hasher = netgen_rs::FingerprintWriter::new();
+    f(&mut netgen_rs::TeeWriter::new([out, &mut hasher]))?;
+    manifest.fingerprint = Some(netgen_rs::to_hex(&hasher.finish()));
+    Ok(())
+}
+
+#[cfg(not(feature = "sha2"))]
+fn write_fingerprinted(
+    out: &mut dyn Write,
+    _manifest: Option<&mut InstanceManifest>,
+    f: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    f(out)
+}
+
+/// Write `path.sha256` in `sha256sum` format, hashing the file as written.
+#[cfg(feature = "sha2")]
+fn write_sidecar(path: &str) -> io::Result<()> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    let name = std::path::Path::new(path)
+        .file_name()
+        .map_or(path.into(), |n| n.to_string_lossy());
+    let line = format!("{}  {name}\n", netgen_rs::to_hex(&hasher.finalize()));
+    std::fs::write(format!("{path}.sha256"), line)
+}
+
+#[cfg(not(feature = "sha2"))]
+fn write_sidecar(_path: &str) -> io::Result<()> {
+    unreachable!("--checksum is rejected without the sha2 feature")
+}
+
+/// The current UTC time in RFC 3339 form, or that of `SOURCE_DATE_EPOCH`
+/// when it is set, for reproducible builds.
+fn rfc3339_now() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+    // Civil date from days since 1970-01-01, after Howard Hinnant's
+    // `civil_from_days`.
+    let z = secs / 86400 + 719_468;
+    let (era, doe) = (z / 146_097, z % 146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + yoe + u64::from(month <= 2);
+    let time = secs % 86400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Write `path.json` with one manifest per line.
+fn write_manifests(path: &str, manifests: &[InstanceManifest]) -> io::Result<()> {
+    let mut w = BufWriter::new(File::create(format!("{path}.json"))?);
+    for manifest in manifests {
+        netgen_rs::write_manifest(&mut w, manifest)?;
+    }
+    w.flush()
+}
+
+/// One instance to generate: a problem from the input paired with one of
+/// its seeds.
+struct Job {
+    /// 1-based position of the instance in the run, used for `{index}`.
+    instance: usize,
+    /// 0-based position of the seed among the problem's `--count` seeds,
+    /// the replicate letter of `{name}`.
+    replicate: usize,
+    /// 1-based position and start line of the problem in the input.
+    index: usize,
+    line: usize,
+    seed: i64,
+    problem: i64,
+    parms: [i64; 13],
+    /// The `--params-file` the problem was read from, if any.
+    param_file: Option<PathBuf>,
+}
+
+impl Job {
+    /// Generate the instance, returning it with its report. An instance
+    /// left short of `density` is warned about.
+    fn generate(
+        &self,
+        options: &Options,
+    ) -> Result<(NetgenParams, NetgenResult, GenerationReport), String> {
+        let params = NetgenParams::from_slice(&self.parms).map_err(|e| e.to_string())?;
+        let (result, report) =
+            netgen_rs::generate_with_report(self.seed, &params).map_err(|e| e.to_string())?;
+        if report.shortfall > 0 && !options.no_warnings {
+            // Drops that later tails made up for are no cause of the
+            // shortfall, so they are only named when there are some.
+            let cause = match report.dropped_arcs {
+                0 => String::new(),
+                dropped => format!(
+                    "; {dropped} random arcs were dropped because their tails ran out of heads"
+                ),
+            };
+            eprintln!(
+                "Warning: {} (line {}, seed {}): the network has {} of {} arcs, {} short{cause}",
+                self.problem(),
+                self.line,
+                self.seed,
+                result.arcs.len(),
+                params.density,
+                report.shortfall
+            );
+        }
+        Ok((params, result, report))
+    }
+
+    /// Write the instance as `options` ask. A `--provenance` header also
+    /// stamps the arcs `report` counts as dropped; the default header stays
+    /// the reference's.
+    fn write(
+        &self,
+        mut out: &mut dyn Write,
+        params: &NetgenParams,
+        result: &NetgenResult,
+        report: &GenerationReport,
+        options: &Options,
+    ) -> io::Result<()> {
+        if options.checksum && options.to_stdout() {
+            write_fingerprint(out, self, params, result)
+        } else if options.stats_only {
+            writeln!(
+                out,
+                "seed={} problem={} {}",
+                self.seed,
+                self.problem,
+                result.stats(params)
+            )
+        } else if let Some(command) = &options.provenance {
+            let options = DimacsOptions {
+                header: HeaderOptions {
+                    include_provenance: true,
+                    generated: Some(rfc3339_now()),
+                    command: Some(command.clone()),
+                    dropped_arcs: report.dropped_arcs,
+                    ..HeaderOptions::default()
+                },
+                ..DimacsOptions::default()
+            };
+            netgen_rs::write_dimacs_with_options(
+                &mut out,
+                self.seed,
+                self.problem,
+                params,
+                result,
+                &options,
+            )
+        } else {
+            netgen_rs::write_dimacs(&mut out, self.seed, self.problem, params, result)
+        }
+    }
+
+    /// The instance's name for `{name}`, e.g. `netgen-14a.min`, or for a
+    /// `--params-file` the name of its instance file, which is also its
+    /// path when there is no `--output`.
+    fn name(&self, params: &NetgenParams, options: &Options) -> String {
+        if let Some(param_file) = &self.param_file {
+            let path = param_output_name(param_file, params);
+            let name = match options.output {
+                Some(_) => path.file_name().map(PathBuf::from).unwrap_or(path),
+                None => path,
             };
+            return name.to_string_lossy().into_owned();
+        }
+        let family = options.family.as_deref().unwrap_or("netgen");
+        naming::instance_name(family, params, self.replicate, params.problem_type())
+    }
+
+    /// The manifest to write next to the output, if `--manifest` is set.
+    fn manifest(
+        &self,
+        params: &NetgenParams,
+        result: &NetgenResult,
+        options: &Options,
+    ) -> Option<InstanceManifest> {
+        options
+            .manifest
+            .then(|| InstanceManifest::from_run(self.seed, self.problem, params, result))
+    }
+
+    /// The problem's `--params-file`, or its position in the input.
+    fn problem(&self) -> String {
+        match &self.param_file {
+            Some(path) => path.display().to_string(),
+            None => format!("problem {}", self.index),
+        }
+    }
+
+    fn report(&self, error: &str) {
+        let parms = self.parms.map(|p| p.to_string()).join(" ");
+        eprintln!(
+            "Error: {} (line {}, seed {}, parameters {parms}): {error}",
+            self.problem(),
+            self.line,
+            self.seed
+        );
+    }
+}
+
+/// Print the `sha256sum`-style fingerprint line of an instance.
+#[cfg(feature = "sha2")]
+fn write_fingerprint(
+    out: &mut dyn Write,
+    job: &Job,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> io::Result<()> {
+    let digest = result.fingerprint(job.seed, job.problem, params);
+    writeln!(out, "{}  -", netgen_rs::to_hex(&digest))
+}
+
+#[cfg(not(feature = "sha2"))]
+fn write_fingerprint(
+    _out: &mut dyn Write,
+    _job: &Job,
+    _params: &NetgenParams,
+    _result: &NetgenResult,
+) -> io::Result<()> {
+    unreachable!("--checksum is rejected without the sha2 feature")
+}
+
+/// Print the [`NetgenParams::lint`] warnings for the problem at `location`
+/// (e.g. `problem 2 (line 3)`) to stderr, unless `--no-warnings` is set.
+fn warn(location: &str, params: &NetgenParams, options: &Options) {
+    if options.no_warnings {
+        return;
+    }
+    for warning in params.lint() {
+        eprintln!("Warning: {location}: {warning}");
+    }
+}
+
+/// Expand the problems in `input` into jobs, one per seed. Also returns the
+/// error for a malformed parameter list, which ends the input.
+fn collect_jobs(input: &str, options: &Options) -> (Vec<Job>, Vec<String>) {
+    let mut jobs = Vec::new();
+    for raw in ProblemReader::new(input) {
+        let location = format!("problem {} (line {})", raw.index, raw.line);
+        match raw.parms {
+            Ok(parms) => push_jobs(&mut jobs, &raw, parms, &location, None, options),
+            Err(e) => return (jobs, vec![format!("{location}: {e}")]),
         }
+    }
+    (jobs, Vec::new())
+}
 
-        let params = match netgen_rs::NetgenParams::from_slice(&parms) {
-            Ok(p) => p,
+/// Expand the problem in each of `paths` into jobs, one per seed. Also
+/// returns the errors for files that cannot be read or do not hold exactly
+/// one problem; with `--fail-fast` the first of them ends the input.
+fn collect_param_jobs(paths: &[PathBuf], options: &Options) -> (Vec<Job>, Vec<String>) {
+    let mut jobs = Vec::new();
+    let mut errors = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let read = std::fs::read_to_string(path).map_err(|e| e.to_string());
+        let problem = read.and_then(|input| {
+            let mut reader = ProblemReader::new(&input);
+            let raw = reader.next().ok_or("no valid seed and problem number")?;
+            let parms = raw.parms.clone()?;
+            if reader.next().is_some() {
+                return Err("more than one problem".to_string());
+            }
+            Ok((raw, parms))
+        });
+        match problem {
+            Ok((mut raw, parms)) => {
+                raw.index = i + 1;
+                let location = format!("{} (line {})", path.display(), raw.line);
+                push_jobs(&mut jobs, &raw, parms, &location, Some(path), options);
+            }
             Err(e) => {
-                eprintln!("Error: {e}");
-                std::process::exit(1);
+                errors.push(format!("{}: {e}", path.display()));
+                if options.fail_fast {
+                    break;
+                }
+            }
+        }
+    }
+    (jobs, errors)
+}
+
+/// Append the jobs for `raw`, whose parameters are `parms`, to `jobs`: one,
+/// or one per seed with `--count`.
+fn push_jobs(
+    jobs: &mut Vec<Job>,
+    raw: &RawProblem,
+    parms: [i64; 13],
+    location: &str,
+    param_file: Option<&Path>,
+    options: &Options,
+) {
+    if let Ok(params) = NetgenParams::from_slice(&parms) {
+        warn(location, &params, options);
+    }
+    let seed = options.seed.unwrap_or(raw.seed);
+    let seeds: Vec<i64> = match options.count {
+        Some(n) => SeedSequence::new(seed as u64)
+            .iter()
+            .take(n as usize)
+            .collect(),
+        None => vec![seed],
+    };
+    for (replicate, seed) in seeds.into_iter().enumerate() {
+        jobs.push(Job {
+            instance: jobs.len() + 1,
+            replicate,
+            index: raw.index,
+            line: raw.line,
+            seed,
+            problem: raw.problem,
+            parms,
+            param_file: param_file.map(Path::to_path_buf),
+        });
+    }
+}
+
+/// Generate the jobs from [`collect_jobs`] or [`collect_param_jobs`], then
+/// report the errors that came with them. Returns whether all succeeded.
+///
+/// A problem with invalid parameters is reported on stderr and skipped,
+/// unless `--fail-fast` is set. A malformed parameter list always ends the
+/// run, since the remaining tokens can no longer be aligned to problems.
+fn generate_all(
+    sink: &mut Sink,
+    (jobs, errors): (Vec<Job>, Vec<String>),
+    options: &Options,
+) -> io::Result<bool> {
+    if !sink.check_paths(&jobs, options) {
+        return Ok(false);
+    }
+    let mut timing = Timing::default();
+    let finished = if options.jobs > 1 {
+        generate_parallel(sink, &jobs, &mut timing, options)?
+    } else {
+        generate_serial(sink, &jobs, &mut timing, options)?
+    };
+    let start = Instant::now();
+    sink.finish()?;
+    timing.write += start.elapsed();
+    if options.timing {
+        eprintln!(
+            "Timing: total: instances={} {}",
+            timing.instances,
+            timing.fields()
+        );
+    }
+    match finished {
+        Some(all_ok) => {
+            for error in &errors {
+                eprintln!("Error: {error}");
             }
+            Ok(all_ok && errors.is_empty())
+        }
+        None => Ok(false),
+    }
+}
+
+/// Wall-clock times for `--timing`, summed over the instances so far.
+#[derive(Default)]
+struct Timing {
+    instances: usize,
+    arcs: usize,
+    generate: Duration,
+    write: Duration,
+}
+
+impl Timing {
+    /// Add an instance of `job` with `arcs` arcs, generated and written in
+    /// the given times, and print its times if `--timing` is set.
+    fn record(
+        &mut self,
+        job: &Job,
+        arcs: usize,
+        generate: Duration,
+        write: Duration,
+        options: &Options,
+    ) {
+        let instance = Timing {
+            instances: 1,
+            arcs,
+            generate,
+            write,
+        };
+        if options.timing {
+            eprintln!(
+                "Timing: {} (line {}, seed {}): {}",
+                job.problem(),
+                job.line,
+                job.seed,
+                instance.fields()
+            );
+        }
+        self.instances += 1;
+        self.arcs += arcs;
+        self.generate += generate;
+        self.write += write;
+    }
+
+    /// `generate=...s write=...s arcs=... arcs_per_sec=...`, the rate being
+    /// that of generation alone.
+    fn fields(&self) -> String {
+        let seconds = self.generate.as_secs_f64();
+        let rate = if seconds > 0.0 {
+            self.arcs as f64 / seconds
+        } else {
+            0.0
         };
+        format!(
+            "generate={:.6}s write={:.6}s arcs={} arcs_per_sec={rate:.0}",
+            self.generate.as_secs_f64(),
+            self.write.as_secs_f64(),
+            self.arcs
+        )
+    }
+}
 
-        let result = match netgen_rs::generate(seed, &params) {
-            Ok(r) => r,
+/// Generate and write `jobs` one after another. Returns `None` if stopped
+/// early by `--fail-fast`, otherwise whether all jobs succeeded.
+fn generate_serial(
+    sink: &mut Sink,
+    jobs: &[Job],
+    timing: &mut Timing,
+    options: &Options,
+) -> io::Result<Option<bool>> {
+    let mut all_ok = true;
+    for job in jobs {
+        match job.generate(options) {
+            Ok((params, result, report)) => {
+                let manifest = job.manifest(&params, &result, options);
+                let name = job.name(&params, options);
+                let generated = report.duration.unwrap_or_default();
+                let start = Instant::now();
+                sink.emit(job.instance, &name, manifest, |out| {
+                    job.write(out, &params, &result, &report, options)
+                })?;
+                timing.record(job, result.arcs.len(), generated, start.elapsed(), options);
+            }
             Err(e) => {
-                eprintln!("Error: {e}");
-                std::process::exit(1);
+                job.report(&e);
+                if options.fail_fast {
+                    return Ok(None);
+                }
+                all_ok = false;
             }
-        };
-        netgen_rs::write_dimacs(&mut out, seed, problem, &params, &result)
-            .expect("writing DIMACS output");
+        }
+    }
+    Ok(Some(all_ok))
+}
+
+/// Generate `jobs` on `options.jobs` worker threads and write them in input
+/// order, buffering instances that complete ahead of their turn. A worker
+/// only starts an instance less than `options.jobs` ahead of the next one
+/// to write, so at most that many wait in the buffer. The output is
+/// identical to [`generate_serial`].
+fn generate_parallel(
+    sink: &mut Sink,
+    jobs: &[Job],
+    timing: &mut Timing,
+    options: &Options,
+) -> io::Result<Option<bool>> {
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    // The number of instances written, and its change.
+    let written = (Mutex::new(0), Condvar::new());
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.min(jobs.len()) {
+            let (tx, next, stop, written) = (tx.clone(), &next, &stop, &written);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(job) = jobs.get(i) else { break };
+                    let mut emitted = written.0.lock().unwrap();
+                    while i >= *emitted + options.jobs && !stop.load(Ordering::Relaxed) {
+                        emitted = written.1.wait(emitted).unwrap();
+                    }
+                    drop(emitted);
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let rendered = job.generate(options).map(|(params, result, report)| {
+                        let start = Instant::now();
+                        let mut buf = Vec::new();
+                        job.write(&mut buf, &params, &result, &report, options)
+                            .expect("writing to Vec should not fail");
+                        let manifest = job.manifest(&params, &result, options);
+                        let name = job.name(&params, options);
+                        let times = (report.duration.unwrap_or_default(), start.elapsed());
+                        (buf, name, manifest, result.arcs.len(), times)
+                    });
+                    if tx.send((i, rendered)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        let outcome = (|| {
+            let mut all_ok = true;
+            let mut pending = BTreeMap::new();
+            let mut emitted = 0;
+            for (i, rendered) in &rx {
+                pending.insert(i, rendered);
+                while let Some(rendered) = pending.remove(&emitted) {
+                    let job = &jobs[emitted];
+                    emitted += 1;
+                    *written.0.lock().unwrap() = emitted;
+                    written.1.notify_all();
+                    match rendered {
+                        Ok((buf, name, manifest, arcs, (generated, rendered))) => {
+                            let start = Instant::now();
+                            sink.emit(job.instance, &name, manifest, |out| out.write_all(&buf))?;
+                            let written = rendered + start.elapsed();
+                            timing.record(job, arcs, generated, written, options);
+                        }
+                        Err(e) => {
+                            job.report(&e);
+                            if options.fail_fast {
+                                return Ok(None);
+                            }
+                            all_ok = false;
+                        }
+                    }
+                }
+            }
+            Ok(Some(all_ok))
+        })();
+        // Wake the workers waiting for their turn, so that they can stop.
+        {
+            let _emitted = written.0.lock().unwrap();
+            stop.store(true, Ordering::Relaxed);
+            written.1.notify_all();
+        }
+        outcome
+    })
+}
+
+/// Print a verdict for every problem in `input`, or with `--dry-run` its size
+/// estimate. Returns whether all passed.
+///
+/// A malformed parameter list ends the run, since the remaining tokens can
+/// no longer be aligned to problems.
+fn validate_all(out: &mut impl Write, input: &str, options: &Options) -> io::Result<bool> {
+    let mut all_ok = true;
+    for raw in ProblemReader::new(input) {
+        let verdict = raw
+            .parms
+            .as_ref()
+            .map_err(|e| e.clone())
+            .and_then(|parms| NetgenParams::from_slice(parms).map_err(|e| e.to_string()));
+        let prefix = format!("problem {} (line {})", raw.index, raw.line);
+        match verdict {
+            Ok(params) if options.dry_run => {
+                let estimate = params.estimate();
+                writeln!(
+                    out,
+                    "{prefix}: type={} nodes={} arcs={} peak_memory={} dimacs_bytes={}",
+                    params.problem_type(),
+                    params.nodes,
+                    estimate.arcs,
+                    estimate.peak_memory_bytes,
+                    estimate.dimacs_bytes
+                )?;
+                warn(&prefix, &params, options);
+            }
+            Ok(params) => {
+                writeln!(out, "{prefix}: OK")?;
+                warn(&prefix, &params, options);
+            }
+            Err(e) => {
+                all_ok = false;
+                writeln!(out, "{prefix}: {e}")?;
+                if raw.parms.is_err() {
+                    break;
+                }
+            }
+        }
     }
+    Ok(all_ok)
 }
@@ -0,0 +1,145 @@
+//! Provenance metadata for generated instances, written as a JSON sidecar.
+
+use std::io::{self, Write};
+
+use crate::{NetgenParams, NetgenResult, PARAM_NAMES, ProblemType};
+
+/// Everything needed to reproduce a generated instance and check it: the
+/// crate version, seed, problem number and parameters, plus the resulting
+/// counts and SHA-256 fingerprint.
+///
+/// Written as a single line of JSON by [`write_manifest`]; with the `serde`
+/// feature it also implements `Serialize` and `Deserialize` with the same
+/// field names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceManifest {
+    /// Version of netgen_rs that generated the instance.
+    pub crate_version: String,
+    pub seed: i64,
+    pub problem: i64,
+    pub params: NetgenParams,
+    pub problem_type: ProblemType,
+    pub nodes: usize,
+    pub arcs: usize,
+    /// Lowercase hex [`fingerprint`](NetgenResult::fingerprint) of the
+    /// DIMACS output; `None` without the `sha2` feature.
+    pub fingerprint: Option<String>,
+}
+
+impl InstanceManifest {
+    /// Describe the instance `result` generated from `seed` and `params`.
+    pub fn from_run(seed: i64, problem: i64, params: &NetgenParams, result: &NetgenResult) -> Self {
+        #[cfg(feature = "sha2")]
+        let fingerprint = Some(crate::to_hex(&result.fingerprint(seed, problem, params)));
+        #[cfg(not(feature = "sha2"))]
+        let fingerprint = None;
+        InstanceManifest {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+            problem,
+            params: params.clone(),
+            problem_type: params.problem_type(),
+            nodes: result.supply.len(),
+            arcs: result.arcs.len(),
+            fingerprint,
+        }
+    }
+}
+
+/// Write `manifest` as one line of JSON, followed by a newline.
+///
+/// ```rust
+/// use netgen_rs::{InstanceManifest, NetgenParams, generate, write_manifest};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let manifest = InstanceManifest::from_run(13502460, 1, &params, &result);
+/// let mut out = Vec::new();
+/// write_manifest(&mut out, &manifest).unwrap();
+/// let json = String::from_utf8(out).unwrap();
+/// assert!(json.contains(r#""problem_type":"min-cost-flow","nodes":512,"arcs":2000"#));
+/// ```
+pub fn write_manifest(w: &mut impl Write, manifest: &InstanceManifest) -> io::Result<()> {
+    write!(w, "{{\"crate_version\":")?;
+    write_string(w, &manifest.crate_version)?;
+    write!(
+        w,
+        ",\"seed\":{},\"problem\":{},\"params\":{{",
+        manifest.seed, manifest.problem
+    )?;
+    for (i, (name, value)) in PARAM_NAMES
+        .iter()
+        .zip(manifest.params.to_array())
+        .enumerate()
+    {
+        let sep = if i == 0 { "" } else { "," };
+        write!(w, "{sep}\"{name}\":{value}")?;
+    }
+    write!(
+        w,
+        "}},\"problem_type\":\"{}\",\"nodes\":{},\"arcs\":{},\"fingerprint\":",
+        manifest.problem_type, manifest.nodes, manifest.arcs
+    )?;
+    match &manifest.fingerprint {
+        Some(fingerprint) => write_string(w, fingerprint)?,
+        None => write!(w, "null")?,
+    }
+    writeln!(w, "}}")
+}
+
+/// Write `s` as a quoted JSON string.
+fn write_string(w: &mut impl Write, s: &str) -> io::Result<()> {
+    write!(w, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(w, "\\\"")?,
+            '\\' => write!(w, "\\\\")?,
+            c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+            c => write!(w, "{c}")?,
+        }
+    }
+    write!(w, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::doc_params;
+    use crate::generate;
+
+    #[test]
+    fn writes_one_line_of_json() {
+        let params =
+            NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap();
+        let result = generate(12345, &params).unwrap();
+        let mut manifest = InstanceManifest::from_run(12345, 7, &params, &result);
+        manifest.crate_version = "1.2.3-\"x\"\n".to_string();
+        manifest.fingerprint = None;
+        let mut out = Vec::new();
+        write_manifest(&mut out, &manifest).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"crate_version\":\"1.2.3-\\\"x\\\"\\u000a\",\"seed\":12345,\"problem\":7,\
+             \"params\":{\"nodes\":100,\"sources\":50,\"sinks\":50,\"density\":500,\
+             \"mincost\":1,\"maxcost\":100,\"supply\":50,\"tsources\":0,\"tsinks\":0,\
+             \"hicost_pct\":0,\"capacitated_pct\":0,\"mincap\":1,\"maxcap\":100},\
+             \"problem_type\":\"assignment\",\"nodes\":100,\"arcs\":500,\"fingerprint\":null}\n"
+        );
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn fingerprint_matches_the_result() {
+        let params = doc_params();
+        let result = generate(13502460, &params).unwrap();
+        let manifest = InstanceManifest::from_run(13502460, 1, &params, &result);
+        assert_eq!(manifest.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            manifest.fingerprint.as_deref(),
+            Some("c7ca560e963827076225def4b5b8b7326feff3816a33d156791e69c73e5a24c1")
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Matrix Market export for MATLAB, Julia and SciPy.
+
+use std::io::{self, Write};
+
+use crate::{NetgenParams, NetgenResult};
+
+/// Which matrix [`write_matrix_market`] writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixMarketKind {
+    /// The `nodes × arcs` node–arc incidence matrix: column `k` holds +1 at
+    /// the tail and −1 at the head of arc `k`.
+    Incidence,
+    /// The `nodes × nodes` adjacency matrix with arc costs as values.
+    AdjacencyCost,
+    /// The `nodes × nodes` adjacency matrix with arc capacities as values.
+    AdjacencyCapacity,
+}
+
+/// Write one matrix of a generated network as a Matrix Market
+/// `coordinate integer general` file.
+///
+/// Indices are 1-based, so NETGEN node ids are row (and adjacency column)
+/// numbers directly, and arc `k` of [`NetgenResult::arcs`] is incidence
+/// column `k + 1`. Entries follow arc order. Parallel arcs appear as
+/// repeated coordinates in the adjacency matrices; readers that build a
+/// sparse matrix from them (e.g. MATLAB's `sparse`) sum the duplicates.
+///
+/// ```rust
+/// use netgen_rs::{MatrixMarketKind, NetgenParams, generate, write_matrix_market};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut mtx = Vec::new();
+/// write_matrix_market(&mut mtx, 13502460, &params, &result, MatrixMarketKind::Incidence)
+///     .unwrap();
+/// ```
+pub fn write_matrix_market(
+    w: &mut impl Write,
+    seed: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    kind: MatrixMarketKind,
+) -> io::Result<()> {
+    let nodes = result.supply.len();
+    let arcs = result.arcs.len();
+    writeln!(w, "%%MatrixMarket matrix coordinate integer general")?;
+    let what = match kind {
+        MatrixMarketKind::Incidence => "node-arc incidence",
+        MatrixMarketKind::AdjacencyCost => "adjacency, arc costs",
+        MatrixMarketKind::AdjacencyCapacity => "adjacency, arc capacities",
+    };
+    writeln!(w, "% NETGEN {} ({what})", params.problem_type())?;
+    writeln!(w, "% seed {seed}")?;
+    writeln!(w, "% parameters {params}")?;
+
+    match kind {
+        MatrixMarketKind::Incidence => {
+            writeln!(w, "{nodes} {arcs} {}", 2 * arcs)?;
+            for (k, arc) in result.arcs.iter().enumerate() {
+                writeln!(w, "{} {} 1", arc.from, k + 1)?;
+                writeln!(w, "{} {} -1", arc.to, k + 1)?;
+            }
+        }
+        MatrixMarketKind::AdjacencyCost | MatrixMarketKind::AdjacencyCapacity => {
+            writeln!(w, "{nodes} {nodes} {arcs}")?;
+            for arc in &result.arcs {
+                let value = match kind {
+                    MatrixMarketKind::AdjacencyCost => arc.cost,
+                    _ => arc.capacity,
+                };
+                writeln!(w, "{} {} {value}", arc.from, arc.to)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::doc_params;
+    use crate::generate;
+
+    fn mtx(params: &NetgenParams, kind: MatrixMarketKind) -> (NetgenResult, String) {
+        let result = generate(13502460, params).unwrap();
+        let mut out = Vec::new();
+        write_matrix_market(&mut out, 13502460, params, &result, kind).unwrap();
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    /// The size line and the entries, skipping the banner and comments.
+    fn body(mtx: &str) -> (Vec<i64>, Vec<Vec<i64>>) {
+        let mut lines = mtx
+            .lines()
+            .filter(|l| !l.starts_with('%'))
+            .map(|l| l.split(' ').map(|x| x.parse().unwrap()).collect());
+        (lines.next().unwrap(), lines.collect())
+    }
+
+    #[test]
+    fn incidence_has_two_entries_per_arc() {
+        let params = doc_params();
+        let (result, mtx) = mtx(&params, MatrixMarketKind::Incidence);
+        assert!(mtx.starts_with("%%MatrixMarket matrix coordinate integer general\n"));
+        assert!(mtx.contains(
+            "% seed 13502460\n% parameters 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n"
+        ));
+        let (size, entries) = body(&mtx);
+        let arcs = result.arcs.len() as i64;
+        assert_eq!(size, [512, arcs, 2 * arcs]);
+        assert_eq!(entries.len() as i64, 2 * arcs);
+    }
+
+    #[test]
+    fn small_instance_triplets() {
+        let params = NetgenParams::from_slice(&[8, 2, 2, 10, 1, 9, 4, 0, 0, 0, 100, 1, 5]).unwrap();
+        let (result, cost) = mtx(&params, MatrixMarketKind::AdjacencyCost);
+        let (size, entries) = body(&cost);
+        assert_eq!(size, [8, 8, 10]);
+        // `netgen_rs 13502460 1 8 2 2 10 1 9 4 0 0 0 100 1 5`, `a` lines.
+        let expected = [
+            [1, 6, 6],
+            [4, 7, 8],
+            [6, 4, 9],
+            [6, 8, 4],
+            [2, 5, 8],
+            [3, 7, 1],
+            [3, 6, 1],
+            [5, 3, 1],
+            [5, 8, 1],
+            [5, 4, 2],
+        ];
+        assert_eq!(entries, expected);
+
+        let (_, capacity) = mtx(&params, MatrixMarketKind::AdjacencyCapacity);
+        let (_, entries) = body(&capacity);
+        assert!(
+            entries
+                .iter()
+                .zip(&result.arcs)
+                .all(|(e, a)| e[2] == a.capacity)
+        );
+
+        let (_, incidence) = mtx(&params, MatrixMarketKind::Incidence);
+        let (_, entries) = body(&incidence);
+        let arc = &result.arcs[3];
+        assert_eq!(entries[6], [arc.from as i64, 4, 1]);
+        assert_eq!(entries[7], [arc.to as i64, 4, -1]);
+    }
+}
@@ -0,0 +1,202 @@
+//! Collapsing parallel arcs, for solvers that reject multigraphs.
+
+use std::collections::HashMap;
+
+use crate::NetgenResult;
+
+/// How [`NetgenResult::merge_parallel_arcs`] replaces a group of arcs with
+/// the same endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep only the cheapest arc of the group (the first among equals).
+    /// Every solution of the merged network is one of the original, but the
+    /// dropped capacity may make it infeasible or costlier.
+    KeepCheapestOnly,
+    /// One arc with the summed capacity at the minimum cost. This keeps the
+    /// network feasible but relaxes it: flow beyond the cheapest arc's
+    /// capacity becomes cheaper, so the optimal objective can drop. Only
+    /// splitting the capacity into cost tiers preserves it exactly. The
+    /// merged arc is uncapacitated if any arc of the group was.
+    SumCapMinCost,
+}
+
+/// What [`NetgenResult::merge_parallel_arcs_with_report`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Groups of two or more parallel arcs.
+    pub groups: usize,
+    /// Arcs removed, the group sizes minus one each.
+    pub merged: usize,
+    /// Groups whose summed capacity overflowed and was clamped to
+    /// `i64::MAX`.
+    pub saturated: usize,
+}
+
+impl NetgenResult {
+    /// A copy with at most one arc per `(from, to)` pair.
+    ///
+    /// See [`merge_parallel_arcs_with_report`](Self::merge_parallel_arcs_with_report).
+    pub fn merge_parallel_arcs(&self, policy: MergePolicy) -> NetgenResult {
+        self.merge_parallel_arcs_with_report(policy).0
+    }
+
+    /// A copy with at most one arc per `(from, to)` pair, and a report of
+    /// how many arcs were merged.
+    ///
+    /// Each group is replaced according to `policy` at the position of its
+    /// first arc; other arcs are untouched. The merged arc keeps the
+    /// multiplier and provenance ([`Arc::kind`](crate::Arc::kind),
+    /// [`Arc::hicost`](crate::Arc::hicost)) of the cheapest arc.
+    ///
+    /// ```rust
+    /// use netgen_rs::{MergePolicy, parse_dimacs};
+    ///
+    /// let result = parse_dimacs("p min 2 3\na 1 2 0 5 3\na 1 2 0 4 2\na 2 1 0 1 1\n").unwrap();
+    /// let (merged, report) = result.merge_parallel_arcs_with_report(MergePolicy::SumCapMinCost);
+    /// assert_eq!(merged.arcs.len(), 2);
+    /// assert_eq!((merged.arcs[0].capacity, merged.arcs[0].cost), (9, 2));
+    /// assert_eq!(report.merged, 1);
+    /// ```
+    pub fn merge_parallel_arcs_with_report(
+        &self,
+        policy: MergePolicy,
+    ) -> (NetgenResult, MergeReport) {
+        let mut report = MergeReport::default();
+        let mut arcs = Vec::with_capacity(self.arcs.len());
+        // Per group: position in `arcs`, exact capacity sum, size.
+        let mut groups: HashMap<(u64, u64), (usize, i128, usize)> = HashMap::new();
+        for arc in &self.arcs {
+            match groups.get_mut(&(arc.from, arc.to)) {
+                None => {
+                    groups.insert((arc.from, arc.to), (arcs.len(), arc.capacity as i128, 1));
+                    arcs.push(arc.clone());
+                }
+                Some((k, total, size)) => {
+                    *total += arc.capacity as i128;
+                    *size += 1;
+                    let kept = &mut arcs[*k];
+                    let uncapacitated = kept.uncapacitated || arc.uncapacitated;
+                    if arc.cost < kept.cost {
+                        let capacity = kept.capacity;
+                        *kept = arc.clone();
+                        if policy == MergePolicy::SumCapMinCost {
+                            kept.capacity = capacity;
+                        }
+                    }
+                    if policy == MergePolicy::SumCapMinCost {
+                        kept.capacity = i64::try_from(*total).unwrap_or(i64::MAX);
+                        kept.uncapacitated = uncapacitated;
+                    }
+                }
+            }
+        }
+        for &(_, total, size) in groups.values() {
+            if size > 1 {
+                report.groups += 1;
+                report.merged += size - 1;
+                if policy == MergePolicy::SumCapMinCost && total > i64::MAX as i128 {
+                    report.saturated += 1;
+                }
+            }
+        }
+        let merged = NetgenResult {
+            arcs,
+            supply: self.supply.clone(),
+        };
+        (merged, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Arc, ArcKind, NetgenParams, generate, parse_dimacs};
+
+    fn arc(from: u64, to: u64, cost: i64, capacity: i64) -> Arc {
+        Arc {
+            from,
+            to,
+            cost,
+            capacity,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Unknown,
+            hicost: false,
+        }
+    }
+
+    #[test]
+    fn groups_collapse_to_one_arc() {
+        let result = NetgenResult {
+            arcs: vec![
+                arc(1, 2, 5, 10),
+                arc(2, 3, 1, 7),
+                arc(1, 2, 3, 4),
+                arc(3, 1, 2, 2),
+                arc(1, 2, 3, 6),
+            ],
+            supply: vec![1, 0, -1],
+        };
+        let (sum, report) = result.merge_parallel_arcs_with_report(MergePolicy::SumCapMinCost);
+        assert_eq!(
+            sum.arcs,
+            [arc(1, 2, 3, 20), arc(2, 3, 1, 7), arc(3, 1, 2, 2)]
+        );
+        assert_eq!(
+            report,
+            MergeReport {
+                groups: 1,
+                merged: 2,
+                saturated: 0
+            }
+        );
+        let cheapest = result.merge_parallel_arcs(MergePolicy::KeepCheapestOnly);
+        assert_eq!(
+            cheapest.arcs,
+            [arc(1, 2, 3, 4), arc(2, 3, 1, 7), arc(3, 1, 2, 2)]
+        );
+        assert_eq!(cheapest.supply, result.supply);
+    }
+
+    #[test]
+    fn capacity_sums_saturate() {
+        let result = NetgenResult {
+            arcs: vec![arc(1, 2, 1, i64::MAX - 1), arc(1, 2, 1, 5), arc(1, 2, 1, 1)],
+            supply: vec![0, 0],
+        };
+        let (merged, report) = result.merge_parallel_arcs_with_report(MergePolicy::SumCapMinCost);
+        assert_eq!(merged.arcs, [arc(1, 2, 1, i64::MAX)]);
+        assert_eq!(report.saturated, 1);
+    }
+
+    #[test]
+    fn generated_networks_round_trip() {
+        let params =
+            NetgenParams::from_slice(&[50, 10, 10, 1500, 1, 100, 1000, 5, 5, 0, 100, 1, 50])
+                .unwrap();
+        let result = generate(13502460, &params).unwrap();
+        // This instance has no parallel arcs, so nothing changes.
+        let (merged, report) = result.merge_parallel_arcs_with_report(MergePolicy::SumCapMinCost);
+        assert_eq!(merged, result);
+        assert_eq!(report, MergeReport::default());
+
+        // Loaded back with every arc doubled, each pair collapses again.
+        let doubled = parse_dimacs(&result.to_dimacs_string(13502460, 1, &params)).unwrap();
+        let doubled = NetgenResult {
+            arcs: [&doubled.arcs[..], &doubled.arcs[..]].concat(),
+            ..doubled
+        };
+        let (merged, report) = doubled.merge_parallel_arcs_with_report(MergePolicy::SumCapMinCost);
+        assert_eq!(report.merged, result.arcs.len());
+        for (a, b) in merged.arcs.iter().zip(&result.arcs) {
+            assert_eq!((a.from, a.to, a.cost), (b.from, b.to, b.cost));
+            assert_eq!(a.capacity, 2 * b.capacity);
+        }
+        assert_eq!(
+            doubled
+                .merge_parallel_arcs(MergePolicy::KeepCheapestOnly)
+                .canonicalize(),
+            result.canonicalize()
+        );
+    }
+}
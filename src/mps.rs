@@ -0,0 +1,242 @@
+//! Fixed-format MPS export.
+//!
+//! Fixed MPS limits names to 8 characters, so nodes and arcs get compact
+//! base-36 names: node `v` (1-based) is row `N{v}` and arc `k` (0-based, in
+//! [`NetgenResult::arcs`] order) is column `X{k + 1}`, with the numbers in
+//! base 36. [`MpsNames`] maps them back.
+
+use std::io::{self, Write};
+
+use crate::{LpOptions, NetgenParams, NetgenResult, ProblemType};
+
+/// Row and column names used by [`write_mps`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MpsNames {
+    /// Row name of node `v`, at index `v - 1`.
+    pub rows: Vec<String>,
+    /// Column name of arc `k`, at index `k`.
+    pub columns: Vec<String>,
+}
+
+impl MpsNames {
+    pub fn new(result: &NetgenResult) -> Self {
+        MpsNames {
+            rows: (1..=result.supply.len())
+                .map(|v| format!("N{}", base36(v as u64)))
+                .collect(),
+            columns: (1..=result.arcs.len())
+                .map(|k| format!("X{}", base36(k as u64)))
+                .collect(),
+        }
+    }
+}
+
+/// Write the min-cost flow LP of a generated network in fixed-format MPS.
+///
+/// Sections are NAME, ROWS (the objective row `OBJ`, then one `E` row per
+/// node), COLUMNS (each arc as three entries: `OBJ` with its cost, +1 in
+/// its tail row and −1 in its head row), RHS (the nonzero supplies, so that
+/// `out(v) - in(v) = supply_v`), BOUNDS (an `UP` bound per arc) and ENDATA.
+/// Arc capacities are the upper bounds, except for assignment problems,
+/// whose arcs are bounded by 1. Every problem type, maximum flow included,
+/// is written as this min-cost flow LP; use [`write_lp`](crate::write_lp)
+/// for a max-flow objective.
+///
+/// Returns the names used, for mapping a solution back to arcs.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate, write_mps};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let mut mps = Vec::new();
+/// let names = write_mps(&mut mps, &params, &result).unwrap();
+/// assert_eq!(names.columns[35], "X10");
+/// ```
+pub fn write_mps(
+    w: &mut impl Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> io::Result<MpsNames> {
+    write_mps_with_options(w, params, result, &LpOptions::default())
+}
+
+/// Write the min-cost flow LP of a generated network in fixed-format MPS
+/// with [`LpOptions`].
+///
+/// With [`UncapacitatedAs::Free`](crate::UncapacitatedAs::Free), arcs
+/// flagged [`uncapacitated`](crate::Arc::uncapacitated) get a `PL` bound
+/// (no upper bound) instead of `UP`. See [`write_mps`].
+pub fn write_mps_with_options(
+    w: &mut impl Write,
+    params: &NetgenParams,
+    result: &NetgenResult,
+    options: &LpOptions,
+) -> io::Result<MpsNames> {
+    let names = MpsNames::new(result);
+    writeln!(w, "NAME          NETGEN")?;
+
+    writeln!(w, "ROWS")?;
+    writeln!(w, " N  OBJ")?;
+    for row in &names.rows {
+        writeln!(w, " E  {row}")?;
+    }
+
+    writeln!(w, "COLUMNS")?;
+    for (arc, column) in result.arcs.iter().zip(&names.columns) {
+        let tail = &names.rows[arc.from as usize - 1];
+        let head = &names.rows[arc.to as usize - 1];
+        writeln!(w, "    {column:<8}  {:<8}  {:>12}", "OBJ", arc.cost)?;
+        writeln!(w, "    {column:<8}  {tail:<8}  {:>12}", 1)?;
+        writeln!(w, "    {column:<8}  {head:<8}  {:>12}", -1)?;
+    }
+
+    writeln!(w, "RHS")?;
+    for (row, &supply) in names.rows.iter().zip(&result.supply) {
+        if supply != 0 {
+            writeln!(w, "    {:<8}  {row:<8}  {supply:>12}", "RHS")?;
+        }
+    }
+
+    writeln!(w, "BOUNDS")?;
+    let assignment = params.problem_type() == ProblemType::Assignment;
+    for (arc, column) in result.arcs.iter().zip(&names.columns) {
+        let upper = if assignment {
+            Some(1)
+        } else {
+            options.uncapacitated_as.bound(arc)
+        };
+        match upper {
+            Some(upper) => writeln!(w, " UP {:<8}  {column:<8}  {upper:>12}", "BND")?,
+            None => writeln!(w, " PL {:<8}  {column}", "BND")?,
+        }
+    }
+    writeln!(w, "ENDATA")?;
+    Ok(names)
+}
+
+/// `n` in base 36 with digits `0-9A-Z`.
+fn base36(mut n: u64) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(n % 36) as usize]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::doc_params;
+    use crate::{UncapacitatedAs, generate};
+
+    #[test]
+    fn base36_names() {
+        assert_eq!(base36(0), "0");
+        assert_eq!(base36(35), "Z");
+        assert_eq!(base36(36), "10");
+        // Seven digits, plus the prefix, fit any arc count below u32::MAX.
+        assert_eq!(base36(u32::MAX as u64).len(), 7);
+    }
+
+    #[test]
+    fn mps_structure() {
+        let params = doc_params();
+        let result = generate(13502460, &params).unwrap();
+        let mut out = Vec::new();
+        let names = write_mps(&mut out, &params, &result).unwrap();
+        let mps = String::from_utf8(out).unwrap();
+
+        let headings: Vec<_> = mps.lines().filter(|l| !l.starts_with(' ')).collect();
+        assert_eq!(
+            headings,
+            [
+                "NAME          NETGEN",
+                "ROWS",
+                "COLUMNS",
+                "RHS",
+                "BOUNDS",
+                "ENDATA"
+            ]
+        );
+        let section = |name: &str| -> Vec<Vec<&str>> {
+            mps.lines()
+                .skip_while(|l| *l != name)
+                .skip(1)
+                .take_while(|l| l.starts_with(' '))
+                .map(|l| l.split_whitespace().collect())
+                .collect()
+        };
+        assert!(mps.lines().all(|l| l.len() <= 36));
+
+        let rows = section("ROWS");
+        assert_eq!(rows.len(), 513);
+        assert_eq!(rows[0], ["N", "OBJ"]);
+
+        let columns = section("COLUMNS");
+        assert_eq!(columns.len(), 3 * result.arcs.len());
+        for (entries, (arc, name)) in columns
+            .chunks(3)
+            .zip(result.arcs.iter().zip(&names.columns))
+        {
+            assert!(entries.iter().all(|e| e[0] == name));
+            assert_eq!(entries[0][1..], ["OBJ", &arc.cost.to_string()]);
+            assert_eq!(entries[1][1], format!("N{}", base36(arc.from)));
+            assert_eq!(entries[2][1], format!("N{}", base36(arc.to)));
+            assert_eq!((entries[1][2], entries[2][2]), ("1", "-1"));
+        }
+
+        let mut rhs = vec![0i64; result.supply.len()];
+        for entry in section("RHS") {
+            let v = names.rows.iter().position(|r| r == entry[1]).unwrap();
+            rhs[v] = entry[2].parse().unwrap();
+        }
+        assert_eq!(rhs, result.supply);
+
+        let bounds = section("BOUNDS");
+        assert_eq!(bounds.len(), result.arcs.len());
+        assert_eq!(
+            bounds[7],
+            ["UP", "BND", "X8", &result.arcs[7].capacity.to_string()]
+        );
+    }
+    #[test]
+    fn free_mode_writes_pl_bounds() {
+        let params = doc_params();
+        let result = generate(13502460, &params).unwrap();
+        let options = LpOptions {
+            uncapacitated_as: UncapacitatedAs::Free,
+        };
+        let mut out = Vec::new();
+        let names = write_mps_with_options(&mut out, &params, &result, &options).unwrap();
+        let mps = String::from_utf8(out).unwrap();
+        let bounds: Vec<Vec<&str>> = mps
+            .lines()
+            .skip_while(|l| *l != "BOUNDS")
+            .skip(1)
+            .take_while(|l| l.starts_with(' '))
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+        assert_eq!(bounds.len(), result.arcs.len());
+        assert_eq!(
+            bounds.iter().filter(|b| b[0] == "PL").count(),
+            result.uncapacitated_arc_count()
+        );
+        for ((arc, column), bound) in result.arcs.iter().zip(&names.columns).zip(&bounds) {
+            let capacity = arc.capacity.to_string();
+            let expected = match arc.uncapacitated {
+                true => vec!["PL", "BND", column],
+                false => vec!["UP", "BND", column, &capacity],
+            };
+            assert_eq!(*bound, expected);
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! Seeded perturbations of generated networks, for testing how solvers
+//! cope with instances NETGEN would not produce.
+
+use crate::seed::SplitMix;
+use crate::{Arc, ArcKind, NetgenResult, NodeId, verify_flow};
+
+/// One perturbation applied by [`NetgenResult::apply_mutations`].
+///
+/// Percentages are of the arcs the mutation may touch, rounded down, and
+/// are capped at 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation {
+    /// Negate the cost of `percent`% of the arcs.
+    FlipCostSigns { percent: u8 },
+    /// Multiply the capacity of `percent`% of the arcs by `num / den`,
+    /// rounding toward zero and saturating at the `i64` range. `den` must
+    /// be positive. The [`uncapacitated`](Arc::uncapacitated) flag is left
+    /// as it was.
+    ScaleCapacities { percent: u8, num: i64, den: i64 },
+    /// Delete `percent`% of the random arcs ([`ArcKind::Random`]). Skeleton
+    /// arcs and arcs of unknown provenance are kept.
+    DeleteRandomArcs { percent: u8 },
+    /// Swap the supplies of two distinct sources (nodes with positive
+    /// supply). Does nothing with fewer than two sources.
+    SwapSourceSupplies,
+}
+
+/// One entity changed by a [`Mutation`], with its value before the change.
+///
+/// Arc indices are into [`NetgenResult::arcs`] as it was just before the
+/// mutation, so they account for arcs deleted by earlier ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The cost of arc `arc` was negated from `old`.
+    Cost { arc: usize, old: i64 },
+    /// The capacity of arc `arc` went from `old` to `new`.
+    Capacity { arc: usize, old: i64, new: i64 },
+    /// Arc `arc`, which was `old`, was deleted. Deletions are listed in
+    /// ascending order of `arc`.
+    Deleted { arc: usize, old: Arc },
+    /// The supplies of two nodes were swapped; `old` holds them before.
+    Supplies {
+        nodes: (NodeId, NodeId),
+        old: (i64, i64),
+    },
+}
+
+/// A [`Mutation`] as it was applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMutation {
+    pub mutation: Mutation,
+    /// Every entity the mutation changed, in the order it was changed.
+    pub changes: Vec<Change>,
+    /// Whether the network could still route all its supplies afterwards.
+    /// Shrinking capacities and swapping supplies can break feasibility;
+    /// the check is exact (a maximum flow).
+    pub feasible: bool,
+}
+
+/// What [`NetgenResult::apply_mutations`] did, enough to repeat it or undo
+/// it with [`revert`](Self::revert).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MutationLog {
+    /// The seed the mutations drew from.
+    pub seed: u64,
+    /// One entry per mutation, in the order applied.
+    pub applied: Vec<AppliedMutation>,
+}
+
+impl MutationLog {
+    /// Whether the mutated network can still route all its supplies.
+    pub fn feasible(&self) -> bool {
+        self.applied.last().is_none_or(|m| m.feasible)
+    }
+
+    /// Undo the logged mutations on `mutated`, the network
+    /// [`apply_mutations`](NetgenResult::apply_mutations) returned with this
+    /// log, recovering the original network.
+    ///
+    /// # Panics
+    ///
+    /// If `mutated` is too small for the logged changes.
+    pub fn revert(&self, mutated: &NetgenResult) -> NetgenResult {
+        let mut result = mutated.clone();
+        for applied in self.applied.iter().rev() {
+            for change in &applied.changes {
+                match change {
+                    Change::Cost { arc, old } => result.arcs[*arc].cost = *old,
+                    Change::Capacity { arc, old, .. } => result.arcs[*arc].capacity = *old,
+                    Change::Deleted { arc, old } => result.arcs.insert(*arc, old.clone()),
+                    Change::Supplies { nodes, old } => {
+                        result.supply[nodes.0.index()] = old.0;
+                        result.supply[nodes.1.index()] = old.1;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+impl NetgenResult {
+    /// A copy with `mutations` applied in order, drawing from a splitmix64
+    /// stream started at `rng_seed`, and a log of every change.
+    ///
+    /// The same network, mutations and seed always give the same result,
+    /// and [`MutationLog::revert`] undoes it.
+    ///
+    /// # Panics
+    ///
+    /// If a [`Mutation::ScaleCapacities`] has `den <= 0`.
+    ///
+    /// ```rust
+    /// use netgen_rs::{Mutation, NetgenParams};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = params.generate(13502460).unwrap();
+    /// let (mutated, log) = result.apply_mutations(
+    ///     &[
+    ///         Mutation::DeleteRandomArcs { percent: 10 },
+    ///         Mutation::FlipCostSigns { percent: 5 },
+    ///     ],
+    ///     7,
+    /// );
+    /// assert!(mutated.arcs.len() < result.arcs.len());
+    /// assert!(log.feasible()); // the skeleton is untouched
+    /// assert_eq!(log.revert(&mutated), result);
+    /// ```
+    pub fn apply_mutations(
+        &self,
+        mutations: &[Mutation],
+        rng_seed: u64,
+    ) -> (NetgenResult, MutationLog) {
+        let mut result = self.clone();
+        let mut rng = SplitMix::new(rng_seed);
+        let mut applied = Vec::with_capacity(mutations.len());
+        for &mutation in mutations {
+            let changes = result.mutate(mutation, &mut rng);
+            let feasible = verify_flow(&result, &result.skeleton_flow()).is_ok();
+            applied.push(AppliedMutation {
+                mutation,
+                changes,
+                feasible,
+            });
+        }
+        let log = MutationLog {
+            seed: rng_seed,
+            applied,
+        };
+        (result, log)
+    }
+
+    fn mutate(&mut self, mutation: Mutation, rng: &mut SplitMix) -> Vec<Change> {
+        match mutation {
+            Mutation::FlipCostSigns { percent } => {
+                let all: Vec<usize> = (0..self.arcs.len()).collect();
+                pick(all, percent, rng)
+                    .into_iter()
+                    .map(|arc| {
+                        let old = self.arcs[arc].cost;
+                        self.arcs[arc].cost = old.saturating_neg();
+                        Change::Cost { arc, old }
+                    })
+                    .collect()
+            }
+            Mutation::ScaleCapacities { percent, num, den } => {
+                assert!(den > 0, "capacity scale denominator {den} must be positive");
+                let all: Vec<usize> = (0..self.arcs.len()).collect();
+                pick(all, percent, rng)
+                    .into_iter()
+                    .map(|arc| {
+                        let old = self.arcs[arc].capacity;
+                        let scaled = i128::from(old) * i128::from(num) / i128::from(den);
+                        let new = scaled.clamp(i64::MIN.into(), i64::MAX.into()) as i64;
+                        self.arcs[arc].capacity = new;
+                        Change::Capacity { arc, old, new }
+                    })
+                    .collect()
+            }
+            Mutation::DeleteRandomArcs { percent } => {
+                let random = (0..self.arcs.len())
+                    .filter(|&k| self.arcs[k].kind == ArcKind::Random)
+                    .collect();
+                let mut doomed = pick(random, percent, rng);
+                doomed.sort_unstable();
+                // Delete from the back so the logged indices stay valid.
+                let mut changes: Vec<Change> = doomed
+                    .iter()
+                    .rev()
+                    .map(|&arc| Change::Deleted {
+                        arc,
+                        old: self.arcs.remove(arc),
+                    })
+                    .collect();
+                changes.reverse();
+                changes
+            }
+            Mutation::SwapSourceSupplies => {
+                let sources: Vec<usize> = (0..self.supply.len())
+                    .filter(|&v| self.supply[v] > 0)
+                    .collect();
+                if sources.len() < 2 {
+                    return Vec::new();
+                }
+                let a = rng.below(sources.len());
+                let b = (a + 1 + rng.below(sources.len() - 1)) % sources.len();
+                let (a, b) = (sources[a], sources[b]);
+                let old = (self.supply[a], self.supply[b]);
+                self.supply.swap(a, b);
+                vec![Change::Supplies {
+                    nodes: (NodeId(a as u32), NodeId(b as u32)),
+                    old,
+                }]
+            }
+        }
+    }
+}
+
+/// `percent`% of `candidates`, rounded down, chosen by a partial
+/// Fisher–Yates shuffle.
+fn pick(mut candidates: Vec<usize>, percent: u8, rng: &mut SplitMix) -> Vec<usize> {
+    let count = candidates.len() * usize::from(percent.min(100)) / 100;
+    for i in 0..count {
+        let j = i + rng.below(candidates.len() - i);
+        candidates.swap(i, j);
+    }
+    candidates.truncate(count);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, generate};
+
+    fn result() -> NetgenResult {
+        let params =
+            NetgenParams::from_slice(&[100, 5, 5, 600, 1, 100, 500, 2, 2, 20, 80, 10, 200])
+                .unwrap();
+        generate(13502460, &params).unwrap()
+    }
+
+    fn apply(mutation: Mutation) -> (NetgenResult, NetgenResult, AppliedMutation) {
+        let original = result();
+        let (mutated, log) = original.apply_mutations(&[mutation], 42);
+        assert_eq!(log.seed, 42);
+        assert_eq!(log.revert(&mutated), original);
+        let [applied] = <[_; 1]>::try_from(log.applied).unwrap();
+        (original, mutated, applied)
+    }
+
+    #[test]
+    fn flips_cost_signs() {
+        let (original, mutated, applied) = apply(Mutation::FlipCostSigns { percent: 25 });
+        assert_eq!(applied.changes.len(), original.arcs.len() / 4);
+        for change in &applied.changes {
+            let Change::Cost { arc, old } = *change else {
+                panic!("{change:?}");
+            };
+            assert_eq!(original.arcs[arc].cost, old);
+            assert_eq!(mutated.arcs[arc].cost, -old);
+        }
+        let changed = (original.arcs.iter().zip(&mutated.arcs))
+            .filter(|(a, b)| a != b)
+            .count();
+        assert_eq!(changed, applied.changes.len());
+        // Costs do not affect feasibility.
+        assert!(applied.feasible);
+    }
+
+    #[test]
+    fn scales_capacities() {
+        let mutation = Mutation::ScaleCapacities {
+            percent: 100,
+            num: 1,
+            den: 10,
+        };
+        let (original, mutated, applied) = apply(mutation);
+        assert_eq!(applied.changes.len(), original.arcs.len());
+        for change in &applied.changes {
+            let Change::Capacity { arc, old, new } = *change else {
+                panic!("{change:?}");
+            };
+            assert_eq!(original.arcs[arc].capacity, old);
+            assert_eq!(mutated.arcs[arc].capacity, new);
+            assert_eq!(new, old / 10);
+        }
+        // A tenth of every capacity cannot carry the supplies.
+        assert!(!applied.feasible);
+    }
+
+    #[test]
+    fn deletes_random_arcs_only() {
+        let (original, mutated, applied) = apply(Mutation::DeleteRandomArcs { percent: 50 });
+        let random = original
+            .arcs
+            .iter()
+            .filter(|a| a.kind == ArcKind::Random)
+            .count();
+        assert_eq!(applied.changes.len(), random / 2);
+        assert_eq!(mutated.arcs.len(), original.arcs.len() - random / 2);
+        let mut last = None;
+        for change in &applied.changes {
+            let Change::Deleted { arc, old } = change else {
+                panic!("{change:?}");
+            };
+            assert!(last < Some(*arc));
+            last = Some(*arc);
+            assert_eq!(&original.arcs[*arc], old);
+            assert_eq!(old.kind, ArcKind::Random);
+        }
+        assert!(applied.feasible);
+    }
+
+    #[test]
+    fn swaps_two_source_supplies() {
+        let (original, mutated, applied) = apply(Mutation::SwapSourceSupplies);
+        let [Change::Supplies { nodes, old }] = applied.changes[..] else {
+            panic!("{:?}", applied.changes);
+        };
+        assert_ne!(nodes.0, nodes.1);
+        assert_eq!(
+            old,
+            (original.supply_of(nodes.0), original.supply_of(nodes.1))
+        );
+        assert_eq!(
+            (mutated.supply_of(nodes.0), mutated.supply_of(nodes.1)),
+            (old.1, old.0)
+        );
+        let changed = (original.supply.iter().zip(&mutated.supply))
+            .filter(|(a, b)| a != b)
+            .count();
+        assert_eq!(changed, if old.0 == old.1 { 0 } else { 2 });
+    }
+
+    #[test]
+    fn sequences_are_reproducible_and_reversible() {
+        let original = result();
+        let mutations = [
+            Mutation::DeleteRandomArcs { percent: 30 },
+            Mutation::ScaleCapacities {
+                percent: 10,
+                num: 3,
+                den: 2,
+            },
+            Mutation::FlipCostSigns { percent: 10 },
+            Mutation::SwapSourceSupplies,
+        ];
+        let (mutated, log) = original.apply_mutations(&mutations, 9);
+        assert_eq!(
+            original.apply_mutations(&mutations, 9),
+            (mutated.clone(), log.clone())
+        );
+        assert_ne!(original.apply_mutations(&mutations, 10).0, mutated);
+        assert_eq!(log.applied.len(), 4);
+        assert_eq!(log.revert(&mutated), original);
+        // Later indices are into the network after the deletions.
+        for change in &log.applied[2].changes {
+            let Change::Cost { arc, .. } = *change else {
+                panic!("{change:?}");
+            };
+            assert!(arc < mutated.arcs.len());
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! The DIMACS-era naming convention for benchmark instances, as in
+//! `netgen-8-14a.min`: a family, the size exponent and a replicate letter,
+//! with the problem type as the extension.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{NetgenParams, ProblemType};
+
+/// A name split into its parts by [`parse_instance_name`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedName {
+    pub family: String,
+    /// Size exponent, `floor(log2(nodes))`.
+    pub exponent: u32,
+    /// Replicate, from the letters: 0 for `a`, 25 for `z`, 26 for `aa`.
+    pub seed_index: usize,
+    pub problem_type: ProblemType,
+}
+
+/// Displays as the canonical name, with the exponent padded to two digits.
+impl fmt::Display for ParsedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{:02}{}.{}",
+            self.family,
+            self.exponent,
+            replicate_letters(self.seed_index),
+            extension(self.problem_type)
+        )
+    }
+}
+
+/// The file extension for `problem_type`, its DIMACS problem-line token:
+/// `min`, `max` or `asn`.
+pub fn extension(problem_type: ProblemType) -> &'static str {
+    match problem_type {
+        ProblemType::Assignment => "asn",
+        ProblemType::MaxFlow => "max",
+        ProblemType::MinCostFlow => "min",
+    }
+}
+
+/// The name of replicate `seed_index` (0-based) of an instance of `family`
+/// with `params`: `<family>-<exponent><letters>.<extension>`.
+///
+/// The exponent is `floor(log2(params.nodes))`, padded to two digits. The
+/// letters count replicates like spreadsheet columns: `a` to `z`, then
+/// `aa`, `ab` and so on.
+///
+/// ```rust
+/// use netgen_rs::naming::instance_name;
+/// use netgen_rs::{NetgenParams, ProblemType};
+///
+/// let params = NetgenParams::with_average_degree(1 << 14, 8).unwrap();
+/// let name = instance_name("netgen-8", &params, 0, ProblemType::MinCostFlow);
+/// assert_eq!(name, "netgen-8-14a.min");
+/// ```
+pub fn instance_name(
+    family: &str,
+    params: &NetgenParams,
+    seed_index: usize,
+    problem_type: ProblemType,
+) -> String {
+    ParsedName {
+        family: family.to_string(),
+        exponent: params.nodes.max(1).ilog2(),
+        seed_index,
+        problem_type,
+    }
+    .to_string()
+}
+
+/// Split a name made by [`instance_name`] into its parts, or `None` if it
+/// does not follow the convention.
+///
+/// The family is everything before the last `-`, which must be followed by
+/// the exponent's digits, lowercase replicate letters, and a `.min`, `.max`
+/// or `.asn` extension.
+///
+/// ```rust
+/// use netgen_rs::ProblemType;
+/// use netgen_rs::naming::parse_instance_name;
+///
+/// let parsed = parse_instance_name("netgen-deg-03ab.asn").unwrap();
+/// assert_eq!(parsed.family, "netgen-deg");
+/// assert_eq!((parsed.exponent, parsed.seed_index), (3, 27));
+/// assert_eq!(parsed.problem_type, ProblemType::Assignment);
+/// assert_eq!(parse_instance_name("netgen-8-14.min"), None);
+/// ```
+pub fn parse_instance_name(name: &str) -> Option<ParsedName> {
+    let (stem, ext) = name.rsplit_once('.')?;
+    let problem_type = match ext {
+        "asn" => ProblemType::Assignment,
+        "max" => ProblemType::MaxFlow,
+        "min" => ProblemType::MinCostFlow,
+        _ => return None,
+    };
+    let (family, size) = stem.rsplit_once('-')?;
+    let digits = size.len() - size.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (exponent, letters) = size.split_at(digits);
+    if family.is_empty() || exponent.is_empty() || letters.is_empty() {
+        return None;
+    }
+    Some(ParsedName {
+        family: family.to_string(),
+        exponent: exponent.parse().ok()?,
+        seed_index: replicate_index(letters)?,
+        problem_type,
+    })
+}
+
+/// Bijective base-26 letters for replicate `index`: `a`, ..., `z`, `aa`, ...
+fn replicate_letters(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).expect("letters are ASCII")
+}
+
+/// Inverse of [`replicate_letters`].
+fn replicate_index(letters: &str) -> Option<usize> {
+    let mut n: usize = 0;
+    for c in letters.bytes() {
+        if !c.is_ascii_lowercase() {
+            return None;
+        }
+        n = n.checked_mul(26)?.checked_add((c - b'a') as usize + 1)?;
+    }
+    Some(n - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::DOC_PARMS;
+    use alloc::format;
+
+    #[test]
+    fn replicate_letters_round_trip() {
+        for (index, letters) in [(0, "a"), (4, "e"), (25, "z"), (26, "aa"), (701, "zz")] {
+            assert_eq!(replicate_letters(index), letters);
+            assert_eq!(replicate_index(letters), Some(index));
+        }
+        assert_eq!(replicate_letters(702), "aaa");
+        assert_eq!(replicate_index("A"), None);
+    }
+
+    #[test]
+    fn canonical_names_round_trip() {
+        for name in [
+            "netgen-8-14a.min",
+            "netgen-sr-09e.min",
+            "netgen-lo-8-22c.min",
+            "netgen-deg-12b.min",
+            "maxflow-16aa.max",
+            "asn-10z.asn",
+        ] {
+            let parsed = parse_instance_name(name).unwrap();
+            assert_eq!(parsed.to_string(), name);
+        }
+        for bad in [
+            "netgen-8-14a",
+            "netgen-8-14a.dimacs",
+            "netgen-8-a.min",
+            "netgen-8-14.min",
+            "-14a.min",
+            "netgen8.min",
+            "netgen-8-14A.min",
+            "netgen-8-14a1.min",
+        ] {
+            assert_eq!(parse_instance_name(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn extension_follows_the_problem_type() {
+        let cases = [
+            (DOC_PARMS, "min"),
+            ([200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100], "max"),
+            ([100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100], "asn"),
+        ];
+        for (parms, ext) in cases {
+            let params = NetgenParams::from_slice(&parms).unwrap();
+            let name = instance_name("fam", &params, 2, params.problem_type());
+            assert_eq!(name, format!("fam-{:02}c.{ext}", params.nodes.ilog2()));
+            let parsed = parse_instance_name(&name).unwrap();
+            assert_eq!(parsed.problem_type, params.problem_type());
+            assert_eq!(extension(parsed.problem_type), ext);
+        }
+    }
+}
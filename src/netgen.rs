@@ -1,43 +1,428 @@
 //! Core NETGEN network generator, faithfully ported from netgen.c.
+//!
+//! Generation runs in two phases. The first draws the source supplies and
+//! threads the transshipment nodes into one chain per source. The second
+//! walks the sources in order, choosing sinks, routing supply along the
+//! skeleton arcs and emitting rubbish arcs after each skeleton tail. The
+//! second phase can replay a previously recorded [`Skeleton`] while
+//! consuming the RNG exactly as a fresh run would.
+//!
+//! In [`GenerationMode::Fast`] the second phase only fixes each tail's
+//! rubbish-arc budget; the arcs themselves are drawn afterwards from
+//! per-tail RNGs, in parallel with the `rayon` feature.
 
+use crate::analysis::Residual;
 use crate::index_list::IndexList;
 use crate::random::Rng;
-use crate::{Arc, NetgenParams, NetgenResult};
+use crate::seed::SeedSequence;
+use crate::trace::{RngPhase, RngTrace};
+use crate::{
+    Arc, ArcKind, GenerateOptions, GenerationMode, GenerationReport, MultiplierRange, NetgenError,
+    NetgenParams, NetgenResult, ProblemType, Progress, Shortfall, SupplyDistribution,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::ControlFlow;
+
+/// Progress messages are logged for source 1, every `PROGRESS_EVERY`-th
+/// source and the last one, so logging stays cheap on huge instances.
+#[cfg(feature = "logging")]
+const PROGRESS_EVERY: usize = 64;
+
+/// `log::debug!` with the `logging` feature, nothing without it.
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "logging")]
+        log::debug!($($arg)*);
+    };
+}
+
+/// Whether to log progress after source `source` of `sources`.
+#[cfg(feature = "logging")]
+fn report_progress(source: usize, sources: usize) -> bool {
+    source == 1 || source == sources || source.is_multiple_of(PROGRESS_EVERY)
+}
+
+/// Random arc draws between progress callbacks.
+const PROGRESS_ARCS: u64 = 4096;
+
+/// State threaded through the per-source phase: the report, and the
+/// progress callback with what it has been told so far.
+#[derive(Default)]
+struct Context<'a> {
+    report: GenerationReport,
+    progress: Option<&'a mut dyn FnMut(Progress) -> ControlFlow<()>>,
+    sources: usize,
+    sources_done: usize,
+    random_draws: u64,
+    cancelled: bool,
+    /// Rubbish arcs left for [`draw_deferred`], in
+    /// [`GenerationMode::Fast`] only.
+    deferred: Option<Vec<RubbishBlock>>,
+    /// Out-degree bookkeeping for [`GenerateOptions::max_out_degree`].
+    degree: Option<DegreeCap>,
+    /// Stop after the last source's skeleton arcs, for [`Skeleton::build`].
+    skeleton_only: bool,
+}
+
+/// The out-degree bound and what it has kept from the tails so far.
+struct DegreeCap {
+    max: i64,
+    /// Out-degree of each node (1-based) among `arcs[..counted]`.
+    out: Vec<i64>,
+    counted: usize,
+    /// Random arcs cut from earlier tails, still to be placed.
+    carry: i64,
+}
+
+impl DegreeCap {
+    /// How many of `budget` random arcs, plus those carried over, `tail`
+    /// may take with `heads` heads left and `remaining` arcs to go.
+    fn allow(&mut self, arcs: &[Arc], tail: usize, budget: i64, heads: i64, remaining: i64) -> i64 {
+        for arc in &arcs[self.counted..] {
+            self.out[arc.from as usize] += 1;
+        }
+        self.counted = arcs.len();
+        let wanted = (budget + self.carry).min(remaining.max(budget));
+        let room = (self.max - self.out[tail]).clamp(0, heads);
+        let allowed = wanted.min(room);
+        self.carry = wanted - allowed;
+        allowed
+    }
+}
+
+/// The rubbish arcs out of one tail, deferred in [`GenerationMode::Fast`]:
+/// placeholders at `arcs[start..start + len]`, to be drawn from `handle`.
+struct RubbishBlock {
+    tail: usize,
+    handle: IndexList,
+    start: usize,
+    len: usize,
+}
+
+impl Context<'_> {
+    /// Call the progress callback, if any, and note a request to stop.
+    fn notify(&mut self, phase: RngPhase, arcs: usize) {
+        if self.cancelled {
+            return;
+        }
+        if let Some(progress) = self.progress.as_mut() {
+            let update = Progress {
+                phase,
+                sources_done: self.sources_done,
+                sources: self.sources,
+                arcs,
+            };
+            self.cancelled = progress(update).is_break();
+        }
+    }
+}
 
 pub fn netgen(seed: i64, params: &NetgenParams) -> NetgenResult {
-    let nodes = params.nodes;
-    let sources = params.sources;
-    let sinks = params.sinks;
-    let density = params.density;
+    netgen_with(seed, params, &GenerateOptions::default()).0
+}
 
+pub fn netgen_traced(seed: i64, params: &NetgenParams) -> (NetgenResult, RngTrace) {
     let mut rng = Rng::new(seed);
-    let mut arcs: Vec<Arc> = Vec::new();
-    let mut supply: Vec<i64> = vec![0; nodes as usize];
+    rng.start_trace();
+    let chains = build_chains(params, &SupplyDistribution::NetgenRandom, &mut rng);
+    let options = GenerateOptions::default();
+    let mut ctx = Context::default();
+    let (result, _) = fill(params, &options, &chains, &mut rng, None, &mut ctx);
+    let trace = rng.finish_trace().unwrap_or_default();
+    (result, trace)
+}
 
-    let nodes_u = nodes as usize;
-    let sources_u = sources as usize;
-    let sinks_u = sinks as usize;
-    let tsources = params.tsources;
-    let tsinks = params.tsinks;
-    let mut nodes_left: i64 = nodes - sinks + tsinks;
+/// Generate with a progress callback; `None` if it asked to stop.
+pub fn netgen_with_progress(
+    seed: i64,
+    params: &NetgenParams,
+    progress: &mut dyn FnMut(Progress) -> ControlFlow<()>,
+) -> Option<NetgenResult> {
+    let mut rng = Rng::new(seed);
+    let chains = build_chains(params, &SupplyDistribution::NetgenRandom, &mut rng);
+    let options = GenerateOptions::default();
+    let mut ctx = Context {
+        progress: Some(progress),
+        ..Context::default()
+    };
+    let (result, _) = fill(params, &options, &chains, &mut rng, None, &mut ctx);
+    (!ctx.cancelled).then_some(result)
+}
 
-    // Check for assignment problem
-    if (sources - tsources) + (sinks - tsinks) == nodes
-        && (sources - tsources) == (sinks - tsinks)
-        && sources == params.supply
+pub fn netgen_with(
+    seed: i64,
+    params: &NetgenParams,
+    options: &GenerateOptions,
+) -> (NetgenResult, GenerationReport) {
+    #[cfg(feature = "std")]
+    let start = std::time::Instant::now();
+    let mut rng = Rng::new(seed);
+    let chains = build_chains(params, &options.supply_distribution, &mut rng);
+    progress!(
+        "supply created for {} sources, transshipment nodes chained",
+        params.sources
+    );
+    let mut ctx = Context {
+        deferred: (options.mode == GenerationMode::Fast).then(Vec::new),
+        degree: options.max_out_degree.map(|max| DegreeCap {
+            max,
+            out: vec![0; params.nodes as usize + 1],
+            counted: 0,
+            carry: 0,
+        }),
+        ..Context::default()
+    };
+    let (mut result, recorded) = fill(params, options, &chains, &mut rng, None, &mut ctx);
+    if let Some(blocks) = ctx.deferred.take() {
+        draw_deferred(params, &mut result.arcs, blocks, rng.state() as u64);
+        progress!("{} rubbish arcs drawn", ctx.report.random_arcs);
+    }
+    let mut report = ctx.report;
+    if let Some(max) = options.max_out_degree {
+        fill_out_degree(params, &mut result.arcs, max, &mut rng, &mut report);
+    } else if options.on_shortfall == Shortfall::TopUp {
+        top_up(params, &mut result.arcs, &mut rng, &mut report);
+    }
+    if let Some(range) = &options.multipliers {
+        assign_multipliers(&mut result.arcs, &recorded.positions, range, &mut rng);
+    }
+    #[cfg(feature = "std")]
     {
-        create_assignment(params, &mut rng, &mut arcs, &mut supply, &mut nodes_left);
-        return NetgenResult { arcs, supply };
+        report.duration = Some(start.elapsed());
+    }
+    #[cfg(feature = "logging")]
+    log::info!(
+        "generated {} arcs on {} nodes (seed {seed}) in {:.3?}",
+        result.arcs.len(),
+        result.supply.len(),
+        report.duration.unwrap_or_default()
+    );
+    (result, report)
+}
+
+/// The skeleton arcs and supplies [`generate`](crate::generate) lays out
+/// for `seed` and `params`, and the RNG just after its last draw.
+///
+/// This is a whole [`generate`](crate::generate) run, random arcs included:
+/// they are drawn between the skeleton arcs of successive sources, so the
+/// skeleton depends on `params.density`, and the returned RNG continues the
+/// full stream.
+pub(crate) fn skeleton_of(seed: i64, params: &NetgenParams) -> (Vec<Arc>, Vec<i64>, Rng) {
+    let mut rng = Rng::new(seed);
+    let chains = build_chains(params, &SupplyDistribution::NetgenRandom, &mut rng);
+    let (result, recorded) = fill(
+        params,
+        &GenerateOptions::default(),
+        &chains,
+        &mut rng,
+        None,
+        &mut Context::default(),
+    );
+    (recorded.arcs, result.supply, rng)
+}
+
+/// Give every non-skeleton arc a multiplier `num / range.den`, drawing
+/// `num` from `range.num_min..=range.num_max` in arc order. The draws come
+/// after all others, so the network itself is unchanged.
+fn assign_multipliers(
+    arcs: &mut [Arc],
+    skeleton: &[usize],
+    range: &MultiplierRange,
+    rng: &mut Rng,
+) {
+    let mut skeleton = skeleton.iter().peekable();
+    for (k, arc) in arcs.iter_mut().enumerate() {
+        if skeleton.next_if(|&&p| p == k).is_some() {
+            continue;
+        }
+        arc.multiplier = Some((rng.next(range.num_min, range.num_max), range.den));
+    }
+}
+
+/// The feasibility-guaranteeing part of a generated network.
+///
+/// A skeleton holds the per-source transshipment chains, the sinks each
+/// source ships to, and the skeleton arcs that carry the supply. It is
+/// produced by [`Skeleton::build`] and turned into a full network by
+/// [`Skeleton::fill_random`], which adds the random ("rubbish") arcs.
+///
+/// For assignment problems there are no chains; the skeleton arcs are the
+/// one-per-source matching arcs and each source has exactly one sink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skeleton {
+    params: NetgenParams,
+    pred: Vec<usize>,
+    chains: Vec<Vec<u64>>,
+    sinks: Vec<Vec<u64>>,
+    arcs: Vec<Arc>,
+    arc_offsets: Vec<usize>,
+    supply: Vec<i64>,
+}
+
+impl Skeleton {
+    /// Build the skeleton for `seed` and `params`.
+    ///
+    /// Returns the RNG positioned just after the chain phase. Passing it to
+    /// [`fill_random`](Self::fill_random) reproduces [`generate`](crate::generate)
+    /// exactly; passing a differently seeded [`Rng`] keeps the skeleton and
+    /// draws different random arcs.
+    ///
+    /// The reference generator draws each tail's random arcs right after
+    /// its skeleton arcs, from the same stream, so the skeleton depends on
+    /// `params.density` and building it costs nearly a whole
+    /// [`generate`](crate::generate) run: only the random arcs of the
+    /// transshipment sinks, drawn after the last skeleton arc, are skipped.
+    pub fn build(seed: i64, params: &NetgenParams) -> Result<(Skeleton, Rng), NetgenError> {
+        crate::check_inputs(seed, params)?;
+        let mut rng = Rng::new(seed);
+        let chains = build_chains(params, &SupplyDistribution::NetgenRandom, &mut rng);
+        let fork = rng.clone();
+        let (_, recorded) = fill(
+            params,
+            &GenerateOptions::default(),
+            &chains,
+            &mut rng,
+            None,
+            &mut Context {
+                skeleton_only: true,
+                ..Context::default()
+            },
+        );
+        let chain_lists = (1..=params.sources as usize)
+            .map(|source| chain_of(&chains.pred, source))
+            .collect();
+        let skeleton = Skeleton {
+            params: params.clone(),
+            pred: chains.pred,
+            chains: chain_lists,
+            sinks: recorded.sinks,
+            arcs: recorded.arcs,
+            arc_offsets: recorded.arc_offsets,
+            supply: recorded.supply,
+        };
+        Ok((skeleton, fork))
+    }
+
+    /// Add random arcs to a copy of this skeleton, drawing from `rng`.
+    ///
+    /// The skeleton arcs, sink assignment and supplies are kept as recorded;
+    /// the RNG is advanced past their draws so the random arcs follow the
+    /// same stream positions as in a one-shot run.
+    pub fn fill_random(&self, mut rng: Rng) -> NetgenResult {
+        let chains = Chains {
+            supply: self.supply.clone(),
+            pred: self.pred.clone(),
+        };
+        fill(
+            &self.params,
+            &GenerateOptions::default(),
+            &chains,
+            &mut rng,
+            Some(self),
+            &mut Context::default(),
+        )
+        .0
+    }
+
+    pub fn params(&self) -> &NetgenParams {
+        &self.params
+    }
+
+    /// Transshipment nodes (1-based) threaded after each source, in chain
+    /// order. Entry `i` belongs to source `i + 1`.
+    pub fn chains(&self) -> &[Vec<u64>] {
+        &self.chains
+    }
+
+    /// Sinks (1-based) that each source routes supply to. Entry `i` belongs
+    /// to source `i + 1`.
+    pub fn sinks(&self) -> &[Vec<u64>] {
+        &self.sinks
+    }
+
+    /// Skeleton arcs, in the order they appear in the generated network.
+    pub fn arcs(&self) -> &[Arc] {
+        &self.arcs
+    }
+
+    /// Skeleton arcs emitted while processing source `source` (1-based).
+    pub fn arcs_of_source(&self, source: usize) -> &[Arc] {
+        &self.arcs[self.arc_offsets[source - 1]..self.arc_offsets[source]]
+    }
+
+    /// Node supplies after routing (0-indexed, like [`NetgenResult::supply`]).
+    pub fn supply(&self) -> &[i64] {
+        &self.supply
     }
+}
 
-    create_supply(sources_u, params.supply, &mut rng, &mut supply);
+/// Output of the chain phase.
+struct Chains {
+    supply: Vec<i64>,
+    /// Successor links forming one cycle per source through its chain.
+    /// Empty for assignment problems.
+    pred: Vec<usize>,
+}
+
+/// Skeleton data captured while filling.
+struct Recorded {
+    sinks: Vec<Vec<u64>>,
+    arcs: Vec<Arc>,
+    /// Positions of the skeleton arcs in the generated network.
+    positions: Vec<usize>,
+    arc_offsets: Vec<usize>,
+    supply: Vec<i64>,
+}
+
+fn chain_of(pred: &[usize], source: usize) -> Vec<u64> {
+    let mut chain = Vec::new();
+    if pred.is_empty() {
+        return chain;
+    }
+    let mut node = pred[source];
+    while node != source {
+        chain.push(node as u64);
+        node = pred[node];
+    }
+    chain
+}
+
+fn build_chains(params: &NetgenParams, distribution: &SupplyDistribution, rng: &mut Rng) -> Chains {
+    let nodes_u = params.nodes as usize;
+    let sources_u = params.sources as usize;
+    let sinks_u = params.sinks as usize;
+    let mut supply: Vec<i64> = vec![0; nodes_u];
+
+    if params.problem_type() == ProblemType::Assignment {
+        for s in supply.iter_mut().take(nodes_u / 2) {
+            *s = 1;
+        }
+        for s in supply.iter_mut().take(nodes_u).skip(nodes_u / 2) {
+            *s = -1;
+        }
+        return Chains {
+            supply,
+            pred: Vec::new(),
+        };
+    }
+
+    rng.phase(RngPhase::SupplyCreation);
+    match distribution {
+        SupplyDistribution::NetgenRandom => {
+            create_supply(sources_u, params.supply, rng, &mut supply)
+        }
+        SupplyDistribution::Equal => {
+            supply[..sources_u].fill(params.supply / sources_u as i64);
+            supply[sources_u - 1] += params.supply % sources_u as i64;
+        }
+        SupplyDistribution::Custom(supplies) => supply[..sources_u].copy_from_slice(supplies),
+    }
 
     // Form skeleton
+    rng.phase(RngPhase::ChainAssignment);
     let max_node = nodes_u;
-    let alloc_size = nodes_u + density as usize + 2;
-    let mut pred: Vec<usize> = vec![0; alloc_size];
-    let mut head_arr: Vec<usize> = vec![0; alloc_size];
-    let mut tail_arr: Vec<usize> = vec![0; alloc_size];
+    let mut pred: Vec<usize> = vec![0; nodes_u + 1];
 
     #[allow(clippy::needless_range_loop)]
     for i in 1..=sources_u {
@@ -62,117 +447,462 @@ pub fn netgen(seed: i64, params: &NetgenParams) -> NetgenResult {
     }
     while remaining > 0 {
         let node = handle.choose(rng.next(1, handle.size() as i64) as usize);
-        source = rng.next(1, sources) as usize;
+        source = rng.next(1, params.sources) as usize;
         pred[node] = pred[source];
         pred[source] = node;
         remaining -= 1;
     }
-    drop(handle);
+
+    Chains { supply, pred }
+}
+
+/// Run the per-source phase. With `fixed` set, sinks, skeleton arcs and
+/// supplies come from the recorded skeleton and the corresponding draws are
+/// discarded.
+fn fill(
+    params: &NetgenParams,
+    options: &GenerateOptions,
+    chains: &Chains,
+    rng: &mut Rng,
+    fixed: Option<&Skeleton>,
+    ctx: &mut Context<'_>,
+) -> (NetgenResult, Recorded) {
+    let mut arcs: Vec<Arc> = Vec::new();
+    let mut supply = chains.supply.clone();
+    let mut nodes_left: i64 = params.nodes - params.sinks + params.tsinks;
+    let mut recorded = Recorded {
+        sinks: Vec::new(),
+        arcs: Vec::new(),
+        positions: Vec::new(),
+        arc_offsets: vec![0],
+        supply: Vec::new(),
+    };
+    ctx.sources = match params.problem_type() {
+        ProblemType::Assignment => params.nodes as usize / 2,
+        _ => params.sources as usize,
+    };
+    ctx.notify(RngPhase::ChainAssignment, 0);
+
+    if params.problem_type() == ProblemType::Assignment {
+        fill_assignment(params, options, rng, &mut arcs, fixed, &mut recorded, ctx);
+        recorded.supply = supply.clone();
+        ctx.report.finish(params, &arcs, recorded.arcs.len());
+        ctx.report.record_demands(params, &supply);
+        ctx.notify(RngPhase::RubbishArcs, arcs.len());
+        return (NetgenResult { arcs, supply }, recorded);
+    }
+
+    let nodes_u = params.nodes as usize;
+    let sources_u = params.sources as usize;
+    let sinks_u = params.sinks as usize;
+    let tsources = params.tsources;
+    let tsinks = params.tsinks;
+    let max_node = nodes_u;
+    let pred = &chains.pred;
+    let (skeleton_mincost, skeleton_maxcost) = options.skeleton_costs(params);
+    let skeleton_caps = options.skeleton_caps(params);
+    // With target demands, sink supplies are set after all sources are
+    // processed; until then `picked` stands in for "supply[j] != 0".
+    let demands = options.demand_distribution.as_deref();
+    let mut picked = vec![false; nodes_u];
+    let mut sinks_of: Vec<Vec<usize>> = Vec::new();
 
     for source in 1..=sources_u {
-        let mut sort_count: usize = 0;
+        if ctx.cancelled {
+            break;
+        }
+        let mut skeleton: Vec<(usize, usize)> = Vec::new();
         let mut node = pred[source];
         while node != source {
-            sort_count += 1;
-            head_arr[sort_count] = node;
-            tail_arr[sort_count] = pred[node];
+            skeleton.push((pred[node], node));
             node = pred[node];
         }
+        let chain_length = skeleton.len();
 
         let sinks_per_source: usize = if nodes_u - sources_u - sinks_u == 0 {
             (sinks_u / sources_u) + 1
         } else {
-            // BCJL overflow fix: use f64
-            ((2.0 * sort_count as f64 * sinks_u as f64) / (nodes_u - sources_u - sinks_u) as f64)
+            // BCJL overflow fix: use f64. Conversion, `*` and `/` are
+            // correctly rounded IEEE 754 operations and the cast truncates,
+            // so the result is the same on every platform.
+            ((2.0 * chain_length as f64 * sinks_u as f64) / (nodes_u - sources_u - sinks_u) as f64)
                 as usize
         };
         let sinks_per_source = sinks_per_source.max(2).min(sinks_u);
 
-        let mut sinks_vec: Vec<usize> = Vec::with_capacity(sinks_per_source);
-        let mut handle = IndexList::new(max_node - sinks_u, max_node - 1);
-        for _ in 0..sinks_per_source {
-            sinks_vec.push(handle.choose(rng.next(1, handle.size() as i64) as usize));
-        }
+        // Sinks are stored as 0-based node indices.
+        rng.phase(RngPhase::SinkSelection);
+        let sinks_vec: Vec<usize> = match fixed {
+            Some(sk) => {
+                rng.discard(sinks_per_source);
+                sk.sinks[source - 1]
+                    .iter()
+                    .map(|&s| s as usize - 1)
+                    .collect()
+            }
+            None => {
+                let mut sinks_vec: Vec<usize> = Vec::with_capacity(sinks_per_source);
+                let mut handle = IndexList::new(max_node - sinks_u, max_node - 1);
+                for _ in 0..sinks_per_source {
+                    sinks_vec.push(handle.choose(rng.next(1, handle.size() as i64) as usize));
+                }
 
-        if source == sources_u && handle.size() > 0 {
-            while handle.size() > 0 {
-                let j = handle.choose(1);
-                if supply[j] == 0 {
-                    sinks_vec.push(j);
+                if source == sources_u && handle.size() > 0 {
+                    while handle.size() > 0 {
+                        let j = handle.choose(1);
+                        let unused = match demands {
+                            Some(_) => !picked[j],
+                            None => supply[j] == 0,
+                        };
+                        if unused {
+                            sinks_vec.push(j);
+                        }
+                    }
                 }
+                sinks_vec
             }
-        }
-        drop(handle);
+        };
 
         let actual_sinks = sinks_vec.len();
-        let chain_length = sort_count;
-        let supply_per_sink = supply[source - 1] / actual_sinks as i64;
-        let mut k = pred[source];
-
-        for i in 0..actual_sinks {
-            sort_count += 1;
-            let partial_supply = rng.next(1, supply_per_sink);
-            let j = rng.next(0, actual_sinks as i64 - 1) as usize;
-            tail_arr[sort_count] = k;
-            head_arr[sort_count] = sinks_vec[i] + 1;
-            supply[sinks_vec[i]] -= partial_supply;
-            supply[sinks_vec[j]] -= supply_per_sink - partial_supply;
-            k = source;
-            let mut steps = rng.next(1, chain_length as i64);
-            while steps > 0 {
-                k = pred[k];
-                steps -= 1;
+        if demands.is_some() {
+            for &j in &sinks_vec {
+                picked[j] = true;
             }
+            sinks_of.push(sinks_vec.clone());
         }
-        supply[sinks_vec[0]] -= supply[source - 1] % actual_sinks as i64;
-
-        sort_skeleton(&mut head_arr, &mut tail_arr, sort_count);
-        tail_arr[sort_count + 1] = 0;
+        match fixed {
+            Some(sk) => {
+                rng.discard(3 * actual_sinks);
+                skeleton = sk
+                    .arcs_of_source(source)
+                    .iter()
+                    .map(|a| (a.from as usize, a.to as usize))
+                    .collect();
+            }
+            None => {
+                // The subtractions below are non-negative and sum to this
+                // source's supply, so summed over all sources a sink loses
+                // at most `params.supply`: no sink demand can pass
+                // `-i64::MAX`.
+                let supply_per_sink = supply[source - 1] / actual_sinks as i64;
+                // With less supply than sinks, `next(1, 0)` draws 0 for every
+                // sink and the remainder below gives the whole supply to the
+                // first one, as in the reference.
+                if supply_per_sink == 0 && demands.is_none() {
+                    ctx.report.undivided_sources += 1;
+                }
+                let mut k = pred[source];
+                for i in 0..actual_sinks {
+                    let partial_supply = rng.next(1, supply_per_sink);
+                    let j = rng.next(0, actual_sinks as i64 - 1) as usize;
+                    skeleton.push((k, sinks_vec[i] + 1));
+                    if demands.is_none() {
+                        supply[sinks_vec[i]] -= partial_supply;
+                        supply[sinks_vec[j]] -= supply_per_sink - partial_supply;
+                    }
+                    k = source;
+                    let mut steps = rng.next(1, chain_length as i64);
+                    while steps > 0 {
+                        k = pred[k];
+                        steps -= 1;
+                    }
+                }
+                if demands.is_none() {
+                    supply[sinks_vec[0]] -= supply[source - 1] % actual_sinks as i64;
+                }
+                match options.mode {
+                    GenerationMode::ReferenceExact => sort_skeleton(&mut skeleton),
+                    GenerationMode::Fast => sort_skeleton_fast(&mut skeleton),
+                }
+            }
+        }
+        recorded
+            .sinks
+            .push(sinks_vec.iter().map(|&s| s as u64 + 1).collect());
 
-        let mut i = 1;
-        while i <= sort_count {
+        let first_arc = recorded.arcs.len();
+        let mut i = 0;
+        while i < skeleton.len() && !ctx.cancelled {
             let mut handle = IndexList::new(sources_u - tsources as usize + 1, max_node);
-            handle.remove(tail_arr[i]);
-            let it = tail_arr[i];
-            while it == tail_arr[i] {
-                handle.remove(head_arr[i]);
+            let it = skeleton[i].0;
+            handle.remove(it);
+            rng.phase(RngPhase::SkeletonArcs);
+            while i < skeleton.len() && skeleton[i].0 == it {
+                let head = skeleton[i].1;
+                handle.remove(head);
                 let mut cap = params.supply;
-                if rng.next(1, 100) <= params.capacitated_pct {
-                    cap = supply[source - 1].max(params.mincap);
+                let capacitated = rng.next(1, 100) <= params.capacitated_pct;
+                if capacitated {
+                    cap = match skeleton_caps {
+                        Some((lo, hi)) => rng.next_in(lo, hi).max(supply[source - 1]),
+                        None => supply[source - 1].max(params.mincap),
+                    };
                 }
-                let mut cost = params.maxcost;
-                if rng.next(1, 100) > params.hicost_pct {
-                    cost = rng.next(params.mincost, params.maxcost);
+                let mut cost = skeleton_maxcost;
+                let hicost = rng.next(1, 100) <= params.hicost_pct;
+                if !hicost {
+                    cost = rng.next_in(skeleton_mincost, skeleton_maxcost);
                 }
-                arcs.push(Arc {
-                    from: it as u64,
-                    to: head_arr[i] as u64,
-                    cost,
-                    capacity: cap,
-                });
+                let arc = match fixed {
+                    Some(sk) => sk.arcs[sk.arc_offsets[source - 1] + i].clone(),
+                    None => Arc {
+                        from: it as u64,
+                        to: head as u64,
+                        cost,
+                        capacity: cap,
+                        multiplier: None,
+                        uncapacitated: !capacitated,
+                        kind: ArcKind::Skeleton,
+                        hicost,
+                    },
+                };
+                recorded.arcs.push(arc.clone());
+                recorded.positions.push(arcs.len());
+                arcs.push(arc);
                 i += 1;
             }
+            rng.phase(RngPhase::RubbishArcs);
             pick_head(
                 params,
                 &mut handle,
                 it,
                 &mut nodes_left,
                 &mut arcs,
-                &mut rng,
+                rng,
+                ctx,
+            );
+        }
+        if ctx.cancelled {
+            break;
+        }
+        debug_assert_eq!(recorded.arcs.len() - first_arc, skeleton.len());
+        recorded.arc_offsets.push(recorded.arcs.len());
+        ctx.sources_done = source;
+        ctx.notify(RngPhase::SkeletonArcs, arcs.len());
+        #[cfg(feature = "logging")]
+        if report_progress(source, sources_u) {
+            log::debug!(
+                "skeleton for source {source} of {sources_u} done with {} arcs, {} arcs so far",
+                skeleton.len(),
+                arcs.len()
             );
         }
     }
 
+    if ctx.skeleton_only {
+        recorded.supply = supply.clone();
+        return (NetgenResult { arcs, supply }, recorded);
+    }
+
     // Add rubbish arcs out of transshipment sinks
     for i in (max_node - sinks_u + 1)..=(max_node - sinks_u + tsinks as usize) {
+        if ctx.cancelled {
+            break;
+        }
         let mut handle = IndexList::new(sources_u - tsources as usize + 1, max_node);
         handle.remove(i);
-        pick_head(params, &mut handle, i, &mut nodes_left, &mut arcs, &mut rng);
+        pick_head(params, &mut handle, i, &mut nodes_left, &mut arcs, rng, ctx);
+    }
+    progress!(
+        "rubbish arcs for {tsinks} transshipment sinks done, {} arcs",
+        arcs.len()
+    );
+
+    let mut skeleton_arcs = recorded.arcs.len();
+    if let Some(demands) = demands {
+        let first_sink = nodes_u - sinks_u;
+        for (j, &d) in demands.iter().enumerate() {
+            supply[first_sink + j] = -d;
+        }
+        for (source, sink) in unrouted_demand(&supply, &sinks_of, first_sink) {
+            recorded.positions.push(arcs.len());
+            arcs.push(Arc {
+                from: source as u64 + 1,
+                to: sink as u64 + 1,
+                cost: skeleton_maxcost,
+                capacity: params.supply,
+                multiplier: None,
+                uncapacitated: true,
+                kind: ArcKind::Skeleton,
+                hicost: false,
+            });
+            skeleton_arcs += 1;
+        }
+    }
+
+    recorded.supply = supply.clone();
+    ctx.report.finish(params, &arcs, skeleton_arcs);
+    ctx.report.record_demands(params, &supply);
+    ctx.notify(RngPhase::RubbishArcs, arcs.len());
+    (NetgenResult { arcs, supply }, recorded)
+}
+
+/// Source-sink pairs (0-based) that need a direct arc so the sources'
+/// supplies can meet the sinks' target demands.
+///
+/// Source `i` can ship to the sinks in `sinks_of[i]` through its skeleton.
+/// A maximum flow over those pairs finds how much demand the skeleton can
+/// serve; the remainder is matched greedily, in node order, between
+/// sources with supply left and sinks with demand left.
+fn unrouted_demand(
+    supply: &[i64],
+    sinks_of: &[Vec<usize>],
+    first_sink: usize,
+) -> Vec<(usize, usize)> {
+    let sources = sinks_of.len();
+    let sinks = supply.len() - first_sink;
+    let (s, t) = (sources + sinks, sources + sinks + 1);
+    let mut graph = Residual::new(sources + sinks + 2);
+    for (i, &supply) in supply[..sources].iter().enumerate() {
+        graph.add(s, i, supply);
+    }
+    for j in 0..sinks {
+        graph.add(sources + j, t, -supply[first_sink + j]);
+    }
+    for (i, sinks_i) in sinks_of.iter().enumerate() {
+        for &j in sinks_i {
+            graph.add(i, sources + j - first_sink, i64::MAX);
+        }
+    }
+    graph.max_flow(s, t);
+
+    // Edge 2k + 1 holds the flow on the k-th edge added.
+    let mut left: Vec<i64> = (0..sources)
+        .map(|i| supply[i] - graph.cap[2 * i + 1])
+        .collect();
+    let mut need: Vec<i64> = (0..sinks)
+        .map(|j| -supply[first_sink + j] - graph.cap[2 * (sources + j) + 1])
+        .collect();
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < sources && j < sinks {
+        if left[i] == 0 {
+            i += 1;
+        } else if need[j] == 0 {
+            j += 1;
+        } else {
+            let amount = left[i].min(need[j]);
+            left[i] -= amount;
+            need[j] -= amount;
+            pairs.push((i, first_sink + j));
+        }
+    }
+    pairs
+}
+
+/// Draw extra random arcs until `density` is met or no tail can take another
+/// distinct head. Each draw picks a tail uniformly among the non-sink nodes
+/// and a head from a fresh index list that excludes the tail, pure sources,
+/// and heads the tail already reaches.
+fn top_up(
+    params: &NetgenParams,
+    arcs: &mut Vec<Arc>,
+    rng: &mut Rng,
+    report: &mut GenerationReport,
+) {
+    let nodes = params.nodes as usize;
+    let first_head = (params.sources - params.tsources) as usize + 1;
+    let last_tail = (params.nodes - params.sinks + params.tsinks) as usize;
+    let mut heads_of: Vec<Vec<usize>> = vec![Vec::new(); nodes + 1];
+    for arc in arcs.iter() {
+        heads_of[arc.from as usize].push(arc.to as usize);
+    }
+
+    let mut tails: Vec<usize> = (1..=last_tail).collect();
+    while (arcs.len() as i64) < params.density && !tails.is_empty() {
+        let t = rng.next(0, tails.len() as i64 - 1) as usize;
+        let tail = tails[t];
+        // Only remove values that are present, so pseudo_size tracks size.
+        let mut taken = heads_of[tail].clone();
+        taken.push(tail);
+        taken.sort_unstable();
+        taken.dedup();
+        let mut handle = IndexList::new(first_head, nodes);
+        for &node in taken.iter().filter(|&&node| node >= first_head) {
+            handle.remove(node);
+        }
+        if handle.size() == 0 {
+            tails.swap_remove(t);
+            continue;
+        }
+        let head = handle.choose(rng.next(1, handle.size() as i64) as usize);
+        arcs.push(extra_arc(params, tail, head, rng));
+        heads_of[tail].push(head);
+        report.topped_up_arcs += 1;
+    }
+    report.finish(params, arcs, report.skeleton_arcs);
+}
+
+/// Place the random arcs [`GenerateOptions::max_out_degree`] could not fit
+/// while generating: walk the tails in node order, giving each as many
+/// arcs to heads it does not reach yet as its bound allows, until
+/// `density` is met.
+fn fill_out_degree(
+    params: &NetgenParams,
+    arcs: &mut Vec<Arc>,
+    max: i64,
+    rng: &mut Rng,
+    report: &mut GenerationReport,
+) {
+    let nodes = params.nodes as usize;
+    let first_head = (params.sources - params.tsources) as usize + 1;
+    let last_tail = (params.nodes - params.sinks + params.tsinks) as usize;
+    let mut heads_of: Vec<Vec<usize>> = vec![Vec::new(); nodes + 1];
+    for arc in arcs.iter() {
+        heads_of[arc.from as usize].push(arc.to as usize);
     }
 
-    NetgenResult { arcs, supply }
+    for (tail, heads) in heads_of.iter().enumerate().take(last_tail + 1).skip(1) {
+        let missing = params.density - arcs.len() as i64;
+        if missing <= 0 {
+            break;
+        }
+        let room = max - heads.len() as i64;
+        if room <= 0 {
+            continue;
+        }
+        let mut taken = heads.clone();
+        taken.push(tail);
+        taken.sort_unstable();
+        taken.dedup();
+        let mut handle = IndexList::new(first_head, nodes);
+        for &node in taken.iter().filter(|&&node| node >= first_head) {
+            handle.remove(node);
+        }
+        for _ in 0..room.min(missing).min(handle.size() as i64) {
+            let head = handle.choose(rng.next(1, handle.size() as i64) as usize);
+            arcs.push(extra_arc(params, tail, head, rng));
+            report.topped_up_arcs += 1;
+        }
+    }
+    report.finish(params, arcs, report.skeleton_arcs);
+}
+
+/// A random arc from `tail` to `head` added after generation, drawing its
+/// capacity and cost as a rubbish arc does.
+pub(crate) fn extra_arc(params: &NetgenParams, tail: usize, head: usize, rng: &mut Rng) -> Arc {
+    let mut cap = params.supply;
+    let capacitated = rng.next(1, 100) <= params.capacitated_pct;
+    if capacitated {
+        cap = rng.next_in(params.mincap, params.maxcap);
+    }
+    Arc {
+        from: tail as u64,
+        to: head as u64,
+        cost: rng.next_in(params.mincost, params.maxcost),
+        capacity: cap,
+        multiplier: None,
+        uncapacitated: !capacitated,
+        kind: ArcKind::Random,
+        hicost: false,
+    }
 }
 
+/// Share `total_supply` among the sources as the reference does.
+///
+/// Every addition is non-negative and the additions sum to `total_supply`,
+/// so no entry, and no partial sum, can exceed it: any `total_supply` up
+/// to `i64::MAX` is safe.
 fn create_supply(sources: usize, total_supply: i64, rng: &mut Rng, supply: &mut [i64]) {
     let supply_per_source = total_supply / sources as i64;
     for i in 0..sources {
@@ -183,57 +913,98 @@ fn create_supply(sources: usize, total_supply: i64, rng: &mut Rng, supply: &mut
     supply[rng.next(0, sources as i64 - 1) as usize] += total_supply % sources as i64;
 }
 
-fn create_assignment(
+fn fill_assignment(
     params: &NetgenParams,
+    options: &GenerateOptions,
     rng: &mut Rng,
     arcs: &mut Vec<Arc>,
-    supply: &mut [i64],
-    nodes_left: &mut i64,
+    fixed: Option<&Skeleton>,
+    recorded: &mut Recorded,
+    ctx: &mut Context<'_>,
 ) {
+    let mut nodes_left: i64 = params.nodes - params.sinks + params.tsinks;
     let nodes = params.nodes as usize;
     let sources = params.sources as usize;
 
-    for s in supply.iter_mut().take(nodes / 2) {
-        *s = 1;
-    }
-    for s in supply.iter_mut().take(nodes).skip(nodes / 2) {
-        *s = -1;
-    }
-
+    let (mincost, maxcost) = options.skeleton_costs(params);
     let mut skeleton = IndexList::new(sources + 1, nodes);
     for source in 1..=nodes / 2 {
-        let index = skeleton.choose(rng.next(1, skeleton.size() as i64) as usize);
-        arcs.push(Arc {
-            from: source as u64,
-            to: index as u64,
-            cost: rng.next(params.mincost, params.maxcost),
-            capacity: 1,
-        });
+        if ctx.cancelled {
+            break;
+        }
+        rng.phase(RngPhase::SkeletonArcs);
+        let arc = match fixed {
+            Some(sk) => {
+                rng.next(1, skeleton.size() as i64);
+                rng.next_in(mincost, maxcost);
+                sk.arcs[source - 1].clone()
+            }
+            None => {
+                let index = skeleton.choose(rng.next(1, skeleton.size() as i64) as usize);
+                Arc {
+                    from: source as u64,
+                    to: index as u64,
+                    cost: rng.next_in(mincost, maxcost),
+                    capacity: 1,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Skeleton,
+                    hicost: false,
+                }
+            }
+        };
+        let index = arc.to as usize;
+        recorded.sinks.push(vec![arc.to]);
+        recorded.arcs.push(arc.clone());
+        recorded.arc_offsets.push(recorded.arcs.len());
+        recorded.positions.push(arcs.len());
+        arcs.push(arc);
         let mut handle = IndexList::new(sources + 1, nodes);
         handle.remove(index);
-        pick_head(params, &mut handle, source, nodes_left, arcs, rng);
+        rng.phase(RngPhase::RubbishArcs);
+        pick_head(params, &mut handle, source, &mut nodes_left, arcs, rng, ctx);
+        ctx.sources_done = source;
+        ctx.notify(RngPhase::SkeletonArcs, arcs.len());
+        #[cfg(feature = "logging")]
+        if report_progress(source, nodes / 2) {
+            log::debug!(
+                "assignment source {source} of {} done, {} arcs so far",
+                nodes / 2,
+                arcs.len()
+            );
+        }
     }
 }
 
-fn sort_skeleton(head: &mut [usize], tail: &mut [usize], sort_count: usize) {
+/// Shell sort of the skeleton arcs by tail, matching the C code's order for
+/// equal tails.
+fn sort_skeleton(skeleton: &mut [(usize, usize)]) {
+    let sort_count = skeleton.len();
     let mut m = sort_count;
     while {
         m /= 2;
         m != 0
     } {
         let k = sort_count - m;
-        for j in 1..=k {
+        for j in 0..k {
             let mut i = j as isize;
-            while i >= 1 && tail[i as usize] > tail[(i as usize) + m] {
+            while i >= 0 && skeleton[i as usize].0 > skeleton[i as usize + m].0 {
                 let iu = i as usize;
-                tail.swap(iu, iu + m);
-                head.swap(iu, iu + m);
+                skeleton.swap(iu, iu + m);
                 i -= m as isize;
             }
         }
     }
 }
 
+/// Sort by tail, keeping arcs with equal tails in their original order,
+/// which [`sort_skeleton`] does not.
+fn sort_skeleton_fast(skeleton: &mut Vec<(usize, usize)>) {
+    let mut order: Vec<usize> = (0..skeleton.len()).collect();
+    order.sort_unstable_by_key(|&i| (skeleton[i].0, i));
+    *skeleton = order.into_iter().map(|i| skeleton[i]).collect();
+}
+
 fn pick_head(
     params: &NetgenParams,
     handle: &mut IndexList,
@@ -241,17 +1012,20 @@ fn pick_head(
     nodes_left: &mut i64,
     arcs: &mut Vec<Arc>,
     rng: &mut Rng,
+    ctx: &mut Context<'_>,
 ) {
     let non_sources = params.nodes - params.sources + params.tsources;
     let remaining_arcs = params.density - arcs.len() as i64;
 
     *nodes_left -= 1;
-    if (2 * *nodes_left) >= remaining_arcs {
-        return;
-    }
-
     let limit: i64;
-    if (remaining_arcs + non_sources - handle.pseudo_size() as i64 - 1) / (*nodes_left + 1)
+    if (2 * *nodes_left) >= remaining_arcs {
+        // Only arcs a degree bound carried over are left to place.
+        if ctx.degree.as_ref().is_none_or(|cap| cap.carry <= 0) {
+            return;
+        }
+        limit = 0;
+    } else if (remaining_arcs + non_sources - handle.pseudo_size() - 1) / (*nodes_left + 1)
         >= non_sources - 1
     {
         limit = non_sources;
@@ -262,7 +1036,8 @@ fn pick_head(
             if *nodes_left == 0 {
                 l = remaining_arcs;
             }
-            // BCJL overflow fix: use f64 for the comparison
+            // BCJL overflow fix: use f64 for the comparison. Both sides are
+            // correctly rounded, so the outcome is platform-independent.
             let lhs = *nodes_left as f64 * (non_sources - 1) as f64;
             let rhs = (remaining_arcs - l) as f64;
             if lhs >= rhs {
@@ -272,21 +1047,129 @@ fn pick_head(
         }
     };
 
+    if let Some(blocks) = ctx.deferred.as_mut() {
+        // Never draw past the end of the list, so that no arc is dropped
+        // and each tail gets exactly its budget.
+        let mut len = limit.min(handle.pseudo_size()).max(0);
+        if let Some(cap) = ctx.degree.as_mut() {
+            len = cap.allow(
+                arcs,
+                desired_tail,
+                len,
+                handle.pseudo_size(),
+                remaining_arcs,
+            );
+        }
+        let len = len as usize;
+        blocks.push(RubbishBlock {
+            tail: desired_tail,
+            handle: handle.clone(),
+            start: arcs.len(),
+            len,
+        });
+        let placeholder = Arc {
+            from: desired_tail as u64,
+            to: 0,
+            cost: 0,
+            capacity: 0,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Random,
+            hicost: false,
+        };
+        arcs.resize(arcs.len() + len, placeholder);
+        return;
+    }
+
     for _ in 0..limit {
-        let index = handle.choose(rng.next(1, handle.pseudo_size() as i64) as usize);
+        // `limit` is drawn up to twice the average out-degree, so once that
+        // nears the number of heads a tail can reach, some tails draw more
+        // arcs than `handle` holds. The pseudo size then reaches zero, the
+        // position is invalid, `choose` returns 0 and the arc is dropped
+        // below, exactly as in the reference; later tails inherit the
+        // unspent budget. Fast mode caps `limit` instead.
+        let position = rng.next(1, handle.pseudo_size());
+        let index = handle.choose(usize::try_from(position).unwrap_or(0));
         let mut cap = params.supply;
-        if rng.next(1, 100) <= params.capacitated_pct {
-            cap = rng.next(params.mincap, params.maxcap);
+        let capacitated = rng.next(1, 100) <= params.capacitated_pct;
+        if capacitated {
+            cap = rng.next_in(params.mincap, params.maxcap);
         }
 
-        // BCJL bounds check
+        // BCJL bounds check. Every handle covers a subrange of
+        // `1..=nodes`, so only the exhausted-list 0 fails it.
+        debug_assert!(
+            index <= params.nodes as usize,
+            "index list returned head {index} outside 1..={}",
+            params.nodes
+        );
         if index >= 1 && index <= params.nodes as usize {
             arcs.push(Arc {
                 from: desired_tail as u64,
                 to: index as u64,
-                cost: rng.next(params.mincost, params.maxcost),
+                cost: rng.next_in(params.mincost, params.maxcost),
                 capacity: cap,
+                multiplier: None,
+                uncapacitated: !capacitated,
+                kind: ArcKind::Random,
+                hicost: false,
             });
+        } else {
+            ctx.report.dropped_arcs += 1;
+        }
+        ctx.random_draws += 1;
+        if ctx.random_draws.is_multiple_of(PROGRESS_ARCS) {
+            ctx.notify(RngPhase::RubbishArcs, arcs.len());
+            if ctx.cancelled {
+                return;
+            }
+        }
+    }
+}
+
+/// Draw the rubbish arcs of `blocks` into their placeholders. Each tail
+/// draws from its own [`Rng`], seeded with element `tail` of the
+/// [`SeedSequence`] of `master`, so the result does not depend on the
+/// order or the thread the blocks are drawn in.
+fn draw_deferred(params: &NetgenParams, arcs: &mut [Arc], blocks: Vec<RubbishBlock>, master: u64) {
+    let seeds = SeedSequence::new(master);
+    let mut jobs = Vec::with_capacity(blocks.len());
+    let mut rest = arcs;
+    let mut offset = 0;
+    for block in blocks {
+        let (slots, after) = rest[block.start - offset..].split_at_mut(block.len);
+        offset = block.start + block.len;
+        rest = after;
+        jobs.push((block, slots));
+    }
+    let draw = |(block, slots): (RubbishBlock, &mut [Arc])| {
+        let mut rng = Rng::new(seeds.nth(block.tail as u64));
+        let mut handle = block.handle;
+        for slot in slots {
+            let position = rng.next(1, handle.pseudo_size());
+            let head = handle.choose(position as usize);
+            let mut cap = params.supply;
+            let capacitated = rng.next(1, 100) <= params.capacitated_pct;
+            if capacitated {
+                cap = rng.next_in(params.mincap, params.maxcap);
+            }
+            *slot = Arc {
+                from: block.tail as u64,
+                to: head as u64,
+                cost: rng.next_in(params.mincost, params.maxcost),
+                capacity: cap,
+                multiplier: None,
+                uncapacitated: !capacitated,
+                kind: ArcKind::Random,
+                hicost: false,
+            };
         }
+    };
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+        jobs.into_par_iter().for_each(draw);
     }
+    #[cfg(not(feature = "rayon"))]
+    jobs.into_iter().for_each(draw);
 }
@@ -0,0 +1,467 @@
+//! Options that extend or adjust the reference generator.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::seed::shuffle;
+use crate::{Arc, NetgenParams, ParamError};
+
+/// What to do when the generated network has fewer arcs than `density`.
+///
+/// The reference generator can fall short of `density`: random heads drawn
+/// past the end of an exhausted index list are dropped, and the per-node
+/// arc budget is only approximate. See [`GenerationReport::shortfall`](crate::GenerationReport::shortfall).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shortfall {
+    /// Return the network as generated (reference behavior).
+    #[default]
+    Ignore,
+    /// Return the network as generated and log a warning via the `log` crate.
+    ///
+    /// The warning only shows if the application installs a logger, as the
+    /// CLI does with the `logging` feature; otherwise this is
+    /// [`Ignore`](Self::Ignore). [`generate_with_options`](crate::generate_with_options)
+    /// returns the shortfall in its report either way.
+    Warn,
+    /// Fail with [`NetgenError::ArcShortfall`](crate::NetgenError::ArcShortfall).
+    Error,
+    /// Keep drawing random arcs until `density` is met, continuing the same
+    /// RNG stream. Heads are drawn without repeating an existing arc from the
+    /// same tail, so a network that cannot hold `density` distinct arcs stays
+    /// short. This diverges from the C reference whenever it adds arcs.
+    TopUp,
+}
+
+/// Speed/fidelity trade-off of [`generate_with`](crate::generate_with) and
+/// [`generate_with_options`](crate::generate_with_options).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GenerationMode {
+    /// Reproduce the C reference exactly.
+    #[default]
+    ReferenceExact,
+    /// Allow changes that make generation faster but no longer reproduce
+    /// the C reference's output. The instance keeps the reference's
+    /// statistical shape: the same parameters, source supplies and cost and
+    /// capacity ranges, about as many arcs, and a feasible skeleton. It may
+    /// order arcs differently and consume the random stream differently,
+    /// but is still determined by the seed. The divergences:
+    ///
+    /// - Each source's skeleton arcs are sorted by tail with an
+    ///   `O(n log n)` stable sort instead of the reference's shell sort,
+    ///   which dominates the run time for sources with very long chains.
+    ///   The shell sort is not stable, so arcs with the same tail can come
+    ///   out in a different order and then draw their costs and capacities
+    ///   in that order.
+    /// - The rubbish arcs after each tail are budgeted as in the reference
+    ///   while the skeleton is laid out, but drawn afterwards, each tail
+    ///   from its own [`Rng`](crate::Rng) seeded from the main stream and
+    ///   the tail. With the `rayon` feature they are drawn in parallel; the
+    ///   result does not depend on the number of threads. A budget larger
+    ///   than the heads left is cut to them, so no draw is dropped (see
+    ///   [`GenerationReport::dropped_arcs`](crate::GenerationReport::dropped_arcs)).
+    Fast,
+}
+
+/// How the total supply is split across the sources.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SupplyDistribution {
+    /// Random split, as in the reference generator.
+    #[default]
+    NetgenRandom,
+    /// `supply / sources` per source, with the remainder on the last source.
+    /// Draws no random numbers, so the rest of the network diverges from
+    /// the reference.
+    Equal,
+    /// One supply per source, in source order. Entries must be
+    /// non-negative and sum to `supply`. Draws no random numbers, like
+    /// [`Equal`](Self::Equal).
+    Custom(Vec<i64>),
+}
+
+/// Range of arc gain multipliers for generalized networks: each random arc
+/// gets `num / den` with `num` drawn uniformly from `num_min..=num_max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiplierRange {
+    pub num_min: i64,
+    pub num_max: i64,
+    pub den: i64,
+}
+
+/// Options for [`generate_with_options`](crate::generate_with_options).
+///
+/// The default reproduces [`generate`](crate::generate) exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerateOptions {
+    pub on_shortfall: Shortfall,
+    /// Supply of each source. Ignored for assignment problems, where every
+    /// source has supply 1.
+    pub supply_distribution: SupplyDistribution,
+    /// Target demand of each sink, in sink order. Entries must be
+    /// non-negative and sum to `supply`. Ignored for assignment problems.
+    ///
+    /// Sinks are chosen and skeleton arcs laid out with the same random
+    /// draws as without targets, except that the last source picks up
+    /// every sink no earlier source chose. The sinks then get exactly their
+    /// target demands. If the skeleton cannot route them, because the
+    /// sources reaching a sink lack the supply, the generator repairs it by
+    /// appending direct source-to-sink skeleton arcs, uncapacitated and at
+    /// the skeleton maximum cost, after the random arcs.
+    pub demand_distribution: Option<Vec<i64>>,
+    /// Give every random arc a gain multiplier from this range, making a
+    /// generalized network. Skeleton arcs keep multiplier 1 (`None`), so the
+    /// supplies can still be routed. The multipliers are drawn after
+    /// everything else, so the arcs are otherwise those of a run without
+    /// them.
+    pub multipliers: Option<MultiplierRange>,
+    /// Lower end of the skeleton arc cost range, `mincost` if `None`.
+    ///
+    /// Skeleton arcs draw their cost from the skeleton range instead of
+    /// `mincost..=maxcost`, and `hicost%` of them get the skeleton maximum.
+    /// The number of random draws is unchanged, so random arcs still match
+    /// the reference.
+    pub skeleton_mincost: Option<i64>,
+    /// Upper end of the skeleton arc cost range, `maxcost` if `None`.
+    pub skeleton_maxcost: Option<i64>,
+    /// Lower end of the skeleton arc capacity range, `mincap` if `None`.
+    ///
+    /// If either capacity bound is set, capacitated skeleton arcs draw their
+    /// capacity from the skeleton range, raised to their source's supply so
+    /// the skeleton can still carry it. This takes one extra random draw per
+    /// capacitated skeleton arc, so the rest of the network diverges from
+    /// the reference. Without either bound, capacitated skeleton arcs get
+    /// `max(source supply, mincap)` as in the reference.
+    pub skeleton_mincap: Option<i64>,
+    /// Upper end of the skeleton arc capacity range, `maxcap` if `None`.
+    pub skeleton_maxcap: Option<i64>,
+    /// Give no node more than this many outgoing arcs, instead of piling
+    /// random arcs onto a few tails as the reference does. Requires
+    /// [`GenerationMode::Fast`], since it changes the random stream, and
+    /// `density` must not exceed the bound times the number of tails
+    /// (`nodes - sinks + tsinks`).
+    ///
+    /// Each tail's random arc budget is cut to the room its bound leaves,
+    /// and the cut arcs are carried over to later tails. Whatever is still
+    /// missing at the end is drawn tail by tail, in node order, to heads
+    /// the tail does not reach yet (counted in
+    /// [`GenerationReport::topped_up_arcs`](crate::GenerationReport::topped_up_arcs)),
+    /// so `density` is met whenever the tails have room for it. Skeleton
+    /// arcs are never dropped: a node whose skeleton arcs alone exceed the
+    /// bound keeps them and gets no random arcs. [`Shortfall::TopUp`] adds
+    /// nothing beyond that final pass.
+    pub max_out_degree: Option<i64>,
+    pub mode: GenerationMode,
+}
+
+impl GenerateOptions {
+    /// The skeleton arc cost range for `params`.
+    pub(crate) fn skeleton_costs(&self, params: &NetgenParams) -> (i64, i64) {
+        (
+            self.skeleton_mincost.unwrap_or(params.mincost),
+            self.skeleton_maxcost.unwrap_or(params.maxcost),
+        )
+    }
+
+    /// The skeleton arc capacity range for `params`, if one is set.
+    pub(crate) fn skeleton_caps(&self, params: &NetgenParams) -> Option<(i64, i64)> {
+        if self.skeleton_mincap.is_none() && self.skeleton_maxcap.is_none() {
+            return None;
+        }
+        Some((
+            self.skeleton_mincap.unwrap_or(params.mincap),
+            self.skeleton_maxcap.unwrap_or(params.maxcap),
+        ))
+    }
+
+    /// Check the options against `params`: the skeleton ranges must be
+    /// non-empty, a custom supply distribution must fit the sources,
+    /// target demands must fit the sinks, multipliers must be positive and
+    /// an out-degree bound must leave room for `density` in fast mode.
+    pub fn validate(&self, params: &NetgenParams) -> Result<(), ParamError> {
+        if let SupplyDistribution::Custom(supplies) = &self.supply_distribution {
+            check_entries(supplies, params.sources, params.supply).map_err(|e| match e {
+                EntryError::Length(len) => ParamError::CustomSupplyLength {
+                    len,
+                    sources: params.sources,
+                },
+                EntryError::Negative(index, value) => {
+                    ParamError::NegativeCustomSupply { index, value }
+                }
+                EntryError::Sum(sum) => ParamError::CustomSupplySum {
+                    sum,
+                    supply: params.supply,
+                },
+            })?;
+        }
+        let (mincost, maxcost) = self.skeleton_costs(params);
+        if mincost > maxcost {
+            return Err(ParamError::SkeletonMinCostExceedsMaxCost { mincost, maxcost });
+        }
+        if let Some(demands) = &self.demand_distribution {
+            check_entries(demands, params.sinks, params.supply).map_err(|e| match e {
+                EntryError::Length(len) => ParamError::DemandLength {
+                    len,
+                    sinks: params.sinks,
+                },
+                EntryError::Negative(index, value) => ParamError::NegativeDemand { index, value },
+                EntryError::Sum(sum) => ParamError::DemandSum {
+                    sum,
+                    supply: params.supply,
+                },
+            })?;
+        }
+        if let Some(range) = &self.multipliers
+            && (range.den <= 0 || range.num_min <= 0 || range.num_min > range.num_max)
+        {
+            return Err(ParamError::MultiplierRange {
+                num_min: range.num_min,
+                num_max: range.num_max,
+                den: range.den,
+            });
+        }
+        if let Some((mincap, maxcap)) = self.skeleton_caps(params)
+            && mincap > maxcap
+        {
+            return Err(ParamError::SkeletonMinCapExceedsMaxCap { mincap, maxcap });
+        }
+        if let Some(max_out_degree) = self.max_out_degree {
+            if self.mode != GenerationMode::Fast {
+                return Err(ParamError::OutDegreeNeedsFastMode { max_out_degree });
+            }
+            let tails = params.nodes - params.sinks + params.tsinks;
+            if max_out_degree < 0 || params.density > tails.saturating_mul(max_out_degree) {
+                return Err(ParamError::OutDegreeTooLow {
+                    max_out_degree,
+                    density: params.density,
+                    tails,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why per-node entries (custom supplies or target demands) do not fit.
+enum EntryError {
+    /// The number of entries.
+    Length(usize),
+    /// Index and value of the first negative entry.
+    Negative(usize, i64),
+    /// The sum of the entries.
+    Sum(i128),
+}
+
+/// Check that `entries` has `count` non-negative entries summing to `total`.
+fn check_entries(entries: &[i64], count: i64, total: i64) -> Result<(), EntryError> {
+    if entries.len() as i64 != count {
+        return Err(EntryError::Length(entries.len()));
+    }
+    if let Some((index, &value)) = entries.iter().enumerate().find(|(_, v)| **v < 0) {
+        return Err(EntryError::Negative(index, value));
+    }
+    let sum: i128 = entries.iter().map(|&v| i128::from(v)).sum();
+    if sum != i128::from(total) {
+        return Err(EntryError::Sum(sum));
+    }
+    Ok(())
+}
+
+/// Options for [`write_dimacs_header_with_options`](crate::write_dimacs_header_with_options).
+///
+/// The default writes the reference header exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderOptions {
+    /// Append a `c optimal max flow: X` comment, for example with the value
+    /// of [`analysis::max_flow_value`](crate::analysis::max_flow_value).
+    pub optimal_max_flow: Option<i64>,
+    /// Append `c PARAM <key> <value>` lines for the seed, the problem
+    /// number and every [`NetgenParams`](crate::NetgenParams) field, named
+    /// as the fields, for [`parse_header`](crate::parse_header).
+    pub machine_readable: bool,
+    /// The mode the instance was generated in. Anything but the default
+    /// appends a `c Generation mode: fast (not reference-exact)` comment.
+    pub mode: GenerationMode,
+    /// [`GenerationReport::dropped_arcs`](crate::GenerationReport::dropped_arcs)
+    /// of the instance. Anything above 0 appends a `c Dropped arcs: N`
+    /// comment. The writers never see the report, so it is 0 unless copied
+    /// in from it.
+    pub dropped_arcs: usize,
+    /// [`GenerateOptions::max_out_degree`] of the instance. `Some` appends a
+    /// `c Maximum out-degree: D` comment.
+    pub max_out_degree: Option<i64>,
+    /// Append a `c generator: netgen_rs vX.Y.Z` comment naming this
+    /// version, followed by [`generated`](Self::generated) and
+    /// [`command`](Self::command) when they are set.
+    pub include_provenance: bool,
+    /// With `include_provenance`, an RFC 3339 timestamp written as
+    /// `c generated: <timestamp>`. The library never reads the clock, so
+    /// `None` keeps the output reproducible.
+    pub generated: Option<String>,
+    /// With `include_provenance`, the command that generated the instance,
+    /// written as `c command: <command>`. Here and in `generated`, line
+    /// breaks are written escaped as `\r` and `\n`, so that the value stays
+    /// on its comment line.
+    pub command: Option<String>,
+    /// Free-form comments appended as `c <note>` lines, one per note, after
+    /// the others, such as a [`CostNote`](crate::CostNote) recording how the
+    /// network was transformed. A note with line breaks is written as one
+    /// `c` line per line of text.
+    pub notes: Vec<String>,
+}
+
+/// Options for [`write_dimacs_with_options`](crate::write_dimacs_with_options).
+///
+/// The default writes the reference output exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DimacsOptions {
+    pub header: HeaderOptions,
+    /// Write min-cost flow arcs in the generalized dialect,
+    /// `a <from> <to> <low> <cap> <cost> <mult>`, with each arc's multiplier
+    /// as the exact fraction `num/den` (`1/1` for arcs without one). Other
+    /// problem types are unaffected.
+    pub generalized: bool,
+    /// Order of the arc lines. The problem line and node lines are the same
+    /// in every order.
+    pub arc_order: ArcOrder,
+    /// Renumber the nodes with the random permutation
+    /// [`RelabelMap::from_seed`](crate::RelabelMap::from_seed)`(seed, nodes)`
+    /// before writing, so that sources no longer come first and sinks
+    /// last. Arc endpoints and node lines are rewritten; the arc order
+    /// applies to the relabeled arcs.
+    pub relabel_nodes: Option<u64>,
+    /// The capacity written for arcs flagged
+    /// [`uncapacitated`](crate::Arc::uncapacitated).
+    pub uncapacitated_as: UncapacitatedAs,
+    /// The line terminator, for the header and the network alike.
+    pub line_ending: LineEnding,
+    /// End the last line with a line terminator, as the reference does.
+    /// Without it the output ends with the last arc line's last field.
+    pub trailing_newline: bool,
+    /// Right-align the fields of the node and arc lines in columns as wide
+    /// as their largest value, so the network reads as a table. Only the
+    /// spacing changes, but the compact default is the reference format.
+    /// The generalized multiplier column is not aligned.
+    pub aligned: bool,
+    /// For assignment problems, also write an `n` line for each sink-side
+    /// node, after those of the source side, as some matching codes expect.
+    /// The reference lists the source side only.
+    pub asn_list_both_sides: bool,
+    /// What max-flow output carries besides capacities, whose costs `p max`
+    /// has no field for.
+    pub max_flow_extended: MaxFlowExtra,
+}
+
+impl Default for DimacsOptions {
+    fn default() -> Self {
+        DimacsOptions {
+            header: HeaderOptions::default(),
+            generalized: false,
+            arc_order: ArcOrder::default(),
+            relabel_nodes: None,
+            uncapacitated_as: UncapacitatedAs::default(),
+            line_ending: LineEnding::default(),
+            trailing_newline: true,
+            aligned: false,
+            asn_list_both_sides: false,
+            max_flow_extended: MaxFlowExtra::default(),
+        }
+    }
+}
+
+/// Extra data in max-flow DIMACS output, for
+/// [`DimacsOptions::max_flow_extended`]. Other problem types are unaffected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaxFlowExtra {
+    /// Capacities only, as the reference writes.
+    #[default]
+    None,
+    /// A `c cost <cost>` comment after each arc line.
+    CostComments,
+    /// Write the network as a `p min` instance, with its supplies and
+    /// costs, so the same network can drive min-cost flow solvers.
+    MinCostFormat,
+}
+
+/// The line terminator of DIMACS output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, as the reference writes.
+    #[default]
+    Lf,
+    /// `\r\n`, for Windows tools that require it.
+    CrLf,
+}
+
+/// Options for [`write_lp_with_options`](crate::write_lp_with_options) and
+/// [`write_mps_with_options`](crate::write_mps_with_options).
+///
+/// The default writes the same model as `write_lp` and `write_mps`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LpOptions {
+    /// The upper bound written for arcs flagged
+    /// [`uncapacitated`](crate::Arc::uncapacitated). Assignment arcs are
+    /// bounded by 1 regardless.
+    pub uncapacitated_as: UncapacitatedAs,
+}
+
+/// The upper bound written for [uncapacitated](crate::Arc::uncapacitated)
+/// arcs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UncapacitatedAs {
+    /// The arc's `capacity`, i.e. the total supply (reference behavior).
+    #[default]
+    Supply,
+    /// A fixed big-M capacity.
+    BigM(i64),
+    /// No upper bound, in formats that allow one: the LP and MPS exports
+    /// write a free upper bound. DIMACS requires a capacity, so its writers
+    /// fall back to [`Supply`](Self::Supply).
+    Free,
+}
+
+impl UncapacitatedAs {
+    /// The upper bound of `arc`, or `None` for no bound.
+    pub(crate) fn bound(self, arc: &Arc) -> Option<i64> {
+        match self {
+            _ if !arc.uncapacitated => Some(arc.capacity),
+            UncapacitatedAs::Supply => Some(arc.capacity),
+            UncapacitatedAs::BigM(m) => Some(m),
+            UncapacitatedAs::Free => None,
+        }
+    }
+}
+
+/// Order in which the DIMACS writers emit arcs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArcOrder {
+    /// The order in which the arcs were generated (reference behavior).
+    #[default]
+    Generation,
+    /// Sorted by `(from, to)`; arcs with the same endpoints keep their
+    /// generation order.
+    SortedTailHead,
+    /// A random permutation determined by `seed` alone, independent of the
+    /// NETGEN generator.
+    ///
+    /// Draw `k` (from 0) is `splitmix64(seed + k * 0x9e3779b97f4a7c15)`
+    /// (wrapping), as in [`SeedSequence`](crate::SeedSequence). A
+    /// Fisher–Yates shuffle takes one draw `x` for each `i` from `len - 1`
+    /// down to 1 and swaps position `i` with `(x * (i + 1)) >> 64`. This
+    /// mapping is stable across crate versions.
+    Shuffled { seed: u64 },
+}
+
+impl ArcOrder {
+    /// Indices into `arcs` in this order, or `None` for generation order.
+    pub(crate) fn permutation(self, arcs: &[Arc]) -> Option<Vec<usize>> {
+        if self == ArcOrder::Generation {
+            return None;
+        }
+        let mut order: Vec<usize> = (0..arcs.len()).collect();
+        match self {
+            ArcOrder::Generation => {}
+            ArcOrder::SortedTailHead => order.sort_by_key(|&i| (arcs[i].from, arcs[i].to)),
+            ArcOrder::Shuffled { seed } => shuffle(&mut order, seed),
+        }
+        Some(order)
+    }
+}
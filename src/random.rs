@@ -1,20 +1,219 @@
+use alloc::boxed::Box;
+
+use crate::limits::MAX_EXACT_RANGE;
+use crate::trace::{RngPhase, RngTrace};
+
 /// Portable random number generator, faithfully ported from random.c.
 ///
 /// Uses the LCG: i = 7^5 * i mod (2^31-1), with overflow-avoiding bit manipulation.
 const MULTIPLIER: i64 = 16807;
 const MODULUS: i64 = 2147483647;
 
+/// The NETGEN linear congruential generator.
+///
+/// Every call to [`next`](Self::next) advances the state by exactly one step,
+/// whatever the requested interval; [`next_wide`](Self::next_wide) may take
+/// several.
+///
+/// Generators compare equal when their states are equal, whatever their
+/// [`draws`](Self::draws) counts.
+#[derive(Debug, Clone)]
 pub struct Rng {
     seed: i64,
+    draws: u64,
+    tracer: Option<Box<Tracer>>,
+}
+
+impl PartialEq for Rng {
+    fn eq(&self, other: &Self) -> bool {
+        self.seed == other.seed
+    }
+}
+
+impl Eq for Rng {}
+
+/// Trace in progress: the open phase, with the draw count and state at
+/// which it was entered.
+#[derive(Debug, Clone)]
+struct Tracer {
+    trace: RngTrace,
+    phase: Option<RngPhase>,
+    mark: u64,
+    mark_state: i64,
 }
 
 impl Rng {
+    /// Create a generator from a seed in `1..=2^31-2`.
     pub fn new(seed: i64) -> Self {
-        Rng { seed }
+        Rng {
+            seed,
+            draws: 0,
+            tracer: None,
+        }
+    }
+
+    /// Resume a generator from a [`state`](Self::state).
+    pub fn from_state(state: i64) -> Self {
+        Rng::new(state)
+    }
+
+    /// The current state, from which [`from_state`](Self::from_state)
+    /// continues the same stream. It is also the last raw value drawn.
+    pub fn state(&self) -> i64 {
+        self.seed
+    }
+
+    /// Raw draws consumed since creation, counting those passed over by
+    /// [`skip`](Self::skip).
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Start attributing draws to phases (see [`phase`](Self::phase)).
+    pub(crate) fn start_trace(&mut self) {
+        self.tracer = Some(Box::new(Tracer {
+            trace: RngTrace::default(),
+            phase: None,
+            mark: self.draws,
+            mark_state: self.seed,
+        }));
+    }
+
+    /// Attribute the following draws to `phase`. A no-op unless tracing.
+    pub(crate) fn phase(&mut self, phase: RngPhase) {
+        self.close_phase();
+        if let Some(tracer) = &mut self.tracer {
+            tracer.phase = Some(phase);
+        }
+    }
+
+    /// Stop tracing and return the trace, if one was started.
+    pub(crate) fn finish_trace(&mut self) -> Option<RngTrace> {
+        self.close_phase();
+        self.tracer.take().map(|t| t.trace)
+    }
+
+    fn close_phase(&mut self) {
+        let Some(tracer) = &mut self.tracer else {
+            return;
+        };
+        let draws = self.draws - tracer.mark;
+        if let Some(phase) = tracer.phase
+            && draws > 0
+        {
+            let first = Rng::from_state(tracer.mark_state).raw_next();
+            tracer.trace.record(phase, draws, first, self.seed);
+        }
+        tracer.mark = self.draws;
+        tracer.mark_state = self.seed;
+    }
+
+    /// Advance the generator by `n` raw draws in `O(log n)` time, as if
+    /// calling [`raw_next`](Self::raw_next) (or [`next`](Self::next)) `n`
+    /// times: the state is multiplied by `16807^n mod (2^31 - 1)`.
+    ///
+    /// ```rust
+    /// use netgen_rs::Rng;
+    ///
+    /// let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+    /// a.skip(1000);
+    /// (0..1000).for_each(|_| { b.raw_next(); });
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn skip(&mut self, n: u64) {
+        let (n_total, mut n) = (n, n);
+        let modulus = MODULUS as u64;
+        let (mut factor, mut base) = (1u64, MULTIPLIER as u64);
+        while n > 0 {
+            if n & 1 == 1 {
+                factor = factor * base % modulus;
+            }
+            base = base * base % modulus;
+            n >>= 1;
+        }
+        self.seed = (self.seed as i128 * factor as i128).rem_euclid(MODULUS as i128) as i64;
+        self.draws += n_total;
     }
 
-    /// Generate a random integer in the interval [a, b] (b >= a >= 0).
+    /// Advance the generator by `n` draws, discarding the values.
+    pub(crate) fn discard(&mut self, n: usize) {
+        self.skip(n as u64);
+    }
+
+    /// Generate a random integer in the interval [a, b].
+    ///
+    /// Either bound may be negative, as long as `b - a + 1` fits in an
+    /// `i64`. If `b <= a` the draw is discarded and `b` returned, so a
+    /// single-value range such as `[-5, -5]` yields that value. Ranges that
+    /// do not divide the generator's period are slightly biased, and only
+    /// the first `2^31 - 2` values of wider ranges can appear; see
+    /// [`next_wide`](Self::next_wide).
     pub fn next(&mut self, a: i64, b: i64) -> i64 {
+        let seed = self.raw_next();
+        if b <= a {
+            return b;
+        }
+        a + seed % (b - a + 1)
+    }
+
+    /// Generate a uniformly distributed integer in the interval [a, b],
+    /// for any `a <= b`.
+    ///
+    /// Combines as many steps as the width of the range needs (up to three
+    /// for the full `i64` range) into one large value and rejects those that
+    /// would bias the result, so the number of steps consumed varies. If
+    /// `b <= a`, a single step is consumed and `b` returned, as with
+    /// [`next`](Self::next).
+    ///
+    /// ```rust
+    /// use netgen_rs::Rng;
+    ///
+    /// let mut rng = Rng::new(42);
+    /// let v = rng.next_wide(-(1 << 40), 1 << 40);
+    /// assert!((-(1 << 40)..=1 << 40).contains(&v));
+    /// ```
+    pub fn next_wide(&mut self, a: i64, b: i64) -> i64 {
+        if b <= a {
+            self.raw_next();
+            return b;
+        }
+        let span = (b as i128 - a as i128 + 1) as u128;
+        // Each step yields one of `PERIOD` values, `1..=MODULUS - 1`.
+        const PERIOD: u128 = (MODULUS - 1) as u128;
+        let mut total = 1u128;
+        while total < span {
+            total *= PERIOD;
+        }
+        let limit = total - total % span;
+        loop {
+            let mut value = 0u128;
+            let mut k = 1u128;
+            while k < total {
+                value = value * PERIOD + (self.raw_next() - 1) as u128;
+                k *= PERIOD;
+            }
+            if value < limit {
+                return (a as i128 + (value % span) as i128) as i64;
+            }
+        }
+    }
+
+    /// Draw from [a, b] with [`next`](Self::next) while `b - a + 1` fits
+    /// in one step's `2^31 - 2` values, reproducing the reference output,
+    /// and with [`next_wide`](Self::next_wide) beyond that.
+    pub(crate) fn next_in(&mut self, a: i64, b: i64) -> i64 {
+        if (b as i128 - a as i128) < MAX_EXACT_RANGE as i128 {
+            self.next(a, b)
+        } else {
+            self.next_wide(a, b)
+        }
+    }
+
+    /// Advance the state one step and return it, a value in `1..=2^31-2`.
+    ///
+    /// This is the raw stream behind every other draw; [`next`](Self::next)
+    /// reduces exactly one raw value into its interval.
+    pub fn raw_next(&mut self) -> i64 {
         let hi = MULTIPLIER * (self.seed >> 16);
         let lo_raw = MULTIPLIER * (self.seed & 0xffff);
         let hi = hi + (lo_raw >> 16);
@@ -26,11 +225,8 @@ impl Rng {
         if self.seed < 0 {
             self.seed += MODULUS;
         }
-
-        if b <= a {
-            return b;
-        }
-        a + self.seed % (b - a + 1)
+        self.draws += 1;
+        self.seed
     }
 }
 
@@ -68,4 +264,109 @@ mod tests {
         assert_eq!(rng.next(5, 5), 5);
         assert_eq!(rng.next(10, 3), 3);
     }
+
+    #[test]
+    fn rng_negative_ranges() {
+        let mut rng = Rng::new(7);
+        let values: Vec<i64> = (0..1000).map(|_| rng.next(-100, 100)).collect();
+        assert!(values.iter().all(|v| (-100..=100).contains(v)));
+        assert!(values.contains(&-100) && values.iter().any(|&v| v > 0));
+
+        // A single negative value still advances the state.
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        assert_eq!(a.next(-5, -5), -5);
+        b.next(0, 0);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn skip_matches_sequential_draws() {
+        for k in [0u64, 1, 2, 17, 1000, 1_000_000] {
+            let (mut fast, mut slow) = (Rng::new(13502460), Rng::new(13502460));
+            fast.skip(k);
+            for _ in 0..k {
+                slow.raw_next();
+            }
+            assert_eq!(fast, slow, "skip({k})");
+        }
+        // The period is 2^31 - 2, so skipping it returns to the start.
+        let mut rng = Rng::new(7);
+        rng.skip(MODULUS as u64 - 1);
+        assert_eq!(rng.state(), 7);
+    }
+
+    #[test]
+    fn draws_count_raw_steps() {
+        let mut rng = Rng::new(42);
+        rng.next(1, 100);
+        rng.next(5, 5);
+        rng.skip(10);
+        assert_eq!(rng.draws(), 12);
+        let before = rng.clone();
+        rng.next_wide(0, i64::MAX);
+        assert!(rng.draws() >= 15);
+        assert_eq!(Rng::from_state(before.state()), before);
+    }
+
+    #[test]
+    fn state_round_trip_resumes_the_stream() {
+        let mut rng = Rng::new(42);
+        rng.next(1, 100);
+        let checkpoint = rng.state();
+        let first: Vec<i64> = (0..100).map(|_| rng.next(-50, 50)).collect();
+        let mut resumed = Rng::from_state(checkpoint);
+        let second: Vec<i64> = (0..100).map(|_| resumed.next(-50, 50)).collect();
+        assert_eq!(first, second);
+        assert_eq!(resumed.state(), rng.state());
+    }
+
+    #[test]
+    fn wide_draws_stay_in_bounds() {
+        let mut rng = Rng::new(13502460);
+        for (a, b) in [
+            (0, 1 << 40),
+            (-(1 << 45), 1 << 45),
+            (i64::MIN, i64::MAX),
+            (3, 5),
+        ] {
+            for _ in 0..1000 {
+                let v = rng.next_wide(a, b);
+                assert!((a..=b).contains(&v));
+            }
+        }
+        assert_eq!(rng.next_wide(i64::MAX, i64::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn classic_window_matches_next() {
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        for hi in [0, 1, 100, 1 << 20, MODULUS - 2] {
+            assert_eq!(a.next_in(1, hi + 1), b.next(1, hi + 1));
+            assert_eq!(a.next_in(-hi, 0), b.next(-hi, 0));
+        }
+        assert_eq!(a, b);
+        // One value more than MAX_EXACT_RANGE switches to next_wide.
+        let wide = MAX_EXACT_RANGE + 1;
+        assert!((0..100).any(|_| a.next_in(1, wide) != b.next(1, wide)));
+    }
+
+    #[test]
+    fn wide_draws_are_uniform() {
+        // Chi-square over 16 equal buckets of a 2^40-wide range; the 0.1%
+        // critical value for 15 degrees of freedom is 37.7.
+        const BUCKETS: usize = 16;
+        const DRAWS: usize = 32_000;
+        let mut rng = Rng::new(7);
+        let mut counts = [0usize; BUCKETS];
+        for _ in 0..DRAWS {
+            let v = rng.next_wide(0, (1 << 40) - 1);
+            counts[(v >> 36) as usize] += 1;
+        }
+        let expected = (DRAWS / BUCKETS) as f64;
+        let chi2: f64 = counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi2 < 37.7, "chi-square {chi2} for {counts:?}");
+    }
 }
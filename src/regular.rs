@@ -0,0 +1,183 @@
+//! Near-regular networks: the NETGEN skeleton, with every node's random
+//! arcs chosen so that its out-degree is exactly `d`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::index_list::IndexList;
+use crate::netgen::{extra_arc, skeleton_of};
+use crate::{NetgenError, NetgenParams, NetgenResult, NodeId};
+
+/// The nodes [`generate_regular`] could not give out-degree exactly `d`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegularReport {
+    /// Arcs in the network, which replaces `density`.
+    pub arcs: usize,
+    /// Nodes whose skeleton arcs alone exceed `d`. They keep them all, so
+    /// the network stays feasible.
+    pub over_degree: Vec<NodeId>,
+    /// Nodes with fewer than `d` distinct heads to reach, which reach all
+    /// of them.
+    pub short_of_heads: Vec<NodeId>,
+}
+
+/// Generate a network in which every node that may have outgoing arcs
+/// (all but the pure sinks) has out-degree exactly `d`.
+///
+/// The skeleton, and so the supplies and feasibility, is the one
+/// [`generate`](crate::generate) builds for `seed` and `params`. Each node
+/// then gets random arcs to distinct heads it does not reach yet, drawn
+/// from an [`IndexList`] without the pure sources and the node itself,
+/// until its out-degree is `d`. The random arcs continue the generator's
+/// random stream and draw their costs and capacities as rubbish arcs do.
+/// `params.density` only shapes the skeleton, through the random arcs
+/// [`generate`](crate::generate) draws between skeleton arcs: laying it out
+/// takes a whole `generate` run, whose random arcs are then discarded. The
+/// arc count is reported in [`RegularReport::arcs`]. The skeleton arcs come first, in generation
+/// order, then the random arcs by tail.
+///
+/// Nodes the bound cannot be met for are listed in the report rather than
+/// failing. Fails like [`generate`](crate::generate) for a bad seed or
+/// parameters.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate_regular};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let (result, report) = generate_regular(13502460, &params, 6).unwrap();
+/// assert_eq!(report.arcs, result.arcs.len());
+/// let out_of_100 = result.arcs.iter().filter(|a| a.from == 100).count();
+/// assert_eq!(out_of_100, 6); // node 100 is a transshipment node
+/// ```
+pub fn generate_regular(
+    seed: i64,
+    params: &NetgenParams,
+    d: u32,
+) -> Result<(NetgenResult, RegularReport), NetgenError> {
+    crate::check_inputs(seed, params)?;
+    let (mut arcs, supply, mut rng) = skeleton_of(seed, params);
+    let nodes = params.nodes as usize;
+    let first_head = (params.sources - params.tsources) as usize + 1;
+    let last_tail = (params.nodes - params.sinks + params.tsinks) as usize;
+    let mut heads_of: Vec<Vec<usize>> = vec![Vec::new(); nodes + 1];
+    for arc in &arcs {
+        heads_of[arc.from as usize].push(arc.to as usize);
+    }
+
+    let mut report = RegularReport::default();
+    let d = d as usize;
+    for (tail, heads) in heads_of.iter().enumerate().take(last_tail + 1).skip(1) {
+        let node = NodeId::from_dimacs(tail as u64);
+        if heads.len() > d {
+            report.over_degree.push(node);
+            continue;
+        }
+        let mut taken = heads.clone();
+        taken.push(tail);
+        taken.sort_unstable();
+        taken.dedup();
+        let mut handle = IndexList::new(first_head, nodes);
+        for &head in taken.iter().filter(|&&head| head >= first_head) {
+            handle.remove(head);
+        }
+        let missing = d - heads.len();
+        if handle.size() < missing {
+            report.short_of_heads.push(node);
+        }
+        for _ in 0..missing.min(handle.size()) {
+            let head = handle.choose(rng.next(1, handle.size() as i64) as usize);
+            arcs.push(extra_arc(params, tail, head, &mut rng));
+        }
+    }
+    report.arcs = arcs.len();
+    Ok((NetgenResult { arcs, supply }, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{DOC_PARMS, doc_params};
+    use crate::{ArcKind, generate};
+
+    fn out_degrees(result: &NetgenResult) -> Vec<usize> {
+        let mut out = vec![0; result.supply.len() + 1];
+        for arc in &result.arcs {
+            out[arc.from as usize] += 1;
+        }
+        out
+    }
+
+    #[test]
+    fn transshipment_nodes_have_exactly_d_arcs() {
+        for (params, d) in [
+            (DOC_PARMS, 4),
+            ([1000, 20, 40, 3000, 1, 100, 800, 0, 10, 30, 50, 1, 100], 3),
+            ([300, 5, 5, 900, 1, 100, 100, 2, 2, 0, 100, 10, 50], 10),
+        ] {
+            let params = NetgenParams::from_slice(&params).unwrap();
+            let (result, report) = generate_regular(7, &params, d).unwrap();
+            assert_eq!(report.arcs, result.arcs.len());
+            assert!(report.short_of_heads.is_empty());
+            let out = out_degrees(&result);
+            let last_tail = (params.nodes - params.sinks + params.tsinks) as usize;
+            for (node, &degree) in out.iter().enumerate().skip(1) {
+                let id = NodeId::from_dimacs(node as u64);
+                if node > last_tail {
+                    assert_eq!(degree, 0, "pure sink {node}");
+                } else if report.over_degree.contains(&id) {
+                    assert!(degree > d as usize);
+                } else {
+                    assert_eq!(degree, d as usize, "node {node}");
+                }
+            }
+            // No node gets a repeated head or an arc into a pure source.
+            let mut pairs: Vec<_> = result.arcs.iter().map(|a| (a.from, a.to)).collect();
+            pairs.sort_unstable();
+            pairs.dedup();
+            assert_eq!(pairs.len(), result.arcs.len());
+            assert!(result.terminal_violations(&params).is_empty());
+            assert_eq!(crate::verify_flow(&result, &result.skeleton_flow()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn the_skeleton_is_that_of_generate() {
+        let params = doc_params();
+        let reference = generate(13502460, &params).unwrap();
+        let (result, _) = generate_regular(13502460, &params, 5).unwrap();
+        assert_eq!(result.supply, reference.supply);
+        let skeleton = |r: &NetgenResult| {
+            (r.arcs.iter())
+                .filter(|a| a.kind == ArcKind::Skeleton)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(skeleton(&result), skeleton(&reference));
+    }
+
+    #[test]
+    fn exceptions_are_reported() {
+        let params = doc_params();
+        // With d = 0 every node with a skeleton arc is over the bound.
+        let (result, report) = generate_regular(1, &params, 0).unwrap();
+        assert!(result.arcs.iter().all(|a| a.kind == ArcKind::Skeleton));
+        let tails: Vec<_> = (1..=505)
+            .filter(|&t| result.arcs.iter().any(|a| a.from == t))
+            .map(NodeId::from_dimacs)
+            .collect();
+        assert_eq!(report.over_degree, tails);
+        // Heads are nodes 8..=512, so nodes from 8 on have 504 to reach.
+        let (_, report) = generate_regular(1, &params, 505).unwrap();
+        assert!(report.over_degree.is_empty());
+        let expected: Vec<_> = (8..=505).map(NodeId::from_dimacs).collect();
+        assert_eq!(report.short_of_heads, expected);
+        let (_, report) = generate_regular(1, &params, 504).unwrap();
+        assert!(report.short_of_heads.is_empty());
+        assert_eq!(
+            generate_regular(0, &params, 3).unwrap_err(),
+            NetgenError::BadSeed
+        );
+    }
+}
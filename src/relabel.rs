@@ -0,0 +1,167 @@
+//! Random node relabeling, hiding the sources-first, sinks-last numbering
+//! of generated networks from solver heuristics.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::seed::shuffle;
+use crate::{Arc, NetgenResult};
+
+/// A permutation of the node labels `1..=n`, mapping between the labels of
+/// a generated network and a relabeled copy (see [`NetgenResult::relabel`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelabelMap {
+    /// `new[v - 1]` is the new label of original node `v`.
+    new: Vec<u64>,
+    /// `original[v - 1]` is the original label of new node `v`.
+    original: Vec<u64>,
+}
+
+impl RelabelMap {
+    /// The random permutation of `nodes` labels determined by `seed`, as
+    /// applied by [`DimacsOptions::relabel_nodes`](crate::DimacsOptions::relabel_nodes).
+    ///
+    /// The labels `1..=nodes` are shuffled like the arcs of
+    /// [`ArcOrder::Shuffled`](crate::ArcOrder::Shuffled); position `v - 1`
+    /// of the shuffled list is the original label of new node `v`. This
+    /// mapping is stable across crate versions.
+    pub fn from_seed(seed: u64, nodes: usize) -> Self {
+        let mut original: Vec<u64> = (1..=nodes as u64).collect();
+        shuffle(&mut original, seed);
+        Self::from_original_labels(original)
+    }
+
+    fn from_original_labels(original: Vec<u64>) -> Self {
+        let mut new = vec![0; original.len()];
+        for (i, &v) in original.iter().enumerate() {
+            new[v as usize - 1] = i as u64 + 1;
+        }
+        RelabelMap { new, original }
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.new.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new.is_empty()
+    }
+
+    /// The new label of original node `node`.
+    ///
+    /// # Panics
+    ///
+    /// If `node` is not in `1..=len`.
+    pub fn new_label(&self, node: u64) -> u64 {
+        self.new[node as usize - 1]
+    }
+
+    /// The original label of new node `node`, e.g. to map a solution of
+    /// the relabeled instance back.
+    ///
+    /// # Panics
+    ///
+    /// If `node` is not in `1..=len`.
+    pub fn original_label(&self, node: u64) -> u64 {
+        self.original[node as usize - 1]
+    }
+
+    /// The map in the opposite direction, from new labels to original ones.
+    pub fn inverse(&self) -> RelabelMap {
+        RelabelMap {
+            new: self.original.clone(),
+            original: self.new.clone(),
+        }
+    }
+}
+
+impl NetgenResult {
+    /// Rename every node `v` to `map.new_label(v)`: arc endpoints are
+    /// rewritten and supplies move with their nodes. Arcs keep their order,
+    /// costs, capacities and multipliers, so the relabeled network poses
+    /// the same problem.
+    ///
+    /// # Panics
+    ///
+    /// If `map` does not have one label per node.
+    pub fn relabel(&self, map: &RelabelMap) -> NetgenResult {
+        assert_eq!(map.len(), self.supply.len(), "relabel map size mismatch");
+        let supply = map
+            .original
+            .iter()
+            .map(|&v| self.supply[v as usize - 1])
+            .collect();
+        let arcs = self
+            .arcs
+            .iter()
+            .map(|arc| Arc {
+                from: map.new_label(arc.from),
+                to: map.new_label(arc.to),
+                ..arc.clone()
+            })
+            .collect();
+        NetgenResult { arcs, supply }
+    }
+
+    /// Relabel the nodes with the random permutation
+    /// [`RelabelMap::from_seed`]`(seed, nodes)`, returning the relabeled
+    /// network and the map.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let (relabeled, map) = result.relabeled(7);
+    /// assert_eq!(relabeled.relabel(&map.inverse()), result);
+    /// ```
+    pub fn relabeled(&self, seed: u64) -> (NetgenResult, RelabelMap) {
+        let map = RelabelMap::from_seed(seed, self.supply.len());
+        (self.relabel(&map), map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArcKind;
+
+    #[test]
+    fn labels_round_trip() {
+        let map = RelabelMap::from_seed(3, 10);
+        let mut labels: Vec<u64> = (1..=10).map(|v| map.new_label(v)).collect();
+        for v in 1..=10 {
+            assert_eq!(map.original_label(map.new_label(v)), v);
+            assert_eq!(map.inverse().new_label(map.new_label(v)), v);
+        }
+        labels.sort_unstable();
+        assert_eq!(labels, (1..=10).collect::<Vec<_>>());
+        assert_eq!(RelabelMap::from_seed(3, 10), map);
+        assert_ne!(RelabelMap::from_seed(4, 10), map);
+    }
+
+    #[test]
+    fn supplies_move_with_their_nodes() {
+        let result = NetgenResult {
+            arcs: vec![Arc {
+                from: 1,
+                to: 3,
+                cost: 5,
+                capacity: 7,
+                multiplier: None,
+                uncapacitated: false,
+                kind: ArcKind::Unknown,
+                hicost: false,
+            }],
+            supply: vec![4, 0, -4],
+        };
+        let (relabeled, map) = result.relabeled(11);
+        let arc = &relabeled.arcs[0];
+        assert_eq!((arc.from, arc.to), (map.new_label(1), map.new_label(3)));
+        assert_eq!(relabeled.supply[map.new_label(1) as usize - 1], 4);
+        assert_eq!(relabeled.supply[map.new_label(3) as usize - 1], -4);
+    }
+}
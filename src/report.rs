@@ -0,0 +1,86 @@
+//! Counters describing how a network was generated.
+
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use crate::{Arc, NetgenParams, RngPhase};
+
+/// Statistics gathered while generating a network.
+///
+/// Collecting them does not change the generated arcs; see
+/// [`generate_with_report`](crate::generate_with_report).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerationReport {
+    /// Arcs laid down by the skeleton (chain, sink and assignment arcs).
+    pub skeleton_arcs: usize,
+    /// Random ("rubbish") arcs added after the skeleton arcs of each tail,
+    /// including any added by [`Shortfall::TopUp`](crate::Shortfall::TopUp).
+    pub random_arcs: usize,
+    /// Arcs added by [`Shortfall::TopUp`](crate::Shortfall::TopUp), or by
+    /// the final pass of
+    /// [`GenerateOptions::max_out_degree`](crate::GenerateOptions::max_out_degree),
+    /// after the reference generation finished.
+    pub topped_up_arcs: usize,
+    /// Random arc draws discarded by the BCJL bounds check. They occur,
+    /// as in the reference, when a tail is given more random arcs than it
+    /// has heads left, which happens once the average out-degree
+    /// (`density / nodes`) nears half the heads a tail can reach. Later
+    /// tails make up for drops while they have heads to spare, so only
+    /// drops near [`NetgenParams::max_arcs`](crate::NetgenParams::max_arcs)
+    /// leave a [`shortfall`](Self::shortfall).
+    pub dropped_arcs: usize,
+    /// The requested arc count (`density`).
+    pub requested_arcs: usize,
+    /// How many arcs short of `density` the network ended up.
+    pub shortfall: usize,
+    /// Demand of each sink, as a non-negative amount, in node order (the
+    /// last `sinks` nodes). Sums to `supply`.
+    pub sink_demands: Vec<i64>,
+    /// Sources whose supply was smaller than their number of sinks. The
+    /// reference then divides nothing among the sinks and gives the whole
+    /// supply to the source's first sink, so demands are more lopsided
+    /// than usual, though never positive. Happens with small supplies and
+    /// many sinks per source.
+    pub undivided_sources: usize,
+    /// Wall-clock time spent generating the network, measured with
+    /// `std::time::Instant`; `None` without the `std` feature. Unlike the
+    /// counters it differs from run to run.
+    pub duration: Option<Duration>,
+}
+
+impl GenerationReport {
+    pub(crate) fn finish(&mut self, params: &NetgenParams, arcs: &[Arc], skeleton_arcs: usize) {
+        self.skeleton_arcs = skeleton_arcs;
+        self.random_arcs = arcs.len() - skeleton_arcs;
+        self.requested_arcs = params.density as usize;
+        self.shortfall = self.requested_arcs.saturating_sub(arcs.len());
+    }
+
+    pub(crate) fn record_demands(&mut self, params: &NetgenParams, supply: &[i64]) {
+        let first_sink = (params.nodes - params.sinks) as usize;
+        self.sink_demands = supply[first_sink..].iter().map(|&s| -s).collect();
+    }
+
+    /// Arcs generated per second of [`duration`](Self::duration), if it was
+    /// measured and is not zero.
+    pub fn arcs_per_second(&self) -> Option<f64> {
+        let seconds = self.duration?.as_secs_f64();
+        let arcs = self.skeleton_arcs + self.random_arcs;
+        (seconds > 0.0).then(|| arcs as f64 / seconds)
+    }
+}
+
+/// A progress update passed to the callback of
+/// [`generate_with_progress`](crate::generate_with_progress).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The phase generation is in.
+    pub phase: RngPhase,
+    /// Sources whose skeleton and random arcs are done.
+    pub sources_done: usize,
+    /// Sources in total (for assignment problems, the matched pairs).
+    pub sources: usize,
+    /// Arcs generated so far.
+    pub arcs: usize,
+}
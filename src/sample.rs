@@ -0,0 +1,289 @@
+//! Random parameter sets for fuzzing and property-testing flow solvers.
+//!
+//! [`NetgenParams::random`] draws parameters that pass
+//! [`NetgenParams::validate`] by construction: every field is sampled from an
+//! interval derived from the fields drawn before it, so no draw is ever
+//! rejected.
+
+use std::ops::RangeInclusive;
+
+use crate::{NetgenParams, Rng};
+
+/// Source of uniform integers for [`NetgenParams::random`].
+///
+/// Implemented for the NETGEN [`Rng`]; implement it for other generators
+/// (or for a fuzzer's byte stream) to drive the sampler from them.
+pub trait RngLike {
+    /// A uniform integer in `lo..=hi`. Callers guarantee `lo <= hi`.
+    fn range(&mut self, lo: i64, hi: i64) -> i64;
+}
+
+impl RngLike for Rng {
+    /// Uses [`Rng::next`] where one step covers the range, so narrow
+    /// bounds keep their samples, and [`Rng::next_wide`] beyond that.
+    fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        self.next_in(lo, hi)
+    }
+}
+
+/// Upper limits for [`NetgenParams::random`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamBounds {
+    /// Largest node count. At least 8.
+    pub max_nodes: i64,
+    /// Largest arc count. At least 8.
+    pub max_density: i64,
+    /// Largest total supply. At least 4.
+    pub max_supply: i64,
+    /// Range that `mincost..=maxcost` is drawn from.
+    pub costs: RangeInclusive<i64>,
+    /// Range that `mincap..=maxcap` is drawn from.
+    pub capacities: RangeInclusive<i64>,
+    /// Percentage of samples shaped as assignment problems (0–100).
+    pub assignment_pct: i64,
+}
+
+impl Default for ParamBounds {
+    fn default() -> Self {
+        ParamBounds {
+            max_nodes: 1000,
+            max_density: 20_000,
+            max_supply: 100_000,
+            costs: 1..=10_000,
+            capacities: 1..=1000,
+            assignment_pct: 20,
+        }
+    }
+}
+
+impl ParamBounds {
+    fn check(&self) {
+        assert!(self.max_nodes >= 8, "ParamBounds::max_nodes must be >= 8");
+        assert!(
+            self.max_density >= 8,
+            "ParamBounds::max_density must be >= 8"
+        );
+        assert!(self.max_supply >= 4, "ParamBounds::max_supply must be >= 4");
+        assert!(!self.costs.is_empty(), "ParamBounds::costs is empty");
+        assert!(
+            !self.capacities.is_empty(),
+            "ParamBounds::capacities is empty"
+        );
+        assert!(
+            (0..=100).contains(&self.assignment_pct),
+            "ParamBounds::assignment_pct must be in 0..=100"
+        );
+    }
+}
+
+impl NetgenParams {
+    /// Draw a random parameter set within `bounds`.
+    ///
+    /// The result always passes [`validate`](Self::validate). Every problem
+    /// leaves at least four nodes that can be arc heads, since NETGEN (like
+    /// the C original) may never terminate with fewer. About
+    /// `bounds.assignment_pct` percent of samples are assignment problems;
+    /// the rest are general transshipment networks, which are max-flow
+    /// problems whenever the cost range collapses to `1..=1`.
+    ///
+    /// # Panics
+    ///
+    /// If `bounds` is too small to hold such a problem: fewer than 8 nodes
+    /// or arcs, a supply below 4, an empty cost or capacity range, or an
+    /// `assignment_pct` outside `0..=100`.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, ParamBounds, Rng};
+    ///
+    /// let mut rng = Rng::new(42);
+    /// let params = NetgenParams::random(&mut rng, &ParamBounds::default());
+    /// assert_eq!(params.validate(), Ok(()));
+    /// ```
+    pub fn random(rng: &mut impl RngLike, bounds: &ParamBounds) -> NetgenParams {
+        bounds.check();
+        let mut params = if rng.range(1, 100) <= bounds.assignment_pct {
+            random_assignment(rng, bounds)
+        } else {
+            random_network(rng, bounds)
+        };
+        let (lo, hi) = (*bounds.costs.start(), *bounds.costs.end());
+        params.mincost = rng.range(lo, hi);
+        params.maxcost = rng.range(params.mincost, hi);
+        let (lo, hi) = (*bounds.capacities.start(), *bounds.capacities.end());
+        params.mincap = rng.range(lo, hi);
+        params.maxcap = rng.range(params.mincap, hi);
+        params.hicost_pct = rng.range(0, 100);
+        params.capacitated_pct = rng.range(0, 100);
+        params
+    }
+}
+
+/// `h` sources and `h` sinks of one unit each, with `4 <= h`.
+fn random_assignment(rng: &mut impl RngLike, bounds: &ParamBounds) -> NetgenParams {
+    let most = (bounds.max_nodes / 2)
+        .min(bounds.max_density / 2)
+        .min(bounds.max_supply);
+    let half = rng.range(4, most);
+    let density = rng.range(2 * half, bounds.max_density.min(half * half));
+    sized(2 * half, half, half, density, half, 0, 0)
+}
+
+/// A general network with at least four possible arc heads.
+fn random_network(rng: &mut impl RngLike, bounds: &ParamBounds) -> NetgenParams {
+    let nodes = rng.range(8, bounds.max_nodes.min(bounds.max_density));
+    let sources = rng.range(1, (nodes - 4).min(bounds.max_supply));
+    let sinks = rng.range(1, nodes - sources);
+    let tsources = rng.range(0, sources);
+    let tsinks = rng.range(0, sinks);
+    let mut params = sized(nodes, sources, sinks, nodes, sources, tsources, tsinks);
+    if params.max_arcs() < nodes {
+        // Letting every sink emit arcs always leaves room for `nodes` arcs.
+        params.tsinks = sinks;
+    }
+    params.density = rng.range(nodes, bounds.max_density.min(params.max_arcs()));
+    params.supply = rng.range(sources, bounds.max_supply);
+    params
+}
+
+fn sized(
+    nodes: i64,
+    sources: i64,
+    sinks: i64,
+    density: i64,
+    supply: i64,
+    tsources: i64,
+    tsinks: i64,
+) -> NetgenParams {
+    NetgenParams {
+        nodes,
+        sources,
+        sinks,
+        density,
+        mincost: 0,
+        maxcost: 0,
+        supply,
+        tsources,
+        tsinks,
+        hicost_pct: 0,
+        capacitated_pct: 0,
+        mincap: 0,
+        maxcap: 0,
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for NetgenParams {
+    /// Samples with [`ParamBounds::default`], reading the fuzzer's bytes as
+    /// the random stream. Exhausted input yields the smallest choices.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        struct Bytes<'b, 'a>(&'b mut arbitrary::Unstructured<'a>);
+
+        impl RngLike for Bytes<'_, '_> {
+            fn range(&mut self, lo: i64, hi: i64) -> i64 {
+                self.0.int_in_range(lo..=hi).unwrap_or(lo)
+            }
+        }
+
+        Ok(NetgenParams::random(&mut Bytes(u), &ParamBounds::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProblemType;
+
+    #[test]
+    fn samples_always_validate() {
+        let bounds = ParamBounds::default();
+        let mut rng = Rng::new(13502460);
+        let (mut assignment, mut general) = (0, 0);
+        for _ in 0..10_000 {
+            let params = NetgenParams::random(&mut rng, &bounds);
+            assert_eq!(params.validate(), Ok(()), "{params}");
+            assert!(params.nodes <= bounds.max_nodes, "{params}");
+            assert!(params.density <= bounds.max_density, "{params}");
+            assert!(params.supply <= bounds.max_supply, "{params}");
+            match params.problem_type() {
+                ProblemType::Assignment => assignment += 1,
+                _ => general += 1,
+            }
+        }
+        assert!(assignment > 1000, "{assignment} assignment samples");
+        assert!(general > 6000, "{general} general samples");
+
+        // Cost and capacity ranges wider than one step of the generator.
+        for wide in [-(1 << 40)..=1 << 40, i64::MIN / 2..=i64::MAX / 2] {
+            let bounds = ParamBounds {
+                costs: wide.clone(),
+                capacities: 1..=*wide.end(),
+                ..ParamBounds::default()
+            };
+            let mut highest = i64::MIN;
+            for _ in 0..1000 {
+                let params = NetgenParams::random(&mut rng, &bounds);
+                assert_eq!(params.validate(), Ok(()), "{params}");
+                assert!(wide.contains(&params.mincost), "{params}");
+                assert!(wide.contains(&params.maxcost), "{params}");
+                highest = highest.max(params.mincost);
+            }
+            assert!(highest > wide.start() + (1 << 31), "highest cost {highest}");
+        }
+    }
+
+    #[test]
+    fn tight_bounds_still_validate() {
+        let bounds = ParamBounds {
+            max_nodes: 8,
+            max_density: 8,
+            max_supply: 4,
+            costs: 1..=1,
+            capacities: 5..=5,
+            assignment_pct: 50,
+        };
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let params = NetgenParams::random(&mut rng, &bounds);
+            assert_eq!(params.validate(), Ok(()), "{params}");
+            assert_eq!(params.density, 8);
+        }
+    }
+
+    #[test]
+    fn samples_generate() {
+        let bounds = ParamBounds {
+            max_nodes: 200,
+            max_density: 2000,
+            ..ParamBounds::default()
+        };
+        let mut rng = Rng::new(1);
+        for seed in 1..=200 {
+            let params = NetgenParams::random(&mut rng, &bounds);
+            let result = params.generate(seed).unwrap();
+            result.check_invariants(&params).unwrap();
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_params_validate() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let mut rng = Rng::new(99);
+        for len in [0, 1, 7, 64, 512] {
+            let bytes: Vec<u8> = (0..len).map(|_| rng.next(0, 255) as u8).collect();
+            let params = NetgenParams::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert_eq!(params.validate(), Ok(()), "{params}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "max_nodes")]
+    fn rejects_tiny_bounds() {
+        let bounds = ParamBounds {
+            max_nodes: 7,
+            ..ParamBounds::default()
+        };
+        NetgenParams::random(&mut Rng::new(1), &bounds);
+    }
+}
@@ -0,0 +1,188 @@
+//! Seed handling: folding arbitrary `u64` seeds into the range accepted by
+//! the NETGEN LCG, deriving per-instance seeds from a master seed, and
+//! drawing fresh seeds from process entropy.
+
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hasher};
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::limits::MAX_SEED;
+
+/// Increment of the splitmix64 state between consecutive outputs.
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// One step of the splitmix64 mixing function.
+pub(crate) fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(GOLDEN_GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The splitmix64 stream started at a seed.
+pub(crate) struct SplitMix {
+    state: u64,
+}
+
+impl SplitMix {
+    pub(crate) fn new(seed: u64) -> Self {
+        SplitMix { state: seed }
+    }
+
+    /// A value in `0..n`, by multiply-shift, for `n >= 1`.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        let x = splitmix64(self.state);
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        ((u128::from(x) * n as u128) >> 64) as usize
+    }
+}
+
+/// Fisher–Yates shuffle of `items` driven by the splitmix64 stream started
+/// at `seed`, as documented on [`ArcOrder::Shuffled`](crate::ArcOrder::Shuffled).
+pub(crate) fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut stream = SplitMix::new(seed);
+    for i in (1..items.len()).rev() {
+        items.swap(i, stream.below(i + 1));
+    }
+}
+
+/// Map an arbitrary `u64` onto a valid NETGEN seed in `1..=MAX_SEED`.
+///
+/// The mapping is `1 + splitmix64(seed) % MAX_SEED`. It is part of the
+/// crate's stable API: the same input always yields the same seed, across
+/// platforms and crate versions.
+pub fn fold_seed(seed: u64) -> i64 {
+    1 + (splitmix64(seed) % MAX_SEED as u64) as i64
+}
+
+/// A reproducible sequence of NETGEN seeds derived from one master seed.
+///
+/// Seed `i` is `1 + splitmix64(master + i * 0x9e3779b97f4a7c15) % MAX_SEED`
+/// (wrapping arithmetic), i.e. the splitmix64 output stream started at
+/// `master`, folded into `1..=MAX_SEED`. Element 0 equals
+/// `fold_seed(master)`. This mapping is stable across crate versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedSequence {
+    master: u64,
+}
+
+impl SeedSequence {
+    pub fn new(master: u64) -> Self {
+        SeedSequence { master }
+    }
+
+    pub fn master(&self) -> u64 {
+        self.master
+    }
+
+    /// The `i`-th derived seed (0-based).
+    pub fn nth(&self, i: u64) -> i64 {
+        fold_seed(self.master.wrapping_add(i.wrapping_mul(GOLDEN_GAMMA)))
+    }
+
+    /// Iterate over the derived seeds, starting at index 0.
+    pub fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        (0..).map(|i| self.nth(i))
+    }
+}
+
+/// Draw a valid NETGEN seed from process entropy.
+///
+/// Uses the randomly keyed hasher behind `HashMap` mixed with the clock and
+/// a per-process counter, so no external RNG crate is needed.
+#[cfg(feature = "std")]
+pub(crate) fn entropy_seed() -> i64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    hasher.write_u128(nanos);
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    fold_seed(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, generate, generate_family, generate_random, generate_seeded};
+
+    #[test]
+    fn fold_seed_is_stable() {
+        // Pinned values: changing these breaks reproducibility for users.
+        assert_eq!(fold_seed(0), 60_845_732);
+        assert_eq!(fold_seed(1), 1_294_668_924);
+        assert_eq!(fold_seed(42), 659_044_154);
+        assert_eq!(fold_seed(u64::MAX), 785_052_171);
+    }
+
+    #[test]
+    fn fold_seed_in_range() {
+        for s in [0, 1, 2, 1 << 31, 1 << 32, u64::MAX - 1, u64::MAX] {
+            let folded = fold_seed(s);
+            assert!((1..=MAX_SEED).contains(&folded), "{s} -> {folded}");
+        }
+    }
+
+    #[test]
+    fn generate_seeded_accepts_extremes() {
+        let params =
+            NetgenParams::from_slice(&[100, 5, 5, 400, 1, 50, 100, 1, 1, 10, 50, 5, 50]).unwrap();
+        for s in [0, u64::MAX] {
+            let result = generate_seeded(s, &params).unwrap();
+            assert_eq!(result, generate(fold_seed(s), &params).unwrap());
+        }
+    }
+
+    #[test]
+    fn generate_random_seed_reproduces() {
+        let params =
+            NetgenParams::from_slice(&[100, 5, 5, 400, 1, 50, 100, 1, 1, 10, 50, 5, 50]).unwrap();
+        let (seed, result) = generate_random(&params).unwrap();
+        assert_eq!(result, generate(seed, &params).unwrap());
+    }
+
+    #[test]
+    fn seed_sequence_is_stable() {
+        // Pinned values: the derivation is part of the stable API.
+        let seq = SeedSequence::new(42);
+        let seeds: Vec<i64> = seq.iter().take(10).collect();
+        assert_eq!(
+            seeds,
+            vec![
+                659044154, 1445363156, 811154611, 1857973141, 1262060891, 898736893, 618465746,
+                1383532395, 1733582774, 584242329
+            ]
+        );
+        assert_eq!(seq.nth(0), fold_seed(42));
+    }
+
+    #[test]
+    fn generate_family_uses_sequence() {
+        let small =
+            NetgenParams::from_slice(&[100, 5, 5, 400, 1, 50, 100, 1, 1, 10, 50, 5, 50]).unwrap();
+        let asn =
+            NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap();
+        let family = generate_family(7, &[small.clone(), asn.clone(), small.clone()]).unwrap();
+        let seq = SeedSequence::new(7);
+        assert_eq!(family.len(), 3);
+        for (i, ((seed, result), params)) in family.iter().zip([&small, &asn, &small]).enumerate() {
+            assert_eq!(*seed, seq.nth(i as u64));
+            assert_eq!(*result, generate(*seed, params).unwrap());
+        }
+        assert_ne!(family[0].1, family[2].1);
+    }
+
+    #[test]
+    fn entropy_seed_in_range() {
+        for _ in 0..100 {
+            assert!((1..=MAX_SEED).contains(&entropy_seed()));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Single-source shortest-path instances: the arcs of a network weighted by
+//! their costs, capacities and supplies dropped.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::{ArcId, NetgenResult, NodeId};
+
+/// A shortest-path problem derived from a network by
+/// [`NetgenResult::to_shortest_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPathInstance {
+    pub nodes: usize,
+    pub source: NodeId,
+    /// `(from, to, weight)` per arc of the network, in order, with 1-based
+    /// DIMACS node ids as in [`Arc`](crate::Arc).
+    pub arcs: Vec<(u64, u64, i64)>,
+}
+
+/// Why [`NetgenResult::to_shortest_path`] could not build an instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortestPathError {
+    /// The source is not a node of the network.
+    SourceOutOfRange { source: NodeId, nodes: usize },
+    /// The first arc with a negative cost, without `allow_negative`.
+    NegativeCost { arc: ArcId, cost: i64 },
+}
+
+impl fmt::Display for ShortestPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortestPathError::SourceOutOfRange { source, nodes } => write!(
+                f,
+                "source node {} is outside the network's {nodes} nodes",
+                source.to_dimacs()
+            ),
+            ShortestPathError::NegativeCost { arc, cost } => {
+                write!(f, "arc {} has negative cost {cost}", arc.index())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShortestPathError {}
+
+impl NetgenResult {
+    /// The single-source shortest-path problem from `source` on this
+    /// network: every arc, weighted by its cost. Capacities, multipliers
+    /// and supplies are dropped.
+    ///
+    /// Negative costs are rejected unless `allow_negative` is set, since
+    /// many shortest-path codes assume nonnegative weights; NETGEN networks
+    /// have them only when `mincost` is negative. Fails as well for a
+    /// source outside the network.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, NodeId, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let instance = result.to_shortest_path(NodeId::from_dimacs(1), false).unwrap();
+    /// let mut out = Vec::new();
+    /// netgen_rs::write_dimacs_sp(&mut out, &instance).unwrap();
+    /// assert!(String::from_utf8(out).unwrap().starts_with("p sp 512 2000\nn 1\na "));
+    /// ```
+    pub fn to_shortest_path(
+        &self,
+        source: NodeId,
+        allow_negative: bool,
+    ) -> Result<ShortestPathInstance, ShortestPathError> {
+        let nodes = self.supply.len();
+        if source.index() >= nodes {
+            return Err(ShortestPathError::SourceOutOfRange { source, nodes });
+        }
+        let negative = (!allow_negative)
+            .then(|| self.arcs.iter().position(|a| a.cost < 0))
+            .flatten();
+        if let Some(i) = negative {
+            return Err(ShortestPathError::NegativeCost {
+                arc: ArcId(i as u32),
+                cost: self.arcs[i].cost,
+            });
+        }
+        Ok(ShortestPathInstance {
+            nodes,
+            source,
+            arcs: self.arcs.iter().map(|a| (a.from, a.to, a.cost)).collect(),
+        })
+    }
+}
+
+/// Write `instance` in the DIMACS shortest-path format: `p sp <nodes>
+/// <arcs>`, `n <source>`, then an `a <from> <to> <weight>` line per arc.
+pub fn write_dimacs_sp(w: &mut impl Write, instance: &ShortestPathInstance) -> io::Result<()> {
+    writeln!(w, "p sp {} {}", instance.nodes, instance.arcs.len())?;
+    writeln!(w, "n {}", instance.source.to_dimacs())?;
+    for (from, to, weight) in &instance.arcs {
+        writeln!(w, "a {from} {to} {weight}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, generate};
+
+    #[test]
+    fn negative_costs_need_the_flag() {
+        let params =
+            NetgenParams::from_slice(&[200, 5, 5, 1000, -50, 50, 500, 2, 2, 20, 50, 10, 100])
+                .unwrap();
+        let result = generate(99999, &params).unwrap();
+        let first = result.arcs.iter().position(|a| a.cost < 0).unwrap();
+        assert_eq!(
+            result.to_shortest_path(NodeId(0), false),
+            Err(ShortestPathError::NegativeCost {
+                arc: ArcId(first as u32),
+                cost: result.arcs[first].cost
+            })
+        );
+        let instance = result.to_shortest_path(NodeId(0), true).unwrap();
+        assert_eq!(instance.arcs.len(), result.arcs.len());
+        assert_eq!(
+            result.to_shortest_path(NodeId(200), true),
+            Err(ShortestPathError::SourceOutOfRange {
+                source: NodeId(200),
+                nodes: 200
+            })
+        );
+    }
+}
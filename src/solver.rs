@@ -0,0 +1,408 @@
+//! A reference min-cost flow solver, for test oracles on small instances.
+//!
+//! Successive shortest paths with node potentials: ship supply from a
+//! virtual source to a virtual sink along cheapest residual paths (Dijkstra
+//! on reduced costs) until every supply is routed. Simple rather than fast;
+//! intended for networks up to about 10⁵ arcs.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+
+use crate::NetgenResult;
+
+/// An optimal flow returned by [`solve_min_cost_flow`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// Total cost, `sum cost_a * flow_a`.
+    pub objective: i64,
+    /// Flow on each arc, in [`NetgenResult::arcs`] order.
+    pub flow: Vec<i64>,
+    /// Node potentials (duals) proving optimality, 0-indexed like
+    /// [`NetgenResult::supply`]. With reduced cost
+    /// `cost + potentials[from - 1] - potentials[to - 1]`, arcs below
+    /// capacity have reduced cost ≥ 0 and arcs with positive flow ≤ 0.
+    pub potentials: Vec<i64>,
+}
+
+/// Why [`solve_min_cost_flow`] found no solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// Supplies and demands do not cancel out.
+    Unbalanced { sum: i64 },
+    /// The arcs cannot carry all supply; `shipped` of `required` units
+    /// reached the sinks.
+    Infeasible { shipped: i64, required: i64 },
+    /// A cycle of negative cost has spare capacity. The successive
+    /// shortest path method does not handle this case.
+    NegativeCycle,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Unbalanced { sum } => write!(f, "supplies sum to {sum}, expected 0"),
+            SolveError::Infeasible { shipped, required } => {
+                write!(
+                    f,
+                    "infeasible: only {shipped} of {required} units can be routed"
+                )
+            }
+            SolveError::NegativeCycle => write!(f, "network has a negative-cost cycle"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Solve the min-cost flow problem of a network exactly: route every
+/// node's supply to the demands within the arc capacities at minimum total
+/// cost.
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, generate, solve_min_cost_flow, verify_flow};
+///
+/// let params = NetgenParams::from_slice(&[
+///     64, 4, 4, 300, 1, 100, 200, 1, 1, 20, 80, 10, 50,
+/// ]).unwrap();
+/// let result = generate(13502460, &params).unwrap();
+/// let solution = solve_min_cost_flow(&result).unwrap();
+/// assert_eq!(verify_flow(&result, &solution.flow), Ok(()));
+/// ```
+pub fn solve_min_cost_flow(result: &NetgenResult) -> Result<Solution, SolveError> {
+    let sum: i64 = result.supply.iter().sum();
+    if sum != 0 {
+        return Err(SolveError::Unbalanced { sum });
+    }
+    let nodes = result.supply.len();
+    let (source, sink) = (nodes, nodes + 1);
+    let mut graph = Residual::new(nodes + 2);
+    for arc in &result.arcs {
+        graph.add(
+            arc.from as usize - 1,
+            arc.to as usize - 1,
+            arc.capacity.max(0),
+            arc.cost,
+        );
+    }
+    let mut required = 0;
+    for (v, &s) in result.supply.iter().enumerate() {
+        if s > 0 {
+            graph.add(source, v, s, 0);
+            required += s;
+        } else if s < 0 {
+            graph.add(v, sink, -s, 0);
+        }
+    }
+
+    let mut potential = graph
+        .bellman_ford(source)
+        .ok_or(SolveError::NegativeCycle)?;
+    let mut shipped = 0;
+    while shipped < required {
+        let (dist, parent) = graph.dijkstra(source, &potential);
+        if dist[sink] == INF {
+            break;
+        }
+        for (p, &d) in potential.iter_mut().zip(&dist) {
+            if d != INF {
+                *p += d;
+            }
+        }
+        let mut push = required - shipped;
+        let mut v = sink;
+        while v != source {
+            let e = parent[v];
+            push = push.min(graph.cap[e]);
+            v = graph.to[e ^ 1];
+        }
+        let mut v = sink;
+        while v != source {
+            let e = parent[v];
+            graph.cap[e] -= push;
+            graph.cap[e ^ 1] += push;
+            v = graph.to[e ^ 1];
+        }
+        shipped += push;
+    }
+    if shipped < required {
+        return Err(SolveError::Infeasible { shipped, required });
+    }
+
+    let flow: Vec<i64> = (0..result.arcs.len())
+        .map(|k| graph.cap[2 * k + 1])
+        .collect();
+    let objective = result.arcs.iter().zip(&flow).map(|(a, f)| a.cost * f).sum();
+    // Exact duals: shortest distances in the final residual network from a
+    // virtual root joined to every node at cost 0. The residual network
+    // of an optimal flow has no negative cycle, so these exist.
+    let potentials = graph
+        .bellman_ford_all(nodes)
+        .ok_or(SolveError::NegativeCycle)?;
+    Ok(Solution {
+        objective,
+        flow,
+        potentials,
+    })
+}
+
+const NONE: usize = usize::MAX;
+const INF: i64 = i64::MAX;
+
+/// Residual graph; edge `e` and `e ^ 1` are reverses of each other.
+struct Residual {
+    first: Vec<usize>,
+    next: Vec<usize>,
+    to: Vec<usize>,
+    cap: Vec<i64>,
+    cost: Vec<i64>,
+}
+
+impl Residual {
+    fn new(nodes: usize) -> Self {
+        Residual {
+            first: vec![NONE; nodes],
+            next: Vec::new(),
+            to: Vec::new(),
+            cap: Vec::new(),
+            cost: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, from: usize, to: usize, cap: i64, cost: i64) {
+        for (u, v, c, w) in [(from, to, cap, cost), (to, from, 0, -cost)] {
+            self.next.push(self.first[u]);
+            self.first[u] = self.to.len();
+            self.to.push(v);
+            self.cap.push(c);
+            self.cost.push(w);
+        }
+    }
+
+    /// Edges out of `u` with spare capacity.
+    fn edges(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut e = self.first[u];
+        std::iter::from_fn(move || {
+            while e != NONE {
+                let current = e;
+                e = self.next[e];
+                if self.cap[current] > 0 {
+                    return Some(current);
+                }
+            }
+            None
+        })
+    }
+
+    /// Queue-based Bellman–Ford over residual edges from the given start
+    /// distances. `None` on a negative cycle.
+    fn relax(&self, mut dist: Vec<i64>) -> Option<Vec<i64>> {
+        let n = dist.len();
+        let mut queue: VecDeque<usize> = (0..n).filter(|&v| dist[v] != INF).collect();
+        let mut queued = vec![false; n];
+        let mut count = vec![0usize; n];
+        for &v in &queue {
+            queued[v] = true;
+        }
+        while let Some(u) = queue.pop_front() {
+            queued[u] = false;
+            for e in self.edges(u) {
+                let v = self.to[e];
+                let d = dist[u] + self.cost[e];
+                if d < dist[v] {
+                    dist[v] = d;
+                    if !queued[v] {
+                        count[v] += 1;
+                        if count[v] > n {
+                            return None;
+                        }
+                        queued[v] = true;
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+        Some(dist)
+    }
+
+    /// Shortest distances from `s`, with `INF` replaced by 0 so they are
+    /// usable as initial potentials.
+    fn bellman_ford(&self, s: usize) -> Option<Vec<i64>> {
+        let mut dist = vec![INF; self.first.len()];
+        dist[s] = 0;
+        let dist = self.relax(dist)?;
+        Some(
+            dist.into_iter()
+                .map(|d| if d == INF { 0 } else { d })
+                .collect(),
+        )
+    }
+
+    /// Shortest distances from a virtual root with a 0-cost edge to each
+    /// of the first `nodes` nodes.
+    fn bellman_ford_all(&self, nodes: usize) -> Option<Vec<i64>> {
+        let mut dist = vec![INF; self.first.len()];
+        dist[..nodes].fill(0);
+        let mut dist = self.relax(dist)?;
+        dist.truncate(nodes);
+        Some(dist)
+    }
+
+    /// Dijkstra on reduced costs `cost + potential[u] - potential[v]`.
+    /// Returns reduced distances and the edge into each reached node.
+    fn dijkstra(&self, s: usize, potential: &[i64]) -> (Vec<i64>, Vec<usize>) {
+        let n = self.first.len();
+        let mut dist = vec![INF; n];
+        let mut parent = vec![NONE; n];
+        let mut heap = BinaryHeap::from([Reverse((0i64, s))]);
+        dist[s] = 0;
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if d > dist[u] {
+                continue;
+            }
+            for e in self.edges(u) {
+                let v = self.to[e];
+                let reduced = self.cost[e] + potential[u] - potential[v];
+                let nd = d + reduced;
+                if nd < dist[v] {
+                    dist[v] = nd;
+                    parent[v] = e;
+                    heap.push(Reverse((nd, v)));
+                }
+            }
+        }
+        (dist, parent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::DOC_PARMS;
+    use crate::{Arc, ArcKind, NetgenParams, generate, verify_flow};
+
+    fn arc(from: u64, to: u64, cost: i64, capacity: i64) -> Arc {
+        Arc {
+            from,
+            to,
+            cost,
+            capacity,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Unknown,
+            hicost: false,
+        }
+    }
+
+    #[test]
+    fn hand_computed_five_nodes() {
+        // Five units from node 1 to node 5. Arc 3 -> 5 takes at most four,
+        // so one unit must use the 6-cost path 1-2-4-5; of the rest, two
+        // go 1-2-3-5 (cost 4) and two go 1-3-5 (cost 5): 6 + 8 + 10 = 24.
+        let result = NetgenResult {
+            arcs: vec![
+                arc(1, 2, 1, 4),
+                arc(1, 3, 3, 3),
+                arc(2, 3, 1, 2),
+                arc(2, 4, 4, 3),
+                arc(3, 5, 2, 4),
+                arc(4, 5, 1, 5),
+            ],
+            supply: vec![5, 0, 0, 0, -5],
+        };
+        let solution = solve_min_cost_flow(&result).unwrap();
+        assert_eq!(solution.objective, 24);
+        assert_eq!(solution.flow, [3, 2, 2, 1, 4, 1]);
+    }
+
+    #[test]
+    fn generated_instances_are_solved() {
+        let cases: [&[i64]; 3] = [
+            &DOC_PARMS,
+            &[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+            &[256, 16, 16, 2048, 1, 10000, 16000, 0, 0, 100, 100, 1, 1000],
+        ];
+        for parms in cases {
+            let params = NetgenParams::from_slice(parms).unwrap();
+            for seed in [1, 13502460] {
+                let result = generate(seed, &params).unwrap();
+                let solution = solve_min_cost_flow(&result).unwrap();
+                assert_eq!(verify_flow(&result, &solution.flow), Ok(()));
+                let feasible = result.skeleton_flow();
+                let cost: i64 = result
+                    .arcs
+                    .iter()
+                    .zip(&feasible)
+                    .map(|(a, f)| a.cost * f)
+                    .sum();
+                assert!(solution.objective <= cost);
+            }
+        }
+    }
+
+    #[test]
+    fn complementary_slackness() {
+        let params =
+            NetgenParams::from_slice(&[30, 4, 4, 120, 1, 50, 40, 1, 1, 20, 60, 2, 10]).unwrap();
+        let result = generate(42, &params).unwrap();
+        let solution = solve_min_cost_flow(&result).unwrap();
+        let pi = &solution.potentials;
+        let mut saturated_or_empty = 0;
+        for (a, &f) in result.arcs.iter().zip(&solution.flow) {
+            let reduced = a.cost + pi[a.from as usize - 1] - pi[a.to as usize - 1];
+            if f < a.capacity {
+                assert!(reduced >= 0, "{a:?} flow {f} reduced {reduced}");
+            }
+            if f > 0 {
+                assert!(reduced <= 0, "{a:?} flow {f} reduced {reduced}");
+            }
+            if f == 0 || f == a.capacity {
+                saturated_or_empty += 1;
+            }
+        }
+        assert!(saturated_or_empty > 0);
+        // Strong duality: the dual objective equals the primal one.
+        let dual: i64 = -result
+            .supply
+            .iter()
+            .zip(pi)
+            .map(|(s, p)| s * p)
+            .sum::<i64>()
+            - result
+                .arcs
+                .iter()
+                .map(|a| {
+                    let reduced = a.cost + pi[a.from as usize - 1] - pi[a.to as usize - 1];
+                    if reduced < 0 {
+                        -reduced * a.capacity
+                    } else {
+                        0
+                    }
+                })
+                .sum::<i64>();
+        assert_eq!(dual, solution.objective);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut result = NetgenResult {
+            arcs: vec![arc(1, 2, 1, 3)],
+            supply: vec![5, -4],
+        };
+        assert_eq!(
+            solve_min_cost_flow(&result),
+            Err(SolveError::Unbalanced { sum: 1 })
+        );
+        result.supply = vec![5, -5];
+        assert_eq!(
+            solve_min_cost_flow(&result),
+            Err(SolveError::Infeasible {
+                shipped: 3,
+                required: 5
+            })
+        );
+        result.arcs = vec![arc(1, 2, 1, 5), arc(2, 3, -4, 5), arc(3, 2, 1, 5)];
+        result.supply = vec![5, -5, 0];
+        assert_eq!(solve_min_cost_flow(&result), Err(SolveError::NegativeCycle));
+    }
+}
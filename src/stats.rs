@@ -0,0 +1,125 @@
+//! Summary statistics of a generated instance.
+
+use std::fmt;
+
+use crate::{NetgenParams, NetgenResult, ProblemType};
+
+/// Compact metadata about a generated network.
+///
+/// Displays as a single line of `key=value` fields, e.g.
+/// `type=min-cost-flow nodes=512 arcs=2000 supply=1000 cost=5..500 cap=50..2000 max_out_degree=42 max_in_degree=12`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceStats {
+    pub problem_type: ProblemType,
+    pub nodes: usize,
+    pub arcs: usize,
+    /// Sum of the positive node supplies.
+    pub total_supply: i64,
+    pub min_cost: i64,
+    pub max_cost: i64,
+    pub min_capacity: i64,
+    pub max_capacity: i64,
+    pub max_out_degree: usize,
+    pub max_in_degree: usize,
+}
+
+impl InstanceStats {
+    pub fn new(params: &NetgenParams, result: &NetgenResult) -> Self {
+        let nodes = result.supply.len();
+        let mut out_degree = vec![0usize; nodes + 1];
+        let mut in_degree = vec![0usize; nodes + 1];
+        let mut min_cost = i64::MAX;
+        let mut max_cost = i64::MIN;
+        let mut min_capacity = i64::MAX;
+        let mut max_capacity = i64::MIN;
+        for arc in &result.arcs {
+            out_degree[arc.from as usize] += 1;
+            in_degree[arc.to as usize] += 1;
+            min_cost = min_cost.min(arc.cost);
+            max_cost = max_cost.max(arc.cost);
+            min_capacity = min_capacity.min(arc.capacity);
+            max_capacity = max_capacity.max(arc.capacity);
+        }
+        if result.arcs.is_empty() {
+            (min_cost, max_cost, min_capacity, max_capacity) = (0, 0, 0, 0);
+        }
+        InstanceStats {
+            problem_type: params.problem_type(),
+            nodes,
+            arcs: result.arcs.len(),
+            total_supply: result.supply.iter().filter(|&&s| s > 0).sum(),
+            min_cost,
+            max_cost,
+            min_capacity,
+            max_capacity,
+            max_out_degree: out_degree.into_iter().max().unwrap_or(0),
+            max_in_degree: in_degree.into_iter().max().unwrap_or(0),
+        }
+    }
+}
+
+impl fmt::Display for InstanceStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "type={} nodes={} arcs={} supply={} cost={}..{} cap={}..{} max_out_degree={} max_in_degree={}",
+            self.problem_type,
+            self.nodes,
+            self.arcs,
+            self.total_supply,
+            self.min_cost,
+            self.max_cost,
+            self.min_capacity,
+            self.max_capacity,
+            self.max_out_degree,
+            self.max_in_degree
+        )
+    }
+}
+
+impl NetgenResult {
+    /// Summary statistics for this result.
+    pub fn stats(&self, params: &NetgenParams) -> InstanceStats {
+        InstanceStats::new(params, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Arc, ArcKind};
+
+    #[test]
+    fn stats_of_small_network() {
+        let params =
+            NetgenParams::from_slice(&[4, 1, 1, 4, 1, 10, 5, 0, 0, 0, 100, 1, 10]).unwrap();
+        let arc = |from, to, cost, capacity| Arc {
+            from,
+            to,
+            cost,
+            capacity,
+            multiplier: None,
+            uncapacitated: false,
+            kind: ArcKind::Unknown,
+            hicost: false,
+        };
+        let result = NetgenResult {
+            arcs: vec![
+                arc(1, 2, 3, 5),
+                arc(1, 3, 7, 2),
+                arc(2, 4, 1, 9),
+                arc(3, 4, 10, 4),
+            ],
+            supply: vec![5, 0, 0, -5],
+        };
+        let stats = result.stats(&params);
+        assert_eq!(stats.total_supply, 5);
+        assert_eq!((stats.min_cost, stats.max_cost), (1, 10));
+        assert_eq!((stats.min_capacity, stats.max_capacity), (2, 9));
+        assert_eq!((stats.max_out_degree, stats.max_in_degree), (2, 2));
+        assert_eq!(
+            stats.to_string(),
+            "type=min-cost-flow nodes=4 arcs=4 supply=5 cost=1..10 cap=2..9 max_out_degree=2 max_in_degree=2"
+        );
+    }
+}
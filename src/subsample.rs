@@ -0,0 +1,212 @@
+//! Thinning generated networks down to a target number of arcs.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{NetgenResult, Rng, Skeleton};
+
+/// Which arcs [`NetgenResult::subsample_arcs`] may drop.
+#[derive(Debug, Clone, Copy)]
+pub enum KeepPolicy<'a> {
+    /// Keep every arc of the skeleton, which carries the supplies, so the
+    /// thinned network stays feasible. Only the random arcs are dropped.
+    SkeletonAlways(&'a Skeleton),
+    /// Any arc may be dropped; the result need not be feasible.
+    Uniform,
+}
+
+/// Why [`NetgenResult::subsample_arcs`] could not thin a network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubsampleError {
+    /// The target is smaller than the skeleton that must be kept.
+    TargetBelowSkeleton { target: usize, skeleton: usize },
+    /// The target is larger than the network.
+    TargetAboveArcs { target: usize, arcs: usize },
+    /// A skeleton arc (0-based index into [`Skeleton::arcs`]) is not in the
+    /// network, so the skeleton belongs to a different instance.
+    SkeletonArcMissing { arc: usize },
+}
+
+impl fmt::Display for SubsampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubsampleError::TargetBelowSkeleton { target, skeleton } => {
+                write!(
+                    f,
+                    "target of {target} arcs is below the {skeleton} skeleton arcs"
+                )
+            }
+            SubsampleError::TargetAboveArcs { target, arcs } => {
+                write!(f, "target of {target} arcs exceeds the {arcs} arcs present")
+            }
+            SubsampleError::SkeletonArcMissing { arc } => {
+                write!(f, "skeleton arc {arc} is not in the network")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubsampleError {}
+
+impl NetgenResult {
+    /// A copy with exactly `target_arcs` arcs, the others removed uniformly
+    /// at random with draws from `rng`. Surviving arcs keep their order.
+    ///
+    /// With [`KeepPolicy::SkeletonAlways`], each skeleton arc is matched to
+    /// an equal arc of the network and never dropped; parallel copies of it
+    /// beyond those the skeleton accounts for count as random arcs.
+    ///
+    /// ```rust
+    /// use netgen_rs::{KeepPolicy, NetgenParams, Rng, Skeleton, verify_flow};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let (skeleton, rng) = Skeleton::build(13502460, &params).unwrap();
+    /// let dense = skeleton.fill_random(rng);
+    /// let mut rng = Rng::new(1);
+    /// let sparse = dense
+    ///     .subsample_arcs(1000, &mut rng, KeepPolicy::SkeletonAlways(&skeleton))
+    ///     .unwrap();
+    /// assert_eq!(sparse.arcs.len(), 1000);
+    /// assert_eq!(verify_flow(&sparse, &sparse.skeleton_flow()), Ok(()));
+    /// ```
+    pub fn subsample_arcs(
+        &self,
+        target_arcs: usize,
+        rng: &mut Rng,
+        keep: KeepPolicy<'_>,
+    ) -> Result<NetgenResult, SubsampleError> {
+        if target_arcs > self.arcs.len() {
+            return Err(SubsampleError::TargetAboveArcs {
+                target: target_arcs,
+                arcs: self.arcs.len(),
+            });
+        }
+        let mut kept = vec![false; self.arcs.len()];
+        if let KeepPolicy::SkeletonAlways(skeleton) = keep {
+            if target_arcs < skeleton.arcs().len() {
+                return Err(SubsampleError::TargetBelowSkeleton {
+                    target: target_arcs,
+                    skeleton: skeleton.arcs().len(),
+                });
+            }
+            let mut positions: HashMap<_, Vec<usize>> = HashMap::new();
+            for (k, a) in self.arcs.iter().enumerate().rev() {
+                let key = (a.from, a.to, a.cost, a.capacity, a.multiplier);
+                positions.entry(key).or_default().push(k);
+            }
+            for (i, a) in skeleton.arcs().iter().enumerate() {
+                let key = (a.from, a.to, a.cost, a.capacity, a.multiplier);
+                let k = positions
+                    .get_mut(&key)
+                    .and_then(Vec::pop)
+                    .ok_or(SubsampleError::SkeletonArcMissing { arc: i })?;
+                kept[k] = true;
+            }
+        }
+
+        // Partial Fisher-Yates over the droppable arcs picks the survivors.
+        let mut candidates: Vec<usize> = (0..self.arcs.len()).filter(|&k| !kept[k]).collect();
+        let wanted = target_arcs - (self.arcs.len() - candidates.len());
+        for i in 0..wanted {
+            let j = rng.next(i as i64, candidates.len() as i64 - 1) as usize;
+            candidates.swap(i, j);
+            kept[candidates[i]] = true;
+        }
+        let arcs = self
+            .arcs
+            .iter()
+            .zip(&kept)
+            .filter(|(_, keep)| **keep)
+            .map(|(a, _)| a.clone())
+            .collect();
+        Ok(NetgenResult {
+            arcs,
+            supply: self.supply.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, verify_flow};
+
+    fn dense() -> (Skeleton, NetgenResult) {
+        let params =
+            NetgenParams::from_slice(&[200, 10, 10, 4000, 1, 100, 1000, 4, 4, 20, 80, 20, 200])
+                .unwrap();
+        let (skeleton, rng) = Skeleton::build(42, &params).unwrap();
+        let result = skeleton.fill_random(rng);
+        (skeleton, result)
+    }
+
+    #[test]
+    fn keeps_skeleton_and_hits_target() {
+        let (skeleton, result) = dense();
+        let skeleton_len = skeleton.arcs().len();
+        for target in [skeleton_len, skeleton_len + 1, 1000, result.arcs.len()] {
+            let sparse = result
+                .subsample_arcs(
+                    target,
+                    &mut Rng::new(5),
+                    KeepPolicy::SkeletonAlways(&skeleton),
+                )
+                .unwrap();
+            assert_eq!(sparse.arcs.len(), target);
+            assert_eq!(sparse.supply, result.supply);
+            let mut rest = sparse.arcs.clone();
+            for a in skeleton.arcs() {
+                let k = rest.iter().position(|b| b == a).expect("skeleton arc kept");
+                rest.swap_remove(k);
+            }
+            assert_eq!(verify_flow(&sparse, &sparse.skeleton_flow()), Ok(()));
+        }
+
+        let uniform = result
+            .subsample_arcs(100, &mut Rng::new(5), KeepPolicy::Uniform)
+            .unwrap();
+        assert_eq!(uniform.arcs.len(), 100);
+    }
+
+    #[test]
+    fn fixed_seed_is_deterministic() {
+        let (skeleton, result) = dense();
+        let keep = KeepPolicy::SkeletonAlways(&skeleton);
+        let a = result.subsample_arcs(800, &mut Rng::new(9), keep).unwrap();
+        let b = result.subsample_arcs(800, &mut Rng::new(9), keep).unwrap();
+        let c = result.subsample_arcs(800, &mut Rng::new(10), keep).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn reports_bad_targets() {
+        let (skeleton, result) = dense();
+        let keep = KeepPolicy::SkeletonAlways(&skeleton);
+        let below = skeleton.arcs().len() - 1;
+        assert_eq!(
+            result.subsample_arcs(below, &mut Rng::new(1), keep),
+            Err(SubsampleError::TargetBelowSkeleton {
+                target: below,
+                skeleton: below + 1
+            })
+        );
+        let above = result.arcs.len() + 1;
+        assert_eq!(
+            result.subsample_arcs(above, &mut Rng::new(1), keep),
+            Err(SubsampleError::TargetAboveArcs {
+                target: above,
+                arcs: above - 1
+            })
+        );
+        let mut other = result.clone();
+        let first = skeleton.arcs()[0].clone();
+        other.arcs.retain(|a| *a != first);
+        assert_eq!(
+            other.subsample_arcs(1000, &mut Rng::new(1), keep),
+            Err(SubsampleError::SkeletonArcMissing { arc: 0 })
+        );
+    }
+}
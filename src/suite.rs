@@ -0,0 +1,235 @@
+//! The LEMON benchmark families of `lemon_scripts/`, encoded natively so
+//! that the instance files can be produced without the scripts or the C
+//! binary.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+
+use crate::{NetgenParams, round_sqrt};
+
+/// Seeds of the five instances of each size, in script order. Instance
+/// files carry the suffixes `a` to `e` in the same order.
+pub const LEMON_SEEDS: [i64; 5] = [13502460, 69013527, 91208735, 73460581, 37240194];
+
+/// A LEMON NETGEN benchmark family, one per script in `lemon_scripts/`.
+///
+/// Every family is indexed by an exponent, which is also the problem
+/// number. For all but [`NetgenDeg`](Self::NetgenDeg) it sets
+/// `nodes = 2^exp`, with `sources = sinks = round(sqrt(nodes))`; the
+/// remaining parameters are costs `1..=10000`, capacities `1..=1000`, no
+/// transshipment sources or sinks, and 100% hicost and capacitated arcs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LemonSuite {
+    /// `netgen_8.sh`: `density = 8 * nodes`, `supply = 1000 * sources`,
+    /// exponents 8 to 22.
+    Netgen8,
+    /// `netgen_sr.sh`: `density = round(nodes * sqrt(nodes))`,
+    /// `supply = 1000 * sources`, exponents 8 to 16.
+    NetgenSr,
+    /// `netgen_lo_8.sh`: as [`Netgen8`](Self::Netgen8) with
+    /// `supply = 10 * sources`.
+    NetgenLo8,
+    /// `netgen_lo_sr.sh`: as [`NetgenSr`](Self::NetgenSr) with
+    /// `supply = 10 * sources`.
+    NetgenLoSr,
+    /// `netgen_deg.sh`: 4096 nodes with 64 sources and sinks,
+    /// `density = 4096 * 2^exp` and `supply = 64000`, exponents 1 to 12.
+    NetgenDeg,
+}
+
+/// One instance of a [`LemonSuite`]: the problem line the script writes to
+/// `<file_name>.param`, and the name of the DIMACS file generated from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteInstance {
+    /// Output file name, e.g. `netgen_8_13a.min`.
+    pub file_name: String,
+    pub seed: i64,
+    /// Problem number, the family's exponent.
+    pub problem: i64,
+    pub params: NetgenParams,
+}
+
+impl LemonSuite {
+    pub const ALL: [LemonSuite; 5] = [
+        LemonSuite::Netgen8,
+        LemonSuite::NetgenSr,
+        LemonSuite::NetgenLo8,
+        LemonSuite::NetgenLoSr,
+        LemonSuite::NetgenDeg,
+    ];
+
+    /// The family's name on the command line, e.g. `lemon-netgen-8`.
+    pub fn name(self) -> &'static str {
+        match self {
+            LemonSuite::Netgen8 => "lemon-netgen-8",
+            LemonSuite::NetgenSr => "lemon-netgen-sr",
+            LemonSuite::NetgenLo8 => "lemon-netgen-lo-8",
+            LemonSuite::NetgenLoSr => "lemon-netgen-lo-sr",
+            LemonSuite::NetgenDeg => "lemon-netgen-deg",
+        }
+    }
+
+    /// The script the family comes from, e.g. `netgen_8.sh`.
+    pub fn script(self) -> &'static str {
+        match self {
+            LemonSuite::Netgen8 => "netgen_8.sh",
+            LemonSuite::NetgenSr => "netgen_sr.sh",
+            LemonSuite::NetgenLo8 => "netgen_lo_8.sh",
+            LemonSuite::NetgenLoSr => "netgen_lo_sr.sh",
+            LemonSuite::NetgenDeg => "netgen_deg.sh",
+        }
+    }
+
+    /// Exponents the script covers.
+    pub fn exponents(self) -> RangeInclusive<u32> {
+        match self {
+            LemonSuite::Netgen8 | LemonSuite::NetgenLo8 => 8..=22,
+            LemonSuite::NetgenSr | LemonSuite::NetgenLoSr => 8..=16,
+            LemonSuite::NetgenDeg => 1..=12,
+        }
+    }
+
+    /// Parameters of the instances with exponent `exp`, which need not be
+    /// in [`exponents`](Self::exponents).
+    ///
+    /// ```rust
+    /// use netgen_rs::{LemonSuite, parse_problem_line};
+    ///
+    /// let (_, _, expected) = parse_problem_line(
+    ///     "13502460  13      8192    91    91    741455  1 10000    91000  0 0 100 100  1 1000",
+    /// ).unwrap();
+    /// assert_eq!(LemonSuite::NetgenSr.params(13), expected);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the node or arc count overflows `i64`.
+    pub fn params(self, exp: u32) -> NetgenParams {
+        let (nodes, density) = match self {
+            LemonSuite::Netgen8 | LemonSuite::NetgenLo8 => {
+                let nodes = 1i64 << exp;
+                (nodes, 8 * nodes)
+            }
+            LemonSuite::NetgenSr | LemonSuite::NetgenLoSr => {
+                let nodes = 1i64 << exp;
+                let cube = nodes
+                    .checked_mul(nodes)
+                    .and_then(|n2| n2.checked_mul(nodes))
+                    .expect("arc count overflows i64");
+                (nodes, round_sqrt(cube))
+            }
+            LemonSuite::NetgenDeg => (4096, 4096 << exp),
+        };
+        let terminals = round_sqrt(nodes);
+        let supply = match self {
+            LemonSuite::NetgenLo8 | LemonSuite::NetgenLoSr => 10 * terminals,
+            _ => 1000 * terminals,
+        };
+        NetgenParams::new(
+            nodes, terminals, terminals, density, 1, 10000, supply, 0, 0, 100, 100, 1, 1000,
+        )
+        .expect("LEMON suite parameters are valid")
+    }
+
+    /// The instances with exponents in `exps`, in script order: all sizes
+    /// for the first seed of [`LEMON_SEEDS`], then for the next.
+    ///
+    /// ```rust
+    /// use netgen_rs::LemonSuite;
+    ///
+    /// let instances = LemonSuite::Netgen8.instances(8..=18);
+    /// assert_eq!(instances.len(), 11 * 5);
+    /// assert_eq!(instances[5].file_name, "netgen_8_13a.min");
+    /// assert_eq!(instances[11].file_name, "netgen_8_08b.min");
+    /// ```
+    pub fn instances(self, exps: RangeInclusive<u32>) -> Vec<SuiteInstance> {
+        let (lo, hi) = (
+            (*exps.start()).max(*self.exponents().start()),
+            (*exps.end()).min(*self.exponents().end()),
+        );
+        let prefix = &self.script()[..self.script().len() - ".sh".len()];
+        let mut instances = Vec::new();
+        for (seed, suffix) in LEMON_SEEDS.into_iter().zip('a'..='e') {
+            for exp in lo..=hi {
+                instances.push(SuiteInstance {
+                    file_name: format!("{prefix}_{exp:02}{suffix}.min"),
+                    seed,
+                    problem: exp as i64,
+                    params: self.params(exp),
+                });
+            }
+        }
+        instances
+    }
+}
+
+impl fmt::Display for LemonSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for LemonSuite {
+    type Err = ParseSuiteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LemonSuite::ALL
+            .into_iter()
+            .find(|suite| suite.name() == s)
+            .ok_or_else(|| ParseSuiteError(s.to_string()))
+    }
+}
+
+/// Error returned when parsing an unknown [`LemonSuite`] name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSuiteError(String);
+
+impl fmt::Display for ParseSuiteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown suite: {:?} (expected one of", self.0)?;
+        for suite in LemonSuite::ALL {
+            write!(f, " {suite}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl core::error::Error for ParseSuiteError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for suite in LemonSuite::ALL {
+            assert_eq!(suite.to_string().parse(), Ok(suite));
+        }
+        let err = "netgen-8".parse::<LemonSuite>().unwrap_err();
+        assert!(err.to_string().contains("lemon-netgen-lo-sr"));
+    }
+
+    #[test]
+    fn exponents_are_clamped_to_the_script() {
+        let instances = LemonSuite::NetgenDeg.instances(0..=3);
+        let names: Vec<&str> = instances[..4]
+            .iter()
+            .map(|i| i.file_name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "netgen_deg_01a.min",
+                "netgen_deg_02a.min",
+                "netgen_deg_03a.min",
+                "netgen_deg_01b.min"
+            ]
+        );
+        assert_eq!(instances.len(), 15);
+        assert!(LemonSuite::NetgenSr.instances(17..=20).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Writing the same bytes to several sinks in one pass.
+
+use std::io::{self, Write};
+
+/// An [`io::Write`] that writes everything to each of its inner writers in
+/// turn, in the order given.
+///
+/// A write succeeds only once every writer has taken all of the buffer.
+/// The first error stops the fan-out and is returned; writers after the
+/// failing one do not see that buffer.
+///
+/// ```rust
+/// use std::io::Write;
+/// use netgen_rs::TeeWriter;
+///
+/// let (mut a, mut b) = (Vec::new(), Vec::new());
+/// let mut tee = TeeWriter::new([&mut a, &mut b]);
+/// tee.write_all(b"p min 2 1\n").unwrap();
+/// assert_eq!(a, b);
+/// ```
+#[derive(Debug)]
+pub struct TeeWriter<W> {
+    writers: Vec<W>,
+}
+
+impl<W: Write> TeeWriter<W> {
+    pub fn new(writers: impl IntoIterator<Item = W>) -> Self {
+        TeeWriter {
+            writers: writers.into_iter().collect(),
+        }
+    }
+
+    /// The inner writers, in order.
+    pub fn into_inner(self) -> Vec<W> {
+        self.writers
+    }
+}
+
+impl<W: Write> Write for TeeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for w in &mut self.writers {
+            w.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writers.iter_mut().try_for_each(Write::flush)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes `room` bytes, then fails.
+    struct Full {
+        room: usize,
+        flushed: bool,
+    }
+
+    impl Write for Full {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.room == 0 {
+                return Err(io::Error::new(io::ErrorKind::StorageFull, "full"));
+            }
+            let n = buf.len().min(self.room);
+            self.room -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writes_everything_to_every_writer() {
+        // Short writes from one writer are completed before the next.
+        let mut short = Full {
+            room: 4,
+            flushed: false,
+        };
+        let mut out = Vec::new();
+        let mut tee = TeeWriter::<&mut dyn Write>::new([&mut short as _, &mut out as _]);
+        assert_eq!(tee.write(b"abc").unwrap(), 3);
+        tee.flush().unwrap();
+        assert!(short.flushed);
+        assert_eq!(out, b"abc");
+    }
+
+    #[test]
+    fn stops_at_the_first_error() {
+        let (mut before, mut after) = (Vec::new(), Vec::new());
+        let mut full = Full {
+            room: 2,
+            flushed: false,
+        };
+        let mut tee =
+            TeeWriter::<&mut dyn Write>::new([&mut before as _, &mut full as _, &mut after as _]);
+        tee.write_all(b"ab").unwrap();
+        let err = tee.write_all(b"cd").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::StorageFull);
+        assert_eq!(before, b"abcd");
+        assert_eq!(after, b"ab");
+    }
+}
@@ -0,0 +1,227 @@
+//! Shrinking random arc capacities towards a target maximum flow, for
+//! max-flow benchmarks with a tight minimum cut.
+
+use std::fmt;
+
+use crate::analysis::max_flow_value;
+use crate::seed::SplitMix;
+use crate::{ArcKind, NetgenResult};
+
+/// Scale-and-measure rounds [`NetgenResult::tighten_capacities`] takes
+/// before giving up.
+pub const TIGHTEN_ROUNDS: u32 = 40;
+
+/// Why [`NetgenResult::tighten_capacities`] could not reach its target.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TightenError {
+    /// The ratio is negative, NaN or infinite.
+    Ratio(f64),
+    /// The target is outside what scaling the random arcs can reach: the
+    /// maximum flow with every random arc at capacity 0 (`floor`) and as
+    /// generated (`ceiling`). The skeleton routes the whole supply, so
+    /// `floor` is at least the total supply and a ratio below 1 is never
+    /// reachable.
+    Unreachable {
+        target: i64,
+        floor: i64,
+        ceiling: i64,
+    },
+    /// No scale within [`TIGHTEN_ROUNDS`] rounds gave a maximum flow within
+    /// the tolerance; `reached` is the closest value seen. A few arcs with
+    /// large capacities can make the flow jump past the target.
+    NotConverged { target: i64, reached: i64 },
+}
+
+impl fmt::Display for TightenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TightenError::Ratio(ratio) => {
+                write!(f, "max flow ratio {ratio} must be finite and non-negative")
+            }
+            TightenError::Unreachable {
+                target,
+                floor,
+                ceiling,
+            } => write!(
+                f,
+                "max flow {target} is outside the reachable range {floor}..={ceiling}"
+            ),
+            TightenError::NotConverged { target, reached } => write!(
+                f,
+                "max flow did not converge to {target} in {TIGHTEN_ROUNDS} rounds (closest {reached})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TightenError {}
+
+impl NetgenResult {
+    /// A copy whose [maximum flow](crate::analysis::max_flow_value) is
+    /// within 2% of `ratio` times the total supply, reached by scaling down
+    /// the capacities of the random arcs ([`ArcKind::Random`]).
+    ///
+    /// Each random arc draws a weight in `[0.5, 1)` from a splitmix64
+    /// stream started at `rng_seed`; its capacity becomes the original times
+    /// its weight times a common scale, rounded down, and loses the
+    /// [`uncapacitated`](crate::Arc::uncapacitated) flag if it changed. The
+    /// scale is found by bisection, measuring the maximum flow each round.
+    /// Skeleton arcs, arcs of unknown provenance and supplies are untouched.
+    ///
+    /// Since the skeleton alone routes the total supply, only ratios of 1
+    /// and above can be met, and the tightest cut reachable is that of the
+    /// skeleton, whose maximum flow is often well above the supply (see
+    /// [`TightenError::Unreachable`]).
+    /// Networks read from formats without provenance have no random arcs to
+    /// scale.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, analysis};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     1000, 20, 20, 8000, 1, 100, 1000, 5, 5, 10, 50, 100, 1000,
+    /// ]).unwrap();
+    /// let result = params.generate(13502460).unwrap();
+    /// let tight = result.tighten_capacities(5.0, 7).unwrap();
+    /// let flow = analysis::max_flow_value(&tight);
+    /// assert!((4900..=5100).contains(&flow));
+    /// ```
+    pub fn tighten_capacities(
+        &self,
+        ratio: f64,
+        rng_seed: u64,
+    ) -> Result<NetgenResult, TightenError> {
+        if !ratio.is_finite() || ratio < 0.0 {
+            return Err(TightenError::Ratio(ratio));
+        }
+        let total: i64 = self.supply.iter().filter(|&&s| s > 0).sum();
+        // Float to int `as` saturates.
+        let target = (ratio * total as f64).round() as i64;
+        let tolerance = target / 50;
+        let within = |flow: i64| flow.abs_diff(target) <= tolerance.unsigned_abs();
+
+        let mut rng = SplitMix::new(rng_seed);
+        let weights: Vec<f64> = (self.arcs.iter())
+            .map(|arc| match arc.kind {
+                ArcKind::Random => 0.5 + rng.below(1 << 20) as f64 / f64::from(1 << 21),
+                _ => 1.0,
+            })
+            .collect();
+        let scaled = |scale: f64| {
+            let mut result = self.clone();
+            for (arc, weight) in result.arcs.iter_mut().zip(&weights) {
+                if arc.kind != ArcKind::Random {
+                    continue;
+                }
+                let capacity = (arc.capacity as f64 * weight * scale) as i64;
+                if capacity != arc.capacity {
+                    arc.capacity = capacity;
+                    arc.uncapacitated = false;
+                }
+            }
+            result
+        };
+
+        let ceiling = max_flow_value(self);
+        if within(ceiling) {
+            return Ok(self.clone());
+        }
+        let floor = max_flow_value(&scaled(0.0));
+        if ceiling < target || floor > target + tolerance {
+            return Err(TightenError::Unreachable {
+                target,
+                floor,
+                ceiling,
+            });
+        }
+        // The flow is nondecreasing in the scale, from `floor` at 0 to
+        // `ceiling` at 1.
+        let (mut low, mut high) = (0.0, 1.0);
+        let mut reached = ceiling;
+        for _ in 0..TIGHTEN_ROUNDS {
+            let mid = (low + high) / 2.0;
+            let result = scaled(mid);
+            let flow = max_flow_value(&result);
+            if within(flow) {
+                return Ok(result);
+            }
+            if flow.abs_diff(target) < reached.abs_diff(target) {
+                reached = flow;
+            }
+            if flow > target {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        Err(TightenError::NotConverged { target, reached })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, generate};
+
+    fn result() -> NetgenResult {
+        let params =
+            NetgenParams::from_slice(&[1000, 20, 20, 8000, 1, 100, 1000, 5, 5, 10, 50, 100, 1000])
+                .unwrap();
+        generate(13502460, &params).unwrap()
+    }
+
+    #[test]
+    fn max_flow_lands_near_the_target() {
+        let original = result();
+        for ratio in [2.5, 10.0, 50.0] {
+            let tight = original.tighten_capacities(ratio, 1).unwrap();
+            let target = 1000.0 * ratio;
+            let flow = max_flow_value(&tight) as f64;
+            assert!((flow - target).abs() <= target / 10.0, "{ratio}: {flow}");
+            for (old, new) in original.arcs.iter().zip(&tight.arcs) {
+                match old.kind {
+                    ArcKind::Random => assert!(new.capacity <= old.capacity),
+                    _ => assert_eq!(old, new),
+                }
+                assert_eq!((old.from, old.to, old.cost), (new.from, new.to, new.cost));
+            }
+            assert_eq!(tight.supply, original.supply);
+        }
+    }
+
+    #[test]
+    fn seeds_give_different_but_reproducible_capacities() {
+        let original = result();
+        let a = original.tighten_capacities(5.0, 1).unwrap();
+        assert_eq!(original.tighten_capacities(5.0, 1).unwrap(), a);
+        assert_ne!(original.tighten_capacities(5.0, 2).unwrap(), a);
+    }
+
+    #[test]
+    fn ratios_below_the_skeleton_are_unreachable() {
+        let original = result();
+        let Err(TightenError::Unreachable {
+            target,
+            floor,
+            ceiling,
+        }) = original.tighten_capacities(0.5, 1)
+        else {
+            panic!("half the supply cannot be reached with the skeleton intact");
+        };
+        assert_eq!(target, 500);
+        assert!(floor >= 1000);
+        assert!(ceiling >= floor);
+        assert!(matches!(
+            original.tighten_capacities(1e9, 1),
+            Err(TightenError::Unreachable { .. })
+        ));
+        assert_eq!(
+            original.tighten_capacities(-1.0, 1),
+            Err(TightenError::Ratio(-1.0))
+        );
+        assert!(matches!(
+            original.tighten_capacities(f64::NAN, 1),
+            Err(TightenError::Ratio(_))
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+//! Time-expanded networks for dynamic-flow experiments.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Arc, ArcKind, NetgenResult};
+
+/// How many time steps an arc takes to traverse (see
+/// [`NetgenResult::time_expand`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitTimes {
+    /// Every arc takes one step.
+    Unit,
+    /// An arc takes `cost / divisor` steps, rounded down, and at least one.
+    FromCost { divisor: i64 },
+}
+
+impl TransitTimes {
+    fn of(self, arc: &Arc) -> u64 {
+        match self {
+            TransitTimes::Unit => 1,
+            TransitTimes::FromCost { divisor } => (arc.cost / divisor).max(1) as u64,
+        }
+    }
+}
+
+impl NetgenResult {
+    /// Expand the network over `horizon` time steps, with holdover arcs of
+    /// unbounded capacity (the total supply).
+    ///
+    /// See [`time_expand_with_holdover`](Self::time_expand_with_holdover).
+    pub fn time_expand(&self, horizon: u32, transit: TransitTimes) -> NetgenResult {
+        let total: i64 = self.supply.iter().filter(|&&s| s > 0).sum();
+        self.time_expand_with_holdover(horizon, transit, total)
+    }
+
+    /// Expand the network over `horizon` time steps.
+    ///
+    /// With `n` nodes, node `v` at time `t` (for `t` in `0..=horizon`) is
+    /// node `v + t * n` of the expanded network. Each arc `u -> v` taking
+    /// `τ` steps becomes the arcs `(u, t) -> (v, t + τ)` with its cost and
+    /// capacity, for every `t` with `t + τ <= horizon`; then each node gets
+    /// cost-0 holdover arcs `(v, t) -> (v, t + 1)` of capacity
+    /// `holdover_capacity`. Arcs are listed by time step, original arcs
+    /// before holdovers. Sources supply at time 0 and sinks demand at time
+    /// `horizon`.
+    ///
+    /// # Panics
+    ///
+    /// If `transit` is [`TransitTimes::FromCost`] with a non-positive
+    /// divisor.
+    ///
+    /// ```rust
+    /// use netgen_rs::{NetgenParams, TransitTimes, generate};
+    ///
+    /// let params = NetgenParams::from_slice(&[
+    ///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    /// ]).unwrap();
+    /// let result = generate(13502460, &params).unwrap();
+    /// let expanded = result.time_expand(4, TransitTimes::Unit);
+    /// assert_eq!(expanded.supply.len(), 512 * 5);
+    /// assert_eq!(expanded.arcs.len(), (result.arcs.len() + 512) * 4);
+    /// ```
+    pub fn time_expand_with_holdover(
+        &self,
+        horizon: u32,
+        transit: TransitTimes,
+        holdover_capacity: i64,
+    ) -> NetgenResult {
+        if let TransitTimes::FromCost { divisor } = transit {
+            assert!(divisor > 0, "transit divisor must be positive");
+        }
+        let n = self.supply.len() as u64;
+        let horizon = horizon as u64;
+        let steps: Vec<u64> = self.arcs.iter().map(|a| transit.of(a)).collect();
+        let mut arcs = Vec::new();
+        for t in 0..=horizon {
+            for (arc, &tau) in self.arcs.iter().zip(&steps) {
+                if t + tau <= horizon {
+                    arcs.push(Arc {
+                        from: arc.from + t * n,
+                        to: arc.to + (t + tau) * n,
+                        ..arc.clone()
+                    });
+                }
+            }
+            if t < horizon {
+                arcs.extend((1..=n).map(|v| Arc {
+                    from: v + t * n,
+                    to: v + (t + 1) * n,
+                    cost: 0,
+                    capacity: holdover_capacity,
+                    multiplier: None,
+                    uncapacitated: false,
+                    kind: ArcKind::Unknown,
+                    hicost: false,
+                }));
+            }
+        }
+        let mut supply = vec![0; self.supply.len() * (horizon as usize + 1)];
+        let last = horizon as usize * self.supply.len();
+        for (v, &s) in self.supply.iter().enumerate() {
+            if s > 0 {
+                supply[v] = s;
+            } else if s < 0 {
+                supply[last + v] = s;
+            }
+        }
+        NetgenResult { arcs, supply }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetgenParams, generate, verify_flow};
+
+    #[test]
+    fn counts_follow_the_expansion_formulas() {
+        let params =
+            NetgenParams::from_slice(&[50, 5, 5, 200, 1, 30, 100, 0, 0, 0, 100, 10, 50]).unwrap();
+        let result = generate(7, &params).unwrap();
+        let (n, m) = (result.supply.len(), result.arcs.len());
+        for horizon in [0, 1, 6] {
+            let unit = result.time_expand(horizon, TransitTimes::Unit);
+            let h = horizon as usize;
+            assert_eq!(unit.supply.len(), n * (h + 1));
+            assert_eq!(unit.arcs.len(), (m + n) * h);
+            assert_eq!(unit.supply.iter().sum::<i64>(), 0);
+
+            let transit = TransitTimes::FromCost { divisor: 10 };
+            let by_cost = result.time_expand(horizon, transit);
+            let expected: usize = result
+                .arcs
+                .iter()
+                .map(|a| (h + 1).saturating_sub(transit.of(a) as usize))
+                .sum();
+            assert_eq!(by_cost.arcs.len(), expected + n * h);
+        }
+    }
+
+    #[test]
+    fn long_enough_horizon_stays_feasible() {
+        let params =
+            NetgenParams::from_slice(&[30, 3, 3, 90, 1, 20, 60, 0, 0, 0, 100, 5, 30]).unwrap();
+        let result = generate(13502460, &params).unwrap();
+        assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+        let nodes = result.supply.len() as u32;
+        for transit in [TransitTimes::Unit, TransitTimes::FromCost { divisor: 5 }] {
+            // Any path of the static flow fits within this many steps.
+            let horizon = nodes * 4;
+            let expanded = result.time_expand(horizon, transit);
+            assert_eq!(verify_flow(&expanded, &expanded.skeleton_flow()), Ok(()));
+        }
+
+        let tiny = NetgenResult {
+            arcs: vec![Arc {
+                from: 1,
+                to: 2,
+                cost: 1,
+                capacity: 5,
+                multiplier: None,
+                uncapacitated: false,
+                kind: ArcKind::Unknown,
+                hicost: false,
+            }],
+            supply: vec![3, -3],
+        };
+        // Zero steps leave no arc to ship along.
+        let expanded = tiny.time_expand(0, TransitTimes::Unit);
+        assert!(verify_flow(&expanded, &expanded.skeleton_flow()).is_err());
+        let expanded = tiny.time_expand_with_holdover(2, TransitTimes::Unit, 0);
+        assert_eq!(expanded.supply, [3, 0, 0, 0, 0, -3]);
+        // Node 2 at time 1 (node 4) cannot hold the flow until time 2.
+        assert!(verify_flow(&expanded, &expanded.skeleton_flow()).is_err());
+        let expanded = tiny.time_expand_with_holdover(2, TransitTimes::Unit, 3);
+        assert_eq!(verify_flow(&expanded, &expanded.skeleton_flow()), Ok(()));
+    }
+}
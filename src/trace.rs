@@ -0,0 +1,119 @@
+//! Per-phase accounting of RNG draws, for locating where two generator
+//! streams diverge.
+
+use alloc::string::ToString;
+use core::fmt;
+
+/// A phase of generation, in the order NETGEN first enters them (see
+/// [`RngTrace`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RngPhase {
+    /// Splitting the total supply among the sources.
+    SupplyCreation,
+    /// Threading the transshipment nodes into one chain per source.
+    ChainAssignment,
+    /// Choosing each source's sinks and dividing its supply among them.
+    SinkSelection,
+    /// Capacities and costs of the skeleton arcs (for assignment problems,
+    /// the matching arcs).
+    SkeletonArcs,
+    /// Random ("rubbish") arcs after each skeleton tail.
+    RubbishArcs,
+}
+
+impl RngPhase {
+    /// Every phase, in order.
+    pub const ALL: [RngPhase; 5] = [
+        RngPhase::SupplyCreation,
+        RngPhase::ChainAssignment,
+        RngPhase::SinkSelection,
+        RngPhase::SkeletonArcs,
+        RngPhase::RubbishArcs,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for RngPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RngPhase::SupplyCreation => "supply creation",
+            RngPhase::ChainAssignment => "chain assignment",
+            RngPhase::SinkSelection => "sink selection",
+            RngPhase::SkeletonArcs => "skeleton arcs",
+            RngPhase::RubbishArcs => "rubbish arcs",
+        };
+        f.write_str(name)
+    }
+}
+
+/// RNG draws consumed by one phase.
+///
+/// Phases interleave (sink selection, skeleton arcs and rubbish arcs repeat
+/// per source), so `first` and `last` are the first and last raw values
+/// drawn in the phase overall.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseDraws {
+    /// Raw draws consumed.
+    pub draws: u64,
+    /// First raw value drawn, if any.
+    pub first: Option<i64>,
+    /// Last raw value drawn, if any.
+    pub last: Option<i64>,
+}
+
+/// RNG consumption per generation phase, from
+/// [`generate_traced`](crate::generate_traced).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RngTrace {
+    phases: [PhaseDraws; 5],
+}
+
+impl RngTrace {
+    /// The draws of `phase`.
+    pub fn phase(&self, phase: RngPhase) -> &PhaseDraws {
+        &self.phases[phase.index()]
+    }
+
+    /// Raw draws consumed over all phases.
+    pub fn total_draws(&self) -> u64 {
+        self.phases.iter().map(|p| p.draws).sum()
+    }
+
+    /// The first phase whose draws differ between two traces, for example
+    /// from a patched build and the reference.
+    pub fn first_divergence(&self, other: &RngTrace) -> Option<RngPhase> {
+        RngPhase::ALL
+            .into_iter()
+            .find(|&p| self.phase(p) != other.phase(p))
+    }
+
+    /// Add `draws` draws to `phase`, the first drawing `first` and the last
+    /// `last`.
+    pub(crate) fn record(&mut self, phase: RngPhase, draws: u64, first: i64, last: i64) {
+        let entry = &mut self.phases[phase.index()];
+        entry.draws += draws;
+        entry.first.get_or_insert(first);
+        entry.last = Some(last);
+    }
+}
+
+/// One line per phase: name, draw count, and first and last raw values.
+impl fmt::Display for RngTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for phase in RngPhase::ALL {
+            let p = self.phase(phase);
+            let value = |v: Option<i64>| v.map_or("-".to_string(), |v| v.to_string());
+            writeln!(
+                f,
+                "{phase}: {} draws, first {}, last {}",
+                p.draws,
+                value(p.first),
+                value(p.last)
+            )?;
+        }
+        Ok(())
+    }
+}
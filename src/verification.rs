@@ -0,0 +1,208 @@
+//! Reference vectors recorded from the original C implementation, so output
+//! fidelity can be checked without a C toolchain.
+
+use std::fmt;
+
+use crate::{NetgenError, NetgenParams, generate, to_hex};
+
+/// One parameter line and what the C implementation prints for it.
+#[derive(Debug, Clone, Copy)]
+pub struct ReferenceCase {
+    pub name: &'static str,
+    pub seed: i64,
+    pub problem: i64,
+    pub parms: [i64; 13],
+    /// SHA-256 of the complete DIMACS output.
+    pub sha256: &'static str,
+    /// The first lines of the network section, starting at the `p` line.
+    pub head: &'static [&'static str],
+    /// The last lines of the output.
+    pub tail: &'static [&'static str],
+}
+
+/// Outputs of the C reference (compiled with `-DDIMACS`) for a small
+/// min-cost flow, an assignment, a max-flow and a larger min-cost flow
+/// problem.
+pub const REFERENCE_CASES: &[ReferenceCase] = &[
+    ReferenceCase {
+        name: "min-cost-flow-512",
+        seed: 13502460,
+        problem: 1,
+        parms: [512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000],
+        sha256: "c7ca560e963827076225def4b5b8b7326feff3816a33d156791e69c73e5a24c1",
+        head: &["p min 512 2000", "n 1 86", "n 2 166"],
+        tail: &[
+            "a 505 297 0 432 471",
+            "a 505 109 0 1347 454",
+            "a 505 150 0 716 33",
+        ],
+    },
+    ReferenceCase {
+        name: "assignment-100",
+        seed: 12345,
+        problem: 1,
+        parms: [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+        sha256: "8c4972895d7e4b56cbef3f651feee71a68540e61bb5b67d59a10a9577244fe50",
+        head: &["p asn 100 500", "n 1", "n 2"],
+        tail: &["a 50 98 36", "a 50 95 81", "a 50 100 36"],
+    },
+    ReferenceCase {
+        name: "max-flow-200",
+        seed: 99999,
+        problem: 1,
+        parms: [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+        sha256: "dec0acd9560c0f502da73714d134ccf373d0411010a022ce8be77be4334893a0",
+        head: &["p max 200 1000", "n 1 s", "n 2 s"],
+        tail: &["a 197 37 74", "a 197 74 67", "a 197 68 67"],
+    },
+    ReferenceCase {
+        name: "min-cost-flow-8192",
+        seed: 13502460,
+        problem: 1,
+        parms: [
+            8192, 50, 50, 50000, 1, 1000, 10000, 10, 10, 25, 75, 100, 5000,
+        ],
+        sha256: "9a8596d8cce6570c547a2bc6a4f8914f722fa5eeaf0600a13b8cfaec160bc9b8",
+        head: &["p min 8192 50000", "n 1 86", "n 2 215"],
+        tail: &[
+            "a 8152 597 0 10000 945",
+            "a 8152 841 0 4778 642",
+            "a 8152 7593 0 10000 875",
+        ],
+    },
+];
+
+/// How generated output differed from a [`ReferenceCase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationMismatch {
+    /// Generation failed where the reference succeeded.
+    Generation {
+        case: &'static str,
+        error: NetgenError,
+    },
+    /// A recorded line differs; `line` is 1-based within the output.
+    Line {
+        case: &'static str,
+        line: usize,
+        expected: &'static str,
+        found: String,
+    },
+    /// The recorded lines match but the full output does not.
+    Hash {
+        case: &'static str,
+        expected: &'static str,
+        found: String,
+    },
+}
+
+impl fmt::Display for VerificationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationMismatch::Generation { case, error } => {
+                write!(f, "{case}: generation failed: {error}")
+            }
+            VerificationMismatch::Line {
+                case,
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{case}: line {line} is {found:?}, reference has {expected:?}"
+            ),
+            VerificationMismatch::Hash {
+                case,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{case}: output SHA-256 is {found}, reference has {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerificationMismatch {}
+
+impl ReferenceCase {
+    /// Regenerate this case and compare it with the recorded output.
+    pub fn verify(&self) -> Result<(), VerificationMismatch> {
+        let params = NetgenParams::from_slice(&self.parms).expect("reference params are valid");
+        let result =
+            generate(self.seed, &params).map_err(|error| VerificationMismatch::Generation {
+                case: self.name,
+                error,
+            })?;
+        let output = result.to_dimacs_string(self.seed, self.problem, &params);
+        let lines: Vec<&str> = output.lines().collect();
+
+        let head_start = lines
+            .iter()
+            .position(|l| l.starts_with("p "))
+            .unwrap_or(lines.len());
+        let tail_start = lines.len().saturating_sub(self.tail.len());
+        let recorded = (head_start..)
+            .zip(self.head)
+            .chain((tail_start..).zip(self.tail));
+        for (i, &expected) in recorded {
+            let found = lines.get(i).copied().unwrap_or("");
+            if found != expected {
+                return Err(VerificationMismatch::Line {
+                    case: self.name,
+                    line: i + 1,
+                    expected,
+                    found: found.to_string(),
+                });
+            }
+        }
+
+        let found = to_hex(&result.fingerprint(self.seed, self.problem, &params));
+        if found != self.sha256 {
+            return Err(VerificationMismatch::Hash {
+                case: self.name,
+                expected: self.sha256,
+                found,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Regenerate every [`REFERENCE_CASES`] entry and compare it with the
+/// output of the C implementation, returning the first mismatch.
+pub fn verify_reference() -> Result<(), VerificationMismatch> {
+    REFERENCE_CASES.iter().try_for_each(ReferenceCase::verify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_cases_match() {
+        for case in REFERENCE_CASES {
+            if let Err(e) = case.verify() {
+                panic!("{e}");
+            }
+        }
+        assert_eq!(verify_reference(), Ok(()));
+    }
+
+    #[test]
+    fn mismatch_is_reported() {
+        let mut case = REFERENCE_CASES[1];
+        case.seed += 1;
+        let err = case.verify().unwrap_err();
+        assert!(
+            matches!(err, VerificationMismatch::Line { line, .. } if line > 23),
+            "{err}"
+        );
+
+        let mut case = REFERENCE_CASES[0];
+        case.sha256 = "00";
+        assert!(matches!(
+            case.verify(),
+            Err(VerificationMismatch::Hash { .. })
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+//! A typed view of a network, indexed by [`NodeId`] and [`ArcId`] instead
+//! of raw integers.
+
+use core::cell::OnceCell;
+
+use alloc::vec::Vec;
+
+use crate::{Arc, ArcId, NetgenResult, NodeId};
+
+/// Read-only access to a [`NetgenResult`] through [`NodeId`] and
+/// [`ArcId`], so 1-based DIMACS ids and 0-based indices cannot be mixed up.
+///
+/// Ids are checked against the network in debug builds, with a panic that
+/// names the offending id; release builds fall back to the slice bounds
+/// check. The outgoing arcs of each node are indexed on the first call to
+/// [`out_arcs`](Self::out_arcs).
+///
+/// ```rust
+/// use netgen_rs::{NetgenParams, NodeId};
+///
+/// let params = NetgenParams::from_slice(&[
+///     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// ]).unwrap();
+/// let result = params.generate(13502460).unwrap();
+/// let view = result.view();
+/// let source = NodeId::from_dimacs(1);
+/// assert!(view.supply(source) > 0);
+/// for &arc in view.out_arcs(source) {
+///     assert_eq!(view.tail(arc), source);
+/// }
+/// ```
+///
+/// Raw integers are not accepted where an id is expected:
+///
+/// ```rust,compile_fail
+/// # use netgen_rs::NetgenParams;
+/// # let params = NetgenParams::from_slice(&[
+/// #     512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+/// # ]).unwrap();
+/// # let result = params.generate(13502460).unwrap();
+/// let view = result.view();
+/// let supply = view.supply(1u64); // expected `NodeId`, found `u64`
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetworkView<'a> {
+    result: &'a NetgenResult,
+    /// `nodes + 1` offsets into `out`, built on first use.
+    out: OnceCell<(Vec<usize>, Vec<ArcId>)>,
+}
+
+impl NetgenResult {
+    /// A [`NetworkView`] of this network.
+    pub fn view(&self) -> NetworkView<'_> {
+        NetworkView {
+            result: self,
+            out: OnceCell::new(),
+        }
+    }
+}
+
+impl<'a> NetworkView<'a> {
+    /// The underlying network.
+    pub fn result(&self) -> &'a NetgenResult {
+        self.result
+    }
+
+    /// Number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.result.supply.len()
+    }
+
+    /// Number of arcs.
+    pub fn arc_count(&self) -> usize {
+        self.result.arcs.len()
+    }
+
+    /// All nodes, in DIMACS order.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + use<> {
+        (0..self.node_count() as u32).map(NodeId)
+    }
+
+    /// All arcs, in generation order.
+    pub fn arcs(&self) -> impl Iterator<Item = ArcId> + use<> {
+        (0..self.arc_count() as u32).map(ArcId)
+    }
+
+    /// The arc `arc`.
+    pub fn arc(&self, arc: ArcId) -> &'a Arc {
+        self.check_arc(arc);
+        &self.result.arcs[arc.index()]
+    }
+
+    /// Tail node of `arc`.
+    pub fn tail(&self, arc: ArcId) -> NodeId {
+        NodeId::from_dimacs(self.arc(arc).from)
+    }
+
+    /// Head node of `arc`.
+    pub fn head(&self, arc: ArcId) -> NodeId {
+        NodeId::from_dimacs(self.arc(arc).to)
+    }
+
+    /// Supply (positive) or demand (negative) of `node`.
+    pub fn supply(&self, node: NodeId) -> i64 {
+        self.check_node(node);
+        self.result.supply[node.index()]
+    }
+
+    /// Arcs leaving `node`, in generation order.
+    pub fn out_arcs(&self, node: NodeId) -> &[ArcId] {
+        self.check_node(node);
+        let (row_ptr, out) = self.out.get_or_init(|| {
+            let (row_ptr, permutation) = self.result.tail_index();
+            let out = permutation.into_iter().map(|k| ArcId(k as u32)).collect();
+            (row_ptr, out)
+        });
+        &out[row_ptr[node.index()]..row_ptr[node.index() + 1]]
+    }
+
+    fn check_node(&self, node: NodeId) {
+        debug_assert!(
+            node.index() < self.node_count(),
+            "node {} is not in this network of {} nodes",
+            node.to_dimacs(),
+            self.node_count()
+        );
+    }
+
+    fn check_arc(&self, arc: ArcId) {
+        debug_assert!(
+            arc.index() < self.arc_count(),
+            "arc index {} is not in this network of {} arcs",
+            arc.0,
+            self.arc_count()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::fixture::doc_result;
+    use crate::{ArcId, NodeId};
+
+    #[test]
+    fn out_arcs_partition_the_arcs_by_tail() {
+        let result = doc_result();
+        let view = result.view();
+        let mut seen = Vec::new();
+        for node in view.nodes() {
+            let out = view.out_arcs(node);
+            assert!(out.windows(2).all(|w| w[0] < w[1]));
+            assert!(out.iter().all(|&arc| view.tail(arc) == node));
+            seen.extend_from_slice(out);
+        }
+        seen.sort_unstable();
+        assert!(seen.into_iter().eq(view.arcs()));
+    }
+
+    #[test]
+    fn out_arcs_of_a_source_match_the_raw_arcs() {
+        let result = doc_result();
+        let view = result.view();
+        let expected: Vec<_> = (0..result.arcs.len() as u32)
+            .filter(|&k| result.arcs[k as usize].from == 1)
+            .map(ArcId)
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(view.out_arcs(NodeId::from_dimacs(1)), expected);
+        assert_eq!(view.supply(NodeId::from_dimacs(1)), result.supply[0]);
+        let arc = ArcId(0);
+        assert_eq!(view.head(arc).to_dimacs(), result.arcs[0].to);
+    }
+
+    #[test]
+    #[should_panic(expected = "node 0 is not a valid DIMACS id")]
+    fn node_zero_is_rejected() {
+        NodeId::from_dimacs(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "node 4294967297 is not a valid DIMACS id")]
+    fn ids_beyond_u32_are_rejected() {
+        assert_eq!(NodeId::from_dimacs(1 << 32), NodeId(u32::MAX));
+        NodeId::from_dimacs((1 << 32) + 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "node 513 is not in this network of 512 nodes")]
+    fn out_of_range_nodes_panic_with_the_dimacs_id() {
+        let result = doc_result();
+        result.view().out_arcs(NodeId(512));
+    }
+}
@@ -0,0 +1,138 @@
+//! wasm-bindgen wrappers for generating small instances in the browser.
+//!
+//! From JavaScript, after building with `--crate-type cdylib` for
+//! `wasm32-unknown-unknown` and running `wasm-bindgen` on the module:
+//!
+//! ```js
+//! const params = new WasmParams({
+//!   nodes: 20, sources: 3, sinks: 3, density: 60, mincost: 1, maxcost: 10,
+//!   supply: 50, tsources: 0, tsinks: 0, hicost_pct: 0, capacitated_pct: 100,
+//!   mincap: 5, maxcap: 30,
+//! });
+//! const text = generate_dimacs(42, params);
+//! const { arcs, supply } = generate_json(42, params);
+//! ```
+//!
+//! Instances are capped at [`MAX_NODES`] nodes and [`MAX_ARCS`] arcs so a
+//! mistyped parameter fails with an error instead of exhausting the tab's
+//! memory.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{NetgenParams, NetgenResult, generate};
+
+/// Most nodes a [`WasmParams`] may ask for.
+pub const MAX_NODES: i64 = 100_000;
+/// Most arcs (`density`) a [`WasmParams`] may ask for.
+pub const MAX_ARCS: i64 = 1_000_000;
+
+/// Generation parameters, built from a JavaScript object with the fields of
+/// [`NetgenParams`].
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmParams {
+    params: NetgenParams,
+}
+
+#[wasm_bindgen]
+impl WasmParams {
+    /// Read and validate the parameters in `object`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(object: JsValue) -> Result<WasmParams, JsError> {
+        let params: NetgenParams = serde_wasm_bindgen::from_value(object)
+            .map_err(|e| JsError::new(&format!("bad parameter object: {e}")))?;
+        WasmParams::checked(params).map_err(|e| JsError::new(&e))
+    }
+}
+
+impl WasmParams {
+    /// Wrap `params` if they are valid and within the size caps.
+    fn checked(params: NetgenParams) -> Result<WasmParams, String> {
+        if params.nodes > MAX_NODES {
+            return Err(format!(
+                "{} nodes requested, at most {MAX_NODES} are allowed in the browser",
+                params.nodes
+            ));
+        }
+        if params.density > MAX_ARCS {
+            return Err(format!(
+                "{} arcs requested, at most {MAX_ARCS} are allowed in the browser",
+                params.density
+            ));
+        }
+        params.validate().map_err(|e| e.to_string())?;
+        Ok(WasmParams { params })
+    }
+}
+
+fn generate_checked(seed: u32, params: &WasmParams) -> Result<NetgenResult, String> {
+    generate(seed.into(), &params.params).map_err(|e| e.to_string())
+}
+
+/// The DIMACS text of the instance for `seed`, as problem 1.
+#[wasm_bindgen]
+pub fn generate_dimacs(seed: u32, params: &WasmParams) -> Result<String, JsError> {
+    let result = generate_checked(seed, params).map_err(|e| JsError::new(&e))?;
+    Ok(result.to_dimacs_string(seed.into(), 1, &params.params))
+}
+
+/// The instance for `seed` as an object with `arcs` and `supply`, node 1
+/// first. Each arc has every field of [`Arc`](crate::Arc): `from`, `to`,
+/// `cost`, `capacity`, `multiplier` (always null here), `uncapacitated`,
+/// `kind` (`"Skeleton"` or `"Random"`) and `hicost`.
+#[wasm_bindgen]
+pub fn generate_json(seed: u32, params: &WasmParams) -> Result<JsValue, JsError> {
+    let result = generate_checked(seed, params).map_err(|e| JsError::new(&e))?;
+    result
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(nodes: i64, density: i64) -> NetgenParams {
+        NetgenParams {
+            nodes,
+            sources: 3,
+            sinks: 3,
+            density,
+            mincost: 1,
+            maxcost: 10,
+            supply: 50,
+            tsources: 0,
+            tsinks: 0,
+            hicost_pct: 0,
+            capacitated_pct: 100,
+            mincap: 5,
+            maxcap: 30,
+        }
+    }
+
+    #[test]
+    fn sizes_are_capped() {
+        assert!(WasmParams::checked(params(20, 60)).is_ok());
+        let err = WasmParams::checked(params(MAX_NODES + 1, 60)).unwrap_err();
+        assert!(err.contains("100001 nodes requested"), "{err}");
+        let err = WasmParams::checked(params(200_000, MAX_ARCS + 1)).unwrap_err();
+        assert!(err.contains("nodes requested"), "{err}");
+        let err = WasmParams::checked(params(20_000, MAX_ARCS + 1)).unwrap_err();
+        assert!(err.contains("1000001 arcs requested"), "{err}");
+        assert!(WasmParams::checked(params(2, 60)).is_err());
+    }
+
+    #[test]
+    fn generates_the_library_instance() {
+        let p = WasmParams::checked(params(20, 60)).unwrap();
+        assert_eq!(
+            generate_checked(42, &p).unwrap(),
+            generate(42, &p.params).unwrap()
+        );
+        assert_eq!(
+            generate_checked(0, &p).unwrap_err(),
+            "seed must be positive"
+        );
+    }
+}
@@ -0,0 +1,2078 @@
+mod fixture;
+
+use std::ops::ControlFlow;
+use std::time::Instant;
+
+use netgen_rs::{
+    Arc, ArcId, ArcKind, ArcOrder, BatchPolicy, ConversionError, CostNote, CountingWriter,
+    DimacsOptions, GenerateOptions, GenerationMode, HeaderOptions, LineEnding, MaxFlowExtra,
+    MultiplierRange, NetgenError, NetgenParams, NodeId, ParamError, ParamErrorKind,
+    ParseParamsError, PhaseDraws, ProblemType, Progress, RelabelMap, Rng, RngPhase, RoundMode,
+    ShortestPathError, Skeleton, SupplyDistribution, UncapacitatedAs, VerifyOutcome,
+    density_for_degree, generate, generate_traced, generate_with, generate_with_options,
+    generate_with_progress, generate_with_report, limits, parse_header, parse_problem_line,
+    to_dimacs_string, verify_flow, verify_instance, write_dimacs, write_dimacs_batch,
+    write_dimacs_network_with_summary, write_dimacs_with_summary, write_problem_file,
+};
+
+use fixture::{DOC_PARMS, doc_params, doc_result};
+
+#[test]
+fn method_forms_match_free_functions() {
+    let params = doc_params();
+    let result = params.generate(13502460).unwrap();
+    assert_eq!(result, generate(13502460, &params).unwrap());
+
+    let mut free = Vec::new();
+    write_dimacs(&mut free, 13502460, 1, &params, &result).unwrap();
+    let mut method = Vec::new();
+    result
+        .write_dimacs(&mut method, 13502460, 1, &params)
+        .unwrap();
+    assert_eq!(free, method);
+
+    let s = result.to_dimacs_string(13502460, 1, &params);
+    assert_eq!(s.as_bytes(), free.as_slice());
+    assert_eq!(s, to_dimacs_string(13502460, 1, &params).unwrap());
+}
+
+#[test]
+fn problem_type_display_round_trip() {
+    for (ty, name) in [
+        (ProblemType::Assignment, "assignment"),
+        (ProblemType::MaxFlow, "max-flow"),
+        (ProblemType::MinCostFlow, "min-cost-flow"),
+    ] {
+        assert_eq!(ty.to_string(), name);
+        assert_eq!(name.parse::<ProblemType>().unwrap(), ty);
+    }
+    assert_eq!(
+        "asn".parse::<ProblemType>().unwrap(),
+        ProblemType::Assignment
+    );
+    assert_eq!("max".parse::<ProblemType>().unwrap(), ProblemType::MaxFlow);
+    assert_eq!(
+        "min".parse::<ProblemType>().unwrap(),
+        ProblemType::MinCostFlow
+    );
+    assert!("transport".parse::<ProblemType>().is_err());
+}
+
+#[test]
+fn params_display_round_trip() {
+    let params = doc_params();
+    let text = params.to_string();
+    assert_eq!(text, "512 10 10 2000 5 500 1000 3 3 20 80 50 2000");
+    let parsed: NetgenParams = text.parse().unwrap();
+    assert_eq!(parsed, params);
+}
+
+#[test]
+fn params_from_str_errors() {
+    assert_eq!(
+        "1 2 3".parse::<NetgenParams>().unwrap_err(),
+        ParseParamsError::WrongCount {
+            expected: 13,
+            found: 3
+        }
+    );
+    assert_eq!(
+        "512 10 x 2000 5 500 1000 3 3 20 80 50 2000"
+            .parse::<NetgenParams>()
+            .unwrap_err(),
+        ParseParamsError::InvalidInteger("x".into())
+    );
+    assert_eq!(
+        "512 0 10 2000 5 500 1000 3 3 20 80 50 2000"
+            .parse::<NetgenParams>()
+            .unwrap_err(),
+        ParseParamsError::Invalid(ParamError::NonPositiveSources { sources: 0 })
+    );
+}
+
+#[test]
+fn parse_lemon_problem_line() {
+    // Line from lemon_scripts/netgen_8.sh, with its original column padding.
+    let line =
+        "13502460   8       256    16    16      2048  1 10000    16000  0 0 100 100  1 1000";
+    let (seed, problem, params) = parse_problem_line(line).unwrap();
+    assert_eq!(seed, 13502460);
+    assert_eq!(problem, 8);
+    assert_eq!(
+        params.to_array(),
+        [256, 16, 16, 2048, 1, 10000, 16000, 0, 0, 100, 100, 1, 1000]
+    );
+    assert_eq!(
+        format!("{seed} {problem} {params}"),
+        line.split_whitespace().collect::<Vec<_>>().join(" ")
+    );
+    assert!(parse_problem_line(&params.to_string()).is_err());
+}
+
+#[test]
+fn problem_file_round_trips() {
+    let specs = [
+        (
+            13502460,
+            1,
+            NetgenParams::from_slice(&[512, 2, 2, 1000, 10, 100, 200, 0, 0, 20, 100, 10, 1000])
+                .unwrap(),
+        ),
+        (
+            12345,
+            7,
+            NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap(),
+        ),
+    ];
+    assert_eq!(
+        specs[1].2.to_problem_line(12345, 7),
+        "12345 7 100 50 50 500 1 100 50 0 0 0 0 1 100"
+    );
+    let mut buf = Vec::new();
+    write_problem_file(&mut buf, &specs).unwrap();
+    let text = String::from_utf8(buf).unwrap();
+    let lines: Vec<_> = text.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2], "0");
+    for (line, spec) in lines.iter().zip(&specs) {
+        assert_eq!(&parse_problem_line(line).unwrap(), spec);
+    }
+
+    let mut buf = Vec::new();
+    let mut bad = specs.to_vec();
+    bad[1].0 = 0;
+    let err = write_problem_file(&mut buf, &bad).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(buf.is_empty());
+    // The readers stop at a non-positive problem number too.
+    for problem in [0, -3] {
+        let mut bad = specs.to_vec();
+        bad[1].1 = problem;
+        let err = write_problem_file(&mut buf, &bad).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn skeleton_phases_compose_to_generate() {
+    let cases: [&[i64]; 4] = [
+        &DOC_PARMS,
+        &[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+        &[200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+        &[256, 16, 16, 2048, 1, 10000, 16000, 0, 0, 100, 100, 1, 1000],
+    ];
+    for parms in cases {
+        let params = NetgenParams::from_slice(parms).unwrap();
+        for seed in [1, 13502460, 2147483646] {
+            let (skeleton, rng) = Skeleton::build(seed, &params).unwrap();
+            assert_eq!(skeleton.fill_random(rng), generate(seed, &params).unwrap());
+        }
+    }
+}
+
+#[test]
+fn skeleton_chains_cover_transshipment_nodes() {
+    let params = doc_params();
+    let (skeleton, _) = Skeleton::build(13502460, &params).unwrap();
+    assert_eq!(skeleton.chains().len(), params.sources as usize);
+    let mut nodes: Vec<u64> = skeleton.chains().iter().flatten().copied().collect();
+    nodes.sort_unstable();
+    let expected: Vec<u64> =
+        ((params.sources + 1) as u64..=(params.nodes - params.sinks) as u64).collect();
+    assert_eq!(nodes, expected);
+}
+
+#[test]
+fn skeleton_survives_a_different_fill() {
+    let params = doc_params();
+    let (skeleton, _) = Skeleton::build(13502460, &params).unwrap();
+    let result = skeleton.fill_random(Rng::new(42));
+    assert_eq!(result.supply, skeleton.supply());
+    let mut arcs = result.arcs.iter();
+    for arc in skeleton.arcs() {
+        assert!(arcs.any(|a| a == arc), "skeleton arc {arc:?} missing");
+    }
+    assert_ne!(result, generate(13502460, &params).unwrap());
+}
+
+#[test]
+fn report_counts_arcs_without_changing_them() {
+    let params = doc_params();
+    let (result, report) = generate_with_report(13502460, &params).unwrap();
+    assert_eq!(result, generate(13502460, &params).unwrap());
+    assert_eq!(report.skeleton_arcs + report.random_arcs, result.arcs.len());
+    let (skeleton, _) = Skeleton::build(13502460, &params).unwrap();
+    assert_eq!(report.skeleton_arcs, skeleton.arcs().len());
+    assert_eq!(report.requested_arcs, 2000);
+    assert_eq!(report.shortfall, 2000 - result.arcs.len());
+    assert!(report.duration.is_some());
+    if let Some(rate) = report.arcs_per_second() {
+        assert!(rate > 0.0);
+    }
+}
+
+#[test]
+fn arc_kinds_match_the_skeleton() {
+    let params = doc_params();
+    let (result, report) = generate_with_report(13502460, &params).unwrap();
+    let (skeleton, _) = Skeleton::build(13502460, &params).unwrap();
+    let skeleton_arcs: Vec<&Arc> = result
+        .arcs
+        .iter()
+        .filter(|a| a.kind == ArcKind::Skeleton)
+        .collect();
+    assert_eq!(skeleton_arcs.len(), report.skeleton_arcs);
+    assert!(skeleton_arcs.iter().copied().eq(skeleton.arcs()));
+    assert!(
+        result
+            .arcs
+            .iter()
+            .all(|a| a.kind != ArcKind::Unknown && (a.kind == ArcKind::Skeleton || !a.hicost))
+    );
+    assert!(
+        skeleton_arcs
+            .iter()
+            .all(|a| !a.hicost || a.cost == params.maxcost)
+    );
+}
+
+#[test]
+fn hicost_share_of_skeleton_arcs_follows_hicost_pct() {
+    for pct in [0, 20, 65, 100] {
+        let params = NetgenParams::from_slice(&[
+            20000, 100, 100, 60000, 1, 1000, 100000, 0, 0, pct, 50, 1, 1000,
+        ])
+        .unwrap();
+        let result = generate(4242, &params).unwrap();
+        let skeleton = result
+            .arcs
+            .iter()
+            .filter(|a| a.kind == ArcKind::Skeleton)
+            .count();
+        assert!(skeleton > 10000, "{skeleton}");
+        let share = result.hicost_arc_count() as f64 / skeleton as f64;
+        assert!(
+            (share - pct as f64 / 100.0).abs() < 0.02,
+            "{pct}% hicost, {share} flagged"
+        );
+    }
+}
+
+#[test]
+fn report_detects_dropped_arcs_near_max_density() {
+    // 20 nodes can hold at most 20 * 19 = 380 distinct arcs.
+    let params =
+        NetgenParams::from_slice(&[20, 2, 2, 380, 1, 100, 100, 0, 0, 10, 50, 1, 50]).unwrap();
+    let (result, report) = generate_with_report(13502460, &params).unwrap();
+    assert!(report.dropped_arcs > 0);
+    assert!(result.arcs.len() < params.density as usize);
+    assert_eq!(report.shortfall, 380 - result.arcs.len());
+}
+
+#[test]
+fn arcs_are_dropped_well_below_max_arcs() {
+    // 303 arcs is 40% of the 758 possible, but 10 arcs per node is over
+    // half of the 27 heads a tail can reach.
+    let params =
+        NetgenParams::from_slice(&[30, 2, 2, 303, 1, 100, 100, 0, 0, 10, 50, 1, 50]).unwrap();
+    assert!(params.density < params.max_arcs());
+    assert!(params.lint().is_empty());
+    let (result, report) = generate_with_report(77, &params).unwrap();
+    assert_eq!(report.dropped_arcs, 3);
+    // Later tails made up for them.
+    assert_eq!(result.arcs.len(), 303);
+    assert_eq!(report.shortfall, 0);
+
+    let options = DimacsOptions {
+        header: HeaderOptions {
+            dropped_arcs: report.dropped_arcs,
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 77, 1, &params, &result, &options).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("\nc Dropped arcs: 3\n"));
+    assert!(text.contains("\np min 30 303\n"));
+
+    // Fast mode never drops.
+    let fast = GenerateOptions {
+        mode: GenerationMode::Fast,
+        ..GenerateOptions::default()
+    };
+    let (_, report) = generate_with_options(77, &params, &fast).unwrap();
+    assert_eq!(report.dropped_arcs, 0);
+}
+
+#[test]
+fn drops_at_max_arcs_leave_the_network_short() {
+    let params =
+        NetgenParams::from_slice(&[20, 2, 2, 308, 1, 100, 100, 0, 0, 10, 50, 1, 50]).unwrap();
+    assert_eq!(params.density, params.max_arcs());
+    let (result, report) = generate_with_report(13502460, &params).unwrap();
+    assert_eq!(report.dropped_arcs, 38);
+    assert_eq!(result.arcs.len(), 306);
+    assert_eq!(report.shortfall, 2);
+}
+
+#[test]
+fn average_degree_matches_lemon_scripts() {
+    // netgen_8.sh, exponent 15: n = 2^15, m = 8n.
+    let params = NetgenParams::with_average_degree(1 << 15, 8).unwrap();
+    let (_, _, lemon) = parse_problem_line(
+        "13502460  15     32768   181   181    262144  1 10000   181000  0 0 100 100  1 1000",
+    )
+    .unwrap();
+    assert_eq!(params, lemon);
+    assert_eq!(params.average_degree(), 8.0);
+
+    // netgen_deg.sh, problem 3: n = 4096, m = 8n.
+    let params = NetgenParams::with_average_degree(4096, 8).unwrap();
+    assert_eq!(
+        params.to_string(),
+        "4096 64 64 32768 1 10000 64000 0 0 100 100 1 1000"
+    );
+}
+
+#[test]
+fn density_for_degree_checks_overflow() {
+    assert_eq!(density_for_degree(4096, 8), Some(32768));
+    assert_eq!(density_for_degree(i64::MAX / 2, 3), None);
+    assert_eq!(density_for_degree(0, 8), None);
+    assert_eq!(
+        NetgenParams::with_average_degree(i64::MAX / 2, 3)
+            .unwrap_err()
+            .kind(),
+        ParamErrorKind::TooBig
+    );
+}
+
+#[test]
+fn average_degree_rejects_infeasible_density() {
+    // 16 nodes with 4 sources and 4 sinks hold at most 12 * 12 - 8 arcs.
+    let params = NetgenParams::with_average_degree(16, 8).unwrap();
+    assert_eq!(params.max_arcs(), 136);
+    assert_eq!(
+        NetgenParams::with_average_degree(16, 9).unwrap_err(),
+        ParamError::DensityTooHigh {
+            density: 144,
+            max: 136
+        }
+    );
+}
+
+#[test]
+fn generate_rejects_invalid_params() {
+    let mut params = doc_params();
+    params.sources = 0;
+    assert_eq!(generate(1, &params), Err(NetgenError::BadParms));
+    params = doc_params();
+    params.density = 1 << 40;
+    assert_eq!(
+        params.validate(),
+        Err(ParamError::TooBig {
+            nodes: 512,
+            density: 1 << 40
+        })
+    );
+    assert_eq!(generate(1, &params), Err(NetgenError::TooBig));
+    params = doc_params();
+    params.density = 512 * 512 + 1;
+    assert_eq!(
+        params.validate().map_err(|e| e.kind()),
+        Err(ParamErrorKind::DensityTooHigh)
+    );
+    params.density = 512 * 512;
+    assert_eq!(params.validate(), Ok(()));
+}
+
+#[test]
+fn wide_ranges_are_drawn_in_full() {
+    let mut params = doc_params();
+    params.mincap = 1;
+    params.maxcap = 1 << 40;
+    params.mincost = i64::MIN;
+    params.maxcost = i64::MAX;
+    assert_eq!(params.validate(), Ok(()));
+    let result = generate(13502460, &params).unwrap();
+    assert_eq!(result.check_invariants(&params), Ok(()));
+    // The classic draw would never exceed 2^31 - 2 above the minimum.
+    assert!(result.arcs.iter().any(|a| a.capacity > 1 << 32));
+    assert!(result.arcs.iter().any(|a| a.cost > 0) && result.arcs.iter().any(|a| a.cost < 0));
+    assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+
+    // Replaying the skeleton with the same generator reproduces the run.
+    let (skeleton, rng) = Skeleton::build(13502460, &params).unwrap();
+    assert_eq!(skeleton.fill_random(rng), result);
+    let assignment =
+        NetgenParams::from_slice(&[100, 50, 50, 500, 0, 1 << 50, 50, 0, 0, 0, 0, 1, 1]).unwrap();
+    let (skeleton, rng) = Skeleton::build(42, &assignment).unwrap();
+    assert_eq!(
+        skeleton.fill_random(rng),
+        generate(42, &assignment).unwrap()
+    );
+}
+
+#[test]
+fn negative_costs_cover_the_full_range() {
+    let params =
+        NetgenParams::from_slice(&[512, 10, 10, 4000, -100, 100, 1000, 3, 3, 20, 80, 50, 2000])
+            .unwrap();
+    assert_eq!(params.problem_type(), ProblemType::MinCostFlow);
+    let result = generate(13502460, &params).unwrap();
+    assert_eq!(result.check_invariants(&params), Ok(()));
+    let costs: Vec<i64> = result.arcs.iter().map(|a| a.cost).collect();
+    assert_eq!(costs.iter().min(), Some(&-100));
+    assert_eq!(costs.iter().max(), Some(&100));
+    assert!(costs.contains(&0));
+    let dimacs = to_dimacs_string(13502460, 1, &params).unwrap();
+    assert!(dimacs.contains("c   Minimum arc cost:           -100\n"));
+    assert!(
+        dimacs
+            .lines()
+            .any(|l| l.starts_with("a ") && l.ends_with(" -100"))
+    );
+
+    // A collapsed negative range still draws once per arc.
+    let mut fixed = params.clone();
+    fixed.maxcost = -7;
+    fixed.mincost = -7;
+    let result = generate(13502460, &fixed).unwrap();
+    assert!(result.arcs.iter().all(|a| a.cost == -7));
+}
+
+#[test]
+fn skeleton_cost_range_applies_to_skeleton_arcs_only() {
+    let params = doc_params();
+    let options = GenerateOptions {
+        skeleton_mincost: Some(1),
+        skeleton_maxcost: Some(1),
+        ..GenerateOptions::default()
+    };
+    let (result, _) = generate_with_options(13502460, &params, &options).unwrap();
+    let reference = generate(13502460, &params).unwrap();
+    let (skeleton, _) = Skeleton::build(13502460, &params).unwrap();
+    assert_eq!(result.supply, reference.supply);
+    assert_eq!(result.arcs.len(), reference.arcs.len());
+
+    // Skeleton arcs appear in order among the reference arcs.
+    let mut next = skeleton.arcs().iter().peekable();
+    let (mut lo, mut hi) = (i64::MAX, i64::MIN);
+    for (arc, reference) in result.arcs.iter().zip(&reference.arcs) {
+        assert_eq!(
+            (arc.from, arc.to, arc.capacity),
+            (reference.from, reference.to, reference.capacity)
+        );
+        if next.next_if(|&s| s == reference).is_some() {
+            assert_eq!(arc.cost, 1, "skeleton arc {arc:?}");
+        } else {
+            assert_eq!(arc, reference);
+            (lo, hi) = (lo.min(arc.cost), hi.max(arc.cost));
+        }
+    }
+    assert!(next.next().is_none());
+    assert_eq!((lo, hi), (params.mincost, params.maxcost));
+}
+
+#[test]
+fn skeleton_capacity_range_keeps_feasibility() {
+    let params = doc_params();
+    let options = GenerateOptions {
+        skeleton_mincap: Some(1),
+        skeleton_maxcap: Some(10),
+        ..GenerateOptions::default()
+    };
+    let (result, _) = generate_with_options(13502460, &params, &options).unwrap();
+    assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+
+    let empty = GenerateOptions {
+        skeleton_mincost: Some(10),
+        skeleton_maxcost: Some(9),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        empty.validate(&params),
+        Err(ParamError::SkeletonMinCostExceedsMaxCost {
+            mincost: 10,
+            maxcost: 9
+        })
+    );
+    assert_eq!(
+        generate_with_options(1, &params, &empty),
+        Err(NetgenError::Options(
+            ParamError::SkeletonMinCostExceedsMaxCost {
+                mincost: 10,
+                maxcost: 9
+            }
+        ))
+    );
+    let empty = GenerateOptions {
+        skeleton_mincap: Some(params.maxcap + 1),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        empty.validate(&params),
+        Err(ParamError::SkeletonMinCapExceedsMaxCap {
+            mincap: 2001,
+            maxcap: 2000
+        })
+    );
+}
+
+#[test]
+fn fast_mode_keeps_invariants_and_feasibility() {
+    let fast = GenerateOptions {
+        mode: GenerationMode::Fast,
+        ..GenerateOptions::default()
+    };
+    for params in [
+        doc_params(),
+        // Few sources, so long chains with many tied tails.
+        NetgenParams::from_slice(&[4096, 2, 8, 20000, 1, 100, 1000, 0, 0, 30, 50, 100, 1000])
+            .unwrap(),
+        NetgenParams::from_slice(&[200, 100, 100, 1500, 1, 100, 100, 0, 0, 0, 0, 1, 1]).unwrap(),
+    ] {
+        for seed in [1, 13502460] {
+            let exact = generate_with(seed, &params, GenerationMode::ReferenceExact).unwrap();
+            let (result, report) = generate_with_options(seed, &params, &fast).unwrap();
+            assert_eq!(result.check_invariants(&params), Ok(()));
+            assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+            // The source supplies are drawn before anything diverges.
+            let sources = params.sources as usize;
+            assert_eq!(result.supply[..sources], exact.supply[..sources]);
+            // Every budgeted random arc is drawn, none dropped.
+            assert_eq!(report.dropped_arcs, 0);
+            let random = result.arcs.iter().filter(|a| a.kind == ArcKind::Random);
+            assert_eq!(random.count(), report.random_arcs);
+            assert!(result.arcs.iter().all(|a| a.to >= 1));
+            assert_eq!(
+                result,
+                generate_with(seed, &params, GenerationMode::Fast).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn max_out_degree_bounds_every_tail() {
+    let options = GenerateOptions {
+        max_out_degree: Some(4),
+        mode: GenerationMode::Fast,
+        ..GenerateOptions::default()
+    };
+    for params in [
+        NetgenParams::from_slice(&[1000, 10, 10, 3500, 1, 100, 1000, 0, 0, 10, 50, 1, 100])
+            .unwrap(),
+        NetgenParams::from_slice(&[600, 20, 40, 2100, 1, 100, 800, 5, 10, 30, 50, 1, 100]).unwrap(),
+        doc_params(),
+    ] {
+        for seed in [1, 13502460] {
+            let (result, report) = generate_with_options(seed, &params, &options).unwrap();
+            let mut out_degree = vec![0; params.nodes as usize + 1];
+            for arc in &result.arcs {
+                out_degree[arc.from as usize] += 1;
+            }
+            assert!(out_degree.iter().all(|&d| d <= 4), "{params}");
+            assert_eq!(result.arcs.len() as i64, params.density, "{params}");
+            assert_eq!(report.shortfall, 0);
+            assert_eq!(result.check_invariants(&params), Ok(()));
+            assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+        }
+    }
+
+    let params = doc_params();
+    let (result, _) = generate_with_options(1, &params, &options).unwrap();
+    let dimacs = DimacsOptions {
+        header: HeaderOptions {
+            mode: GenerationMode::Fast,
+            max_out_degree: Some(4),
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 1, 1, &params, &result, &dimacs).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(
+        text.contains("c Generation mode: fast (not reference-exact)\nc Maximum out-degree: 4\n")
+    );
+}
+
+#[test]
+fn max_out_degree_is_checked_up_front() {
+    let params = doc_params();
+    // 512 nodes less 7 pure sinks leaves 505 tails, room for 1515 arcs.
+    let options = GenerateOptions {
+        max_out_degree: Some(3),
+        mode: GenerationMode::Fast,
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        generate_with_options(1, &params, &options).unwrap_err(),
+        NetgenError::Options(ParamError::OutDegreeTooLow {
+            max_out_degree: 3,
+            density: 2000,
+            tails: 505
+        })
+    );
+    let options = GenerateOptions {
+        max_out_degree: Some(4),
+        ..options
+    };
+    assert_eq!(options.validate(&params), Ok(()));
+    let exact = GenerateOptions {
+        mode: GenerationMode::ReferenceExact,
+        ..options
+    };
+    assert_eq!(
+        exact.validate(&params).map_err(|e| e.kind()),
+        Err(ParamErrorKind::OutDegreeNeedsFastMode)
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn fast_mode_does_not_depend_on_the_thread_count() {
+    let params =
+        NetgenParams::from_slice(&[4096, 20, 20, 40000, 1, 100, 1000, 5, 5, 30, 50, 100, 1000])
+            .unwrap();
+    let on_threads = |threads| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        pool.install(|| generate_with(13502460, &params, GenerationMode::Fast).unwrap())
+    };
+    let serial = on_threads(1);
+    for threads in [2, 3, 8] {
+        assert_eq!(on_threads(threads), serial, "{threads} threads");
+    }
+}
+
+/// Run with `cargo test --release --features rayon -- --ignored` on a
+/// machine with several cores.
+#[cfg(feature = "rayon")]
+#[test]
+#[ignore]
+fn fast_mode_is_faster_on_many_threads() {
+    let params = NetgenParams::with_average_degree(1 << 20, 10).unwrap();
+    let timed = |threads| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let start = Instant::now();
+        pool.install(|| generate_with(13502460, &params, GenerationMode::Fast).unwrap());
+        start.elapsed()
+    };
+    let serial = timed(1);
+    let parallel = timed(8);
+    assert!(
+        parallel < serial,
+        "8 threads {parallel:?}, 1 thread {serial:?}"
+    );
+}
+
+#[test]
+#[ignore]
+fn fast_mode_is_not_slower() {
+    // Few sources with long chains, where the skeleton sort dominates.
+    let params = NetgenParams::from_slice(&[
+        1 << 16,
+        4,
+        4,
+        1 << 18,
+        1,
+        1000,
+        1_000_000,
+        0,
+        0,
+        30,
+        50,
+        100,
+        1000,
+    ])
+    .unwrap();
+    let best_of_five = |mode| {
+        (0..5)
+            .map(|_| {
+                let start = Instant::now();
+                generate_with(13502460, &params, mode).unwrap();
+                start.elapsed()
+            })
+            .min()
+            .unwrap()
+    };
+    let exact = best_of_five(GenerationMode::ReferenceExact);
+    let fast = best_of_five(GenerationMode::Fast);
+    // Generous, so a noisy machine does not fail it.
+    assert!(fast < exact * 3 / 2, "fast {fast:?}, exact {exact:?}");
+}
+
+#[test]
+fn fast_mode_is_stamped_into_the_header() {
+    let params = doc_params();
+    let result = generate_with(13502460, &params, GenerationMode::Fast).unwrap();
+    let options = DimacsOptions {
+        header: HeaderOptions {
+            mode: GenerationMode::Fast,
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &result, &options)
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let stamp = "c Generation mode: fast (not reference-exact)\n";
+    assert!(text.contains(&format!("Maximum arc capacity:       2000\n{stamp}")));
+    let exact = params
+        .generate(13502460)
+        .unwrap()
+        .to_dimacs_string(13502460, 1, &params);
+    assert!(!exact.contains("Generation mode"));
+}
+
+#[test]
+fn cost_transform_notes_are_written_into_the_header() {
+    let params = doc_params();
+    let result = params.generate(13502460).unwrap();
+    let (rounded, rounding) = result.round_costs_with_note(50, RoundMode::Up).unwrap();
+    let (rescaled, rescaling) = rounded.rescale_costs_to_range_with_note(0, 1).unwrap();
+    assert_eq!(
+        rescaling,
+        CostNote::Rescaled {
+            from: (50, 500),
+            to: (0, 1)
+        }
+    );
+    let options = DimacsOptions {
+        header: HeaderOptions {
+            notes: vec![rounding.to_string(), rescaling.to_string()],
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &rescaled, &options)
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains(
+        "Maximum arc capacity:       2000\n\
+         c Costs rounded to multiples of 50 (up)\n\
+         c Costs rescaled from [50, 500] to [0, 1]\n"
+    ));
+    // The notes are ordinary comments to the header parser.
+    let (seed, _, parsed) = parse_header(text.as_bytes()).unwrap();
+    assert_eq!((seed, parsed), (13502460, params.clone()));
+
+    // A note with line breaks stays in comments.
+    let options = DimacsOptions {
+        header: HeaderOptions {
+            notes: vec!["x\np min 1 0\r\ny\rz".to_string()],
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &rescaled, &options)
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(
+        text.contains("2000\nc x\nc p min 1 0\nc y\nc z\n"),
+        "{text}"
+    );
+    assert_eq!(
+        netgen_rs::parse_dimacs(&text).unwrap().canonicalize(),
+        rescaled.canonicalize()
+    );
+}
+
+#[test]
+fn supply_up_to_i64_max_is_distributed_without_overflow() {
+    // Tests run with overflow checks, so any wrapping would panic here.
+    for (sources, sinks) in [(1, 1), (1, 3), (2, 1), (3, 3)] {
+        let params = NetgenParams {
+            nodes: 12,
+            sources,
+            sinks,
+            density: 40,
+            supply: i64::MAX,
+            tsources: 0,
+            tsinks: 0,
+            maxcap: i64::MAX,
+            ..doc_params()
+        };
+        let mut custom = vec![0; sources as usize];
+        custom[0] = i64::MAX;
+        let mut demands = vec![1; sinks as usize];
+        demands[0] = i64::MAX - (sinks - 1);
+        let distributions = [
+            SupplyDistribution::NetgenRandom,
+            SupplyDistribution::Equal,
+            SupplyDistribution::Custom(custom),
+        ];
+        for distribution in distributions {
+            for demand_distribution in [None, Some(demands.clone())] {
+                let options = GenerateOptions {
+                    supply_distribution: distribution.clone(),
+                    demand_distribution,
+                    ..GenerateOptions::default()
+                };
+                for seed in [1, 13502460, 2147483646] {
+                    let (result, _) = generate_with_options(seed, &params, &options).unwrap();
+                    assert_eq!(result.check_invariants(&params), Ok(()));
+                    let total: i128 = result.supply.iter().map(|&s| i128::from(s)).sum();
+                    assert_eq!(total, 0);
+                    let min = result.supply.iter().min().unwrap();
+                    assert!(*min >= -i64::MAX);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn supplies_smaller_than_the_sink_count_go_to_one_sink() {
+    // 12 units over 10 sources, each with 4 to 40 sinks.
+    let params =
+        NetgenParams::from_slice(&[200, 10, 40, 2000, 1, 100, 12, 0, 0, 10, 50, 1, 100]).unwrap();
+    let (result, report) = generate_with_report(13502460, &params).unwrap();
+    assert!(report.undivided_sources > 0, "{report:?}");
+    assert_eq!(report.sink_demands.len(), 40);
+    assert!(report.sink_demands.iter().all(|&d| d >= 0));
+    assert_eq!(report.sink_demands.iter().sum::<i64>(), 12);
+    assert_eq!(result.supply[..10].iter().sum::<i64>(), 12);
+    assert!(result.supply[10..].iter().all(|&s| s <= 0));
+    assert_eq!(result.supply.iter().sum::<i64>(), 0);
+    assert_eq!(result.check_invariants(&params), Ok(()));
+    for (demand, supply) in report.sink_demands.iter().zip(&result.supply[160..]) {
+        assert_eq!(*demand, -supply);
+    }
+
+    let (_, report) = generate_with_report(13502460, &doc_params()).unwrap();
+    assert_eq!(report.undivided_sources, 0);
+}
+
+#[test]
+fn verify_instance_finds_the_first_edited_line() {
+    let params = doc_params();
+    let dimacs = to_dimacs_string(13502460, 1, &params).unwrap();
+    assert_eq!(
+        verify_instance(dimacs.as_bytes()).unwrap(),
+        VerifyOutcome::Match
+    );
+
+    // Extra header comments are not part of the network.
+    let result = params.generate(13502460).unwrap();
+    let options = DimacsOptions {
+        header: HeaderOptions {
+            machine_readable: true,
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut tagged = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut tagged, 13502460, 1, &params, &result, &options)
+        .unwrap();
+    assert_eq!(
+        verify_instance(tagged.as_slice()).unwrap(),
+        VerifyOutcome::Match
+    );
+
+    // The generation mode and out-degree bound are read from their stamps;
+    // skeleton cost ranges leave none.
+    let write = |generate: GenerateOptions| {
+        let (result, _) = generate_with_options(13502460, &params, &generate).unwrap();
+        let options = DimacsOptions {
+            header: HeaderOptions {
+                mode: generate.mode,
+                max_out_degree: generate.max_out_degree,
+                ..HeaderOptions::default()
+            },
+            ..DimacsOptions::default()
+        };
+        let mut out = Vec::new();
+        netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &result, &options)
+            .unwrap();
+        verify_instance(out.as_slice()).unwrap()
+    };
+    let fast = GenerateOptions {
+        mode: GenerationMode::Fast,
+        max_out_degree: Some(8),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(write(fast), VerifyOutcome::Match);
+    let skeleton = GenerateOptions {
+        skeleton_maxcost: Some(10),
+        ..GenerateOptions::default()
+    };
+    assert!(matches!(write(skeleton), VerifyOutcome::HeaderOnly { .. }));
+
+    // A capacity edited on the last line, and a truncated file.
+    let last = dimacs.lines().count();
+    let (body, line) = dimacs.trim_end().rsplit_once('\n').unwrap();
+    let mut fields: Vec<&str> = line.split(' ').collect();
+    let capacity = format!("{}0", fields[4]);
+    fields[4] = &capacity;
+    let edited = format!("{body}\n{}\n", fields.join(" "));
+    assert_eq!(
+        verify_instance(edited.as_bytes()).unwrap(),
+        VerifyOutcome::HeaderOnly { line: last }
+    );
+    assert_eq!(
+        verify_instance(format!("{body}\n").as_bytes()).unwrap(),
+        VerifyOutcome::HeaderOnly { line: last }
+    );
+
+    assert_eq!(
+        verify_instance("p min 2 1\nn 1 5\nn 2 -5\na 1 2 0 5 1\n".as_bytes()).unwrap(),
+        VerifyOutcome::NotNetgen
+    );
+    assert_eq!(
+        verify_instance(&[0xff, 0xfe, b'\n'][..]).unwrap(),
+        VerifyOutcome::NotNetgen
+    );
+}
+
+#[test]
+fn equal_supply_distribution() {
+    let mut params = doc_params();
+    params.supply = 1003;
+    let options = GenerateOptions {
+        supply_distribution: SupplyDistribution::Equal,
+        ..GenerateOptions::default()
+    };
+    let (result, _) = generate_with_options(13502460, &params, &options).unwrap();
+    let mut expected = vec![100; 10];
+    expected[9] = 103;
+    assert_eq!(result.supply[..10], expected);
+    assert_eq!(result.supply.iter().sum::<i64>(), 0);
+    assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+}
+
+#[test]
+fn custom_supply_distribution() {
+    let params = doc_params();
+    let supplies = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 955];
+    let options = GenerateOptions {
+        supply_distribution: SupplyDistribution::Custom(supplies.clone()),
+        ..GenerateOptions::default()
+    };
+    let (result, _) = generate_with_options(13502460, &params, &options).unwrap();
+    assert_eq!(result.supply[..10], supplies);
+    assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+
+    let bad_sum = GenerateOptions {
+        supply_distribution: SupplyDistribution::Custom(vec![101; 10]),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        bad_sum.validate(&params),
+        Err(ParamError::CustomSupplySum {
+            sum: 1010,
+            supply: 1000
+        })
+    );
+    assert_eq!(
+        generate_with_options(13502460, &params, &bad_sum),
+        Err(NetgenError::Options(ParamError::CustomSupplySum {
+            sum: 1010,
+            supply: 1000
+        }))
+    );
+    let bad_length = GenerateOptions {
+        supply_distribution: SupplyDistribution::Custom(vec![500, 500]),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        bad_length.validate(&params),
+        Err(ParamError::CustomSupplyLength {
+            len: 2,
+            sources: 10
+        })
+    );
+}
+
+#[test]
+fn netgen_random_distribution_matches_reference() {
+    let options = GenerateOptions {
+        supply_distribution: SupplyDistribution::NetgenRandom,
+        ..GenerateOptions::default()
+    };
+    for seed in [1, 13502460, 2147483646] {
+        let (result, _) = generate_with_options(seed, &doc_params(), &options).unwrap();
+        assert_eq!(result, generate(seed, &doc_params()).unwrap());
+    }
+}
+
+#[test]
+fn target_demands_are_met_exactly() {
+    let params = doc_params();
+    let mut demands = vec![10; 10];
+    demands[3] = 910;
+    let options = GenerateOptions {
+        demand_distribution: Some(demands.clone()),
+        ..GenerateOptions::default()
+    };
+    for seed in [1, 13502460, 2147483646] {
+        let (result, report) = generate_with_options(seed, &params, &options).unwrap();
+        let sinks: Vec<i64> = result.supply[502..].iter().map(|s| -s).collect();
+        assert_eq!(sinks, demands, "seed {seed}");
+        assert_eq!(result.supply.iter().sum::<i64>(), 0);
+        assert_eq!(verify_flow(&result, &result.skeleton_flow()), Ok(()));
+        assert_eq!(report.skeleton_arcs + report.random_arcs, result.arcs.len());
+        // The sources reaching the dominant sink lack 910 units of supply,
+        // so direct skeleton arcs are appended.
+        let (_, reference) = generate_with_report(seed, &params).unwrap();
+        assert!(
+            report.skeleton_arcs > reference.skeleton_arcs,
+            "seed {seed}"
+        );
+    }
+
+    let bad = |demands: Vec<i64>| GenerateOptions {
+        demand_distribution: Some(demands),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        bad(vec![100; 9]).validate(&params),
+        Err(ParamError::DemandLength { len: 9, sinks: 10 })
+    );
+    assert_eq!(
+        bad(vec![101; 10]).validate(&params),
+        Err(ParamError::DemandSum {
+            sum: 1010,
+            supply: 1000
+        })
+    );
+    let mut negative = vec![100; 10];
+    (negative[0], negative[1]) = (-1, 201);
+    assert_eq!(
+        bad(negative).validate(&params),
+        Err(ParamError::NegativeDemand {
+            index: 0,
+            value: -1
+        })
+    );
+}
+
+#[test]
+fn multipliers_on_random_arcs_only() {
+    let params = doc_params();
+    let range = MultiplierRange {
+        num_min: 50,
+        num_max: 150,
+        den: 100,
+    };
+    let options = GenerateOptions {
+        multipliers: Some(range),
+        ..GenerateOptions::default()
+    };
+    let (result, report) = generate_with_options(13502460, &params, &options).unwrap();
+    let reference = generate(13502460, &params).unwrap();
+    let (skeleton, _) = Skeleton::build(13502460, &params).unwrap();
+
+    let mut next = skeleton.arcs().iter().peekable();
+    let mut random = 0;
+    for (arc, reference) in result.arcs.iter().zip(&reference.arcs) {
+        let plain = netgen_rs::Arc {
+            multiplier: None,
+            ..arc.clone()
+        };
+        assert_eq!(&plain, reference);
+        if next.next_if(|&s| s == reference).is_some() {
+            assert_eq!(arc.multiplier, None, "skeleton arc {arc:?}");
+        } else {
+            let (num, den) = arc.multiplier.unwrap();
+            assert!((50..=150).contains(&num) && den == 100, "{arc:?}");
+            random += 1;
+        }
+    }
+    assert!(next.next().is_none());
+    assert_eq!(random, report.random_arcs);
+
+    // The generalized column appears only when requested.
+    let mut plain = Vec::new();
+    netgen_rs::write_dimacs_with_options(
+        &mut plain,
+        13502460,
+        1,
+        &params,
+        &result,
+        &DimacsOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(plain).unwrap(),
+        reference.to_dimacs_string(13502460, 1, &params)
+    );
+    let mut generalized = Vec::new();
+    let dimacs = DimacsOptions {
+        generalized: true,
+        ..DimacsOptions::default()
+    };
+    netgen_rs::write_dimacs_with_options(&mut generalized, 13502460, 1, &params, &result, &dimacs)
+        .unwrap();
+    let generalized = String::from_utf8(generalized).unwrap();
+    let arc_lines: Vec<&str> = generalized
+        .lines()
+        .filter(|l| l.starts_with("a "))
+        .collect();
+    assert_eq!(arc_lines.len(), result.arcs.len());
+    for (line, arc) in arc_lines.iter().zip(&result.arcs) {
+        let mult = line.split_whitespace().nth(6).unwrap();
+        let (num, den) = arc.multiplier.unwrap_or((1, 1));
+        assert_eq!(mult, format!("{num}/{den}"));
+    }
+
+    let bad = GenerateOptions {
+        multipliers: Some(MultiplierRange { den: 0, ..range }),
+        ..GenerateOptions::default()
+    };
+    assert_eq!(
+        bad.validate(&params).map_err(|e| e.kind()),
+        Err(ParamErrorKind::MultiplierRange)
+    );
+}
+
+#[test]
+fn traced_draw_counts_are_pinned() {
+    let (result, trace) = generate_traced(13502460, &doc_params()).unwrap();
+    assert_eq!(result, doc_result());
+    let phase = |draws, first, last| PhaseDraws {
+        draws,
+        first: Some(first),
+        last: Some(last),
+    };
+    let expected = [
+        (RngPhase::SupplyCreation, phase(21, 1450062285, 1440269091)),
+        (RngPhase::ChainAssignment, phase(689, 166943453, 611468333)),
+        (RngPhase::SinkSelection, phase(83, 1239021836, 1439822771)),
+        (RngPhase::SkeletonArcs, phase(1436, 1910841079, 313194147)),
+        (RngPhase::RubbishArcs, phase(6144, 53174248, 738791036)),
+    ];
+    for (p, draws) in expected {
+        assert_eq!(trace.phase(p), &draws, "{p}");
+    }
+    assert_eq!(trace.total_draws(), 8373);
+
+    // Assignment problems have no supply or chain phase.
+    let params =
+        NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap();
+    let (_, assignment) = generate_traced(12345, &params).unwrap();
+    assert_eq!(
+        assignment.phase(RngPhase::SupplyCreation),
+        &PhaseDraws::default()
+    );
+    assert_eq!(assignment.phase(RngPhase::SkeletonArcs).draws, 100);
+    assert_eq!(
+        trace.first_divergence(&assignment),
+        Some(RngPhase::SupplyCreation)
+    );
+    assert_eq!(trace.first_divergence(&trace.clone()), None);
+}
+
+#[test]
+fn progress_counts_arcs_up_to_the_total() {
+    let params = doc_params();
+    let mut updates: Vec<Progress> = Vec::new();
+    let result = generate_with_progress(13502460, &params, |p| {
+        updates.push(p);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(result, generate(13502460, &params).unwrap());
+    assert_eq!(updates.len(), 12);
+    assert!(updates.windows(2).all(|w| w[0].arcs <= w[1].arcs));
+    assert!(
+        updates
+            .windows(2)
+            .all(|w| w[0].sources_done <= w[1].sources_done)
+    );
+    let last = updates.last().unwrap();
+    assert_eq!((last.phase, last.arcs), (RngPhase::RubbishArcs, 2000));
+    assert_eq!((last.sources_done, last.sources), (10, 10));
+}
+
+#[test]
+fn progress_break_cancels_promptly() {
+    // 100 sources and two million arcs; stop after the first source.
+    let params = NetgenParams::from_slice(&[
+        10000, 100, 100, 2000000, 1, 100, 100000, 0, 0, 0, 100, 1, 1000,
+    ])
+    .unwrap();
+    let mut calls = 0;
+    let mut seen = None;
+    let result = generate_with_progress(7, &params, |p| {
+        calls += 1;
+        seen = Some(p);
+        if p.sources_done >= 1 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(result, Err(NetgenError::Cancelled));
+    let seen = seen.unwrap();
+    assert_eq!(seen.sources_done, 1);
+    assert!(seen.arcs < 200000, "{seen:?}");
+    assert!(calls < 10, "{calls} callbacks");
+
+    // A single source generating all its arcs at once is interrupted
+    // within its random arcs.
+    let params =
+        NetgenParams::from_slice(&[2000, 1, 1, 1000000, 1, 100, 1000, 0, 0, 0, 100, 1, 1000])
+            .unwrap();
+    let mut seen = None;
+    let result = generate_with_progress(7, &params, |p| {
+        seen = Some(p);
+        match p.phase {
+            RngPhase::RubbishArcs => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    });
+    assert_eq!(result, Err(NetgenError::Cancelled));
+    let seen = seen.unwrap();
+    assert_eq!(seen.sources_done, 0);
+    assert!(seen.arcs < 10000, "{seen:?}");
+}
+
+/// DIMACS output of the doc instance with `order`, split into the arc lines
+/// and all other lines.
+fn ordered_dimacs(order: ArcOrder) -> (Vec<String>, Vec<String>) {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let options = DimacsOptions {
+        arc_order: order,
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &result, &options)
+        .unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .partition(|l| l.starts_with("a "))
+}
+
+fn endpoints(line: &str) -> (u64, u64) {
+    let mut fields = line.split_whitespace().skip(1).map(|f| f.parse().unwrap());
+    (fields.next().unwrap(), fields.next().unwrap())
+}
+
+#[test]
+fn generation_arc_order_is_the_default() {
+    assert_eq!(ArcOrder::default(), ArcOrder::Generation);
+    let (arcs, rest) = ordered_dimacs(ArcOrder::Generation);
+    let expected = to_dimacs_string(13502460, 1, &doc_params()).unwrap();
+    let mut lines = rest;
+    lines.extend(arcs);
+    assert_eq!(lines.join("\n") + "\n", expected);
+}
+
+#[test]
+fn sorted_arc_order_is_stable() {
+    let (generation, rest) = ordered_dimacs(ArcOrder::Generation);
+    let (sorted, sorted_rest) = ordered_dimacs(ArcOrder::SortedTailHead);
+    assert_eq!(sorted_rest, rest);
+    assert!(sorted.is_sorted_by_key(|l| endpoints(l)));
+    let mut expected = generation;
+    expected.sort_by_key(|l| endpoints(l));
+    assert_eq!(sorted, expected);
+}
+
+#[test]
+fn shuffled_arc_order_is_a_reproducible_permutation() {
+    let (generation, rest) = ordered_dimacs(ArcOrder::Generation);
+    let (shuffled, shuffled_rest) = ordered_dimacs(ArcOrder::Shuffled { seed: 42 });
+    assert_eq!(shuffled_rest, rest);
+    assert_eq!(ordered_dimacs(ArcOrder::Shuffled { seed: 42 }).0, shuffled);
+    assert_eq!(
+        shuffled[..3],
+        [
+            "a 77 255 0 892 19",
+            "a 425 306 0 1000 305",
+            "a 217 359 0 50 500"
+        ]
+    );
+    assert_ne!(shuffled, generation);
+    assert_ne!(ordered_dimacs(ArcOrder::Shuffled { seed: 43 }).0, shuffled);
+    let (mut a, mut b) = (shuffled, generation);
+    a.sort();
+    b.sort();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn relabeled_output_maps_back_to_the_original() {
+    for (seed, parms) in [
+        (13502460, DOC_PARMS),
+        (12345, [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]),
+        (99999, [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]),
+    ] {
+        let params = NetgenParams::from_slice(&parms).unwrap();
+        let result = generate(seed, &params).unwrap();
+        let options = DimacsOptions {
+            relabel_nodes: Some(5),
+            ..DimacsOptions::default()
+        };
+        let mut out = Vec::new();
+        netgen_rs::write_dimacs_with_options(&mut out, seed, 1, &params, &result, &options)
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let plain = result.to_dimacs_string(seed, 1, &params);
+        let p_line = |s: &str| s.lines().find(|l| l.starts_with("p ")).unwrap().to_string();
+        assert_eq!(p_line(&text), p_line(&plain));
+
+        // Compare parsed files, since the assignment format drops capacities.
+        let parsed = netgen_rs::parse_dimacs(&text).unwrap();
+        let original = netgen_rs::parse_dimacs(&plain).unwrap();
+        let map = RelabelMap::from_seed(5, result.supply.len());
+        let restored = parsed.relabel(&map.inverse());
+        assert_eq!(
+            restored
+                .canonicalize()
+                .first_difference(&original.canonicalize()),
+            None
+        );
+
+        let sources: Vec<u64> = (1..=parsed.supply.len() as u64)
+            .filter(|&v| parsed.supply[v as usize - 1] > 0)
+            .collect();
+        let lowest: Vec<u64> = (1..=sources.len() as u64).collect();
+        assert_ne!(sources, lowest);
+        for &v in &sources {
+            assert!(result.supply[map.original_label(v) as usize - 1] > 0);
+        }
+    }
+}
+
+fn dimacs_with(seed: i64, params: &NetgenParams, uncapacitated_as: UncapacitatedAs) -> String {
+    let result = generate(seed, params).unwrap();
+    let options = DimacsOptions {
+        uncapacitated_as,
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, seed, 1, params, &result, &options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn uncapacitated_as_supply_is_the_legacy_output() {
+    assert_eq!(UncapacitatedAs::default(), UncapacitatedAs::Supply);
+    let params = doc_params();
+    let legacy = to_dimacs_string(13502460, 1, &params).unwrap();
+    // DIMACS needs a capacity, so `Free` falls back to the supply as well.
+    for mode in [UncapacitatedAs::Supply, UncapacitatedAs::Free] {
+        assert_eq!(dimacs_with(13502460, &params, mode), legacy);
+    }
+}
+
+#[test]
+fn big_m_replaces_exactly_the_uncapacitated_capacities() {
+    // Min-cost flow arcs carry the capacity in field 4, max-flow arcs in 3.
+    for (seed, parms, field) in [
+        (13502460, DOC_PARMS, 4),
+        (
+            99999,
+            [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+            3,
+        ),
+    ] {
+        let params = NetgenParams::from_slice(&parms).unwrap();
+        let result = generate(seed, &params).unwrap();
+        assert!(result.uncapacitated_arc_count() > 0);
+        let arc_lines = |s: String| -> Vec<Vec<String>> {
+            s.lines()
+                .filter(|l| l.starts_with("a "))
+                .map(|l| l.split_whitespace().map(str::to_string).collect())
+                .collect()
+        };
+        let legacy = arc_lines(dimacs_with(seed, &params, UncapacitatedAs::Supply));
+        let big_m = arc_lines(dimacs_with(
+            seed,
+            &params,
+            UncapacitatedAs::BigM(987_654_321),
+        ));
+        for ((arc, old), new) in result.arcs.iter().zip(legacy).zip(big_m) {
+            let mut expected = old;
+            if arc.uncapacitated {
+                assert_eq!(expected[field], params.supply.to_string());
+                expected[field] = "987654321".to_string();
+            }
+            assert_eq!(new, expected);
+        }
+    }
+}
+
+#[test]
+fn uncapacitated_arcs_follow_capacitated_pct() {
+    for pct in [0, 30, 75, 100] {
+        let params =
+            NetgenParams::from_slice(&[5000, 50, 50, 50000, 1, 100, 100000, 0, 0, 0, pct, 1, 1000])
+                .unwrap();
+        let result = generate(4242, &params).unwrap();
+        let share = result.uncapacitated_arc_count() as f64 / result.arcs.len() as f64;
+        assert!(
+            (share - (100 - pct) as f64 / 100.0).abs() < 0.02,
+            "{pct}% capacitated, {share} uncapacitated"
+        );
+    }
+}
+
+#[test]
+fn machine_readable_header_regenerates_the_instance() {
+    let params =
+        NetgenParams::from_slice(&[200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]).unwrap();
+    let result = generate(99999, &params).unwrap();
+    let options = DimacsOptions {
+        header: HeaderOptions {
+            machine_readable: true,
+            ..HeaderOptions::default()
+        },
+        ..DimacsOptions::default()
+    };
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 99999, 7, &params, &result, &options).unwrap();
+    let file = String::from_utf8(out).unwrap();
+    assert!(file.contains("c PARAM capacitated_pct 50\n"));
+
+    // Only the PARAM lines and the body, without the classic header.
+    let stripped: String = file
+        .lines()
+        .filter(|l| !l.starts_with('c') || l.starts_with("c PARAM"))
+        .map(|l| format!("{l}\n"))
+        .collect();
+    for text in [&file, &stripped] {
+        let (seed, problem, parsed) = parse_header(text.as_bytes()).unwrap();
+        assert_eq!((seed, problem, &parsed), (99999, 7, &params));
+        let regenerated = generate(seed, &parsed).unwrap();
+        let mut body = Vec::new();
+        netgen_rs::write_dimacs_network(&mut body, &parsed, &regenerated).unwrap();
+        let body = String::from_utf8(body).unwrap();
+        let from_problem_line = |s: &str| s[s.find("\np ").unwrap()..].to_string();
+        assert_eq!(from_problem_line(text), from_problem_line(&body));
+    }
+}
+
+#[test]
+fn size_limits_are_enforced() {
+    let mut params = doc_params();
+    params.nodes = limits::MAX_NODES_SUPPORTED + 1;
+    params.density = params.nodes;
+    assert_eq!(
+        params.validate().map_err(|e| e.kind()),
+        Err(ParamErrorKind::TooBig)
+    );
+    params.nodes = limits::MAX_NODES_SUPPORTED;
+    params.density = limits::MAX_ARCS_SUPPORTED;
+    assert_eq!(params.validate(), Ok(()));
+    params.density = limits::MAX_ARCS_SUPPORTED + 1;
+    assert_eq!(
+        params.validate().map_err(|e| e.kind()),
+        Err(ParamErrorKind::TooBig)
+    );
+}
+
+#[test]
+fn reference_compatibility_follows_the_limits() {
+    let params = doc_params();
+    assert!(params.is_reference_compatible());
+    let with = |f: &dyn Fn(&mut NetgenParams)| {
+        let mut p = params.clone();
+        f(&mut p);
+        assert_eq!(p.validate(), Ok(()), "{p}");
+        p.is_reference_compatible()
+    };
+    assert!(with(
+        &|p| p.maxcost = p.mincost + limits::MAX_EXACT_RANGE - 1
+    ));
+    assert!(!with(&|p| p.maxcost = p.mincost + limits::MAX_EXACT_RANGE));
+    assert!(with(&|p| p.maxcap = p.mincap + limits::MAX_EXACT_RANGE - 1));
+    assert!(!with(&|p| p.maxcap = p.mincap + limits::MAX_EXACT_RANGE));
+    assert!(with(&|p| p.supply = limits::MAX_EXACT_SUPPLY));
+    assert!(!with(&|p| p.supply = limits::MAX_EXACT_SUPPLY + 1));
+    assert!(with(&|p| {
+        p.nodes = limits::REFERENCE_MAX_NODES;
+        p.density = p.nodes;
+    }));
+    assert!(!with(&|p| {
+        p.nodes = limits::REFERENCE_MAX_NODES + 1;
+        p.density = p.nodes;
+    }));
+    assert!(with(&|p| p.density = limits::REFERENCE_MAX_ARCS));
+    assert!(!with(&|p| p.density = limits::REFERENCE_MAX_ARCS + 1));
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn dimacs_is_fingerprinted_while_it_is_written() {
+    use netgen_rs::FingerprintWriter;
+    use std::io::{self, Write};
+
+    let params = doc_params();
+    let result = params.generate(13502460).unwrap();
+    let mut text = Vec::new();
+    let mut hasher = FingerprintWriter::new();
+    netgen_rs::write_dimacs_multi(&mut [&mut text, &mut hasher], 13502460, 1, &params, &result)
+        .unwrap();
+    let digest = hasher.finish();
+    assert_eq!(digest, result.fingerprint(13502460, 1, &params));
+    let mut rehashed = FingerprintWriter::new();
+    rehashed.write_all(&text).unwrap();
+    assert_eq!(rehashed.finish(), digest);
+    assert_eq!(
+        text,
+        result.to_dimacs_string(13502460, 1, &params).into_bytes()
+    );
+
+    /// Fails on the first write.
+    struct Broken;
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut text = Vec::new();
+    let err =
+        netgen_rs::write_dimacs_multi(&mut [&mut text, &mut Broken], 13502460, 1, &params, &result)
+            .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn line_endings_and_trailing_newline() {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let write = |line_ending, trailing_newline| {
+        let options = DimacsOptions {
+            line_ending,
+            trailing_newline,
+            ..DimacsOptions::default()
+        };
+        let mut full = Vec::new();
+        netgen_rs::write_dimacs_with_options(&mut full, 13502460, 1, &params, &result, &options)
+            .unwrap();
+        let mut network = Vec::new();
+        netgen_rs::write_dimacs_network_with_options(&mut network, &params, &result, &options)
+            .unwrap();
+        // The header writer honors the options too.
+        let mut pieces = Vec::new();
+        netgen_rs::write_header_lines(&mut pieces, 13502460, 1, &params, &options).unwrap();
+        pieces.extend_from_slice(&network);
+        assert_eq!(pieces, full);
+        (String::from_utf8(full).unwrap(), network)
+    };
+
+    let today = result.to_dimacs_string(13502460, 1, &params);
+    let (lf, _) = write(LineEnding::Lf, true);
+    assert_eq!(lf, today);
+
+    let (crlf, crlf_network) = write(LineEnding::CrLf, true);
+    assert!(!crlf.replace("\r\n", "").contains('\n'));
+    assert_eq!(crlf.replace("\r\n", "\n"), today);
+    assert!(crlf.ends_with("\r\n"));
+    assert!(crlf.as_bytes().ends_with(&crlf_network));
+
+    let (lf_bare, lf_network) = write(LineEnding::Lf, false);
+    assert_eq!(lf_bare.len(), today.len() - 1);
+    assert_eq!(lf_bare, today[..today.len() - 1]);
+    assert!(lf_bare.as_bytes().ends_with(&lf_network));
+    let (crlf_bare, _) = write(LineEnding::CrLf, false);
+    assert_eq!(crlf_bare, crlf[..crlf.len() - 2]);
+}
+
+#[test]
+fn aligned_output_only_changes_spacing() {
+    for parms in [
+        DOC_PARMS,
+        [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+        [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+        [300, 20, 20, 3000, -50, 50, 100000, 0, 0, 30, 60, 1, 9],
+    ] {
+        let params = NetgenParams::from_slice(&parms).unwrap();
+        let result = generate(13502460, &params).unwrap();
+        let compact = result.to_dimacs_string(13502460, 1, &params);
+        let options = DimacsOptions {
+            aligned: true,
+            ..DimacsOptions::default()
+        };
+        let mut aligned = String::new();
+        netgen_rs::write_dimacs_fmt(&mut aligned, 13502460, 1, &params, &result, &options).unwrap();
+        assert_ne!(aligned, compact, "{parms:?}");
+
+        let arc_lines: Vec<&str> = aligned.lines().filter(|l| l.starts_with("a ")).collect();
+        assert_eq!(arc_lines.len(), result.arcs.len());
+        assert!(
+            arc_lines.iter().all(|l| l.len() == arc_lines[0].len()),
+            "{parms:?}"
+        );
+        let node_lines: Vec<&str> = aligned.lines().filter(|l| l.starts_with("n ")).collect();
+        assert!(node_lines.iter().all(|l| l.len() == node_lines[0].len()));
+
+        let squeezed: Vec<String> = aligned
+            .lines()
+            .map(|l| match l.starts_with("a ") || l.starts_with("n ") {
+                true => l.split_whitespace().collect::<Vec<_>>().join(" "),
+                false => l.to_string(),
+            })
+            .collect();
+        assert_eq!(squeezed.join("\n") + "\n", compact, "{parms:?}");
+    }
+}
+
+#[test]
+fn assignment_can_list_both_sides() {
+    let params =
+        NetgenParams::from_slice(&[100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]).unwrap();
+    assert_eq!(params.problem_type(), ProblemType::Assignment);
+    let result = generate(12345, &params).unwrap();
+    let node_ids = |text: &str| -> Vec<usize> {
+        text.lines()
+            .filter_map(|l| l.strip_prefix("n "))
+            .map(|id| id.parse().unwrap())
+            .collect()
+    };
+
+    let reference = result.to_dimacs_string(12345, 1, &params);
+    assert_eq!(node_ids(&reference), (1..=50).collect::<Vec<_>>());
+
+    let options = DimacsOptions {
+        asn_list_both_sides: true,
+        ..DimacsOptions::default()
+    };
+    let mut both = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut both, 12345, 1, &params, &result, &options).unwrap();
+    let both = String::from_utf8(both).unwrap();
+    assert_eq!(node_ids(&both), (1..=100).collect::<Vec<_>>());
+    let sinks: Vec<usize> = (1..=100).filter(|&i| result.supply[i - 1] < 0).collect();
+    assert_eq!(node_ids(&both)[50..], sinks);
+    // Only the node lines differ.
+    let other = |text: &str| -> Vec<String> {
+        text.lines()
+            .filter(|l| !l.starts_with("n "))
+            .map(String::from)
+            .collect()
+    };
+    assert_eq!(other(&both), other(&reference));
+
+    // Other problem types are unaffected.
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &result, &options)
+        .unwrap();
+    assert_eq!(
+        out,
+        result.to_dimacs_string(13502460, 1, &params).into_bytes()
+    );
+}
+
+#[test]
+fn max_flow_can_carry_costs() {
+    let params =
+        NetgenParams::from_slice(&[200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]).unwrap();
+    assert_eq!(params.problem_type(), ProblemType::MaxFlow);
+    let result = generate(99999, &params).unwrap();
+    let reference = result.to_dimacs_string(99999, 1, &params);
+    let write = |max_flow_extended| {
+        let options = DimacsOptions {
+            max_flow_extended,
+            ..DimacsOptions::default()
+        };
+        let mut out = Vec::new();
+        netgen_rs::write_dimacs_with_options(&mut out, 99999, 1, &params, &result, &options)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert_eq!(write(MaxFlowExtra::None), reference);
+
+    let commented = write(MaxFlowExtra::CostComments);
+    let network: Vec<&str> = commented
+        .lines()
+        .skip_while(|l| !l.starts_with("a "))
+        .collect();
+    assert_eq!(network.len(), 2 * result.arcs.len());
+    for (pair, arc) in network.chunks(2).zip(&result.arcs) {
+        assert_eq!(
+            pair[0],
+            format!("a {} {} {}", arc.from, arc.to, arc.capacity)
+        );
+        assert_eq!(pair[1], format!("c cost {}", arc.cost));
+    }
+    let uncommented: Vec<&str> = commented
+        .lines()
+        .filter(|l| !l.starts_with("c cost "))
+        .collect();
+    assert_eq!(uncommented.join("\n") + "\n", reference);
+    let parsed = netgen_rs::parse_dimacs(&commented).unwrap();
+    assert_eq!(parsed.arcs.len(), result.arcs.len());
+
+    let min_cost = write(MaxFlowExtra::MinCostFormat);
+    assert!(min_cost.contains("\np min 200 "));
+    assert!(min_cost.contains("c  *** Minimum cost flow ***"));
+    let parsed = netgen_rs::parse_dimacs(&min_cost).unwrap();
+    assert_eq!(parsed.supply, result.supply);
+    let fields = |arcs: &[Arc]| -> Vec<(u64, u64, i64, i64)> {
+        arcs.iter()
+            .map(|a| (a.from, a.to, a.capacity, a.cost))
+            .collect()
+    };
+    assert_eq!(fields(&parsed.arcs), fields(&result.arcs));
+}
+
+#[test]
+fn zero_based_accessors_are_one_less_than_dimacs() {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let zero_based: Vec<_> = result.arcs_zero_based().collect();
+    assert_eq!(zero_based.len(), result.arcs.len());
+    for (&(from, to, cost, capacity), arc) in zero_based.iter().zip(&result.arcs) {
+        assert_eq!(u64::from(from) + 1, arc.from);
+        assert_eq!(u64::from(to) + 1, arc.to);
+        assert_eq!((cost, capacity), (arc.cost, arc.capacity));
+        assert_eq!(result.supply_of(NodeId(from)), result.supply[from as usize]);
+    }
+
+    for id in [1, 2, 512, u64::from(u32::MAX) + 1] {
+        let node = NodeId::from_dimacs(id);
+        assert_eq!(node.to_dimacs(), id);
+        assert_eq!(node.index() as u64, id - 1);
+        assert_eq!(NodeId::from_dimacs(node.to_dimacs()), node);
+    }
+    assert_eq!(result.supply_of(NodeId::from_dimacs(1)), result.supply[0]);
+    assert!(std::panic::catch_unwind(|| NodeId::from_dimacs(0)).is_err());
+    assert!(std::panic::catch_unwind(|| NodeId::from_dimacs(u64::from(u32::MAX) + 2)).is_err());
+}
+
+#[test]
+fn provenance_lines_are_opt_in() {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let write = |header: HeaderOptions| {
+        let options = DimacsOptions {
+            header,
+            ..DimacsOptions::default()
+        };
+        let mut out = Vec::new();
+        netgen_rs::write_dimacs_with_options(&mut out, 13502460, 1, &params, &result, &options)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    let plain = write(HeaderOptions::default());
+    assert_eq!(plain, to_dimacs_string(13502460, 1, &params).unwrap());
+    assert!(!plain.contains("c generator:"));
+
+    let text = write(HeaderOptions {
+        include_provenance: true,
+        generated: Some("2024-02-29T23:59:60+01:00".to_string()),
+        command: Some("netgen_rs --output a.min 13502460 1 512".to_string()),
+        ..HeaderOptions::default()
+    });
+    let version = env!("CARGO_PKG_VERSION");
+    assert!(text.contains(&format!(
+        "c   Maximum arc capacity:       2000\n\
+         c generator: netgen_rs v{version}\n\
+         c generated: 2024-02-29T23:59:60+01:00\n\
+         c command: netgen_rs --output a.min 13502460 1 512\n"
+    )));
+    // Readers of the header skip the provenance lines.
+    assert_eq!(
+        parse_header(text.as_bytes()),
+        Some((13502460, 1, params.clone()))
+    );
+    assert_eq!(
+        verify_instance(text.as_bytes()).unwrap(),
+        VerifyOutcome::Match
+    );
+
+    let text = write(HeaderOptions {
+        include_provenance: true,
+        ..HeaderOptions::default()
+    });
+    assert!(text.contains(&format!("c generator: netgen_rs v{version}\nc\n")));
+    assert!(!text.contains("c generated:") && !text.contains("c command:"));
+
+    // A line break in the command cannot inject lines into the header.
+    let text = write(HeaderOptions {
+        include_provenance: true,
+        command: Some("netgen_rs 'x\np min 1 0\r\n'".to_string()),
+        ..HeaderOptions::default()
+    });
+    assert!(text.contains("\nc command: netgen_rs 'x\\np min 1 0\\r\\n'\n"));
+    assert_eq!(text.lines().filter(|l| l.starts_with("p ")).count(), 1);
+}
+
+#[test]
+fn batch_output_is_the_concatenation() {
+    let problems = [
+        (13502460, 1, "512 10 10 2000 5 500 1000 3 3 20 80 50 2000"),
+        (12345, 2, "100 50 50 500 1 100 50 0 0 0 0 1 100"),
+        (99999, 3, "200 5 5 1000 1 1 500 2 2 20 50 10 100"),
+    ]
+    .map(|(seed, problem, params)| (seed, problem, params.parse::<NetgenParams>().unwrap()));
+    let mut expected = Vec::new();
+    for (seed, problem, params) in &problems {
+        let result = generate(*seed, params).unwrap();
+        write_dimacs(&mut expected, *seed, *problem, params, &result).unwrap();
+    }
+    let mut out = Vec::new();
+    let summary = write_dimacs_batch(&mut out, problems.clone(), BatchPolicy::Continue).unwrap();
+    assert_eq!(out, expected);
+    let arcs: Vec<_> = summary.entries.iter().map(|e| e.outcome).collect();
+    assert_eq!(arcs, [Ok(2000), Ok(500), Ok(1000)]);
+    assert_eq!(summary.total_arcs(), 3500);
+
+    // The middle problem has no sources, which `from_slice` would reject.
+    let mut bad = problems.clone();
+    bad[1].2.sources = 0;
+    let mut first_and_last = Vec::new();
+    for (seed, problem, params) in [&problems[0], &problems[2]] {
+        first_and_last.extend(
+            to_dimacs_string(*seed, *problem, params)
+                .unwrap()
+                .into_bytes(),
+        );
+    }
+    let mut out = Vec::new();
+    let summary = write_dimacs_batch(&mut out, bad.clone(), BatchPolicy::Continue).unwrap();
+    assert_eq!(out, first_and_last);
+    let errors: Vec<_> = summary.errors().map(|(e, err)| (e.problem, err)).collect();
+    assert_eq!(errors, [(2, NetgenError::BadParms)]);
+    assert_eq!(summary.entries.len(), 3);
+
+    let mut out = Vec::new();
+    let summary = write_dimacs_batch(&mut out, bad, BatchPolicy::Abort).unwrap();
+    assert_eq!(
+        out,
+        to_dimacs_string(13502460, 1, &problems[0].2)
+            .unwrap()
+            .as_bytes()
+    );
+    let arcs: Vec<_> = summary.entries.iter().map(|e| e.outcome).collect();
+    assert_eq!(arcs, [Ok(2000), Err(NetgenError::BadParms)]);
+}
+
+#[test]
+fn network_sections_compose_to_the_whole() {
+    let problems = [
+        (13502460, "512 10 10 2000 5 500 1000 3 3 20 80 50 2000"),
+        (12345, "100 50 50 500 1 100 50 0 0 0 0 1 100"),
+        (99999, "200 5 5 1000 1 1 500 2 2 20 50 10 100"),
+    ];
+    let variants = [
+        DimacsOptions::default(),
+        DimacsOptions {
+            aligned: true,
+            line_ending: LineEnding::CrLf,
+            arc_order: ArcOrder::SortedTailHead,
+            asn_list_both_sides: true,
+            ..DimacsOptions::default()
+        },
+        DimacsOptions {
+            max_flow_extended: MaxFlowExtra::MinCostFormat,
+            trailing_newline: false,
+            ..DimacsOptions::default()
+        },
+        DimacsOptions {
+            max_flow_extended: MaxFlowExtra::CostComments,
+            ..DimacsOptions::default()
+        },
+    ];
+    for (seed, params) in problems {
+        let params: NetgenParams = params.parse().unwrap();
+        let result = generate(seed, &params).unwrap();
+        let kind = params.problem_type();
+        for options in &variants {
+            let mut whole = Vec::new();
+            netgen_rs::write_dimacs_network_with_options(&mut whole, &params, &result, options)
+                .unwrap();
+            let mut pieces = Vec::new();
+            let (nodes, arcs) = (result.supply.len(), result.arcs.len());
+            netgen_rs::write_problem_line(&mut pieces, kind, nodes, arcs, options).unwrap();
+            netgen_rs::write_node_lines(&mut pieces, kind, &result.supply, options).unwrap();
+            netgen_rs::write_arc_lines(&mut pieces, kind, &result.arcs, options).unwrap();
+            assert_eq!(
+                String::from_utf8(pieces).unwrap(),
+                String::from_utf8(whole).unwrap(),
+                "{params} {options:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn network_sections_without_arcs_keep_the_last_line_ending() {
+    let params = NetgenParams::from_slice(&[3, 1, 1, 3, 1, 10, 5, 0, 0, 0, 0, 1, 1]).unwrap();
+    let result = netgen_rs::NetgenResult {
+        arcs: Vec::new(),
+        supply: vec![5, 0, -5],
+    };
+    let kind = params.problem_type();
+    for line_ending in [LineEnding::Lf, LineEnding::CrLf] {
+        let options = DimacsOptions {
+            line_ending,
+            trailing_newline: false,
+            ..DimacsOptions::default()
+        };
+        let mut whole = Vec::new();
+        netgen_rs::write_dimacs_network_with_options(&mut whole, &params, &result, &options)
+            .unwrap();
+        let mut pieces = Vec::new();
+        netgen_rs::write_problem_line(&mut pieces, kind, 3, 0, &options).unwrap();
+        netgen_rs::write_node_lines(&mut pieces, kind, &result.supply, &options).unwrap();
+        netgen_rs::write_arc_lines(&mut pieces, kind, &result.arcs, &options).unwrap();
+        assert!(whole.ends_with(b"n 3 -5"));
+        // The node lines keep their last line ending, as documented.
+        let ending: &[u8] = match line_ending {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        };
+        assert_eq!(pieces, [&whole[..], ending].concat());
+    }
+}
+
+#[test]
+fn network_sections_can_be_interleaved() {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let (kind, options) = (params.problem_type(), DimacsOptions::default());
+    let mut out = Vec::new();
+    netgen_rs::write_problem_line(&mut out, kind, 512, result.arcs.len(), &options).unwrap();
+    out.extend_from_slice(b"c nodes\n");
+    netgen_rs::write_node_lines(&mut out, kind, &result.supply, &options).unwrap();
+    out.extend_from_slice(b"c arcs\n");
+    netgen_rs::write_arc_lines(&mut out, kind, &result.arcs, &options).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    let mut plain = Vec::new();
+    netgen_rs::write_dimacs_network(&mut plain, &params, &result).unwrap();
+    let plain = String::from_utf8(plain).unwrap();
+    let without: String = (text.lines())
+        .filter(|&line| line != "c nodes" && line != "c arcs")
+        .map(|line| format!("{line}\n"))
+        .collect();
+    assert_eq!(without, plain);
+    let first_node = text.find("\nn ").unwrap();
+    let first_arc = text.find("\na ").unwrap();
+    assert!(text[..first_node].ends_with("\nc nodes"));
+    assert!(text[first_node..first_arc].ends_with("\nc arcs"));
+    // Parsers skip the extra comments.
+    let parsed = netgen_rs::parse_dimacs(&text).unwrap();
+    assert_eq!(parsed.supply, result.supply);
+    assert_eq!(parsed.arcs.len(), result.arcs.len());
+}
+
+#[test]
+fn write_summaries_count_what_was_written() {
+    // (problem line, nodes with a line): min-cost and max-flow list every
+    // node with a supply, assignment only the sources.
+    for (seed, params) in [
+        (13502460, "512 10 10 2000 5 500 1000 3 3 20 80 50 2000"),
+        (12345, "100 50 50 500 1 100 50 0 0 0 0 1 100"),
+        (99999, "200 5 5 1000 1 1 500 2 2 20 50 10 100"),
+    ] {
+        let params: NetgenParams = params.parse().unwrap();
+        let result = generate(seed, &params).unwrap();
+        let listed = match params.problem_type() {
+            ProblemType::Assignment => result.supply.iter().filter(|&&s| s > 0).count(),
+            _ => result.supply.iter().filter(|&&s| s != 0).count(),
+        };
+        let options = DimacsOptions::default();
+
+        let mut out = Vec::new();
+        let summary =
+            write_dimacs_with_summary(&mut out, seed, 1, &params, &result, &options).unwrap();
+        assert_eq!(summary.bytes, out.len() as u64);
+        assert_eq!(summary.node_lines, listed, "{params}");
+        assert_eq!(summary.arc_lines, result.arcs.len());
+        assert_eq!(out, to_dimacs_string(seed, 1, &params).unwrap().as_bytes());
+
+        let mut network = Vec::new();
+        let network_summary =
+            write_dimacs_network_with_summary(&mut network, &params, &result, &options).unwrap();
+        assert_eq!(network_summary.bytes, network.len() as u64);
+        assert_eq!(
+            (network_summary.node_lines, network_summary.arc_lines),
+            (summary.node_lines, summary.arc_lines)
+        );
+    }
+    // Other formats get the byte count.
+    let result = generate(1, &doc_params()).unwrap();
+    let (mut nodes, mut arcs) = (CountingWriter::new(Vec::new()), Vec::new());
+    netgen_rs::write_csv(&mut nodes, &mut arcs, &result).unwrap();
+    let summary = nodes.summary();
+    assert_eq!(summary.bytes, nodes.into_inner().len() as u64);
+}
+
+#[test]
+fn shortest_path_instances_keep_every_arc() {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let source = NodeId::from_dimacs(42);
+    let instance = result.to_shortest_path(source, false).unwrap();
+    assert_eq!(instance.arcs.len(), result.arcs.len());
+    let mut out = Vec::new();
+    netgen_rs::write_dimacs_sp(&mut out, &instance).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("p sp 512 2000"));
+    assert_eq!(lines.next(), Some("n 42"));
+    let arcs: Vec<_> = lines.collect();
+    assert_eq!(arcs.len(), 2000);
+    for (line, arc) in arcs.iter().zip(&result.arcs) {
+        assert_eq!(*line, format!("a {} {} {}", arc.from, arc.to, arc.cost));
+    }
+
+    let mut negative = result.clone();
+    negative.arcs[7].cost = -3;
+    assert_eq!(
+        negative.to_shortest_path(source, false),
+        Err(ShortestPathError::NegativeCost {
+            arc: ArcId(7),
+            cost: -3
+        })
+    );
+    assert_eq!(
+        negative.to_shortest_path(source, true).unwrap().arcs[7].2,
+        -3
+    );
+}
+
+#[test]
+fn assignment_instances_export_as_bipartite_graphs() {
+    let params: NetgenParams = "200 100 100 2000 10 500 100 0 0 0 0 1 1".parse().unwrap();
+    let result = generate(42, &params).unwrap();
+    let instance = result.to_bipartite(&params).unwrap();
+    let half = params.nodes as usize / 2;
+    assert_eq!((instance.left.len(), instance.right.len()), (half, half));
+    assert_eq!(instance.edges.len(), result.arcs.len());
+    for (&(u, v, weight), arc) in instance.edges.iter().zip(&result.arcs) {
+        assert!((u as usize) < half && (v as usize) < half);
+        assert_eq!(instance.left[u as usize].to_dimacs(), arc.from);
+        assert_eq!(instance.right[v as usize].to_dimacs(), arc.to);
+        assert_eq!(weight, arc.cost);
+    }
+
+    let mut out = Vec::new();
+    netgen_rs::write_bipartite_edges(&mut out, &instance).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("100 100"));
+    assert_eq!(lines.count(), result.arcs.len());
+
+    let params = doc_params();
+    assert_eq!(
+        generate(1, &params).unwrap().to_bipartite(&params),
+        Err(ConversionError::NotAssignment(ProblemType::MinCostFlow))
+    );
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "tokio")]
+
+mod fixture;
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use netgen_rs::{
+    DimacsOptions, GenerateOptions, LineEnding, MultiplierRange, NetgenParams, generate,
+    generate_with_options, write_dimacs, write_dimacs_async, write_dimacs_with_options,
+    write_dimacs_with_options_async,
+};
+use tokio::io::AsyncWrite;
+
+use fixture::DOC_PARMS;
+
+/// An in-memory writer that records the largest single write and whether
+/// it was flushed.
+#[derive(Default)]
+struct Recording {
+    data: Vec<u8>,
+    largest_write: usize,
+    flushed: bool,
+}
+
+impl AsyncWrite for Recording {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.largest_write = self.largest_write.max(buf.len());
+        self.data.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.flushed = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn matches_the_sync_writer_for_each_problem_type() {
+    for (seed, parms) in [
+        (13502460, DOC_PARMS),
+        (12345, [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]),
+        (99999, [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]),
+    ] {
+        let params = NetgenParams::from_slice(&parms).unwrap();
+        let result = generate(seed, &params).unwrap();
+        let mut sync = Vec::new();
+        write_dimacs(&mut sync, seed, 3, &params, &result).unwrap();
+        let mut out = Vec::new();
+        write_dimacs_async(&mut out, seed, 3, &params, &result)
+            .await
+            .unwrap();
+        assert_eq!(out, sync);
+    }
+}
+
+#[tokio::test]
+async fn large_instances_are_written_in_chunks() {
+    let params = NetgenParams::from_slice(&[
+        20000, 100, 100, 200000, 1, 10000, 100000, 0, 0, 20, 80, 1, 1000,
+    ])
+    .unwrap();
+    let options = GenerateOptions {
+        multipliers: Some(MultiplierRange {
+            num_min: 80,
+            num_max: 120,
+            den: 100,
+        }),
+        ..GenerateOptions::default()
+    };
+    let (result, _) = generate_with_options(7, &params, &options).unwrap();
+    let dimacs = DimacsOptions {
+        generalized: true,
+        ..DimacsOptions::default()
+    };
+    let mut sync = Vec::new();
+    write_dimacs_with_options(&mut sync, 7, 1, &params, &result, &dimacs).unwrap();
+
+    let mut out = Recording::default();
+    write_dimacs_with_options_async(&mut out, 7, 1, &params, &result, &dimacs)
+        .await
+        .unwrap();
+    assert!(sync.len() > 4_000_000);
+    assert_eq!(out.data, sync);
+    assert!(out.largest_write < 70 * 1024, "{}", out.largest_write);
+    assert!(out.flushed);
+}
+
+#[tokio::test]
+async fn line_endings_and_alignment_match_the_sync_writer() {
+    let params =
+        NetgenParams::from_slice(&[5000, 50, 50, 60000, 1, 10000, 100000, 0, 0, 20, 80, 1, 1000])
+            .unwrap();
+    let result = generate(7, &params).unwrap();
+    for line_ending in [LineEnding::Lf, LineEnding::CrLf] {
+        for (trailing_newline, aligned) in [(true, false), (false, false), (true, true)] {
+            let dimacs = DimacsOptions {
+                line_ending,
+                trailing_newline,
+                aligned,
+                ..DimacsOptions::default()
+            };
+            let mut sync = Vec::new();
+            write_dimacs_with_options(&mut sync, 7, 1, &params, &result, &dimacs).unwrap();
+            let mut out = Vec::new();
+            write_dimacs_with_options_async(&mut out, 7, 1, &params, &result, &dimacs)
+                .await
+                .unwrap();
+            assert_eq!(out, sync, "{dimacs:?}");
+        }
+    }
+}
@@ -0,0 +1,44 @@
+/*
+ * Drives the C interface: generate from the seed and parms on the command
+ * line, print the arcs and supplies, write DIMACS to the given path and
+ * free the result.
+ *
+ * usage: ffi_harness OUT_PATH SEED PARM1 ... PARM13
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "netgen_rs.h"
+
+int main(int argc, char **argv) {
+  if (argc != 3 + NETGEN_PROBLEM_PARMS) {
+    fprintf(stderr, "usage: %s OUT_PATH SEED PARM1 ... PARM13\n", argv[0]);
+    return 2;
+  }
+  int64_t seed = strtoll(argv[2], NULL, 10);
+  int64_t parms[NETGEN_PROBLEM_PARMS];
+  for (int i = 0; i < NETGEN_PROBLEM_PARMS; i++)
+    parms[i] = strtoll(argv[3 + i], NULL, 10);
+
+  NetgenCResult result;
+  int32_t code = netgen_generate(seed, parms, &result);
+  printf("code %d\n", code);
+  if (code != NETGEN_OK)
+    return 0;
+
+  for (size_t i = 0; i < result.node_count; i++)
+    printf("n %zu %lld\n", i + 1, (long long)result.supply[i]);
+  for (size_t i = 0; i < result.arc_count; i++) {
+    NetgenCArc a = result.arcs[i];
+    printf("a %lld %lld %lld %lld\n", (long long)a.from, (long long)a.to,
+           (long long)a.cost, (long long)a.cap);
+  }
+  code = netgen_write_dimacs_file(argv[1], seed, 1, parms, &result);
+  netgen_free_result(&result);
+  if (code != NETGEN_OK || result.arcs != NULL) {
+    fprintf(stderr, "write or free failed: %d\n", code);
+    return 1;
+  }
+  return 0;
+}
@@ -0,0 +1,1142 @@
+mod fixture;
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use fixture::doc_params;
+
+fn netgen(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_netgen_rs"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run netgen_rs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn field<'a>(line: &'a str, key: &str) -> &'a str {
+    line.split_whitespace()
+        .find_map(|kv| kv.strip_prefix(key).and_then(|v| v.strip_prefix('=')))
+        .unwrap_or_else(|| panic!("missing field {key} in {line:?}"))
+}
+
+#[test]
+fn stats_only_prints_one_line_per_problem() {
+    let output = netgen(
+        &["--stats-only"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100\n",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+
+    let params = doc_params();
+    let result = netgen_rs::generate(13502460, &params).unwrap();
+    let first = lines[0];
+    assert_eq!(field(first, "seed"), "13502460");
+    assert_eq!(field(first, "type"), "min-cost-flow");
+    assert_eq!(field(first, "nodes"), "512");
+    assert_eq!(
+        field(first, "arcs").parse::<usize>().unwrap(),
+        result.arcs.len()
+    );
+    assert_eq!(field(first, "supply"), "1000");
+    let (lo, hi) = field(first, "cost").split_once("..").unwrap();
+    assert!(lo.parse::<i64>().unwrap() >= 5 && hi.parse::<i64>().unwrap() <= 500);
+    assert!(field(first, "max_out_degree").parse::<usize>().unwrap() > 0);
+
+    assert_eq!(field(lines[1], "type"), "assignment");
+    assert_eq!(field(lines[1], "problem"), "2");
+}
+
+#[test]
+fn positional_arguments_still_work() {
+    let output = netgen(
+        &[
+            "13502460", "1", "512", "10", "10", "2000", "5", "500", "1000", "3", "3", "20", "80",
+            "50", "2000",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+    let params = doc_params();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        netgen_rs::to_dimacs_string(13502460, 1, &params).unwrap()
+    );
+}
+
+#[test]
+fn unknown_option_is_rejected() {
+    let output = netgen(&["--bogus"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--bogus"));
+}
+
+#[test]
+fn validate_reports_each_problem() {
+    let output = netgen(
+        &["--validate"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345 2 100 0 50 500 1 100 50 0 0 0 0 1 100\n\
+         99999 3 200 5 5 1000 1 1 500 2 2 20 50 10 100\n\
+         7 4 100 5 5 50 1 10 100 0 0 0 0 1 10\n\
+         8 5 5000000000 5 5 6000000000 1 10 100 0 0 0 0 1 10\n\
+         9 6 10 2 2 101 1 10 100 0 0 0 0 1 10\n\
+         10 7 10 2 2 90 1 10 100 0 0 0 0 1 100\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    // More arcs than the network can hold is only a warning, as in NETGEN.
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: problem 7 (line 7): density 90 exceeds the 58 distinct arcs the network \
+         can hold, so it will have fewer arcs\n"
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "problem 1 (line 1): OK",
+            "problem 2 (line 2): sources (0) must be positive",
+            "problem 3 (line 3): OK",
+            "problem 4 (line 4): density (arc count, 50) must be at least nodes (100)",
+            "problem 5 (line 5): nodes (5000000000) and density (6000000000) must not exceed \
+             2^32 - 1",
+            "problem 6 (line 6): density (arc count, 101) exceeds the feasible maximum (100)",
+            "problem 7 (line 7): OK",
+        ]
+    );
+}
+
+#[test]
+fn validate_succeeds_on_valid_batch() {
+    let output = netgen(
+        &["--validate"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000 12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "problem 1 (line 1): OK\nproblem 2 (line 1): OK\n"
+    );
+}
+
+#[test]
+fn dry_run_prints_estimates_without_generating() {
+    let output = netgen(
+        &[
+            "--dry-run",
+            "13502460",
+            "1",
+            "512",
+            "10",
+            "10",
+            "2000",
+            "5",
+            "500",
+            "1000",
+            "3",
+            "3",
+            "20",
+            "80",
+            "50",
+            "2000",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let line = stdout
+        .strip_prefix("problem 1 (line 1): ")
+        .unwrap()
+        .trim_end();
+    assert_eq!(field(line, "type"), "min-cost-flow");
+    assert_eq!(field(line, "nodes"), "512");
+    assert_eq!(field(line, "arcs"), "2000");
+    assert!(field(line, "peak_memory").parse::<u64>().unwrap() > 0);
+    assert!(field(line, "dimacs_bytes").parse::<u64>().unwrap() > 0);
+
+    let output = netgen(
+        &["--dry-run"],
+        "13502460 1 512 10 10 2000 5 5 1000 3 3 20 80 50 2000
+         12345 2 100 0 50 500 1 100 50 0 0 0 0 1 100
+",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("problem 1 (line 1): type=min-cost-flow"));
+    assert_eq!(lines[1], "problem 2 (line 2): sources (0) must be positive");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Warning: problem 1 (line 1): hicost percentage 20"));
+}
+
+#[test]
+fn timing_reports_each_problem_and_the_total() {
+    let input = "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+                 12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100\n";
+    let seconds = |line: &str, key: &str| -> f64 {
+        let value = field(line, key);
+        value.strip_suffix('s').unwrap().parse().unwrap()
+    };
+    for jobs in ["1", "2"] {
+        let output = netgen(&["--timing", "--jobs", jobs], input);
+        assert!(output.status.success());
+        let plain = netgen(&[], input);
+        assert_eq!(output.stdout, plain.stdout, "--timing changed the output");
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let lines: Vec<&str> = stderr.lines().collect();
+        assert_eq!(lines.len(), 3, "{stderr}");
+        for (line, prefix, arcs) in [
+            (
+                lines[0],
+                "Timing: problem 1 (line 1, seed 13502460): ",
+                2000,
+            ),
+            (lines[1], "Timing: problem 2 (line 2, seed 12345): ", 500),
+            (lines[2], "Timing: total: ", 2500),
+        ] {
+            let fields = line
+                .strip_prefix(prefix)
+                .unwrap_or_else(|| panic!("{line}"));
+            assert!(seconds(fields, "generate") >= 0.0);
+            assert!(seconds(fields, "write") >= 0.0);
+            assert_eq!(field(fields, "arcs"), arcs.to_string());
+            field(fields, "arcs_per_sec").parse::<u64>().unwrap();
+        }
+        assert_eq!(field(lines[2], "instances"), "2");
+    }
+    assert!(netgen(&[], input).stderr.is_empty());
+}
+
+/// Run netgen_rs with `args`, read `keep` bytes of its stdout and close the
+/// pipe, as `netgen_rs ... | head -c keep` would.
+fn netgen_closed_early(args: &[&str], stdin: &str, keep: usize) -> Output {
+    use std::io::Read;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_netgen_rs"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run netgen_rs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let mut stdout = child.stdout.take().unwrap();
+    let mut head = vec![0; keep];
+    stdout.read_exact(&mut head).unwrap();
+    drop(stdout);
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn closing_stdout_early_exits_quietly() {
+    let big = "1 1 100000 100 100 1000000 1 100 10000 0 0 0 0 1 100\n";
+    let many = "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n".repeat(10_000);
+    for (args, input) in [
+        (&[][..], big),
+        (&["--jobs", "2"][..], big),
+        (&["--validate"][..], many.as_str()),
+        (&["suite", "lemon-netgen-8", "--dry-run"][..], ""),
+    ] {
+        let output = netgen_closed_early(args, input, 100);
+        assert!(output.status.success(), "{args:?}: {output:?}");
+        assert!(output.stderr.is_empty(), "{args:?}: {output:?}");
+    }
+}
+
+#[test]
+fn each_problem_is_flushed_before_the_next_is_generated() {
+    use std::io::Read;
+
+    let small = "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000";
+    let expected = netgen(&small.split(' ').collect::<Vec<_>>(), "").stdout;
+    // Generating the second problem takes about a second, and its output
+    // is far larger than a pipe buffer, so the child cannot exit before
+    // this test has read it.
+    let slow = "1 2 100000 100 100 2000000 1 100 10000 0 0 0 0 1 100";
+    let mut child = Command::new(env!("CARGO_BIN_EXE_netgen_rs"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to run netgen_rs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(format!("{small}\n{slow}\n").as_bytes())
+        .unwrap();
+    let mut stdout = child.stdout.take().unwrap();
+
+    let mut first = vec![0; expected.len()];
+    stdout.read_exact(&mut first).unwrap();
+    assert_eq!(first, expected);
+    assert!(
+        child.try_wait().unwrap().is_none(),
+        "the first problem arrived only after the child finished"
+    );
+    drop(stdout);
+    child.wait().unwrap();
+}
+
+#[test]
+fn params_dir_writes_one_instance_per_param_file() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_params_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let min = "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n";
+    let max = "12345 2 200 5 5 1000 1 1 500 2 2 20 50 10 100\n";
+    std::fs::write(dir.join("b_01a.min.param"), min).unwrap();
+    // The extension follows the problem type, not the file name.
+    std::fs::write(dir.join("a_02a.min.param"), max).unwrap();
+    std::fs::write(dir.join("broken.param"), "12345 3 100 oops\n").unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a problem\n").unwrap();
+
+    let output = netgen(&["--params-dir", dir.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("broken.param: invalid integer \"oops\""),
+        "{stderr}"
+    );
+
+    let expected = |args: &str| netgen(&args.split(' ').collect::<Vec<_>>(), "").stdout;
+    assert_eq!(
+        std::fs::read(dir.join("b_01a.min")).unwrap(),
+        expected(min.trim())
+    );
+    assert_eq!(
+        std::fs::read(dir.join("a_02a.max")).unwrap(),
+        expected(max.trim())
+    );
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            "a_02a.max",
+            "a_02a.min.param",
+            "b_01a.min",
+            "b_01a.min.param",
+            "broken.param",
+            "notes.txt"
+        ]
+    );
+
+    // --output overrides the names; the files are read in the order given.
+    let out = dir.join("all.txt");
+    let output = netgen(
+        &[
+            "--params-file",
+            dir.join("a_02a.min.param").to_str().unwrap(),
+            "--params-file",
+            dir.join("b_01a.min.param").to_str().unwrap(),
+            "--output",
+            out.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{output:?}");
+    let mut both = expected(max.trim());
+    both.extend(expected(min.trim()));
+    assert_eq!(std::fs::read(&out).unwrap(), both);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn interactive_mode_takes_defaults_and_writes_the_file() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_interactive_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("example.min");
+    // Fifteen empty answers take the README example, then the output path.
+    let answers = format!("{}{}\n", "\n".repeat(15), path.display());
+    let output = netgen(&["--interactive"], &answers);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("nodes (total number of nodes) [512]: "));
+    assert!(stderr.contains("Problem type: min-cost-flow"), "{stderr}");
+
+    let expected = netgen(
+        &[
+            "13502460", "1", "512", "10", "10", "2000", "5", "500", "1000", "3", "3", "20", "80",
+            "50", "2000",
+        ],
+        "",
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), expected.stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn interactive_mode_reprompts_on_bad_answers() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_reprompt_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("assignment.asn");
+    // A word for the seed, then an assignment problem with no sources,
+    // which fails validation; the second round keeps every value but
+    // sources.
+    let first = "abc\n7\n2\n100\n0\n50\n500\n1\n100\n50\n0\n0\n0\n0\n1\n1\n";
+    let second = "\n\n\n50\n\n\n\n\n\n\n\n\n\n\n\n";
+    let answers = format!("{first}{second}{}\n", path.display());
+    let output = netgen(&["--interactive"], &answers);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("not an integer: \"abc\""), "{stderr}");
+    assert!(stderr.contains("Invalid parameters: "), "{stderr}");
+    assert!(stderr.contains("sources (source nodes, including transshipment sources) [0]: "));
+    assert!(stderr.contains("Problem type: assignment"), "{stderr}");
+
+    let expected = netgen(
+        &[
+            "7", "2", "100", "50", "50", "500", "1", "100", "50", "0", "0", "0", "0", "1", "1",
+        ],
+        "",
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), expected.stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn interactive_mode_fails_on_invalid_parameters_at_end_of_input() {
+    let output = netgen(&["--interactive"], "1\n1\n100\n0\n");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Error: "), "{stderr}");
+}
+
+#[test]
+fn validate_stops_at_malformed_parameters() {
+    let output = netgen(
+        &["--validate"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345 2 100 50 50 x 1 100 50 0 0 0 0 1 100\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().last().unwrap(),
+        "problem 2 (line 2): invalid integer \"x\" on line 2"
+    );
+}
+
+const BATCH_WITH_BAD_MIDDLE: &str = "\
+13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000
+12345 2 100 0 50 500 1 100 50 0 0 0 0 1 100
+99999 3 200 5 5 1000 1 1 500 2 2 20 50 10 100
+";
+
+#[test]
+fn bad_problem_is_skipped() {
+    let output = netgen(&[], BATCH_WITH_BAD_MIDDLE);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let problems: Vec<&str> = stdout
+        .lines()
+        .filter(|l| l.starts_with("c  Problem"))
+        .collect();
+    assert_eq!(problems.len(), 2);
+    assert!(problems[0].contains(" 1 "), "{problems:?}");
+    assert!(problems[1].contains(" 3 "), "{problems:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("problem 2"), "{stderr}");
+    assert!(stderr.contains("100 0 50 500"), "{stderr}");
+}
+
+#[test]
+fn fail_fast_stops_at_bad_problem() {
+    let output = netgen(&["--fail-fast"], BATCH_WITH_BAD_MIDDLE);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout
+            .lines()
+            .filter(|l| l.starts_with("c  Problem"))
+            .count(),
+        1
+    );
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("problem 2")
+    );
+}
+
+#[test]
+fn comments_and_blank_lines_are_ignored() {
+    let plain = netgen(
+        &["--stats-only"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100\n",
+    );
+    let commented = netgen(
+        &["--stats-only"],
+        "# NETGEN batch\n\
+         c DIMACS-style header line\n\
+         \n\
+         13502460 1 512 10 10 2000 # seed, problem, nodes, sources, sinks, density\n\
+         \x20 5 500 1000 3 3 20 80 50 2000\n\
+         \n\
+         # between problems\n\
+         \t\n\
+         12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100 # trailing comment\n\
+         # after the last problem\n",
+    );
+    assert!(commented.status.success());
+    assert_eq!(commented.stdout, plain.stdout);
+    assert_eq!(
+        String::from_utf8(commented.stdout).unwrap().lines().count(),
+        2
+    );
+}
+
+#[test]
+fn malformed_seed_or_problem_number_is_reported() {
+    let output = netgen(
+        &[],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12x 2 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("problem 2 (line 2): invalid integer \"12x\" on line 2"),
+        "{stderr}"
+    );
+
+    let output = netgen(
+        &["--validate"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345\n2nd 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().last().unwrap(),
+        "problem 2 (line 2): invalid integer \"2nd\" on line 3"
+    );
+}
+
+#[test]
+fn malformed_token_reports_line_number() {
+    let output = netgen(
+        &[],
+        "# header\n\
+         \n\
+         13502460 1 512 10 10 2000\n\
+         5 500 1O00 3 3 20 80 50 2000\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("invalid integer \"1O00\" on line 4"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("(line 3)"), "{stderr}");
+}
+
+#[test]
+fn count_writes_one_file_per_seed() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_count_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("inst_{index}.min");
+    let output = netgen(
+        &[
+            "--count",
+            "3",
+            "--seed",
+            "42",
+            "--output",
+            template.to_str().unwrap(),
+            "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000",
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty());
+
+    let files: Vec<String> = (1..=3)
+        .map(|i| std::fs::read_to_string(dir.join(format!("inst_{i}.min"))).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let seeds: Vec<i64> = files
+        .iter()
+        .map(|f| {
+            let line = f.lines().find(|l| l.contains("Random seed:")).unwrap();
+            line.split_whitespace().last().unwrap().parse().unwrap()
+        })
+        .collect();
+    let expected: Vec<i64> = netgen_rs::SeedSequence::new(42).iter().take(3).collect();
+    assert_eq!(seeds, expected);
+    let bodies: Vec<Vec<&str>> = files
+        .iter()
+        .map(|f| f.lines().filter(|l| l.starts_with("a ")).collect())
+        .collect();
+    assert_ne!(bodies[0], bodies[1]);
+    assert_ne!(bodies[1], bodies[2]);
+}
+
+#[test]
+fn name_placeholder_uses_the_benchmark_naming_convention() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_name_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("{name}");
+    let output = netgen(
+        &[
+            "--count",
+            "2",
+            "--family",
+            "demo",
+            "--output",
+            template.to_str().unwrap(),
+        ],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100\n",
+    );
+    assert!(output.status.success(), "{output:?}");
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    let first = std::fs::read_to_string(dir.join("demo-09a.min")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        names,
+        [
+            "demo-06a.asn",
+            "demo-06b.asn",
+            "demo-09a.min",
+            "demo-09b.min"
+        ]
+    );
+    assert!(first.contains("Random seed:"));
+    for name in &names {
+        let parsed = netgen_rs::naming::parse_instance_name(name).unwrap();
+        assert_eq!(parsed.family, "demo");
+        assert_eq!(&parsed.to_string(), name);
+    }
+}
+
+#[test]
+fn name_collisions_are_rejected_before_writing() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_clash_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("{name}");
+    // Both problems have 2^9 nodes, so both are netgen-09a.min.
+    let output = netgen(
+        &["--output", template.to_str().unwrap()],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n\
+         12345 2 600 10 10 2000 5 500 1000 3 3 20 80 50 2000\n",
+    );
+    let written = std::fs::read_dir(&dir).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(written, 0);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with("Error: problem 2 (line 2, seed 12345, "),
+        "{stderr}"
+    );
+    assert!(stderr.contains(
+        "netgen-09a.min is also the output of problem 1 (line 1, seed 13502460); \
+         add {index} to --output"
+    ));
+}
+
+#[test]
+fn count_concatenates_to_stdout() {
+    let output = netgen(
+        &[
+            "--count",
+            "2",
+            "--stats-only",
+            "12345 7 100 5 5 400 1 50 100 1 1 10 50 5 50",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let seeds: Vec<String> = stdout
+        .lines()
+        .map(|l| field(l, "seed").to_string())
+        .collect();
+    let expected: Vec<String> = netgen_rs::SeedSequence::new(12345)
+        .iter()
+        .take(2)
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(seeds, expected);
+    assert!(stdout.lines().all(|l| field(l, "problem") == "7"));
+}
+
+const BATCH: &str = "\
+13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000
+12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100
+99999 3 200 5 5 1000 1 1 500 2 2 20 50 10 100
+13502460 4 4096 64 64 32768 1 10000 64000 0 0 100 100 1 1000
+";
+
+#[test]
+fn jobs_output_matches_serial() {
+    let serial = netgen(&[], BATCH);
+    let parallel = netgen(&["--jobs", "4"], BATCH);
+    assert!(serial.status.success());
+    assert!(parallel.status.success());
+    assert_eq!(parallel.stdout, serial.stdout);
+}
+
+#[test]
+fn jobs_keeps_order_of_errors() {
+    let serial = netgen(&[], BATCH_WITH_BAD_MIDDLE);
+    let parallel = netgen(&["--jobs", "3"], BATCH_WITH_BAD_MIDDLE);
+    assert_eq!(parallel.status.code(), Some(1));
+    assert_eq!(parallel.stdout, serial.stdout);
+    assert_eq!(parallel.stderr, serial.stderr);
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn output_gz_is_compressed() {
+    use std::io::Read;
+
+    let dir = std::env::temp_dir().join(format!("netgen_rs_gz_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let single = dir.join("batch.min.gz");
+    let per_instance = dir.join("inst_{index}.min.gz");
+    let plain = netgen(&[], BATCH);
+    let output = netgen(&["--output", single.to_str().unwrap()], BATCH);
+    assert!(output.status.success(), "{output:?}");
+    let output = netgen(
+        &["--output", per_instance.to_str().unwrap(), "--level", "9"],
+        BATCH,
+    );
+    assert!(output.status.success(), "{output:?}");
+
+    let gunzip = |path: std::path::PathBuf| {
+        let mut decoded = Vec::new();
+        flate2::read::MultiGzDecoder::new(std::fs::File::open(path).unwrap())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        decoded
+    };
+    assert_eq!(gunzip(single), plain.stdout);
+    let joined: Vec<u8> = (1..=4)
+        .flat_map(|i| gunzip(dir.join(format!("inst_{i}.min.gz"))))
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(joined, plain.stdout);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn compress_zstd_to_stdout() {
+    let plain = netgen(&[], BATCH);
+    let output = netgen(&["--compress", "zstd"], BATCH);
+    assert!(output.status.success());
+    assert_eq!(
+        zstd::decode_all(output.stdout.as_slice()).unwrap(),
+        plain.stdout
+    );
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn compression_level_is_checked() {
+    let output = netgen(&["--compress", "gzip", "--level", "12"], BATCH);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("compression level 12 out of range 0..=9"),
+        "{stderr}"
+    );
+    let output = netgen(&["--level", "3"], BATCH);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--level requires --compress"), "{stderr}");
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn checksum_lines_on_stdout() {
+    let output = netgen(
+        &["--checksum"],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "c7ca560e963827076225def4b5b8b7326feff3816a33d156791e69c73e5a24c1  -\n"
+    );
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn checksum_sidecars_match_files() {
+    use sha2::{Digest, Sha256};
+
+    let dir = std::env::temp_dir().join(format!("netgen_rs_sha_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("inst_{index}.min");
+    let output = netgen(
+        &["--checksum", "--output", template.to_str().unwrap()],
+        BATCH,
+    );
+    assert!(output.status.success(), "{output:?}");
+    for i in 1..=4 {
+        let data = std::fs::read(dir.join(format!("inst_{i}.min"))).unwrap();
+        let sidecar = std::fs::read_to_string(dir.join(format!("inst_{i}.min.sha256"))).unwrap();
+        let expected = format!(
+            "{}  inst_{i}.min\n",
+            netgen_rs::to_hex(&Sha256::digest(&data))
+        );
+        assert_eq!(sidecar, expected);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_compares_canonically() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_diff_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let params = doc_params();
+    let dimacs = netgen_rs::to_dimacs_string(13502460, 1, &params).unwrap();
+    // The same network with its arc lines reversed, and with one cost changed.
+    let (head, arcs): (Vec<&str>, Vec<&str>) = dimacs.lines().partition(|l| !l.starts_with("a "));
+    let reversed: Vec<&str> = head.iter().chain(arcs.iter().rev()).copied().collect();
+    let mut changed = reversed.clone();
+    let last = changed.pop().unwrap();
+    let mut fields: Vec<String> = last.split_whitespace().map(String::from).collect();
+    fields[5] = (fields[5].parse::<i64>().unwrap() + 1).to_string();
+    let last = fields.join(" ");
+    changed.push(&last);
+
+    let paths = ["a.min", "b.min", "c.min"].map(|name| dir.join(name));
+    std::fs::write(&paths[0], &dimacs).unwrap();
+    std::fs::write(&paths[1], reversed.join("\n")).unwrap();
+    std::fs::write(&paths[2], changed.join("\n")).unwrap();
+    let path = |i: usize| paths[i].to_str().unwrap();
+
+    let output = netgen(&["diff", path(0), path(1)], "");
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "equal\n");
+
+    let output = netgen(&["diff", path(0), path(2)], "");
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("different: arc "), "{stdout}");
+
+    // As `netgen_rs diff a c | head -0`: the verdict stands, quietly.
+    let output = netgen_closed_early(&["diff", path(0), path(2)], "", 0);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+
+    let output = netgen(&["diff", path(0)], "");
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_checks_files_against_their_header() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_verify_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dimacs = netgen(
+        &[],
+        "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n",
+    )
+    .stdout;
+    let dimacs = String::from_utf8(dimacs).unwrap();
+    // Raise the capacity of the first arc, on line 44.
+    let mut lines: Vec<String> = dimacs.lines().map(String::from).collect();
+    let first_arc = lines.iter().position(|l| l.starts_with("a ")).unwrap();
+    assert_eq!(first_arc + 1, 44);
+    let mut fields: Vec<String> = lines[first_arc].split(' ').map(String::from).collect();
+    fields[4] = (fields[4].parse::<i64>().unwrap() + 1).to_string();
+    lines[first_arc] = fields.join(" ");
+
+    let paths = ["pristine.min", "edited.min", "hand.min"].map(|name| dir.join(name));
+    std::fs::write(&paths[0], &dimacs).unwrap();
+    std::fs::write(&paths[1], lines.join("\n") + "\n").unwrap();
+    std::fs::write(
+        &paths[2],
+        "c by hand\np min 2 1\nn 1 5\nn 2 -5\na 1 2 0 5 1\n",
+    )
+    .unwrap();
+    let verify = |i: usize| netgen(&["verify", paths[i].to_str().unwrap()], "");
+
+    let output = verify(0);
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "match\n");
+
+    let output = verify(1);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "header only: the network differs from line 44\n"
+    );
+
+    let output = verify(2);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("not netgen")
+    );
+
+    let missing = dir.join("missing.min");
+    let output = netgen(&["verify", missing.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn manifest_sidecars_describe_each_file() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_manifest_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("inst_{index}.min");
+    let output = netgen(
+        &[
+            "--manifest",
+            "--jobs",
+            "2",
+            "--output",
+            template.to_str().unwrap(),
+        ],
+        BATCH,
+    );
+    assert!(output.status.success(), "{output:?}");
+    let seeds = ["13502460", "12345", "99999", "13502460"];
+    for (i, seed) in (1..=4).zip(seeds) {
+        assert!(dir.join(format!("inst_{i}.min")).exists());
+        let manifest = std::fs::read_to_string(dir.join(format!("inst_{i}.min.json"))).unwrap();
+        assert_eq!(manifest.lines().count(), 1);
+        assert!(manifest.contains(&format!("\"seed\":{seed},\"problem\":{i},")));
+    }
+
+    let single = dir.join("all.min");
+    let output = netgen(&["--manifest", "--output", single.to_str().unwrap()], BATCH);
+    assert!(output.status.success(), "{output:?}");
+    let manifest = std::fs::read_to_string(dir.join("all.min.json")).unwrap();
+    assert_eq!(manifest.lines().count(), 4);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn manifest_fingerprint_matches_checksum() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_manifest_sha_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("inst.min");
+    // With --provenance the header differs from the plain DIMACS text.
+    for extra in [&[][..], &["--provenance"]] {
+        let args = [extra, &["--manifest", "--checksum", "--output"]].concat();
+        let output = netgen(
+            &[&args[..], &[path.to_str().unwrap()]].concat(),
+            "13502460 1 512 10 10 2000 5 500 1000 3 3 20 80 50 2000\n",
+        );
+        assert!(output.status.success(), "{output:?}");
+        let manifest = std::fs::read_to_string(dir.join("inst.min.json")).unwrap();
+        let checksum = std::fs::read_to_string(dir.join("inst.min.sha256")).unwrap();
+        let digest = checksum.split_whitespace().next().unwrap();
+        assert!(
+            manifest.contains(&format!("\"fingerprint\":\"{digest}\"")),
+            "{extra:?}"
+        );
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn manifest_requires_output() {
+    let output = netgen(&["--manifest"], BATCH);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn suite_dry_run_lists_the_script_instances() {
+    let output = netgen(
+        &["suite", "lemon-netgen-deg", "--max-exp", "2", "--dry-run"],
+        "",
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 10);
+    assert_eq!(
+        lines[0],
+        "netgen_deg_01a.min  13502460 1 4096 64 64 8192 1 10000 64000 0 0 100 100 1 1000"
+    );
+    assert!(lines[9].starts_with("netgen_deg_02e.min  37240194 2 4096 64 64 16384 "));
+
+    let output = netgen(&["suite", "netgen-8"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let output = netgen(&["suite", "lemon-netgen-8", "--max-exp", "x"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn suite_writes_the_same_files_as_the_problem_lines() {
+    let dir = std::env::temp_dir().join(format!("netgen_rs_suite_{}", std::process::id()));
+    let output = netgen(
+        &[
+            "suite",
+            "lemon-netgen-lo-8",
+            "--max-exp",
+            "9",
+            "--out-dir",
+            dir.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{output:?}");
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 10);
+    assert_eq!(names[0], "netgen_lo_8_08a.min");
+    let written = std::fs::read_to_string(dir.join("netgen_lo_8_09c.min")).unwrap();
+    let direct = netgen(
+        &[],
+        "91208735   9       512    23    23      4096  1 10000     230  0 0 100 100  1 1000\n",
+    );
+    assert_eq!(written.as_bytes(), direct.stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn short_networks_are_warned_about() {
+    // 308 is the most arcs the network can hold, so the lint is quiet,
+    // but dropped arcs leave it 2 short.
+    let input = "13502460 1 20 2 2 308 1 100 100 0 0 10 50 1 50\n";
+    let output = netgen(&[], input);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(
+        stderr,
+        "Warning: problem 1 (line 1, seed 13502460): the network has 306 of 308 arcs, \
+         2 short; 38 random arcs were dropped because their tails ran out of heads\n"
+    );
+    // The output is still the reference's, unless --provenance stamps it.
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("\np min 20 306\n"));
+    assert!(!stdout.contains("Dropped"));
+    let stamped = netgen(&["--provenance", "--no-warnings"], input);
+    let stdout = String::from_utf8(stamped.stdout).unwrap();
+    assert!(stdout.contains("\nc Dropped arcs: 38\n"));
+
+    let quiet = netgen(&["--no-warnings"], input);
+    assert!(quiet.stderr.is_empty());
+    // Drops that later tails make up for are not worth a warning.
+    let made_up = netgen(&[], "77 1 30 2 2 303 1 100 100 0 0 10 50 1 50\n");
+    assert!(made_up.stderr.is_empty());
+}
+
+#[test]
+fn lint_warnings_go_to_stderr_unless_disabled() {
+    let input = "13502460 1 512 10 10 2000 7 7 1000 3 3 20 80 50 2000\n\
+                 12345 2 100 50 50 500 1 100 50 0 0 0 0 1 100\n";
+    let output = netgen(&["--stats-only"], input);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.lines().count(), 1, "{stderr}");
+    assert!(
+        stderr.starts_with("Warning: problem 1 (line 1): hicost percentage 20"),
+        "{stderr}"
+    );
+
+    let quiet = netgen(&["--stats-only", "--no-warnings"], input);
+    assert!(quiet.status.success());
+    assert!(quiet.stderr.is_empty());
+    assert_eq!(quiet.stdout, output.stdout);
+
+    let validated = netgen(&["--validate"], input);
+    assert!(
+        String::from_utf8(validated.stderr)
+            .unwrap()
+            .contains("hicost percentage 20")
+    );
+}
+
+#[test]
+fn written_problem_file_is_read_to_the_end() {
+    let specs = [
+        (13502460, 1, "512 10 10 2000 5 500 1000 3 3 20 80 50 2000"),
+        (12345, 2, "100 50 50 500 1 100 50 0 0 0 0 1 100"),
+        (99999, 3, "200 5 5 1000 1 1 500 2 2 20 50 10 100"),
+    ]
+    .map(|(seed, problem, params)| (seed, problem, params.parse().unwrap()));
+    let mut file = Vec::new();
+    netgen_rs::write_problem_file(&mut file, &specs).unwrap();
+    // A problem after the terminating line must not be generated.
+    file.extend_from_slice(b"777 4 100 50 50 500 1 100 50 0 0 0 0 1 100\n");
+
+    let output = netgen(&[], &String::from_utf8(file).unwrap());
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let problems: Vec<_> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("c  Problem "))
+        .map(|rest| rest.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(problems, ["1", "2", "3"]);
+}
+
+#[test]
+fn provenance_records_version_time_and_command() {
+    let args = [
+        "--provenance",
+        "13502460",
+        "1",
+        "100",
+        "5",
+        "5",
+        "300",
+        "1",
+        "100",
+    ];
+    let args = [&args[..], &["50", "0", "0", "0", "0", "1", "100"]].concat();
+    let output = Command::new(env!("CARGO_BIN_EXE_netgen_rs"))
+        .args(&args)
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!(
+        "c generator: netgen_rs v{}\n\
+         c generated: 2023-11-14T22:13:20Z\n\
+         c command: netgen_rs {}\n",
+        env!("CARGO_PKG_VERSION"),
+        args.join(" ")
+    )));
+
+    let plain = netgen(&args[1..], "");
+    assert!(
+        !String::from_utf8(plain.stdout)
+            .unwrap()
+            .contains("c generator:")
+    );
+}
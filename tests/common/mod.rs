@@ -94,7 +94,12 @@ fn run_rust(input: &str) -> String {
         }
 
         let params = netgen_rs::NetgenParams::from_slice(&parms).unwrap();
-        let gen_result = netgen_rs::generate(seed, &params).unwrap();
+        assert!(
+            params.is_reference_compatible(),
+            "compared against C outside the reference domain: {params}"
+        );
+        let mode = netgen_rs::GenerationMode::ReferenceExact;
+        let gen_result = netgen_rs::generate_with(seed, &params, mode).unwrap();
         let mut buf = Vec::new();
         netgen_rs::write_dimacs(&mut buf, seed, problem, &params, &gen_result).unwrap();
         result.push_str(&String::from_utf8(buf).unwrap());
@@ -103,7 +108,8 @@ fn run_rust(input: &str) -> String {
     result
 }
 
-pub fn assert_identical(input: &str) {
+/// Compare the C and Rust outputs for `input`, returning the (common) output.
+pub fn assert_identical(input: &str) -> String {
     let c_out = run_c(input);
     let rust_out = run_rust(input);
     assert_eq!(
@@ -112,4 +118,5 @@ pub fn assert_identical(input: &str) {
         "Output mismatch for input: {}",
         input.trim()
     );
+    c_out
 }
@@ -0,0 +1,52 @@
+#![cfg(any(feature = "flate2", feature = "zstd"))]
+
+mod fixture;
+
+use std::io::Read;
+
+use netgen_rs::{NetgenParams, NetgenResult, generate, write_dimacs};
+
+use fixture::doc_params;
+
+fn instance() -> (NetgenParams, NetgenResult, Vec<u8>) {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    let mut plain = Vec::new();
+    write_dimacs(&mut plain, 13502460, 1, &params, &result).unwrap();
+    (params, result, plain)
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn gzip_round_trip() {
+    let (params, result, plain) = instance();
+    for level in [0, netgen_rs::GZIP_DEFAULT_LEVEL, 9] {
+        let gz =
+            netgen_rs::write_dimacs_gz(Vec::new(), 13502460, 1, &params, &result, level).unwrap();
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(gz.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, plain);
+        if level > 0 {
+            assert!(gz.len() < plain.len() / 2);
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_round_trip() {
+    let (params, result, plain) = instance();
+    for level in [1, netgen_rs::ZSTD_DEFAULT_LEVEL, 19] {
+        let zst =
+            netgen_rs::write_dimacs_zstd(Vec::new(), 13502460, 1, &params, &result, level).unwrap();
+        let mut decoded = Vec::new();
+        zstd::Decoder::new(zst.as_slice())
+            .unwrap()
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, plain);
+        assert!(zst.len() < plain.len() / 2);
+    }
+}
@@ -0,0 +1,79 @@
+mod fixture;
+
+use std::fs;
+
+use netgen_rs::{Arc, ArcKind, NetgenResult, write_csv, write_csv_to_dir};
+
+use fixture::doc_result;
+
+/// Header and integer rows of a CSV document.
+fn parse(csv: &str) -> (&str, Vec<Vec<i64>>) {
+    let mut lines = csv.lines();
+    let header = lines.next().unwrap();
+    let rows = lines
+        .map(|l| l.split(',').map(|x| x.parse().unwrap()).collect())
+        .collect();
+    (header, rows)
+}
+
+fn read_back(nodes: &str, arcs: &str) -> NetgenResult {
+    let (header, nodes) = parse(nodes);
+    assert_eq!(header, "node_id,supply");
+    let (header, arcs) = parse(arcs);
+    assert_eq!(header, "arc_id,from,to,cost,capacity,lower");
+    for (i, row) in nodes.iter().enumerate() {
+        assert_eq!(row[0], i as i64 + 1);
+    }
+    for (k, row) in arcs.iter().enumerate() {
+        assert_eq!(row[0], k as i64 + 1);
+        assert_eq!(row[5], 0);
+    }
+    let mut result = NetgenResult {
+        supply: nodes.iter().map(|row| row[1]).collect(),
+        arcs: arcs
+            .iter()
+            .map(|row| Arc {
+                from: row[1] as u64,
+                to: row[2] as u64,
+                cost: row[3],
+                capacity: row[4],
+                multiplier: None,
+                uncapacitated: false,
+                kind: ArcKind::Unknown,
+                hicost: false,
+            })
+            .collect(),
+    };
+    result.infer_uncapacitated();
+    result
+}
+
+#[test]
+fn csv_round_trip() {
+    let result = doc_result();
+    let (mut nodes, mut arcs) = (Vec::new(), Vec::new());
+    write_csv(&mut nodes, &mut arcs, &result).unwrap();
+    let nodes = String::from_utf8(nodes).unwrap();
+    let arcs = String::from_utf8(arcs).unwrap();
+    assert_eq!(nodes.lines().count(), 513);
+    assert_eq!(arcs.lines().count(), result.arcs.len() + 1);
+    assert_eq!(
+        read_back(&nodes, &arcs).canonicalize(),
+        result.canonicalize()
+    );
+}
+
+#[test]
+fn csv_to_dir_writes_both_files() {
+    let result = doc_result();
+    let dir = std::env::temp_dir().join(format!("netgen_rs_csv_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    write_csv_to_dir(&dir, "inst", &result).unwrap();
+    let nodes = fs::read_to_string(dir.join("inst_nodes.csv")).unwrap();
+    let arcs = fs::read_to_string(dir.join("inst_arcs.csv")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        read_back(&nodes, &arcs).canonicalize(),
+        result.canonicalize()
+    );
+}
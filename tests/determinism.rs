@@ -0,0 +1,172 @@
+//! Output that must be byte-identical on every platform.
+//!
+//! Each case is an instance, written in one of the output formats, with
+//! the SHA-256 recorded on x86_64 Linux. A mismatch on another target
+//! (aarch64 macOS, a big-endian or 32-bit target, ...) means generation or
+//! writing depends on the platform, which the crate guarantees it does not.
+//! The cases cover what could plausibly differ: the BCJL `f64` arithmetic
+//! (instances above 2^15 nodes and dense ones), the generalized dialect,
+//! `usize`-indexed relabeling and shuffling, fast mode, grid networks and
+//! the little-endian binary format.
+#![cfg(feature = "sha2")]
+
+mod fixture;
+
+use std::io::{self, Write};
+
+use netgen_rs::{
+    ArcOrder, DimacsOptions, FingerprintWriter, GenerateOptions, GenerationMode, GridParams,
+    MultiplierRange, NetgenParams, NetgenResult, generate, generate_grid, generate_with,
+    generate_with_options, to_hex, write_binary, write_dimacs, write_dimacs_with_options,
+};
+
+use fixture::DOC_PARMS;
+
+fn params(parms: [i64; 13]) -> NetgenParams {
+    NetgenParams::from_slice(&parms).unwrap()
+}
+
+fn assert_fingerprint(expected: &str, write: impl FnOnce(&mut dyn Write) -> io::Result<()>) {
+    let mut hasher = FingerprintWriter::new();
+    write(&mut hasher).unwrap();
+    assert_eq!(to_hex(&hasher.finish()), expected);
+}
+
+fn dimacs(
+    seed: i64,
+    params: &NetgenParams,
+    result: &NetgenResult,
+) -> impl FnOnce(&mut dyn Write) -> io::Result<()> {
+    move |mut w| write_dimacs(&mut w, seed, 1, params, result)
+}
+
+#[test]
+fn min_cost_flow() {
+    let params = params([
+        4096, 40, 40, 30000, -50, 900, 20000, 10, 10, 30, 70, 100, 5000,
+    ]);
+    let result = generate(271828, &params).unwrap();
+    assert_fingerprint(
+        "a9490a05c0c8a4d4214ba66f038d91735274a10fb71b9847e080f83a6ae1b4ed",
+        dimacs(271828, &params, &result),
+    );
+}
+
+#[test]
+fn assignment() {
+    let params = params([600, 300, 300, 4000, 1, 1000, 300, 0, 0, 0, 0, 1, 1]);
+    let result = generate(31415, &params).unwrap();
+    assert_fingerprint(
+        "a8dcaec294b1a09c4dba8d384a05c09c9e997f662ab3038ec8f1d4edd0759bc8",
+        dimacs(31415, &params, &result),
+    );
+}
+
+#[test]
+fn max_flow() {
+    let params = params([1500, 15, 15, 9000, 1, 1, 7000, 5, 5, 10, 60, 1, 800]);
+    let result = generate(2718, &params).unwrap();
+    assert_fingerprint(
+        "35c2cf28217d013d2765685b54c3390b1fad85df0b0002d809b29806eb95cce5",
+        dimacs(2718, &params, &result),
+    );
+}
+
+#[test]
+fn beyond_2_pow_15_nodes() {
+    // The sinks per source and the random arc limits go through `f64`.
+    let params = params([
+        40000, 100, 100, 120000, 1, 10000, 100000, 20, 20, 25, 75, 1, 10000,
+    ]);
+    let result = generate(1618033, &params).unwrap();
+    assert_fingerprint(
+        "9e439bb807d9fa275ab2faee5adb5bb85763927052ac9d0adf1f21025ec92f1a",
+        dimacs(1618033, &params, &result),
+    );
+}
+
+#[test]
+fn dense_with_dropped_arcs() {
+    let params = params([20, 2, 2, 308, 1, 100, 100, 0, 0, 10, 50, 1, 50]);
+    let result = generate(13502460, &params).unwrap();
+    assert_fingerprint(
+        "b64447b8a57feb485d7ff4e94121ba837e11b178bf919cb2625e9a7fb79cad2f",
+        dimacs(13502460, &params, &result),
+    );
+}
+
+#[test]
+fn fast_mode() {
+    let params = params([
+        5000, 50, 50, 40000, 1, 1000, 50000, 10, 10, 20, 80, 10, 2000,
+    ]);
+    let result = generate_with(424242, &params, GenerationMode::Fast).unwrap();
+    assert_fingerprint(
+        "68d5c34f8e1487f69006173cf548a5c77cbb5b322426182dd7f26d5ac4485abc",
+        dimacs(424242, &params, &result),
+    );
+}
+
+#[test]
+fn generalized_multipliers() {
+    let params = params([512, 10, 10, 3000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000]);
+    let options = GenerateOptions {
+        multipliers: Some(MultiplierRange {
+            num_min: 1,
+            num_max: 999,
+            den: 7,
+        }),
+        ..GenerateOptions::default()
+    };
+    let (result, _) = generate_with_options(13502460, &params, &options).unwrap();
+    let options = DimacsOptions {
+        generalized: true,
+        ..DimacsOptions::default()
+    };
+    assert_fingerprint(
+        "2ed7d56f3e5120550b96fd6c346d16b349e42fcef8f29fdbf8d44924fa0d79d6",
+        |mut w| write_dimacs_with_options(&mut w, 13502460, 1, &params, &result, &options),
+    );
+}
+
+#[test]
+fn relabeled_and_shuffled() {
+    let params = params([2048, 20, 20, 12000, 1, 300, 4000, 5, 5, 20, 80, 10, 500]);
+    let result = generate(99991, &params).unwrap();
+    let options = DimacsOptions {
+        arc_order: ArcOrder::Shuffled { seed: 7 },
+        relabel_nodes: Some(11),
+        ..DimacsOptions::default()
+    };
+    assert_fingerprint(
+        "5717fabc4464afacb3cbac0b5cda63b2fe4fd028db19436802830c40000aab5c",
+        |mut w| write_dimacs_with_options(&mut w, 99991, 1, &params, &result, &options),
+    );
+}
+
+#[test]
+fn grid() {
+    let grid = GridParams {
+        width: 40,
+        height: 30,
+        cost_range: 1..=100,
+        cap_range: 10..=1000,
+        supply: 5000,
+        wrap: true,
+    };
+    let result = generate_grid(8675309, &grid).unwrap();
+    assert_fingerprint(
+        "ef8aaee8c39f4306cf76ec26608fc144d4a29ba2a69a5405ca1370968bf161d7",
+        dimacs(8675309, &grid.netgen_params(), &result),
+    );
+}
+
+#[test]
+fn binary_format() {
+    let params = params(DOC_PARMS);
+    let result = generate(13502460, &params).unwrap();
+    assert_fingerprint(
+        "1597b68815fff8886a0a999fe2f49f6c8a780d58449999c7be69e313460da1bf",
+        |mut w| write_binary(&mut w, 13502460, 1, &params, &result),
+    );
+}
@@ -0,0 +1,122 @@
+//! Builds a C program against the static library and the header in
+//! include/, and checks it sees the same networks as the Rust API.
+//!
+//! The static library is built by a nested `cargo rustc --crate-type
+//! staticlib` into its own target directory.
+
+mod fixture;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Once;
+
+use netgen_rs::{NetgenParams, generate, to_dimacs_string};
+
+use fixture::DOC_PARMS;
+
+static BUILD_HARNESS: Once = Once::new();
+
+fn out_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("test-ffi")
+}
+
+fn harness_path() -> PathBuf {
+    out_dir().join("ffi_harness")
+}
+
+fn build_harness() {
+    BUILD_HARNESS.call_once(|| {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let status = Command::new(env!("CARGO"))
+            .args([
+                "rustc",
+                "--lib",
+                "--features",
+                "ffi",
+                "--crate-type",
+                "staticlib",
+            ])
+            .arg("--target-dir")
+            .arg(out_dir())
+            .current_dir(manifest_dir)
+            .status()
+            .expect("Failed to invoke cargo");
+        assert!(status.success(), "Failed to build the static library");
+
+        let status = Command::new("cc")
+            .args(["-O", "-Wall", "-Werror"])
+            .arg(format!("-I{}", manifest_dir.join("include").display()))
+            .arg("-o")
+            .arg(harness_path())
+            .arg(manifest_dir.join("tests").join("c").join("ffi_harness.c"))
+            .arg(out_dir().join("debug").join("libnetgen_rs.a"))
+            .args(["-lpthread", "-ldl", "-lm"])
+            .status()
+            .expect("Failed to invoke C compiler");
+        assert!(status.success(), "Failed to compile the FFI harness");
+    });
+}
+
+/// Run the harness, returning its stdout and the DIMACS file it wrote.
+fn run_harness(seed: i64, parms: &[i64; 13]) -> (String, String) {
+    build_harness();
+    let out = std::env::temp_dir().join(format!("netgen_ffi_{}_{seed}.min", std::process::id()));
+    let output = Command::new(harness_path())
+        .arg(&out)
+        .arg(seed.to_string())
+        .args(parms.iter().map(|p| p.to_string()))
+        .output()
+        .expect("Failed to run the FFI harness");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let dimacs = std::fs::read_to_string(&out).unwrap_or_default();
+    let _ = std::fs::remove_file(&out);
+    (String::from_utf8(output.stdout).unwrap(), dimacs)
+}
+
+fn assert_same_as_rust(seed: i64, parms: [i64; 13]) {
+    let (stdout, dimacs) = run_harness(seed, &parms);
+    let params = NetgenParams::from_slice(&parms).unwrap();
+    let result = generate(seed, &params).unwrap();
+    let mut expected = String::from("code 0\n");
+    for (i, s) in result.supply.iter().enumerate() {
+        expected.push_str(&format!("n {} {s}\n", i + 1));
+    }
+    for a in &result.arcs {
+        expected.push_str(&format!(
+            "a {} {} {} {}\n",
+            a.from, a.to, a.cost, a.capacity
+        ));
+    }
+    assert_eq!(stdout, expected);
+    assert_eq!(dimacs, to_dimacs_string(seed, 1, &params).unwrap());
+}
+
+#[test]
+fn min_cost_flow_matches() {
+    assert_same_as_rust(13502460, DOC_PARMS);
+}
+
+#[test]
+fn assignment_matches() {
+    assert_same_as_rust(12345, [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]);
+}
+
+#[test]
+fn max_flow_matches() {
+    assert_same_as_rust(99999, [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]);
+}
+
+#[test]
+fn errors_use_netgen_h_codes() {
+    let parms = DOC_PARMS;
+    assert_eq!(run_harness(0, &parms).0, "code -1\n");
+    let mut bad = parms;
+    bad[2] = 600;
+    assert_eq!(run_harness(1, &bad).0, "code -3\n");
+}
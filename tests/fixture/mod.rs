@@ -0,0 +1,20 @@
+//! The instance of the crate's examples, shared by the integration tests.
+//! Each test crate uses only some of it.
+#![allow(dead_code)]
+
+use netgen_rs::{NetgenParams, NetgenResult, generate};
+
+/// The parameters of the examples: a min-cost flow problem on 512 nodes
+/// and 2000 arcs.
+pub const DOC_PARMS: [i64; 13] = [512, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000];
+
+/// [`DOC_PARMS`] as parameters.
+pub fn doc_params() -> NetgenParams {
+    NetgenParams::from_slice(&DOC_PARMS).unwrap()
+}
+
+/// The network the examples generate from [`doc_params`], with seed
+/// 13502460.
+pub fn doc_result() -> NetgenResult {
+    generate(13502460, &doc_params()).unwrap()
+}
@@ -0,0 +1,86 @@
+mod fixture;
+
+use netgen_rs::{GraphmlOptions, NetgenResult, write_graphml, write_graphml_with_options};
+use roxmltree::{Document, Node};
+
+use fixture::doc_result;
+
+fn graphml(result: &NetgenResult, options: &GraphmlOptions) -> String {
+    let mut out = Vec::new();
+    write_graphml_with_options(&mut out, result, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn data(node: Node, key: &str) -> Option<i64> {
+    node.children()
+        .find(|c| c.has_tag_name("data") && c.attribute("key") == Some(key))
+        .map(|c| c.text().unwrap().parse().unwrap())
+}
+
+fn elements<'a, 'i>(doc: &'a Document<'i>, tag: &'a str) -> impl Iterator<Item = Node<'a, 'i>> {
+    doc.descendants().filter(move |n| n.has_tag_name(tag))
+}
+
+#[test]
+fn graphml_structure_and_values() {
+    let result = doc_result();
+    let xml = graphml(&result, &GraphmlOptions::default());
+    let mut plain = Vec::new();
+    write_graphml(&mut plain, &result).unwrap();
+    assert_eq!(plain, xml.as_bytes());
+
+    let doc = Document::parse(&xml).unwrap();
+    let root = doc.root_element();
+    assert!(root.has_tag_name("graphml"));
+    let children: Vec<_> = root
+        .children()
+        .filter(|n| n.is_element())
+        .map(|n| n.tag_name().name())
+        .collect();
+    assert_eq!(children, ["key", "key", "key", "key", "graph"]);
+    let graph = elements(&doc, "graph").next().unwrap();
+    assert_eq!(graph.attribute("edgedefault"), Some("directed"));
+
+    let nodes: Vec<_> = elements(&doc, "node").collect();
+    assert_eq!(nodes.len(), result.supply.len());
+    for (i, node) in nodes.iter().enumerate() {
+        assert_eq!(node.attribute("id"), Some(format!("n{}", i + 1).as_str()));
+        assert_eq!(data(*node, "supply"), Some(result.supply[i]));
+    }
+
+    let edges: Vec<_> = elements(&doc, "edge").collect();
+    assert_eq!(edges.len(), result.arcs.len());
+    for (edge, arc) in edges.iter().zip(&result.arcs) {
+        assert_eq!(
+            edge.attribute("source"),
+            Some(format!("n{}", arc.from).as_str())
+        );
+        assert_eq!(
+            edge.attribute("target"),
+            Some(format!("n{}", arc.to).as_str())
+        );
+        assert_eq!(data(*edge, "cost"), Some(arc.cost));
+        assert_eq!(data(*edge, "capacity"), Some(arc.capacity));
+        assert_eq!(data(*edge, "lower"), Some(0));
+    }
+}
+
+#[test]
+fn graphml_can_skip_zero_supplies() {
+    let result = doc_result();
+    let full = graphml(&result, &GraphmlOptions::default());
+    let xml = graphml(
+        &result,
+        &GraphmlOptions {
+            skip_zero_supply: true,
+        },
+    );
+    assert!(xml.len() < full.len());
+    let doc = Document::parse(&xml).unwrap();
+    let with_supply = elements(&doc, "node")
+        .filter(|n| data(*n, "supply").is_some())
+        .count();
+    let nonzero = result.supply.iter().filter(|&&s| s != 0).count();
+    assert_eq!(with_supply, nonzero);
+    assert_eq!(elements(&doc, "node").count(), result.supply.len());
+}
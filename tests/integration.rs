@@ -47,3 +47,50 @@ fn various_seeds() {
         assert_identical(&input);
     }
 }
+
+#[test]
+fn near_complete_density() {
+    assert_identical("13502460 1 20 2 2 380 1 100 100 0 0 10 50 1 50\n");
+}
+
+#[test]
+fn dropped_arcs_below_max_density() {
+    // Random arcs are dropped as in the C code: three made up for by
+    // later tails, and 38 that leave the network at `max_arcs` 2 short.
+    assert_identical("77 1 30 2 2 303 1 100 100 0 0 10 50 1 50\n");
+    assert_identical("13502460 1 20 2 2 308 1 100 100 0 0 10 50 1 50\n");
+}
+
+#[test]
+fn supply_below_sink_count() {
+    // Sources with less supply than sinks give it all to their first sink.
+    assert_identical("13502460 1 200 10 40 2000 1 100 12 0 0 10 50 1 100\n");
+    assert_identical("42 1 60 5 30 300 1 50 5 2 10 0 100 1 10\n");
+}
+
+#[test]
+fn no_transshipment_nodes() {
+    // sources + sinks = nodes, so the transshipment index list is empty.
+    assert_identical("1356170460 1 8 5 3 12 17 239 6796 1 1 27 2 53 92\n");
+    assert_identical("536100990 1 43 12 31 350 17 776 2041 9 19 33 98 73 518\n");
+}
+
+#[test]
+fn negative_costs() {
+    assert_identical("13502460 1 512 10 10 4000 -100 100 1000 3 3 20 80 50 2000\n");
+    assert_identical("42 1 100 50 50 500 -50 -10 50 0 0 30 0 1 100\n");
+}
+
+#[test]
+fn written_problem_file() {
+    let specs = [
+        (13502460, 1, "512 2 2 1000 10 100 200 0 0 20 100 10 1000"),
+        (12345, 2, "100 50 50 500 1 100 50 0 0 0 0 1 100"),
+        (99999, 3, "200 5 5 1000 1 1 500 2 2 20 50 10 100"),
+    ]
+    .map(|(seed, problem, params)| (seed, problem, params.parse().unwrap()));
+    let mut file = Vec::new();
+    netgen_rs::write_problem_file(&mut file, &specs).unwrap();
+    let output = assert_identical(&String::from_utf8(file).unwrap());
+    assert_eq!(output.matches("c  Problem ").count(), specs.len());
+}
@@ -0,0 +1,98 @@
+use netgen_rs::{NetgenParams, generate};
+use proptest::prelude::*;
+
+/// Valid parameters with up to 200 nodes, covering all three problem types.
+///
+/// At least four nodes must be able to receive arcs: with fewer, the arc
+/// count loop in NETGEN (C and Rust alike) can spin forever.
+fn params() -> impl Strategy<Value = NetgenParams> {
+    (2i64..=200)
+        .prop_flat_map(|nodes| (Just(nodes), 1..=nodes - 1))
+        .prop_flat_map(|(nodes, sources)| (Just(nodes), Just(sources), 1..=nodes - sources))
+        .prop_flat_map(|(nodes, sources, sinks)| {
+            (
+                Just([nodes, sources, sinks]),
+                nodes..=nodes * nodes.min(10),
+                (-10i64..100, 0i64..1000),
+                sources..=sources + 10_000,
+                (0..=sources, 0..=sinks),
+                (0i64..=100, 0i64..=100),
+                (0i64..100, 0i64..1000),
+            )
+        })
+        .prop_map(
+            |(
+                [nodes, sources, sinks],
+                density,
+                (mincost, costs),
+                supply,
+                (ts, tk),
+                (hi, cap),
+                (mincap, caps),
+            )| {
+                NetgenParams::from_slice(&[
+                    nodes,
+                    sources,
+                    sinks,
+                    density,
+                    mincost,
+                    mincost + costs,
+                    supply,
+                    ts,
+                    tk,
+                    hi,
+                    cap,
+                    mincap,
+                    mincap + caps,
+                ])
+                .unwrap()
+            },
+        )
+        .prop_filter("fewer than four possible heads", |p| {
+            p.nodes - p.sources + p.tsources >= 4
+        })
+}
+
+/// Assignment problems: `sources = sinks = supply = nodes / 2`, with at
+/// least four sinks for the same reason as above.
+fn assignment_params() -> impl Strategy<Value = NetgenParams> {
+    (4i64..=100)
+        .prop_flat_map(|half| (Just(half), 2 * half..=2 * half * half.min(10), 1i64..100))
+        .prop_map(|(half, density, maxcost)| {
+            NetgenParams::from_slice(&[
+                2 * half,
+                half,
+                half,
+                density,
+                1,
+                maxcost,
+                half,
+                0,
+                0,
+                0,
+                0,
+                1,
+                1,
+            ])
+            .unwrap()
+        })
+}
+
+proptest! {
+    #[test]
+    fn generated_instances_satisfy_invariants(params in params(), seed in 1i64..=2_147_483_646) {
+        let result = generate(seed, &params).unwrap();
+        prop_assert_eq!(result.check_invariants(&params), Ok(()));
+        prop_assert_eq!(result.terminal_violations(&params), []);
+    }
+
+    #[test]
+    fn assignment_instances_satisfy_invariants(
+        params in assignment_params(),
+        seed in 1i64..=2_147_483_646,
+    ) {
+        let result = generate(seed, &params).unwrap();
+        prop_assert_eq!(result.check_invariants(&params), Ok(()));
+        prop_assert_eq!(result.terminal_violations(&params), []);
+    }
+}
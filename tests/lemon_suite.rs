@@ -1,6 +1,7 @@
 mod common;
 
 use common::assert_identical;
+use netgen_rs::LemonSuite;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{self, Command};
@@ -34,6 +35,26 @@ fn lemon_netgen_deg_suite() {
     run_lemon_suite("netgen_deg.sh");
 }
 
+#[test]
+fn suite_encoder_matches_the_scripts() {
+    for suite in LemonSuite::ALL {
+        let cases = run_script(suite.script());
+        let mut instances = suite.instances(suite.exponents());
+        instances.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        assert_eq!(instances.len(), cases.len(), "{suite}");
+        for (instance, case) in instances.iter().zip(&cases) {
+            assert_eq!(format!("{}.param", instance.file_name), case.name);
+            let line = format!("{} {} {}", instance.seed, instance.problem, instance.params);
+            assert_eq!(
+                line,
+                case.line.split_whitespace().collect::<Vec<_>>().join(" "),
+                "{}",
+                case.name
+            );
+        }
+    }
+}
+
 #[derive(Clone)]
 struct Case {
     name: String,
@@ -55,6 +76,16 @@ fn run_lemon_suite(script_name: &str) {
 }
 
 fn load_cases(script_name: &str) -> Vec<Case> {
+    let mut cases = run_script(script_name);
+    cases.retain(|case| case.nodes <= MAX_NODES);
+    if cases.len() > MAX_CASES_PER_SCRIPT {
+        cases.truncate(MAX_CASES_PER_SCRIPT);
+    }
+    cases
+}
+
+/// Run a script and return every case it writes, sorted by file name.
+fn run_script(script_name: &str) -> Vec<Case> {
     let script_path = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("lemon_scripts")
         .join(script_name);
@@ -69,10 +100,6 @@ fn load_cases(script_name: &str) -> Vec<Case> {
 
     let mut cases: Vec<Case> = read_param_files(&temp_dir);
     cases.sort_by(|a, b| a.name.cmp(&b.name));
-    cases.retain(|case| case.nodes <= MAX_NODES);
-    if cases.len() > MAX_CASES_PER_SCRIPT {
-        cases.truncate(MAX_CASES_PER_SCRIPT);
-    }
 
     let _ = fs::remove_dir_all(&temp_dir);
     cases
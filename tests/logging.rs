@@ -0,0 +1,60 @@
+#![cfg(feature = "logging")]
+
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use netgen_rs::{NetgenParams, generate};
+
+struct Capture(Mutex<Vec<(Level, String)>>);
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if record.target().starts_with("netgen_rs") {
+            let mut messages = self.0.lock().unwrap();
+            messages.push((record.level(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn generation_logs_progress_and_summary() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    let params =
+        NetgenParams::from_slice(&[4096, 200, 50, 20000, 1, 100, 10000, 0, 0, 0, 100, 1, 100])
+            .unwrap();
+    let result = generate(13502460, &params).unwrap();
+
+    let messages = LOGGER.0.lock().unwrap();
+    let summary: Vec<_> = messages.iter().filter(|(l, _)| *l == Level::Info).collect();
+    assert_eq!(summary.len(), 1);
+    assert!(
+        summary[0].1.starts_with(&format!(
+            "generated {} arcs on 4096 nodes",
+            result.arcs.len()
+        )),
+        "{}",
+        summary[0].1
+    );
+
+    // Sources 1, 64, 128, 192 and 200 report progress, not all 200.
+    let progress = messages
+        .iter()
+        .filter(|(_, m)| m.starts_with("skeleton for source"))
+        .count();
+    assert_eq!(progress, 5);
+    assert!(
+        messages
+            .iter()
+            .any(|(_, m)| m.starts_with("supply created"))
+    );
+}
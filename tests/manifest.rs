@@ -0,0 +1,48 @@
+#![cfg(all(feature = "serde", feature = "sha2"))]
+
+mod fixture;
+
+use netgen_rs::{InstanceManifest, NetgenParams, ProblemType, generate, to_hex, write_manifest};
+
+use fixture::DOC_PARMS;
+
+#[test]
+fn manifest_round_trips_and_regenerates_the_instance() {
+    for (seed, problem, parms) in [
+        (13502460, 1, DOC_PARMS),
+        (12345, 2, [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]),
+        (
+            99999,
+            3,
+            [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+        ),
+    ] {
+        let params = NetgenParams::from_slice(&parms).unwrap();
+        let result = generate(seed, &params).unwrap();
+        let manifest = InstanceManifest::from_run(seed, problem, &params, &result);
+        let mut out = Vec::new();
+        write_manifest(&mut out, &manifest).unwrap();
+
+        let read: InstanceManifest = serde_json::from_slice(&out).unwrap();
+        assert_eq!(read, manifest);
+        assert_eq!(serde_json::to_vec(&manifest).unwrap(), out.trim_ascii_end());
+
+        let regenerated = generate(read.seed, &read.params).unwrap();
+        assert_eq!(read.nodes, regenerated.supply.len());
+        assert_eq!(read.arcs, regenerated.arcs.len());
+        assert_eq!(read.problem_type, read.params.problem_type());
+        let fingerprint = regenerated.fingerprint(read.seed, read.problem, &read.params);
+        assert_eq!(read.fingerprint, Some(to_hex(&fingerprint)));
+    }
+}
+
+#[test]
+fn problem_types_use_their_display_names() {
+    for kind in [
+        ProblemType::Assignment,
+        ProblemType::MaxFlow,
+        ProblemType::MinCostFlow,
+    ] {
+        assert_eq!(serde_json::to_string(&kind).unwrap(), format!("\"{kind}\""));
+    }
+}
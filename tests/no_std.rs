@@ -0,0 +1,72 @@
+//! Generates and formats instances through the API that remains without the
+//! `std` feature. Run it both ways:
+//! `cargo test --test no_std` and `cargo test --no-default-features --test no_std`.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod fixture;
+
+use alloc::string::String;
+
+use netgen_rs::{
+    DimacsOptions, GenerateOptions, NetgenParams, NetgenResult, generate, generate_with_options,
+    to_dimacs_string, write_dimacs_fmt,
+};
+
+use fixture::{DOC_PARMS, doc_params};
+
+/// FNV-1a, to pin the formatted output without a hashing crate.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn formatted(seed: i64, params: &NetgenParams, result: &NetgenResult) -> String {
+    let mut out = String::new();
+    write_dimacs_fmt(&mut out, seed, 1, params, result, &DimacsOptions::default()).unwrap();
+    out
+}
+
+#[test]
+fn formats_the_pinned_instance() {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    assert_eq!(result.arcs.len(), 2000);
+    let text = formatted(13502460, &params, &result);
+    assert_eq!(text, to_dimacs_string(13502460, 1, &params).unwrap());
+    assert_eq!(text, result.to_dimacs_string(13502460, 1, &params));
+    assert!(text.starts_with("c NETGEN flow network generator (C version)\n"));
+    assert!(text.contains("\np min 512 2000\n"));
+    assert_eq!(fnv1a(&text), 0x8fc7_e684_e292_ccce);
+
+    let (with_options, report) =
+        generate_with_options(13502460, &params, &GenerateOptions::default()).unwrap();
+    assert_eq!(with_options, result);
+    assert_eq!(report.requested_arcs, 2000);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn matches_the_io_writers() {
+    use alloc::vec::Vec;
+
+    for (seed, parms) in [
+        (13502460, DOC_PARMS),
+        (12345, [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100]),
+        (99999, [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100]),
+    ] {
+        let params = NetgenParams::from_slice(&parms).unwrap();
+        let result = generate(seed, &params).unwrap();
+        let mut io = Vec::new();
+        netgen_rs::write_dimacs(&mut io, seed, 1, &params, &result).unwrap();
+        assert_eq!(
+            String::from_utf8(io).unwrap(),
+            formatted(seed, &params, &result)
+        );
+    }
+}
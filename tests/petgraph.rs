@@ -0,0 +1,84 @@
+#![cfg(feature = "petgraph")]
+
+mod fixture;
+
+use netgen_rs::{
+    ArcData, LowerBoundError, NetgenParams, NetgenResult, NodeData, generate, write_dimacs,
+};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+
+use fixture::doc_params;
+
+fn instance() -> (NetgenParams, NetgenResult) {
+    let params = doc_params();
+    let result = generate(13502460, &params).unwrap();
+    (params, result)
+}
+
+#[test]
+fn counts_match() {
+    let (_, result) = instance();
+    let graph = result.to_petgraph();
+    assert_eq!(graph.node_count(), result.supply.len());
+    assert_eq!(graph.edge_count(), result.arcs.len());
+    for (i, &supply) in result.supply.iter().enumerate() {
+        assert_eq!(graph[NodeIndex::new(i)], NodeData { supply });
+    }
+}
+
+#[test]
+fn arc_attributes_survive_round_trip() {
+    let (params, result) = instance();
+    let graph = result.to_petgraph();
+    let arc = &result.arcs[100];
+    let edge = EdgeIndex::new(100);
+    let (from, to) = graph.edge_endpoints(edge).unwrap();
+    assert_eq!(from.index() as u64 + 1, arc.from);
+    assert_eq!(to.index() as u64 + 1, arc.to);
+    assert_eq!(
+        graph[edge],
+        ArcData {
+            cost: arc.cost,
+            capacity: arc.capacity,
+            lower: 0,
+        }
+    );
+
+    let back = NetgenResult::from_petgraph(&graph).unwrap();
+    assert_eq!(back.canonicalize(), result.canonicalize());
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    write_dimacs(&mut a, 13502460, 1, &params, &result).unwrap();
+    write_dimacs(&mut b, 13502460, 1, &params, &back).unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn parallel_arcs_are_preserved() {
+    let (_, result) = instance();
+    let mut graph = result.to_petgraph();
+    let (from, to) = graph.edge_endpoints(EdgeIndex::new(0)).unwrap();
+    let extra = ArcData {
+        cost: 7,
+        capacity: 3,
+        lower: 0,
+    };
+    graph.add_edge(from, to, extra);
+
+    let back = NetgenResult::from_petgraph(&graph).unwrap();
+    assert_eq!(back.arcs.len(), result.arcs.len() + 1);
+    let (first, last) = (&back.arcs[0], back.arcs.last().unwrap());
+    assert_eq!((first.from, first.to), (last.from, last.to));
+    assert_eq!((last.cost, last.capacity), (7, 3));
+    assert_eq!(back.to_petgraph().edges_connecting(from, to).count(), 2);
+}
+
+#[test]
+fn lower_bounds_are_rejected() {
+    let (_, result) = instance();
+    let mut graph = result.to_petgraph();
+    graph[EdgeIndex::new(5)].lower = 2;
+    assert_eq!(
+        NetgenResult::from_petgraph(&graph),
+        Err(LowerBoundError { edge: 5, lower: 2 })
+    );
+}
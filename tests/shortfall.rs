@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+
+use netgen_rs::{
+    GenerateOptions, NetgenError, NetgenParams, Shortfall, generate, generate_with_options,
+};
+
+struct CaptureLogger {
+    messages: Mutex<Vec<String>>,
+}
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.messages
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", record.level(), record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger {
+    messages: Mutex::new(Vec::new()),
+};
+
+/// 20 nodes with 2 pure sources and 2 pure sinks hold at most 308 distinct
+/// arcs; the reference generator stops at 306.
+fn shortfall_params() -> NetgenParams {
+    NetgenParams::from_slice(&[20, 2, 2, 308, 1, 100, 100, 0, 0, 10, 50, 1, 50]).unwrap()
+}
+
+fn run(policy: Shortfall) -> Result<usize, NetgenError> {
+    let options = GenerateOptions {
+        on_shortfall: policy,
+        ..GenerateOptions::default()
+    };
+    generate_with_options(13502460, &shortfall_params(), &options).map(|(r, _)| r.arcs.len())
+}
+
+#[test]
+fn ignore_matches_reference() {
+    let params = shortfall_params();
+    let (result, report) =
+        generate_with_options(13502460, &params, &GenerateOptions::default()).unwrap();
+    assert_eq!(result, generate(13502460, &params).unwrap());
+    assert_eq!(result.arcs.len(), 306);
+    assert_eq!(report.shortfall, 2);
+}
+
+#[test]
+fn error_reports_counts() {
+    assert_eq!(
+        run(Shortfall::Error),
+        Err(NetgenError::ArcShortfall {
+            requested: 308,
+            produced: 306
+        })
+    );
+}
+
+#[test]
+fn warn_logs_and_returns() {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(log::LevelFilter::Warn);
+    assert_eq!(run(Shortfall::Warn), Ok(306));
+    let messages = LOGGER.messages.lock().unwrap();
+    assert!(
+        messages
+            .iter()
+            .any(|m| m == "WARN generated 306 arcs, 308 requested"),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn top_up_reaches_density() {
+    let params = shortfall_params();
+    let options = GenerateOptions {
+        on_shortfall: Shortfall::TopUp,
+        ..GenerateOptions::default()
+    };
+    let (result, report) = generate_with_options(13502460, &params, &options).unwrap();
+    assert_eq!(result.arcs.len(), 308);
+    assert_eq!(report.topped_up_arcs, 2);
+    assert_eq!(report.shortfall, 0);
+
+    let reference = generate(13502460, &params).unwrap();
+    assert_eq!(result.arcs[..306], reference.arcs[..]);
+    let mut pairs: Vec<(u64, u64)> = result.arcs.iter().map(|a| (a.from, a.to)).collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    assert_eq!(pairs.len(), 308, "top-up must not repeat an arc");
+}
+
+#[test]
+fn top_up_stops_when_saturated() {
+    let params =
+        NetgenParams::from_slice(&[20, 2, 2, 380, 1, 100, 100, 0, 0, 10, 50, 1, 50]).unwrap();
+    let options = GenerateOptions {
+        on_shortfall: Shortfall::TopUp,
+        ..GenerateOptions::default()
+    };
+    let (result, report) = generate_with_options(13502460, &params, &options).unwrap();
+    assert_eq!(result.arcs.len(), 308);
+    assert_eq!(report.shortfall, 72);
+}
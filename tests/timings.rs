@@ -0,0 +1,99 @@
+//! Timing measurements behind the crate's performance choices. They only
+//! print, so a noisy machine cannot fail them; run them in release mode:
+//!
+//! ```text
+//! cargo test --release --test timings -- --ignored --nocapture
+//! ```
+
+use std::time::{Duration, Instant};
+
+use netgen_rs::{GenerationMode, IndexList, IndexStrategy, NetgenParams, Rng, generate_with};
+
+/// Mean time per `choose` under `strategy`, drawing `draws` random
+/// positions from a fresh list of `1..=n`, best of 20 runs.
+fn time_choose(n: usize, draws: usize, strategy: IndexStrategy) -> Duration {
+    (0..20)
+        .map(|run| {
+            let mut rng = Rng::new(13502460 + run);
+            let mut list = IndexList::with_strategy(1, n, strategy);
+            let start = Instant::now();
+            for _ in 0..draws {
+                let position = rng.next(1, list.size() as i64) as usize;
+                std::hint::black_box(list.choose(position));
+            }
+            start.elapsed() / draws as u32
+        })
+        .min()
+        .unwrap()
+}
+
+/// The crossover between the sorted vector and the interval tree of
+/// [`IndexList`], which sets [`IndexStrategy::Threshold`]'s default.
+#[test]
+#[ignore]
+fn index_list_crossover() {
+    println!("n      emptying the list      drawing 10%");
+    println!("       vector    tree         vector    tree");
+    for n in [100, 1024, 2048, 4096] {
+        let timed = |draws| {
+            (
+                time_choose(n, draws, IndexStrategy::Flat),
+                time_choose(n, draws, IndexStrategy::Tree),
+            )
+        };
+        let (all_flat, all_tree) = timed(n);
+        let (some_flat, some_tree) = timed(n / 10);
+        println!(
+            "{n:<6} {:>4} ns {:>5} ns      {:>4} ns {:>5} ns",
+            all_flat.as_nanos(),
+            all_tree.as_nanos(),
+            some_flat.as_nanos(),
+            some_tree.as_nanos()
+        );
+    }
+}
+
+/// [`GenerationMode::ReferenceExact`] against [`GenerationMode::Fast`] at
+/// 2^18 nodes and 2^20 arcs, best of three runs each, for few to many
+/// sources: the fewer sources, the longer the skeleton chains the reference
+/// shell sort orders.
+#[test]
+#[ignore]
+fn exact_and_fast_modes_at_2_18_nodes() {
+    println!("sources  sinks  exact      fast");
+    for (sources, sinks) in [(1, 2), (4, 4), (64, 64)] {
+        let params = NetgenParams::from_slice(&[
+            1 << 18,
+            sources,
+            sinks,
+            1 << 20,
+            1,
+            1000,
+            1_000_000,
+            0,
+            0,
+            30,
+            50,
+            100,
+            1000,
+        ])
+        .unwrap();
+        let best_of_three = |mode| {
+            (0..3)
+                .map(|_| {
+                    let start = Instant::now();
+                    generate_with(13502460, &params, mode).unwrap();
+                    start.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+        let exact = best_of_three(GenerationMode::ReferenceExact);
+        let fast = best_of_three(GenerationMode::Fast);
+        println!(
+            "{sources:<8} {sinks:<6} {:>4} ms {:>6} ms",
+            exact.as_millis(),
+            fast.as_millis()
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! The wasm-bindgen wrappers, run with `just test-wasm`: `cargo test
+//! --target wasm32-unknown-unknown --features wasm` with
+//! `wasm-bindgen-test-runner` as the runner.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+mod fixture;
+
+use netgen_rs::wasm::{WasmParams, generate_dimacs, generate_json};
+use netgen_rs::{NetgenParams, ProblemType, generate, to_dimacs_string};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use fixture::DOC_PARMS;
+
+/// A JavaScript object with the 13 parameters of `parms`.
+fn object(parms: [i64; 13]) -> JsValue {
+    let names = [
+        "nodes",
+        "sources",
+        "sinks",
+        "density",
+        "mincost",
+        "maxcost",
+        "supply",
+        "tsources",
+        "tsinks",
+        "hicost_pct",
+        "capacitated_pct",
+        "mincap",
+        "maxcap",
+    ];
+    let fields: Vec<String> = names
+        .iter()
+        .zip(parms)
+        .map(|(name, value)| format!("\"{name}\": {value}"))
+        .collect();
+    js_sys::JSON::parse(&format!("{{{}}}", fields.join(", "))).unwrap()
+}
+
+fn assert_matches_library(seed: u32, parms: [i64; 13], kind: ProblemType) {
+    let expected = NetgenParams::from_slice(&parms).unwrap();
+    assert_eq!(expected.problem_type(), kind);
+    let params = WasmParams::new(object(parms)).unwrap();
+
+    let dimacs = generate_dimacs(seed, &params).unwrap();
+    assert_eq!(dimacs, to_dimacs_string(seed.into(), 1, &expected).unwrap());
+
+    let result = generate(seed.into(), &expected).unwrap();
+    let json = js_sys::JSON::stringify(&generate_json(seed, &params).unwrap()).unwrap();
+    let json = String::from(json);
+    let arcs = js_sys::JSON::parse(&json).unwrap();
+    let arcs = js_sys::Reflect::get(&arcs, &"arcs".into()).unwrap();
+    assert_eq!(
+        js_sys::Array::from(&arcs).length() as usize,
+        result.arcs.len()
+    );
+    // Every `Arc` field, provenance included.
+    for arc in [&result.arcs[0], result.arcs.last().unwrap()] {
+        let expected = format!(
+            "{{\"from\":{},\"to\":{},\"cost\":{},\"capacity\":{},\"multiplier\":null,\
+             \"uncapacitated\":{},\"kind\":\"{:?}\",\"hicost\":{}}}",
+            arc.from, arc.to, arc.cost, arc.capacity, arc.uncapacitated, arc.kind, arc.hicost
+        );
+        assert!(json.contains(&expected), "{expected}");
+    }
+}
+
+#[wasm_bindgen_test]
+fn min_cost_flow() {
+    assert_matches_library(13502460, DOC_PARMS, ProblemType::MinCostFlow);
+}
+
+#[wasm_bindgen_test]
+fn max_flow() {
+    assert_matches_library(
+        99999,
+        [200, 5, 5, 1000, 1, 1, 500, 2, 2, 20, 50, 10, 100],
+        ProblemType::MaxFlow,
+    );
+}
+
+#[wasm_bindgen_test]
+fn assignment() {
+    assert_matches_library(
+        12345,
+        [100, 50, 50, 500, 1, 100, 50, 0, 0, 0, 0, 1, 100],
+        ProblemType::Assignment,
+    );
+}
+
+#[wasm_bindgen_test]
+fn oversized_and_invalid_params_fail() {
+    let huge = [
+        10_000_000, 10, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000,
+    ];
+    assert!(WasmParams::new(object(huge)).is_err());
+    let invalid = [512, 600, 10, 2000, 5, 500, 1000, 3, 3, 20, 80, 50, 2000];
+    assert!(WasmParams::new(object(invalid)).is_err());
+    assert!(WasmParams::new(JsValue::from_str("not an object")).is_err());
+}